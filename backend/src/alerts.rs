@@ -0,0 +1,191 @@
+// Rules that look at fresh readings and raise an alert when something
+// needs the user's attention. An alert is only raised once when its
+// condition starts, so frontends can show and announce every alert they
+// get without repeating themselves. Once the condition is over it can be
+// raised again.
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    time::{Duration, Instant},
+};
+
+use crate::BatteryInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum AlertSeverity {
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Alert {
+    pub severity: AlertSeverity,
+    pub message:  String,
+}
+
+impl Alert {
+    /// Shows the alert as a desktop notification. Returns whether that
+    /// worked, which needs `notify-send` on Linux and isn't supported on
+    /// Windows
+    #[must_use]
+    pub fn notify(&self) -> bool {
+        let mut command = if cfg!(target_os = "macos") {
+            let mut command = std::process::Command::new("osascript");
+            command.args(["-e", &format!("display notification {:?} with title \"crossinfo\"", self.message)]);
+            command
+        } else if cfg!(windows) {
+            return false;
+        } else {
+            let mut command = std::process::Command::new("notify-send");
+            command.args([
+                "--urgency",
+                match self.severity {
+                    AlertSeverity::Warning => "normal",
+                    AlertSeverity::Critical => "critical",
+                },
+                "crossinfo",
+                &self.message,
+            ]);
+            command
+        };
+        command.output().is_ok_and(|output| output.status.success())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatteryAlertRules {
+    /// Percent, only while discharging
+    pub low_charge:      f32,
+    /// Percent, only while discharging
+    pub critical_charge: f32,
+    /// Percentage points lost per minute
+    pub sudden_drop:     f32,
+    /// Percent of the design capacity
+    pub low_health:      f32,
+}
+
+impl Default for BatteryAlertRules {
+    fn default() -> Self {
+        Self {
+            low_charge:      20.0,
+            critical_charge: 5.0,
+            sudden_drop:     5.0,
+            low_health:      80.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum BatteryCondition {
+    Low,
+    Critical,
+    Draining,
+    Worn,
+}
+
+#[derive(Debug, Default)]
+pub struct BatteryAlerts {
+    pub rules:  BatteryAlertRules,
+    /// Battery index and condition of everything currently raised
+    raised:     BTreeSet<(usize, BatteryCondition)>,
+    /// Charge in percent of every battery when the drop was last measured
+    references: HashMap<usize, (Instant, f32)>,
+}
+
+impl BatteryAlerts {
+    // Drops are measured over at least this long, the charge readings of
+    // many batteries jump around too much for anything shorter
+    const DROP_WINDOW: Duration = Duration::from_secs(90);
+
+    #[must_use]
+    pub fn new(rules: BatteryAlertRules) -> Self {
+        Self { rules, ..Self::default() }
+    }
+
+    /// The alerts that started since the last call
+    pub fn check(&mut self, batteries: &[BatteryInfo]) -> Vec<Alert> {
+        self.check_at(batteries, Instant::now())
+    }
+
+    fn check_at(&mut self, batteries: &[BatteryInfo], now: Instant) -> Vec<Alert> {
+        let mut alerts = Vec::new();
+        for (index, battery) in batteries.iter().enumerate() {
+            let name = battery.model.clone().unwrap_or_else(|| format!("Battery {}", index + 1));
+            let charge = battery.charge * 100.0;
+            let discharging = battery.state == battery::State::Discharging;
+
+            let reference = self.references.entry(index).or_insert((now, charge));
+            let draining = if now.duration_since(reference.0) >= Self::DROP_WINDOW {
+                #[allow(clippy::cast_possible_truncation)]
+                let drop_per_minute = (reference.1 - charge) / (now.duration_since(reference.0).as_secs_f64() / 60.0) as f32;
+                *reference = (now, charge);
+                Some(drop_per_minute >= self.rules.sudden_drop)
+            } else {
+                // Stays as it was until the next measurement
+                None
+            };
+
+            let conditions = [
+                (
+                    BatteryCondition::Critical,
+                    Some(discharging && charge <= self.rules.critical_charge),
+                    AlertSeverity::Critical,
+                    format!("{name} is almost empty ({charge:.0}%)"),
+                ),
+                (
+                    BatteryCondition::Low,
+                    Some(discharging && charge <= self.rules.low_charge && charge > self.rules.critical_charge),
+                    AlertSeverity::Warning,
+                    format!("{name} is low ({charge:.0}%)"),
+                ),
+                (BatteryCondition::Draining, draining, AlertSeverity::Warning, format!("{name} is draining unusually fast")),
+                (
+                    BatteryCondition::Worn,
+                    Some(battery.health < self.rules.low_health),
+                    AlertSeverity::Warning,
+                    format!("{name} only holds {:.0}% of its original capacity", battery.health),
+                ),
+            ];
+            for (condition, active, severity, message) in conditions {
+                match active {
+                    Some(true) if self.raised.insert((index, condition)) => alerts.push(Alert { severity, message }),
+                    Some(false) => {
+                        self.raised.remove(&(index, condition));
+                    }
+                    _ => {}
+                }
+            }
+        }
+        alerts
+    }
+}
+
+#[test]
+fn raise_battery_alerts_once() {
+    let battery = |charge, health| BatteryInfo {
+        charge,
+        capacity: uom::si::f64::Energy::new::<uom::si::energy::watt_hour>(50.0),
+        capacity_new: uom::si::f64::Energy::new::<uom::si::energy::watt_hour>(60.0),
+        energy: None,
+        power: None,
+        health,
+        voltage: uom::si::f64::ElectricPotential::new::<uom::si::electric_potential::volt>(12.0),
+        state: battery::State::Discharging,
+        technology: battery::Technology::LithiumIon,
+        cycle_count: None,
+        manufacturer: None,
+        model: Some("BAT0".to_string()),
+    };
+    let start = Instant::now();
+    let mut alerts = BatteryAlerts::default();
+    let messages = |alerts: Vec<Alert>| alerts.into_iter().map(|alert| alert.message).collect::<Vec<String>>();
+
+    assert_eq!(messages(alerts.check_at(&[battery(0.5, 75.0)], start)), ["BAT0 only holds 75% of its original capacity"]);
+    assert!(alerts.check_at(&[battery(0.45, 75.0)], start + Duration::from_secs(30)).is_empty());
+    // 32 percentage points in one and a half minutes
+    assert_eq!(messages(alerts.check_at(&[battery(0.18, 75.0)], start + Duration::from_secs(90))), [
+        "BAT0 is low (18%)",
+        "BAT0 is draining unusually fast"
+    ]);
+    assert_eq!(messages(alerts.check_at(&[battery(0.04, 75.0)], start + Duration::from_secs(150))), ["BAT0 is almost empty (4%)"]);
+}
@@ -0,0 +1,105 @@
+// The Manager owns a tokio runtime and blocks on it for Bluetooth and the
+// connectivity check, which panics when called from an async application.
+// The AsyncManager has no runtime of its own: the functions that wait on
+// the network or on devices are async and run on the caller's tokio
+// runtime, everything else is reached through `blocking`.
+
+use std::time::Instant;
+
+use crate::{bluetooth, BluetoothAdapterInfo, BluetoothInfo, Error, Manager, NetworkInfo, Speedtest, SpeedtestProgress};
+
+pub struct AsyncManager {
+    // Has neither a runtime nor Bluetooth adapters
+    manager:   Manager,
+    bluetooth: bluetooth::Adapters,
+}
+
+impl AsyncManager {
+    pub async fn new() -> Self {
+        Self {
+            manager:   Manager::without_runtime(),
+            bluetooth: bluetooth::Adapters::new().await,
+        }
+    }
+
+    /// The functions that don't wait on anything but the OS. Its
+    /// `network_information` doesn't check connectivity or scan for Wi-Fi
+    /// networks and it finds no Bluetooth adapters, use the ones on the
+    /// AsyncManager instead
+    pub const fn blocking(&mut self) -> &mut Manager {
+        &mut self.manager
+    }
+
+    /// Scanning for Wi-Fi networks happens on tokio's blocking threads
+    pub async fn network_information(&mut self) -> NetworkInfo {
+        let wifis = if let Some((_, wifis)) = self.manager.current_wifi_scan() {
+            wifis.clone()
+        } else {
+            let wifis = tokio::task::spawn_blocking(crate::scan_wifis).await.ok().flatten();
+            self.manager.wifi_scan = Some((Instant::now(), wifis.clone()));
+            wifis
+        };
+        let interfaces = self.manager.interface_information();
+        NetworkInfo {
+            connectivity: crate::connectivity::connectivity_state(&self.manager.connectivity_check, interfaces.networks.as_deref()).await,
+            connected_v6: crate::connectivity::is_connected_v6().await,
+            wifis,
+            ..interfaces
+        }
+    }
+
+    pub async fn speedtest(&self, progress: impl Fn(SpeedtestProgress)) -> Option<Speedtest> {
+        self.try_speedtest(progress).await.ok()
+    }
+
+    /// # Errors
+    ///
+    /// Like `Manager::try_speedtest`
+    pub async fn try_speedtest(&self, progress: impl Fn(SpeedtestProgress)) -> Result<Speedtest, Error> {
+        crate::speedtest::speedtest(progress).await
+    }
+
+    pub async fn bluetooth_information(&self) -> Option<Vec<BluetoothInfo>> {
+        self.try_bluetooth_information().await.ok()
+    }
+
+    /// # Errors
+    ///
+    /// Like `Manager::try_bluetooth_information_on`
+    pub async fn try_bluetooth_information(&self) -> Result<Vec<BluetoothInfo>, Error> {
+        self.bluetooth.devices(self.bluetooth.selected()).await
+    }
+
+    pub async fn bluetooth_information_on(&self, adapter: usize) -> Option<Vec<BluetoothInfo>> {
+        self.bluetooth.devices(adapter).await.ok()
+    }
+
+    /// # Errors
+    ///
+    /// Like `Manager::try_bluetooth_information_on`
+    pub async fn try_bluetooth_information_on(&self, adapter: usize) -> Result<Vec<BluetoothInfo>, Error> {
+        self.bluetooth.devices(adapter).await
+    }
+
+    pub async fn bluetooth_adapters(&self) -> Vec<BluetoothAdapterInfo> {
+        self.bluetooth.information().await
+    }
+
+    pub async fn bluetooth_adapter_information(&self) -> Option<BluetoothAdapterInfo> {
+        self.bluetooth.information().await.into_iter().nth(self.bluetooth.selected())
+    }
+
+    pub const fn selected_bluetooth_adapter(&self) -> usize {
+        self.bluetooth.selected()
+    }
+
+    /// Like `Manager::select_bluetooth_adapter`
+    pub async fn select_bluetooth_adapter(&mut self, adapter: usize) -> bool {
+        self.bluetooth.select(adapter).await
+    }
+
+    /// Like `Manager::set_bluetooth_power`
+    pub async fn set_bluetooth_power(&self, powered: bool) -> bool {
+        self.bluetooth.set_power(powered).await
+    }
+}
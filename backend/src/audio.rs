@@ -0,0 +1,267 @@
+// Audio devices and the streams playing on them. On Linux both PulseAudio
+// and PipeWire (through pipewire-pulse) speak the PulseAudio protocol, so
+// `pactl` covers both. macOS only exposes the volume of the default
+// devices to scripts.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AudioDirection {
+    Output,
+    Input,
+}
+
+impl std::fmt::Display for AudioDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Output => "Output",
+            Self::Input => "Input",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AudioDevice {
+    /// Identifies the device for the audio server, e.g.
+    /// "alsa_output.pci-0000_00_1f.3.analog-stereo"
+    pub id:         String,
+    pub name:       String,
+    pub direction:  AudioDirection,
+    pub is_default: bool,
+    /// 1.0 is 100%. Can be higher than that if the signal is amplified
+    pub volume:     Option<f32>,
+    pub is_muted:   Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AudioStream {
+    pub application: String,
+    pub pid:         Option<u32>,
+    pub direction:   AudioDirection,
+    /// Paused streams stay open but don't play or record anything
+    pub is_active:   bool,
+    pub volume:      Option<f32>,
+    pub is_muted:    Option<bool>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AudioInfo {
+    pub devices: Vec<AudioDevice>,
+    /// Applications that currently have a stream open
+    pub streams: Vec<AudioStream>,
+}
+
+#[cfg(target_os = "linux")]
+fn pactl(args: &[&str]) -> Option<String> {
+    std::process::Command::new("pactl")
+        .args(args)
+        // The output is translated otherwise
+        .env("LC_ALL", "C")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+#[cfg(target_os = "linux")]
+pub fn audio_information() -> Option<AudioInfo> {
+    let server_info = pactl(&["info"])?;
+    let default_sink = server_info.lines().find_map(|line| line.strip_prefix("Default Sink: "));
+    let default_source = server_info.lines().find_map(|line| line.strip_prefix("Default Source: "));
+    let mut devices = parse_pactl_devices(&pactl(&["list", "sinks"])?, AudioDirection::Output, default_sink);
+    devices.extend(parse_pactl_devices(&pactl(&["list", "sources"])?, AudioDirection::Input, default_source));
+    let mut streams = parse_pactl_streams(&pactl(&["list", "sink-inputs"])?, AudioDirection::Output);
+    streams.extend(parse_pactl_streams(&pactl(&["list", "source-outputs"])?, AudioDirection::Input));
+    Some(AudioInfo { devices, streams })
+}
+
+// `get volume settings` prints "output volume:50, input volume:75, alert
+// volume:100, output muted:false"
+#[cfg(target_os = "macos")]
+pub fn audio_information() -> Option<AudioInfo> {
+    let output = std::process::Command::new("osascript")
+        .args(["-e", "get volume settings"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let settings = String::from_utf8_lossy(&output.stdout).to_string();
+    let setting = |name: &str| settings.split(", ").find_map(|setting| setting.trim().strip_prefix(name)).map(ToString::to_string);
+    let device = |direction: AudioDirection, volume: Option<String>, is_muted: Option<String>| AudioDevice {
+        id: direction.to_string().to_lowercase(),
+        name: format!("Default {}", direction.to_string().to_lowercase()),
+        direction,
+        is_default: true,
+        volume: volume.and_then(|volume| volume.parse::<f32>().ok()).map(|volume| volume / 100.0),
+        is_muted: is_muted.map(|is_muted| is_muted == "true"),
+    };
+    Some(AudioInfo {
+        devices: vec![
+            device(AudioDirection::Output, setting("output volume:"), setting("output muted:")),
+            device(AudioDirection::Input, setting("input volume:"), None),
+        ],
+        streams: Vec::new(),
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub const fn audio_information() -> Option<AudioInfo> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+pub fn set_volume(device: &AudioDevice, volume: f32) -> bool {
+    let command = match device.direction {
+        AudioDirection::Output => "set-sink-volume",
+        AudioDirection::Input => "set-source-volume",
+    };
+    pactl(&[command, &device.id, &format!("{:.0}%", volume.max(0.0) * 100.0)]).is_some()
+}
+
+// There's only the default device to choose from, so the id is ignored
+#[cfg(target_os = "macos")]
+pub fn set_volume(device: &AudioDevice, volume: f32) -> bool {
+    let direction = device.direction.to_string().to_lowercase();
+    std::process::Command::new("osascript")
+        .args(["-e", &format!("set volume {direction} volume {:.0}", (volume * 100.0).clamp(0.0, 100.0))])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub const fn set_volume(_device: &AudioDevice, _volume: f32) -> bool {
+    false
+}
+
+// Sinks and sources are made default with different commands, so the id
+// is looked up first to know which one it is
+#[cfg(target_os = "linux")]
+pub fn set_default_device(id: &str) -> bool {
+    let Some(device) = audio_information().and_then(|audio| audio.devices.into_iter().find(|device| device.id == id)) else {
+        return false;
+    };
+    let command = match device.direction {
+        AudioDirection::Output => "set-default-sink",
+        AudioDirection::Input => "set-default-source",
+    };
+    pactl(&[command, id]).is_some()
+}
+
+// macOS only lets scripts see the default devices, so there is nothing to
+// switch to
+#[cfg(not(target_os = "linux"))]
+pub const fn set_default_device(_id: &str) -> bool {
+    false
+}
+
+// pactl prints one block per object, e.g.
+//
+// Sink #47
+//     State: RUNNING
+//     Name: alsa_output.pci-0000_00_1f.3.analog-stereo
+//     Mute: no
+//     Volume: front-left: 32768 /  50% / -18.06 dB, ...
+//     Properties:
+//         application.name = "Firefox"
+//
+// with tabs for indentation. Properties are indented twice and quoted,
+// everything else is a "Key: value" pair.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn pactl_blocks(output: &str) -> Vec<Vec<(&str, &str)>> {
+    let mut blocks: Vec<Vec<(&str, &str)>> = Vec::new();
+    for line in output.lines() {
+        if !line.is_empty() && !line.starts_with(char::is_whitespace) {
+            blocks.push(Vec::new());
+        } else if let Some(block) = blocks.last_mut() {
+            let field = if line.starts_with("\t\t") {
+                line.trim().split_once(" = ").map(|(key, value)| (key, value.trim_matches('"')))
+            } else {
+                line.trim().split_once(": ")
+            };
+            if let Some(field) = field {
+                block.push(field);
+            }
+        }
+    }
+    blocks
+}
+
+// The volume is given per channel, the first one is good enough
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_pactl_volume(volume: &str) -> Option<f32> {
+    volume
+        .split_whitespace()
+        .find_map(|word| word.strip_suffix('%'))
+        .and_then(|percentage| percentage.parse::<f32>().ok())
+        .map(|percentage| percentage / 100.0)
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_pactl_devices(output: &str, direction: AudioDirection, default: Option<&str>) -> Vec<AudioDevice> {
+    pactl_blocks(output)
+        .into_iter()
+        .filter_map(|block| {
+            let field = |name: &str| block.iter().find(|(key, _)| *key == name).map(|(_, value)| *value);
+            // Every output has a monitor source for recording what it plays,
+            // those aren't actual inputs
+            if field("Monitor of Sink").is_some_and(|sink| sink != "n/a") {
+                return None;
+            }
+            let id = field("Name")?;
+            Some(AudioDevice {
+                id: id.to_string(),
+                name: field("Description").unwrap_or(id).to_string(),
+                direction,
+                is_default: Some(id) == default,
+                volume: field("Volume").and_then(parse_pactl_volume),
+                is_muted: field("Mute").map(|is_muted| is_muted == "yes"),
+            })
+        })
+        .collect()
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_pactl_streams(output: &str, direction: AudioDirection) -> Vec<AudioStream> {
+    pactl_blocks(output)
+        .into_iter()
+        .filter_map(|block| {
+            let field = |name: &str| block.iter().find(|(key, _)| *key == name).map(|(_, value)| *value);
+            Some(AudioStream {
+                application: field("application.name").or_else(|| field("media.name"))?.to_string(),
+                pid: field("application.process.id").and_then(|pid| pid.parse().ok()),
+                direction,
+                is_active: field("Corked") != Some("yes"),
+                volume: field("Volume").and_then(parse_pactl_volume),
+                is_muted: field("Mute").map(|is_muted| is_muted == "yes"),
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn parse_pactl_sources() {
+    let devices = parse_pactl_devices(
+        "Source #46
+	State: SUSPENDED
+	Name: alsa_output.pci-0000_00_1f.3.analog-stereo.monitor
+	Description: Monitor of Built-in Audio Analog Stereo
+	Mute: no
+	Volume: front-left: 65536 / 100% / 0.00 dB,   front-right: 65536 / 100% / 0.00 dB
+	Monitor of Sink: alsa_output.pci-0000_00_1f.3.analog-stereo
+
+Source #47
+	State: RUNNING
+	Name: alsa_input.pci-0000_00_1f.3.analog-stereo
+	Description: Built-in Audio Analog Stereo
+	Mute: yes
+	Volume: front-left: 22938 /  35% / -27.36 dB,   front-right: 22938 /  35% / -27.36 dB
+	Monitor of Sink: n/a
+	Properties:
+		alsa.card = \"0\"
+",
+        AudioDirection::Input,
+        Some("alsa_input.pci-0000_00_1f.3.analog-stereo"),
+    );
+    assert_eq!(devices.len(), 1);
+    assert_eq!(devices[0].name, "Built-in Audio Analog Stereo");
+    assert!(devices[0].is_default);
+    assert_eq!(devices[0].volume, Some(0.35));
+    assert_eq!(devices[0].is_muted, Some(true));
+}
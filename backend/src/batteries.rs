@@ -0,0 +1,125 @@
+// Laptops with two batteries drain one after the other, so what the user
+// wants to know is how long they last together. Adding them up takes a
+// bit of care: the charge has to be weighted by capacity and the time
+// remaining comes from the energy and power of all of them, not from the
+// battery that happens to be in use.
+
+use std::time::Duration;
+
+use uom::si::{
+    energy::watt_hour,
+    f64::{Energy, Power},
+    power::watt,
+};
+
+use crate::BatteryInfo;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CombinedBattery {
+    /// How many batteries were combined
+    pub batteries:      usize,
+    /// Between 0 and 1, weighted by the capacity of every battery
+    pub charge:         f32,
+    #[cfg_attr(feature = "serde", serde(rename = "capacity_wh", with = "crate::serialization"))]
+    pub capacity:       Energy,
+    #[cfg_attr(feature = "serde", serde(rename = "capacity_new_wh", with = "crate::serialization"))]
+    pub capacity_new:   Energy,
+    /// Charging as long as one of them is, then discharging as long as
+    /// one of them is, and full or empty once all of them are
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization"))]
+    pub state:          battery::State,
+    /// Until all of them are empty while discharging or full while
+    /// charging. None if they don't report their energy or power
+    pub time_remaining: Option<Duration>,
+}
+
+/// All `batteries` as if they were one. None if there are none
+#[must_use]
+pub fn combine_batteries(batteries: &[BatteryInfo]) -> Option<CombinedBattery> {
+    if batteries.is_empty() {
+        return None;
+    }
+    let capacity = batteries.iter().map(|battery| battery.capacity).fold(Energy::new::<watt_hour>(0.0), |total, capacity| total + capacity);
+    let capacity_new = batteries
+        .iter()
+        .map(|battery| battery.capacity_new)
+        .fold(Energy::new::<watt_hour>(0.0), |total, capacity| total + capacity);
+    // Batteries that don't report their energy are estimated from their
+    // charge
+    let energy = batteries
+        .iter()
+        .map(|battery| battery.energy.unwrap_or_else(|| battery.capacity * f64::from(battery.charge)))
+        .fold(Energy::new::<watt_hour>(0.0), |total, energy| total + energy);
+    let has_state = |state: battery::State| batteries.iter().any(|battery| battery.state == state);
+    let state = if has_state(battery::State::Charging) {
+        battery::State::Charging
+    } else if has_state(battery::State::Discharging) {
+        battery::State::Discharging
+    } else if batteries.iter().all(|battery| battery.state == battery::State::Full) {
+        battery::State::Full
+    } else if batteries.iter().all(|battery| battery.state == battery::State::Empty) {
+        battery::State::Empty
+    } else {
+        battery::State::Unknown
+    };
+    // Only the batteries in that state count towards the power
+    let power = batteries
+        .iter()
+        .filter(|battery| battery.state == state)
+        .map(|battery| battery.power)
+        .try_fold(Power::new::<watt>(0.0), |total, power| Some(total + power?))
+        .filter(|power| power.get::<watt>() > 0.0);
+    let time_remaining = match state {
+        battery::State::Discharging => power.map(|power| energy / power),
+        battery::State::Charging => power.map(|power| (capacity - energy) / power),
+        _ => None,
+    }
+    .map(|time| time.get::<uom::si::time::second>())
+    .filter(|seconds| seconds.is_finite())
+    .map(|seconds| Duration::from_secs_f64(seconds.max(0.0)));
+    #[allow(clippy::cast_possible_truncation)]
+    Some(CombinedBattery {
+        batteries: batteries.len(),
+        charge: if capacity.get::<watt_hour>() > 0.0 { (energy / capacity).value as f32 } else { 0.0 },
+        capacity,
+        capacity_new,
+        state,
+        time_remaining,
+    })
+}
+
+#[test]
+fn combine_two_batteries() {
+    let battery = |capacity: f64, charge: f32, power: Option<f64>, state| BatteryInfo {
+        charge,
+        capacity: Energy::new::<watt_hour>(capacity),
+        capacity_new: Energy::new::<watt_hour>(capacity),
+        energy: Some(Energy::new::<watt_hour>(capacity * f64::from(charge))),
+        power: power.map(Power::new::<watt>),
+        health: 100.0,
+        voltage: uom::si::f64::ElectricPotential::new::<uom::si::electric_potential::volt>(12.0),
+        state,
+        technology: battery::Technology::LithiumIon,
+        cycle_count: None,
+        manufacturer: None,
+        model: None,
+    };
+    assert_eq!(combine_batteries(&[]), None);
+
+    // 20 Wh and 12 Wh left, drained with 8 W
+    let combined = combine_batteries(&[battery(40.0, 0.5, Some(8.0), battery::State::Discharging), battery(24.0, 0.5, Some(0.0), battery::State::Full)]);
+    let combined = combined.expect("there are batteries");
+    assert_eq!(combined.batteries, 2);
+    assert!((combined.charge - 0.5).abs() < 0.001);
+    assert!((combined.capacity.get::<watt_hour>() - 64.0).abs() < 0.001);
+    assert_eq!(combined.state, battery::State::Discharging);
+    assert_eq!(combined.time_remaining.map(|time| time.as_secs()), Some(4 * 3600));
+
+    // 32 Wh missing, charged with 16 W
+    let combined = combine_batteries(&[battery(40.0, 0.5, Some(16.0), battery::State::Charging), battery(24.0, 0.5, None, battery::State::Discharging)]);
+    assert_eq!(combined.and_then(|combined| combined.time_remaining).map(|time| time.as_secs()), Some(2 * 3600));
+
+    let combined = combine_batteries(&[battery(40.0, 1.0, None, battery::State::Full)]);
+    assert_eq!(combined.map(|combined| (combined.state, combined.time_remaining)), Some((battery::State::Full, None)));
+}
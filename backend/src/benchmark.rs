@@ -0,0 +1,236 @@
+// Deliberately simple benchmarks. The numbers aren't comparable with other
+// tools, but they are comparable between machines running crossinfo, which
+// is the point. Every benchmark runs in rounds so callers can show
+// progress.
+//
+// The disk benchmark writes a temporary file to the disk and reads it
+// back, sequentially and in small blocks at random offsets. Bypassing the
+// page cache would need unsafe code, so the file is synced after writing
+// and should be larger than the free memory for reads that actually hit
+// the disk.
+
+use std::{
+    fs::OpenOptions,
+    hint::black_box,
+    io::{Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+    time::Instant,
+};
+
+const ROUNDS: u32 = 10;
+const CPU_ITERATIONS_PER_ROUND: u64 = 20_000_000;
+// Large enough to not fit into any CPU cache
+const MEMORY_BUFFER_SIZE: usize = 256 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy)]
+pub struct CpuBenchmark {
+    /// Millions of operations per second on a single core
+    pub single_thread_score: f64,
+    /// Millions of operations per second using every logical core
+    pub multi_thread_score:  f64,
+    pub threads:             usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct MemoryBenchmark {
+    /// Bytes read and written per second while copying a large buffer
+    pub bandwidth: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchmarkConfig {
+    /// Of the temporary file
+    pub file_size:  u64,
+    /// Of every random read and write
+    pub block_size: usize,
+}
+
+impl Default for BenchmarkConfig {
+    fn default() -> Self {
+        Self {
+            file_size:  1024 * 1024 * 1024,
+            block_size: 4096,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct DiskBenchmark {
+    /// Bytes per second
+    pub sequential_read:   f64,
+    pub sequential_write:  f64,
+    /// Blocks of `BenchmarkConfig::block_size` per second
+    pub random_read_iops:  f64,
+    pub random_write_iops: f64,
+}
+
+// A xorshift generator with some multiplication thrown in. It's integer
+// only and every iteration depends on the previous one, so the compiler
+// can't vectorize it away.
+fn cpu_workload(iterations: u64) -> u64 {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    for iteration in 0..iterations {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state = state.wrapping_mul(0x2545_f491_4f6c_dd1d).wrapping_add(iteration);
+    }
+    state
+}
+
+/// `progress` is called after every round with a value between 0 and 1
+#[allow(clippy::cast_precision_loss)]
+pub fn benchmark_cpu(progress: impl Fn(f32)) -> CpuBenchmark {
+    let threads = std::thread::available_parallelism().map_or(1, std::num::NonZeroUsize::get);
+    // Half of the rounds are single threaded, the other half multi threaded
+    let total_rounds = ROUNDS * 2;
+
+    let start = Instant::now();
+    for round in 0..ROUNDS {
+        black_box(cpu_workload(black_box(CPU_ITERATIONS_PER_ROUND)));
+        progress((round + 1) as f32 / total_rounds as f32);
+    }
+    let single_thread_score = f64::from(ROUNDS) * CPU_ITERATIONS_PER_ROUND as f64 / start.elapsed().as_secs_f64() / 1_000_000.0;
+
+    let start = Instant::now();
+    for round in 0..ROUNDS {
+        std::thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| black_box(cpu_workload(black_box(CPU_ITERATIONS_PER_ROUND))));
+            }
+        });
+        progress((ROUNDS + round + 1) as f32 / total_rounds as f32);
+    }
+    let multi_thread_score = f64::from(ROUNDS) * (CPU_ITERATIONS_PER_ROUND * threads as u64) as f64 / start.elapsed().as_secs_f64() / 1_000_000.0;
+
+    CpuBenchmark {
+        single_thread_score,
+        multi_thread_score,
+        threads,
+    }
+}
+
+/// `progress` is called after every round with a value between 0 and 1
+#[allow(clippy::cast_precision_loss)]
+pub fn benchmark_memory(progress: impl Fn(f32)) -> MemoryBenchmark {
+    let source = vec![1u8; MEMORY_BUFFER_SIZE];
+    let mut destination = vec![0u8; MEMORY_BUFFER_SIZE];
+    // Touch both buffers once so page faults don't end up in the result
+    destination.copy_from_slice(&source);
+
+    let start = Instant::now();
+    for round in 0..ROUNDS {
+        destination.copy_from_slice(black_box(&source));
+        black_box(&mut destination);
+        progress((round + 1) as f32 / ROUNDS as f32);
+    }
+    MemoryBenchmark {
+        bandwidth: 2.0 * f64::from(ROUNDS) * MEMORY_BUFFER_SIZE as f64 / start.elapsed().as_secs_f64(),
+    }
+}
+
+// Large enough for the overhead of a system call not to matter
+const SEQUENTIAL_CHUNK_SIZE: usize = 1024 * 1024;
+const RANDOM_OPERATIONS_PER_ROUND: u32 = 1000;
+
+/// Removes the file when the benchmark is done, whether it failed or not
+struct TemporaryFile(PathBuf);
+
+impl Drop for TemporaryFile {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.0);
+    }
+}
+
+/// Block numbers to read and write at random, the same on every run
+fn random_blocks(blocks: u64) -> impl Iterator<Item = u64> {
+    let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+    std::iter::repeat_with(move || {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        state % blocks
+    })
+}
+
+/// `progress` is called after every round with a value between 0 and 1
+///
+/// # Errors
+///
+/// If the file can't be created in `directory`, e.g. for lack of
+/// permission or space, or reading or writing it fails
+#[allow(clippy::cast_precision_loss)]
+pub fn benchmark_disk(directory: &Path, config: BenchmarkConfig, progress: impl Fn(f32)) -> std::io::Result<DiskBenchmark> {
+    let temporary = TemporaryFile(directory.join(format!(".crossinfo-benchmark-{}", std::process::id())));
+    let mut file = OpenOptions::new().read(true).write(true).create_new(true).open(&temporary.0)?;
+    // Sequential writing, then reading, then both at random, each taking
+    // up a quarter of the progress
+    let total_rounds = ROUNDS * 4;
+    let chunk = vec![0xa5u8; SEQUENTIAL_CHUNK_SIZE];
+    let chunks_per_round = (config.file_size / SEQUENTIAL_CHUNK_SIZE as u64 / u64::from(ROUNDS)).max(1);
+    let file_size = chunks_per_round * u64::from(ROUNDS) * SEQUENTIAL_CHUNK_SIZE as u64;
+
+    let start = Instant::now();
+    for round in 0..ROUNDS {
+        for _ in 0..chunks_per_round {
+            file.write_all(&chunk)?;
+        }
+        file.sync_data()?;
+        progress((round + 1) as f32 / total_rounds as f32);
+    }
+    let sequential_write = file_size as f64 / start.elapsed().as_secs_f64();
+
+    file.seek(SeekFrom::Start(0))?;
+    let mut buffer = vec![0u8; SEQUENTIAL_CHUNK_SIZE];
+    let start = Instant::now();
+    for round in 0..ROUNDS {
+        for _ in 0..chunks_per_round {
+            file.read_exact(&mut buffer)?;
+            black_box(&buffer);
+        }
+        progress((ROUNDS + round + 1) as f32 / total_rounds as f32);
+    }
+    let sequential_read = file_size as f64 / start.elapsed().as_secs_f64();
+
+    let block_size = config.block_size.max(1);
+    let blocks = (file_size / block_size as u64).max(1);
+    let mut block = vec![0u8; block_size];
+    let mut offsets = random_blocks(blocks).map(|block| block * block_size as u64);
+    let start = Instant::now();
+    for round in 0..ROUNDS {
+        for offset in offsets.by_ref().take(RANDOM_OPERATIONS_PER_ROUND as usize) {
+            file.seek(SeekFrom::Start(offset))?;
+            file.read_exact(&mut block)?;
+            black_box(&block);
+        }
+        progress((ROUNDS * 2 + round + 1) as f32 / total_rounds as f32);
+    }
+    let random_read_iops = f64::from(ROUNDS * RANDOM_OPERATIONS_PER_ROUND) / start.elapsed().as_secs_f64();
+
+    let start = Instant::now();
+    for round in 0..ROUNDS {
+        for offset in offsets.by_ref().take(RANDOM_OPERATIONS_PER_ROUND as usize) {
+            file.seek(SeekFrom::Start(offset))?;
+            file.write_all(&block)?;
+        }
+        file.sync_data()?;
+        progress((ROUNDS * 3 + round + 1) as f32 / total_rounds as f32);
+    }
+    let random_write_iops = f64::from(ROUNDS * RANDOM_OPERATIONS_PER_ROUND) / start.elapsed().as_secs_f64();
+
+    drop(file);
+    drop(temporary);
+    Ok(DiskBenchmark {
+        sequential_read,
+        sequential_write,
+        random_read_iops,
+        random_write_iops,
+    })
+}
+
+#[test]
+fn random_blocks_stay_in_the_file() {
+    assert!(random_blocks(100).take(10_000).all(|block| block < 100));
+    assert_eq!(random_blocks(1).take(5).collect::<Vec<u64>>(), vec![0; 5]);
+    assert_eq!(random_blocks(1000).take(3).collect::<Vec<u64>>(), random_blocks(1000).take(3).collect::<Vec<u64>>());
+}
@@ -0,0 +1,385 @@
+// btleplug finds the adapter and its devices and knows whether it's
+// powered, but can't tell its name or address or turn it on and off. That
+// comes from the tools of the OS:
+//
+// - Linux: `bluetoothctl`, which talks to BlueZ
+// - macOS: `system_profiler` to read, `blueutil` (from Homebrew) to turn
+//   it on and off
+// - Windows: nothing without the WinRT radio API
+//
+// Without the `bluetooth` feature there is no btleplug, so there are no
+// devices and only the tools of the OS report the adapters.
+
+#[cfg(feature = "bluetooth")]
+pub use btleplug::api::BDAddr;
+#[cfg(feature = "bluetooth")]
+use btleplug::api::{Central as _, CentralState, Manager as _, Peripheral as _, ScanFilter};
+
+use crate::{BluetoothInfo, Error};
+
+/// Stands in for btleplug's address without the `bluetooth` feature.
+/// Written like "3C:22:FB:00:00:00" as well
+#[cfg(not(feature = "bluetooth"))]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct BDAddr {
+    address: [u8; 6],
+}
+
+#[cfg(not(feature = "bluetooth"))]
+impl std::fmt::Display for BDAddr {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.address.map(|byte| format!("{byte:02X}")).join(":"))
+    }
+}
+
+#[cfg(not(feature = "bluetooth"))]
+impl std::str::FromStr for BDAddr {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let bytes = text
+            .split(':')
+            .map(|byte| u8::from_str_radix(byte, 16))
+            .collect::<Result<Vec<u8>, _>>()
+            .map_err(|error| error.to_string())?;
+        Ok(Self {
+            address: bytes.try_into().map_err(|_| format!("{text} doesn't have six bytes"))?,
+        })
+    }
+}
+
+#[cfg(all(feature = "serde", not(feature = "bluetooth")))]
+impl serde::Serialize for BDAddr {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(all(feature = "serde", not(feature = "bluetooth")))]
+impl<'de> serde::Deserialize<'de> for BDAddr {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BluetoothAdapterInfo {
+    /// How btleplug describes the adapter, e.g. "hci0
+    /// (usb:v1D6Bp0246d0540)"
+    pub id:           Option<String>,
+    pub name:         Option<String>,
+    pub address:      Option<BDAddr>,
+    pub powered:      Option<bool>,
+    pub discoverable: Option<bool>,
+}
+
+/// The adapters btleplug found, e.g. a built-in one and a USB dongle, and
+/// which of them is scanning
+#[cfg(feature = "bluetooth")]
+#[derive(Default)]
+pub struct Adapters {
+    adapters: Vec<btleplug::platform::Adapter>,
+    selected: usize,
+}
+
+#[cfg(feature = "bluetooth")]
+impl Adapters {
+    /// Scans with the first adapter
+    pub async fn new() -> Self {
+        let adapters = match btleplug::platform::Manager::new().await {
+            Ok(manager) => manager.adapters().await.unwrap_or_default(),
+            Err(_) => Vec::new(),
+        };
+        let new_self = Self { adapters, selected: 0 };
+        new_self.start_scan().await;
+        new_self
+    }
+
+    async fn start_scan(&self) {
+        if let Some(adapter) = self.adapters.get(self.selected) {
+            let _ = adapter.start_scan(ScanFilter::default()).await;
+        }
+    }
+
+    pub const fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Returns whether there is an adapter with that index
+    pub async fn select(&mut self, index: usize) -> bool {
+        if index >= self.adapters.len() {
+            return false;
+        }
+        if let Some(adapter) = self.adapters.get(self.selected) {
+            let _ = adapter.stop_scan().await;
+        }
+        self.selected = index;
+        self.start_scan().await;
+        true
+    }
+
+    /// Devices only show up on the adapter that is scanning, or if they
+    /// are connected or paired to it
+    pub async fn devices(&self, index: usize) -> Result<Vec<BluetoothInfo>, Error> {
+        let adapter = self.adapters.get(index).ok_or(Error::NotFound("Bluetooth adapter"))?;
+        if adapter.adapter_state().await? == CentralState::PoweredOff {
+            return Err(Error::BluetoothOff);
+        }
+        let mut devices = Vec::new();
+        for peripheral in adapter.peripherals().await? {
+            let properties = peripheral.properties().await.ok().flatten();
+            devices.push(BluetoothInfo {
+                id:                       peripheral.id().to_string(),
+                address:                  peripheral.address(),
+                local_name:               properties.as_ref().and_then(|props| props.local_name.clone()),
+                transmission_power_level: properties.as_ref().and_then(|props| props.tx_power_level),
+                signal_strength:          properties.as_ref().and_then(|props| props.rssi),
+                is_connected:             peripheral.is_connected().await.is_ok_and(|is_connected| is_connected),
+            });
+        }
+        Ok(devices)
+    }
+
+    /// In the same order as the adapters. Without btleplug, whatever the
+    /// tools of the OS report
+    pub async fn information(&self) -> Vec<BluetoothAdapterInfo> {
+        let mut details = adapter_details();
+        if self.adapters.is_empty() {
+            return details.into_iter().map(|(_, information)| information).collect();
+        }
+        let mut adapters = Vec::new();
+        for adapter in &self.adapters {
+            let id = adapter.adapter_info().await.ok();
+            // On Linux the id is e.g. "hci0 (usb:v1D6Bp0246d0540)", which is
+            // the only thing bluetoothctl also knows. Other platforms have a
+            // single controller to match
+            let matching = details
+                .iter()
+                .position(|(modalias, _)| modalias.as_ref().is_none_or(|modalias| id.as_ref().is_some_and(|id| id.contains(modalias.as_str()))));
+            let mut information = matching.map(|index| details.remove(index).1).unwrap_or_default();
+            information.id = id;
+            match adapter.adapter_state().await {
+                Ok(CentralState::PoweredOn) => information.powered = Some(true),
+                Ok(CentralState::PoweredOff) => information.powered = Some(false),
+                _ => {}
+            }
+            adapters.push(information);
+        }
+        adapters
+    }
+
+    /// Turns the selected adapter on or off
+    pub async fn set_power(&self, powered: bool) -> bool {
+        let address = self.information().await.into_iter().nth(self.selected).and_then(|information| information.address);
+        let success = set_power(address, powered);
+        // Scanning stops with the adapter
+        if success && powered {
+            self.start_scan().await;
+        }
+        success
+    }
+}
+
+/// Nothing to scan with, but the tools of the OS can still report the
+/// default controller and turn it on and off
+#[cfg(not(feature = "bluetooth"))]
+#[derive(Default)]
+pub struct Adapters {}
+
+#[cfg(not(feature = "bluetooth"))]
+#[allow(clippy::unused_async, clippy::unused_self, clippy::needless_pass_by_ref_mut)]
+impl Adapters {
+    pub async fn new() -> Self {
+        Self {}
+    }
+
+    pub const fn selected(&self) -> usize {
+        0
+    }
+
+    pub async fn select(&mut self, _index: usize) -> bool {
+        false
+    }
+
+    pub async fn devices(&self, _index: usize) -> Result<Vec<BluetoothInfo>, Error> {
+        Err(Error::Unsupported)
+    }
+
+    pub async fn information(&self) -> Vec<BluetoothAdapterInfo> {
+        adapter_details().into_iter().map(|(_, information)| information).collect()
+    }
+
+    pub async fn set_power(&self, powered: bool) -> bool {
+        set_power(None, powered)
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program).args(args).output().ok().filter(|output| output.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+// Every controller with the modalias that identifies it to btleplug,
+// which is only needed where there can be more than one
+#[cfg(target_os = "linux")]
+fn adapter_details() -> Vec<(Option<String>, BluetoothAdapterInfo)> {
+    // Controller 3C:22:FB:00:00:00 laptop [default]
+    run("bluetoothctl", &["list"])
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| parse_bluetoothctl(&run("bluetoothctl", &["show", line.strip_prefix("Controller ")?.split_whitespace().next()?])?))
+        .collect()
+}
+
+// Controller 3C:22:FB:00:00:00 (public)
+// 	Name: laptop
+// 	Alias: laptop
+// 	Powered: yes
+// 	Discoverable: no
+// 	Modalias: usb:v1D6Bp0246d0540
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_bluetoothctl(output: &str) -> Option<(Option<String>, BluetoothAdapterInfo)> {
+    let mut lines = output.lines();
+    let address = lines.next()?.strip_prefix("Controller ")?.split_whitespace().next()?.parse().ok();
+    let mut modalias = None;
+    let mut information = BluetoothAdapterInfo {
+        address,
+        ..BluetoothAdapterInfo::default()
+    };
+    for line in lines {
+        let Some((key, value)) = line.trim().split_once(": ") else {
+            continue;
+        };
+        match key {
+            // The alias is what other devices see, the name is the hostname
+            "Alias" => information.name = Some(value.to_string()),
+            "Name" if information.name.is_none() => information.name = Some(value.to_string()),
+            "Powered" => information.powered = Some(value == "yes"),
+            "Discoverable" => information.discoverable = Some(value == "yes"),
+            "Modalias" => modalias = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    Some((modalias, information))
+}
+
+#[cfg(target_os = "macos")]
+fn adapter_details() -> Vec<(Option<String>, BluetoothAdapterInfo)> {
+    run("system_profiler", &["SPBluetoothDataType"])
+        .and_then(|output| parse_system_profiler(&output))
+        .map(|information| (None, information))
+        .into_iter()
+        .collect()
+}
+
+// Macs only have one controller:
+//
+// Bluetooth:
+//
+//       Bluetooth Controller:
+//           Address: 3C:22:FB:00:00:00
+//           State: On
+//           Chipset: BCM_4387
+//           Discoverable: Off
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_system_profiler(output: &str) -> Option<BluetoothAdapterInfo> {
+    let mut lines = output.lines().skip_while(|line| line.trim() != "Bluetooth Controller:").skip(1).peekable();
+    // The controller ends where the less indented list of devices starts
+    let first_line = lines.peek()?;
+    let indentation = first_line.len() - first_line.trim_start().len();
+    let mut information = BluetoothAdapterInfo::default();
+    for line in lines.take_while(|line| line.len() - line.trim_start().len() >= indentation) {
+        match line.trim().split_once(": ") {
+            Some(("Address", address)) => information.address = address.parse().ok(),
+            Some(("State", state)) => information.powered = Some(state == "On"),
+            Some(("Discoverable", discoverable)) => information.discoverable = Some(discoverable == "On"),
+            Some(("Chipset", chipset)) => information.name = Some(chipset.to_string()),
+            _ => {}
+        }
+    }
+    Some(information)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+const fn adapter_details() -> Vec<(Option<String>, BluetoothAdapterInfo)> {
+    Vec::new()
+}
+
+/// Without an address the default controller
+#[cfg(target_os = "linux")]
+fn set_power(address: Option<BDAddr>, powered: bool) -> bool {
+    use std::io::Write as _;
+
+    // Selecting a controller only lasts for the session, so the commands
+    // are piped into a single one
+    let commands = [
+        address.map(|address| format!("select {address}")),
+        Some(format!("power {}", if powered { "on" } else { "off" })),
+        Some("quit".to_string()),
+    ];
+    let Ok(mut child) = std::process::Command::new("bluetoothctl")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .spawn()
+    else {
+        return false;
+    };
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(commands.into_iter().flatten().map(|command| command + "\n").collect::<String>().as_bytes());
+    }
+    // bluetoothctl exits successfully even if it failed
+    child.wait_with_output().is_ok_and(|output| String::from_utf8_lossy(&output.stdout).contains("succeeded"))
+}
+
+#[cfg(target_os = "macos")]
+fn set_power(_address: Option<BDAddr>, powered: bool) -> bool {
+    run("blueutil", &["--power", if powered { "1" } else { "0" }]).is_some()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+const fn set_power(_address: Option<BDAddr>, _powered: bool) -> bool {
+    false
+}
+
+#[test]
+fn parse_adapter_details() {
+    let linux = parse_bluetoothctl(
+        "Controller 3C:22:FB:00:00:01 (public)
+\tName: laptop
+\tAlias: Living room
+\tClass: 0x007c010c
+\tPowered: yes
+\tDiscoverable: no
+\tModalias: usb:v1D6Bp0246d0540
+",
+    );
+    assert_eq!(
+        linux,
+        Some((Some("usb:v1D6Bp0246d0540".to_string()), BluetoothAdapterInfo {
+            id:           None,
+            name:         Some("Living room".to_string()),
+            address:      "3C:22:FB:00:00:01".parse().ok(),
+            powered:      Some(true),
+            discoverable: Some(false),
+        }))
+    );
+
+    let macos = parse_system_profiler(
+        "Bluetooth:
+
+      Bluetooth Controller:
+          Address: 3C:22:FB:00:00:02
+          State: Off
+          Chipset: BCM_4387
+          Discoverable: Off
+      Not Connected:
+          AirPods:
+              Address: 3C:22:FB:00:00:03
+",
+    )
+    .unwrap_or_default();
+    assert_eq!(macos.address, "3C:22:FB:00:00:02".parse().ok());
+    assert_eq!((macos.powered, macos.discoverable), (Some(false), Some(false)));
+}
@@ -0,0 +1,290 @@
+// Boot history comes from the systemd journal, which keeps one entry per
+// boot with the time of its first and last log message. A boot ended
+// cleanly if journald got to log its own shutdown; after a crash or power
+// loss the log just stops.
+//
+// Windows logs the same to its System event log: 6005 when the event log
+// service starts at boot, 6006 when it stops at a clean shutdown and 6008
+// during the next boot if the previous one just ended. macOS only has
+// `last reboot`, whose wtmp times come without a year, so it isn't
+// supported for now.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone)]
+pub struct BootRecord {
+    /// The journal's boot ID on Linux, the boot time in seconds since the
+    /// epoch on Windows
+    pub boot_id:        String,
+    pub boot_time:      SystemTime,
+    /// Time of the last message logged during this boot. `None` for the
+    /// current boot, and on Windows for boots that didn't shut down cleanly
+    pub shutdown_time:  Option<SystemTime>,
+    /// `None` for the current boot or if it couldn't be determined
+    pub clean_shutdown: Option<bool>,
+}
+
+#[cfg(target_os = "linux")]
+pub fn boot_history(count: usize) -> Option<Vec<BootRecord>> {
+    // How a boot ended never changes once it's over, so every boot's
+    // journal is only read once
+    static CLEAN_SHUTDOWNS: std::sync::Mutex<std::collections::BTreeMap<String, Option<bool>>> = std::sync::Mutex::new(std::collections::BTreeMap::new());
+
+    let output = std::process::Command::new("journalctl")
+        .args(["--list-boots", "--output=json", "--no-pager"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let mut boots = parse_list_boots(&String::from_utf8_lossy(&output.stdout));
+    boots.truncate(count);
+    let mut clean_shutdowns = CLEAN_SHUTDOWNS.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    for (index, boot) in boots.iter_mut().enumerate() {
+        // The newest boot is the one we're running in
+        if index == 0 {
+            boot.shutdown_time = None;
+            continue;
+        }
+        boot.clean_shutdown = *clean_shutdowns.entry(boot.boot_id.clone()).or_insert_with(|| {
+            std::process::Command::new("journalctl")
+                .args(["--boot", &boot.boot_id, "--lines=50", "--output=cat", "--no-pager", "--quiet"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .map(|output| {
+                    String::from_utf8_lossy(&output.stdout)
+                        .lines()
+                        .any(|line| line.contains("Journal stopped") || line.contains("systemd-shutdown"))
+                })
+        });
+    }
+    drop(clean_shutdowns);
+    Some(boots)
+}
+
+#[cfg(windows)]
+pub fn boot_history(count: usize) -> Option<Vec<BootRecord>> {
+    // Every boot has at most three of these events
+    let output = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            &format!(
+                "Get-WinEvent -FilterHashtable @{{LogName='System'; Id=6005,6006,6008}} -MaxEvents {} | Select-Object Id, @{{Name='Time'; Expression={{([DateTimeOffset]$_.TimeCreated).ToUnixTimeSeconds()}}}} | ConvertTo-Csv -NoTypeInformation",
+                count.saturating_mul(3)
+            ),
+        ])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    // The first row is the header
+    let rows = crate::performance_counters::parse_csv(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .skip(1)
+        .collect::<Vec<Vec<String>>>();
+    let mut boots = parse_boot_events(&rows);
+    boots.truncate(count);
+    if let Some(boot) = boots.first_mut() {
+        boot.shutdown_time = None;
+        boot.clean_shutdown = None;
+    }
+    Some(boots)
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub const fn boot_history(_count: usize) -> Option<Vec<BootRecord>> {
+    None
+}
+
+// The output is a JSON array with one object per boot, oldest first:
+//
+// [{"index":-1,"boot_id":"8b1f...","first_entry":1696838400000000,...}]
+//
+// Timestamps are in microseconds
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_list_boots(json: &str) -> Vec<BootRecord> {
+    let Ok(serde_json::Value::Array(boots)) = serde_json::from_str(json) else {
+        return Vec::new();
+    };
+    let mut boots = boots
+        .iter()
+        .filter_map(|boot| {
+            let timestamp = |name| boot.get(name)?.as_u64().map(|micros| UNIX_EPOCH + Duration::from_micros(micros));
+            Some(BootRecord {
+                boot_id:        boot.get("boot_id")?.as_str()?.to_string(),
+                boot_time:      timestamp("first_entry")?,
+                shutdown_time:  timestamp("last_entry"),
+                clean_shutdown: None,
+            })
+        })
+        .collect::<Vec<BootRecord>>();
+    boots.reverse();
+    boots
+}
+
+// Event ID and time in seconds since the epoch, newest first, e.g.
+// "6005","1696838400". A 6008 is logged right after the 6005 of the boot
+// following the one that crashed
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_boot_events(rows: &[Vec<String>]) -> Vec<BootRecord> {
+    let mut boots: Vec<BootRecord> = Vec::new();
+    for row in rows.iter().rev() {
+        let [id, time] = row.as_slice() else {
+            continue;
+        };
+        let Ok(seconds) = time.parse::<u64>() else {
+            continue;
+        };
+        let time = UNIX_EPOCH + Duration::from_secs(seconds);
+        match id.as_str() {
+            "6005" => boots.push(BootRecord {
+                boot_id:        seconds.to_string(),
+                boot_time:      time,
+                shutdown_time:  None,
+                clean_shutdown: None,
+            }),
+            "6006" => {
+                if let Some(boot) = boots.last_mut() {
+                    boot.shutdown_time = Some(time);
+                    boot.clean_shutdown = Some(true);
+                }
+            }
+            "6008" => {
+                if let [.., boot, _] = boots.as_mut_slice()
+                    && boot.clean_shutdown.is_none()
+                {
+                    boot.clean_shutdown = Some(false);
+                }
+            }
+            _ => (),
+        }
+    }
+    boots.reverse();
+    boots
+}
+
+#[derive(Debug, Clone)]
+pub struct BootTimes {
+    pub firmware:      Option<Duration>,
+    pub loader:        Option<Duration>,
+    pub kernel:        Option<Duration>,
+    pub initrd:        Option<Duration>,
+    pub userspace:     Option<Duration>,
+    pub total:         Duration,
+    /// Units that took the longest to start, slowest first
+    pub slowest_units: Vec<(String, Duration)>,
+}
+
+#[cfg(target_os = "linux")]
+pub fn boot_times(unit_count: usize) -> Option<BootTimes> {
+    let time = std::process::Command::new("systemd-analyze").arg("time").output().ok().filter(|output| output.status.success())?;
+    let mut boot_times = parse_analyze_time(&String::from_utf8_lossy(&time.stdout))?;
+    if let Ok(blame) = std::process::Command::new("systemd-analyze").args(["blame", "--no-pager"]).output() {
+        boot_times.slowest_units = String::from_utf8_lossy(&blame.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (duration, unit) = line.trim().rsplit_once(' ')?;
+                Some((unit.to_string(), parse_systemd_duration(duration)?))
+            })
+            .take(unit_count)
+            .collect();
+    }
+    Some(boot_times)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn boot_times(_unit_count: usize) -> Option<BootTimes> {
+    None
+}
+
+// Parses "Startup finished in 4.1s (firmware) + 2.3s (loader) + 1.2s
+// (kernel) + 10.5s (userspace) = 18.1s". Which parts are present depends
+// on the machine, e.g. firmware and loader times are only known on EFI
+// systems.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_analyze_time(output: &str) -> Option<BootTimes> {
+    let line = output.lines().next()?.strip_prefix("Startup finished in ")?;
+    let (parts, total) = line.rsplit_once(" = ")?;
+    let mut boot_times = BootTimes {
+        firmware:      None,
+        loader:        None,
+        kernel:        None,
+        initrd:        None,
+        userspace:     None,
+        total:         parse_systemd_duration(total)?,
+        slowest_units: Vec::new(),
+    };
+    for part in parts.split(" + ") {
+        let (duration, phase) = part.trim().rsplit_once(" (")?;
+        let duration = parse_systemd_duration(duration);
+        match phase.trim_end_matches(')') {
+            "firmware" => boot_times.firmware = duration,
+            "loader" => boot_times.loader = duration,
+            "kernel" => boot_times.kernel = duration,
+            "initrd" => boot_times.initrd = duration,
+            "userspace" => boot_times.userspace = duration,
+            _ => (),
+        }
+    }
+    Some(boot_times)
+}
+
+// systemd formats durations like "1min 2.345s", "345ms" or "1h 2min"
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_systemd_duration(duration: &str) -> Option<Duration> {
+    duration.split_whitespace().try_fold(Duration::ZERO, |total, part| {
+        let unit_start = part.find(|chr: char| chr.is_ascii_alphabetic())?;
+        let (value, unit) = part.split_at(unit_start);
+        let value = value.parse::<f64>().ok()?;
+        let seconds = match unit {
+            "h" => value * 3600.0,
+            "min" => value * 60.0,
+            "s" => value,
+            "ms" => value / 1000.0,
+            "us" | "µs" => value / 1_000_000.0,
+            _ => return None,
+        };
+        Some(total + Duration::try_from_secs_f64(seconds).ok()?)
+    })
+}
+
+#[test]
+fn parse_startup_finished() {
+    let boot_times = parse_analyze_time(
+        "Startup finished in 6.520s (firmware) + 1.862s (loader) + 2.211s (kernel) + 1min 9.147s (userspace) = 1min 19.742s\ngraphical.target reached after 1min 9.100s in userspace.\n",
+    )
+    .expect("The output should be parsed");
+    assert_eq!(boot_times.firmware, Some(Duration::from_millis(6520)));
+    assert_eq!(boot_times.kernel, Some(Duration::from_millis(2211)));
+    assert_eq!(boot_times.initrd, None);
+    assert_eq!(boot_times.userspace, Some(Duration::from_millis(69147)));
+    assert_eq!(boot_times.total, Duration::from_millis(79742));
+}
+
+#[test]
+fn parse_boot_history() {
+    let boots = parse_list_boots(
+        r#"[{"index":-1,"boot_id":"8b1f0e3c7d2a4b5e9f6a1c2d3e4f5a6b","first_entry":1696838400000000,"last_entry":1696867200000000},{"index":0,"boot_id":"2c4e6a8b0d1f3a5c7e9b1d3f5a7c9e1b","first_entry":1696924800000000,"last_entry":1696928400000000}]"#,
+    );
+    assert_eq!(boots.len(), 2);
+    assert_eq!(boots[0].boot_id, "2c4e6a8b0d1f3a5c7e9b1d3f5a7c9e1b");
+    assert_eq!(boots[1].boot_time, UNIX_EPOCH + Duration::from_secs(1_696_838_400));
+    assert_eq!(boots[1].shutdown_time, Some(UNIX_EPOCH + Duration::from_secs(1_696_867_200)));
+    assert!(parse_list_boots("No journal files were found.").is_empty());
+
+    // Newest first: a clean shutdown, then a crash noticed at the next boot
+    let row = |id: &str, time: &str| vec![id.to_string(), time.to_string()];
+    let boots = parse_boot_events(&[
+        row("6008", "1696924830"),
+        row("6005", "1696924800"),
+        row("6005", "1696838400"),
+        row("6006", "1696752000"),
+        row("6005", "1696665600"),
+    ]);
+    assert_eq!(boots.len(), 3);
+    assert_eq!(boots[0].boot_id, "1696924800");
+    assert_eq!(boots[1].clean_shutdown, Some(false));
+    assert_eq!(boots[1].shutdown_time, None);
+    assert_eq!(boots[2].clean_shutdown, Some(true));
+    assert_eq!(boots[2].shutdown_time, Some(UNIX_EPOCH + Duration::from_secs(1_696_752_000)));
+}
@@ -0,0 +1,153 @@
+// What crossinfo knows about, as a list frontends go through instead of
+// each hardcoding its own. Every category has an id that stays the same
+// across versions, a name to show, whether this machine has it and, where
+// it comes down to a single reading, how to read it. Frontends decide
+// themselves how to show a category and skip the ones they can't.
+
+use crate::{Manager, Update};
+
+#[derive(Debug, Clone, Copy)]
+pub struct InfoCategory {
+    /// Stays the same across versions, for config files and URLs
+    pub id:          &'static str,
+    pub name:        &'static str,
+    pub description: &'static str,
+    available:       fn(&Manager) -> bool,
+    read:            Option<fn(&mut Manager) -> Option<Update>>,
+}
+
+impl InfoCategory {
+    #[must_use]
+    pub fn is_available(&self, manager: &Manager) -> bool {
+        (self.available)(manager)
+    }
+
+    /// None for categories made of several readings, like the overview,
+    /// or of actions, like the benchmark
+    pub fn read(&self, manager: &mut Manager) -> Option<Update> {
+        self.read.and_then(|read| read(manager))
+    }
+}
+
+const fn always(_manager: &Manager) -> bool {
+    true
+}
+
+const fn sysinfo(_manager: &Manager) -> bool {
+    crate::SYSINFO_SUPPORT
+}
+
+fn batteries(_manager: &Manager) -> bool {
+    crate::BATTERY_SUPPORT.load(std::sync::atomic::Ordering::Relaxed)
+}
+
+const fn audio(_manager: &Manager) -> bool {
+    cfg!(any(target_os = "linux", target_os = "macos"))
+}
+
+const fn displays(_manager: &Manager) -> bool {
+    cfg!(feature = "display")
+}
+
+const fn bluetooth(_manager: &Manager) -> bool {
+    cfg!(feature = "bluetooth")
+}
+
+pub const CATEGORIES: &[InfoCategory] = &[
+    InfoCategory {
+        id:          "overview",
+        name:        "Overview",
+        description: "Total CPU and RAM usage, the processes using the most of them, the fullest disk, the busiest network interface, the hottest component and the battery at a glance",
+        available:   always,
+        read:        None,
+    },
+    InfoCategory {
+        id:          "system",
+        name:        "System",
+        description: "Operating system, kernel, users, uptime and load",
+        available:   sysinfo,
+        read:        Some(|manager| manager.system_information().map(Update::System)),
+    },
+    InfoCategory {
+        id:          "cpu",
+        name:        "CPU",
+        description: "Usage, model, manufacturer and frequency of every core",
+        available:   sysinfo,
+        read:        Some(|manager| manager.cpu_information().map(Update::Cpu)),
+    },
+    InfoCategory {
+        id:          "memory",
+        name:        "Memory/SWAP",
+        description: "How much RAM and SWAP is used and what for",
+        available:   sysinfo,
+        read:        Some(|manager| manager.memory_information().map(Update::Memory)),
+    },
+    InfoCategory {
+        id:          "disks",
+        name:        "Disks",
+        description: "Size, usage and activity of every mounted disk, the drives they are on and a speed benchmark",
+        available:   sysinfo,
+        read:        Some(|manager| manager.disk_information().map(Update::Disks)),
+    },
+    InfoCategory {
+        id:          "battery",
+        name:        "Battery",
+        description: "Charge, capacity, cycles, state and health of the installed batteries",
+        available:   batteries,
+        read:        Some(|manager| manager.battery_information().map(Update::Batteries)),
+    },
+    InfoCategory {
+        id:          "network",
+        name:        "Networks",
+        description: "Network usage, interfaces, available Wi-Fis and the speedtest",
+        available:   always,
+        read:        Some(|manager| Some(Update::Network(manager.network_information()))),
+    },
+    InfoCategory {
+        id:          "processes",
+        name:        "Processes",
+        description: "CPU, memory and SWAP usage of every process, killing them and details like the PID and executable",
+        available:   sysinfo,
+        read:        Some(|manager| manager.process_information().map(Update::ProcessList)),
+    },
+    InfoCategory {
+        id:          "components",
+        name:        "Components",
+        description: "Temperatures of the components, sometimes with their critical temperature",
+        available:   sysinfo,
+        read:        Some(|manager| manager.component_information().map(Update::Components)),
+    },
+    InfoCategory {
+        id:          "audio",
+        name:        "Audio",
+        description: "Input and output devices with their volume, applications playing or recording audio",
+        available:   audio,
+        read:        None,
+    },
+    InfoCategory {
+        id:          "benchmark",
+        name:        "Benchmark",
+        description: "Single and multi threaded CPU score, memory bandwidth",
+        available:   always,
+        read:        None,
+    },
+    InfoCategory {
+        id:          "displays",
+        name:        "Display",
+        description: "ID, display resolution, rotation and scale factor",
+        available:   displays,
+        read:        None,
+    },
+    InfoCategory {
+        id:          "bluetooth",
+        name:        "Bluetooth",
+        description: "Nearby devices with their address, name, signal strength and connection status",
+        available:   bluetooth,
+        read:        None,
+    },
+];
+
+#[must_use]
+pub fn category(id: &str) -> Option<&'static InfoCategory> {
+    CATEGORIES.iter().find(|category| category.id == id)
+}
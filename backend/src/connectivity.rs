@@ -0,0 +1,233 @@
+// Whether the internet is reachable. The HTTPS request is the most
+// reliable check, but some networks only let traffic through a proxy and
+// others block google.com entirely, so resolving a name or opening a TCP
+// connection can be used instead. Neither needs ICMP, which is blocked
+// even more often.
+//
+// When the check fails, the diagnosis narrows down why: whether an
+// interface is up at all, whether the host of the check resolves and,
+// with the HTTPS check, whether a captive portal answers in place of the
+// internet. Portals are found the way Android does it, a plain HTTP
+// request to Google that has to come back empty with status 204. A portal
+// answers with its login page or a redirect to it. The DNS and TCP checks
+// are there to avoid such requests, so they skip it.
+
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+use crate::Network;
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+const PORTAL_HOST: &str = "connectivitycheck.gstatic.com";
+// Cloudflare's DNS server, which also answers HTTPS
+const IPV6_TARGET: &str = "[2606:4700:4700::1111]:443";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConnectivityCheck {
+    /// A GET request to the URL, which needs the `network-extra` feature
+    Https(String),
+    /// Resolving the host name. A local caching resolver can answer this
+    /// without the internet for a while
+    Dns(String),
+    /// Connecting to the host and port, e.g. a DNS server on 53 or a web
+    /// server on 443
+    Tcp(String),
+}
+
+impl Default for ConnectivityCheck {
+    fn default() -> Self {
+        Self::Https("https://google.com".to_string())
+    }
+}
+
+/// "https", "dns" or "tcp", optionally followed by what to reach, e.g.
+/// "tcp 1.1.1.1:443"
+impl std::str::FromStr for ConnectivityCheck {
+    type Err = String;
+
+    fn from_str(check: &str) -> Result<Self, Self::Err> {
+        let check = check.trim();
+        let (method, target) = check
+            .split_once(char::is_whitespace)
+            .map_or((check, None), |(method, target)| (method, Some(target.trim().to_string())));
+        match method.to_lowercase().as_str() {
+            "https" => Ok(target.map_or_else(Self::default, Self::Https)),
+            "dns" => Ok(Self::Dns(target.unwrap_or_else(|| "google.com".to_string()))),
+            "tcp" => Ok(Self::Tcp(target.unwrap_or_else(|| "1.1.1.1:443".to_string()))),
+            _ => Err(format!("Unknown connectivity check {method}, use https, dns or tcp")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ConnectivityState {
+    /// Nothing was checked, e.g. by the `network_information` of
+    /// `AsyncManager::blocking`
+    #[default]
+    Unknown,
+    /// No interface besides loopback is up with an address
+    NoLink,
+    /// Connected to a network, but host names can't be resolved
+    NoDns,
+    /// Host names resolve, but nothing on the internet answers
+    NoInternet,
+    /// A login page answers in place of the internet, common in hotels,
+    /// trains and airports
+    CaptivePortal,
+    Connected,
+}
+
+impl ConnectivityState {
+    #[must_use]
+    pub const fn is_connected(self) -> bool {
+        matches!(self, Self::Connected)
+    }
+}
+
+/// An explanation for the user
+impl std::fmt::Display for ConnectivityState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Unknown => "Not checked",
+            Self::NoLink => "Not connected to a network",
+            Self::NoDns => "Connected to a network, but host names can't be resolved",
+            Self::NoInternet => "Connected to a network, but the internet can't be reached",
+            Self::CaptivePortal => "Behind a captive portal, open a browser to log in",
+            Self::Connected => "Connected to the internet",
+        })
+    }
+}
+
+/// Runs the configured check and, if it fails or a captive portal
+/// intercepts it, finds out why. `networks` are the interfaces of
+/// `NetworkInfo`
+pub async fn connectivity_state(check: &ConnectivityCheck, networks: Option<&[Network]>) -> ConnectivityState {
+    if !has_link(networks) {
+        return ConnectivityState::NoLink;
+    }
+    let portal = async {
+        match check {
+            ConnectivityCheck::Https(_) => behind_captive_portal().await,
+            ConnectivityCheck::Dns(_) | ConnectivityCheck::Tcp(_) => None,
+        }
+    };
+    // Alongside the check, so a failing one doesn't take twice as long. The
+    // DNS check already is this
+    let host_resolves = async {
+        match check {
+            ConnectivityCheck::Https(url) => resolves(url_host(url)).await,
+            ConnectivityCheck::Tcp(address) => resolves(address_host(address)).await,
+            ConnectivityCheck::Dns(_) => false,
+        }
+    };
+    let (connected, portal, host_resolves) = tokio::join!(is_connected(check), portal, host_resolves);
+    match portal {
+        Some(true) => ConnectivityState::CaptivePortal,
+        // The probe got through even if the configured target is blocked
+        Some(false) => ConnectivityState::Connected,
+        None if connected => ConnectivityState::Connected,
+        None if host_resolves => ConnectivityState::NoInternet,
+        None => ConnectivityState::NoDns,
+    }
+}
+
+// https://example.com:8443/path
+fn url_host(url: &str) -> &str {
+    let authority = url.split_once("://").map_or(url, |(_, rest)| rest);
+    let authority = authority.split(['/', '?', '#']).next().unwrap_or(authority);
+    address_host(authority.rsplit_once('@').map_or(authority, |(_, host)| host))
+}
+
+// 1.1.1.1:443, example.com or [2606:4700:4700::1111]:443. Addresses
+// resolve to themselves
+fn address_host(address: &str) -> &str {
+    let host = if address.ends_with(']') {
+        address
+    } else {
+        address.rsplit_once(':').map_or(address, |(host, _)| host)
+    };
+    host.trim_matches(['[', ']'])
+}
+
+/// Without the `network-extra` feature interfaces have no flags, then any
+/// interface counts
+fn has_link(networks: Option<&[Network]>) -> bool {
+    let Some(networks) = networks else {
+        return true;
+    };
+    if networks.iter().all(|network| network.flags.is_none()) {
+        return !networks.is_empty();
+    }
+    networks
+        .iter()
+        .any(|network| network.flags.is_some_and(|flags| flags.is_up && !flags.is_loopback) && network.ips.as_ref().is_some_and(|ips| ips.iter().any(|ip| !ip.is_loopback())))
+}
+
+async fn resolves(host: &str) -> bool {
+    // lookup_host wants a port, which doesn't matter for resolving
+    let lookup = tokio::net::lookup_host((host, 443));
+    tokio::time::timeout(TIMEOUT, lookup)
+        .await
+        .is_ok_and(|addresses| addresses.is_ok_and(|mut addresses| addresses.next().is_some()))
+}
+
+/// `None` if the probe got no answer at all
+async fn behind_captive_portal() -> Option<bool> {
+    let probe = async {
+        let mut stream = tokio::net::TcpStream::connect((PORTAL_HOST, 80)).await.ok()?;
+        let request = format!("GET /generate_204 HTTP/1.1\r\nHost: {PORTAL_HOST}\r\nConnection: close\r\n\r\n");
+        stream.write_all(request.as_bytes()).await.ok()?;
+        // The status line is all that's needed
+        let mut response = vec![0; 64];
+        let read = stream.read(&mut response).await.ok()?;
+        parse_status(&String::from_utf8_lossy(&response[..read]))
+    };
+    let status = tokio::time::timeout(TIMEOUT, probe).await.ok()??;
+    Some(status != 204)
+}
+
+// HTTP/1.1 204 No Content
+fn parse_status(response: &str) -> Option<u16> {
+    let mut fields = response.split_whitespace();
+    fields.next().filter(|version| version.starts_with("HTTP/"))?;
+    fields.next()?.parse().ok()
+}
+
+pub async fn is_connected(check: &ConnectivityCheck) -> bool {
+    let connected = async {
+        match check {
+            ConnectivityCheck::Https(url) => crate::network_extra::is_reachable(url).await,
+            ConnectivityCheck::Dns(host) => resolves(host).await,
+            ConnectivityCheck::Tcp(address) => tokio::net::TcpStream::connect(address.as_str()).await.is_ok(),
+        }
+    };
+    tokio::time::timeout(TIMEOUT, connected).await.unwrap_or_default()
+}
+
+/// Connecting over IPv6 only, since a dual stack network can lose one
+/// protocol and keep working over the other
+pub async fn is_connected_v6() -> bool {
+    tokio::time::timeout(TIMEOUT, tokio::net::TcpStream::connect(IPV6_TARGET)).await.is_ok_and(|stream| stream.is_ok())
+}
+
+#[test]
+fn parse_connectivity_checks() {
+    assert_eq!("https".parse(), Ok(ConnectivityCheck::default()));
+    assert_eq!("DNS example.com".parse(), Ok(ConnectivityCheck::Dns("example.com".to_string())));
+    assert_eq!("tcp  9.9.9.9:53 ".parse(), Ok(ConnectivityCheck::Tcp("9.9.9.9:53".to_string())));
+    assert!("ping".parse::<ConnectivityCheck>().is_err());
+
+    assert_eq!(parse_status("HTTP/1.1 204 No Content\r\nContent-Length: 0\r\n"), Some(204));
+    assert_eq!(parse_status("HTTP/1.0 302 Found\r\nLocation: http://login.example.com/\r\n"), Some(302));
+    assert_eq!(parse_status("SSH-2.0-OpenSSH_9.6\r\n"), None);
+    assert_eq!(url_host("https://user@example.com:8443/path?query"), "example.com");
+    assert_eq!(url_host("https://google.com"), "google.com");
+    assert_eq!(address_host("1.1.1.1:443"), "1.1.1.1");
+    assert_eq!(address_host("[2606:4700:4700::1111]:443"), "2606:4700:4700::1111");
+    assert_eq!(address_host("[::1]"), "::1");
+    assert!(!has_link(Some(&[])));
+    assert!(has_link(None));
+}
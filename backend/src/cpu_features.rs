@@ -0,0 +1,247 @@
+// What the CPU can do beyond its clock speed:
+//
+// - The instruction set extensions are detected by std at runtime, the
+//   same way programs pick their fastest code path, so they are right on
+//   every platform
+// - Virtualization extensions: the vmx/svm flags in /proc/cpuinfo on
+//   Linux, kern.hv_support on macOS and WMI on Windows. Firmware can turn
+//   them off, in which case they aren't reported
+// - Caches: /sys/devices/system/cpu/cpu0/cache on Linux, sysctl on macOS
+//   and WMI on Windows, which only knows the L2 and L3 sizes
+//
+// None of this changes while the machine runs.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum CacheKind {
+    Data,
+    Instruction,
+    /// Holds data and instructions, like practically every L2 and L3
+    Unified,
+}
+
+impl std::fmt::Display for CacheKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Data => "data",
+            Self::Instruction => "instruction",
+            Self::Unified => "unified",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CacheInfo {
+    pub level: u8,
+    pub kind:  CacheKind,
+    /// In bytes, for one instance of the cache. L1 and L2 are usually per
+    /// core, L3 shared by all of them
+    pub size:  u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuFeatures {
+    /// Like "AVX2" or "NEON", in the order they were added to the
+    /// architecture
+    pub instruction_sets: Vec<String>,
+    /// "VT-x", "AMD-V" or "Hypervisor.framework" on macOS, `None` if there
+    /// are none or they are disabled
+    pub virtualization:   Option<String>,
+    /// Ordered by level
+    pub caches:           Vec<CacheInfo>,
+}
+
+/// Only detected on the first call, as it runs commands outside of Linux
+pub fn cpu_features() -> CpuFeatures {
+    static FEATURES: std::sync::OnceLock<CpuFeatures> = std::sync::OnceLock::new();
+    FEATURES
+        .get_or_init(|| CpuFeatures {
+            instruction_sets: instruction_sets(),
+            virtualization:   virtualization(),
+            caches:           caches(),
+        })
+        .clone()
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn instruction_sets() -> Vec<String> {
+    [
+        ("SSE", std::arch::is_x86_feature_detected!("sse")),
+        ("SSE2", std::arch::is_x86_feature_detected!("sse2")),
+        ("SSE3", std::arch::is_x86_feature_detected!("sse3")),
+        ("SSSE3", std::arch::is_x86_feature_detected!("ssse3")),
+        ("SSE4.1", std::arch::is_x86_feature_detected!("sse4.1")),
+        ("SSE4.2", std::arch::is_x86_feature_detected!("sse4.2")),
+        ("AES-NI", std::arch::is_x86_feature_detected!("aes")),
+        ("SHA", std::arch::is_x86_feature_detected!("sha")),
+        ("AVX", std::arch::is_x86_feature_detected!("avx")),
+        ("FMA", std::arch::is_x86_feature_detected!("fma")),
+        ("AVX2", std::arch::is_x86_feature_detected!("avx2")),
+        ("AVX-512F", std::arch::is_x86_feature_detected!("avx512f")),
+        ("AVX-512BW", std::arch::is_x86_feature_detected!("avx512bw")),
+        ("AVX-512VL", std::arch::is_x86_feature_detected!("avx512vl")),
+    ]
+    .into_iter()
+    .filter(|(_, detected)| *detected)
+    .map(|(name, _)| name.to_string())
+    .collect()
+}
+
+#[cfg(target_arch = "aarch64")]
+fn instruction_sets() -> Vec<String> {
+    [
+        ("NEON", std::arch::is_aarch64_feature_detected!("neon")),
+        ("AES", std::arch::is_aarch64_feature_detected!("aes")),
+        ("SHA2", std::arch::is_aarch64_feature_detected!("sha2")),
+        ("CRC32", std::arch::is_aarch64_feature_detected!("crc")),
+        ("Dot Product", std::arch::is_aarch64_feature_detected!("dotprod")),
+        ("SVE", std::arch::is_aarch64_feature_detected!("sve")),
+        ("SVE2", std::arch::is_aarch64_feature_detected!("sve2")),
+    ]
+    .into_iter()
+    .filter(|(_, detected)| *detected)
+    .map(|(name, _)| name.to_string())
+    .collect()
+}
+
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+const fn instruction_sets() -> Vec<String> {
+    Vec::new()
+}
+
+#[cfg(target_os = "linux")]
+fn virtualization() -> Option<String> {
+    parse_virtualization_flags(&std::fs::read_to_string("/proc/cpuinfo").ok()?)
+}
+
+#[cfg(target_os = "macos")]
+fn virtualization() -> Option<String> {
+    (sysctl("kern.hv_support")? == 1).then(|| "Hypervisor.framework".to_string())
+}
+
+#[cfg(windows)]
+fn virtualization() -> Option<String> {
+    let enabled = powershell("(Get-CimInstance Win32_Processor | Select-Object -First 1).VirtualizationFirmwareEnabled")?;
+    let manufacturer = powershell("(Get-CimInstance Win32_Processor | Select-Object -First 1).Manufacturer")?;
+    (enabled == "True").then(|| if manufacturer.contains("AMD") { "AMD-V" } else { "VT-x" }.to_string())
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+const fn virtualization() -> Option<String> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+fn caches() -> Vec<CacheInfo> {
+    let Ok(entries) = std::fs::read_dir("/sys/devices/system/cpu/cpu0/cache") else {
+        return Vec::new();
+    };
+    let mut caches = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with("index"))
+        .filter_map(|entry| {
+            let read = |file: &str| std::fs::read_to_string(entry.path().join(file)).ok().map(|contents| contents.trim().to_string());
+            parse_cache(&read("level")?, &read("type")?, &read("size")?)
+        })
+        .collect::<Vec<CacheInfo>>();
+    caches.sort_by_key(|cache| (cache.level, cache.kind != CacheKind::Data));
+    caches
+}
+
+#[cfg(target_os = "macos")]
+fn caches() -> Vec<CacheInfo> {
+    [
+        (1, CacheKind::Data, "hw.l1dcachesize"),
+        (1, CacheKind::Instruction, "hw.l1icachesize"),
+        (2, CacheKind::Unified, "hw.l2cachesize"),
+        (3, CacheKind::Unified, "hw.l3cachesize"),
+    ]
+    .into_iter()
+    .filter_map(|(level, kind, name)| Some(CacheInfo { level, kind, size: sysctl(name).filter(|size| *size > 0)? }))
+    .collect()
+}
+
+#[cfg(windows)]
+fn caches() -> Vec<CacheInfo> {
+    // In KiB
+    [(2, "L2CacheSize"), (3, "L3CacheSize")]
+        .into_iter()
+        .filter_map(|(level, property)| {
+            let size = powershell(&format!("(Get-CimInstance Win32_Processor | Select-Object -First 1).{property}"))?.parse::<u64>().ok().filter(|size| *size > 0)?;
+            Some(CacheInfo {
+                level,
+                kind: CacheKind::Unified,
+                size: size * 1024,
+            })
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+const fn caches() -> Vec<CacheInfo> {
+    Vec::new()
+}
+
+#[cfg(target_os = "macos")]
+fn sysctl(name: &str) -> Option<u64> {
+    let output = std::process::Command::new("sysctl").args(["-n", name]).output().ok().filter(|output| output.status.success())?;
+    String::from_utf8_lossy(&output.stdout).trim().parse().ok()
+}
+
+#[cfg(windows)]
+fn powershell(command: &str) -> Option<String> {
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", command])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+// Every core has a "flags : fpu vme ... vmx ..." line, the first one is
+// enough
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_virtualization_flags(cpuinfo: &str) -> Option<String> {
+    let (_, flags) = cpuinfo.lines().find(|line| line.starts_with("flags"))?.split_once(':')?;
+    flags.split_whitespace().find_map(|flag| match flag {
+        "vmx" => Some("VT-x".to_string()),
+        "svm" => Some("AMD-V".to_string()),
+        _ => None,
+    })
+}
+
+// The level is a number, the type "Data", "Instruction" or "Unified" and
+// the size something like "48K" or "32768K"
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cache(level: &str, kind: &str, size: &str) -> Option<CacheInfo> {
+    let (number, multiplier) = match size.chars().last()? {
+        'K' => (&size[..size.len() - 1], 1024),
+        'M' => (&size[..size.len() - 1], 1024 * 1024),
+        _ => (size, 1),
+    };
+    Some(CacheInfo {
+        level: level.parse().ok()?,
+        kind:  match kind {
+            "Data" => CacheKind::Data,
+            "Instruction" => CacheKind::Instruction,
+            "Unified" => CacheKind::Unified,
+            _ => return None,
+        },
+        size:  number.parse::<u64>().ok()? * multiplier,
+    })
+}
+
+#[test]
+fn parse_cpu_features() {
+    assert_eq!(parse_virtualization_flags("processor\t: 0\nflags\t\t: fpu vme de pse svm sse4_2\n"), Some("AMD-V".to_string()));
+    assert_eq!(parse_virtualization_flags("flags\t\t: fpu vme de pse hypervisor\n"), None);
+    assert_eq!(parse_cache("1", "Data", "48K"), Some(CacheInfo {
+        level: 1,
+        kind:  CacheKind::Data,
+        size:  48 * 1024,
+    }));
+    assert_eq!(parse_cache("3", "Unified", "32768K").map(|cache| cache.size), Some(32 * 1024 * 1024));
+    assert_eq!(parse_cache("1", "Unknown", "48K"), None);
+}
@@ -0,0 +1,197 @@
+// What the cores are allowed to clock at and who decides:
+//
+// - Linux: cpufreq in /sys/devices/system/cpu, per core. The base
+//   frequency is only there with intel_pstate, turbo is either
+//   intel_pstate's no_turbo or the generic boost switch
+// - macOS: sysctl has the range on Intel Macs only. There is no governor,
+//   the closest thing is Low Power Mode from pmset
+// - Windows: the active power plan from powercfg, and the rated clock
+//   speed from WMI as the base frequency
+//
+// The governor/power plan can be changed with the `cpu-governor` feature,
+// which needs root or an elevated prompt.
+
+use std::time::{Duration, Instant};
+
+use uom::si::{f64::Frequency, frequency::hertz};
+
+/// Limits and policy of one logical core
+#[derive(Debug, Clone, Default)]
+pub struct CoreFrequency {
+    pub minimum:  Option<Frequency>,
+    pub maximum:  Option<Frequency>,
+    pub base:     Option<Frequency>,
+    pub governor: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct FrequencyPolicy {
+    /// Indexed like sysinfo's CPUs. Platforms without per core values
+    /// have a single entry that applies to all of them
+    pub cores: Vec<CoreFrequency>,
+    pub turbo: Option<bool>,
+}
+
+impl FrequencyPolicy {
+    pub fn core(&self, index: usize) -> CoreFrequency {
+        self.cores.get(index).or_else(|| self.cores.first().filter(|_| self.cores.len() == 1)).cloned().unwrap_or_default()
+    }
+}
+
+/// Reading this means running commands outside of Linux, and limits and
+/// governors hardly ever change, so it is only read again after this long
+pub const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// `cached` is replaced once it is older than `REFRESH_INTERVAL`
+pub fn frequency_policy(cached: &mut Option<(Instant, FrequencyPolicy)>, core_count: usize) -> FrequencyPolicy {
+    if let Some((read_at, policy)) = cached
+        && read_at.elapsed() < REFRESH_INTERVAL
+    {
+        return policy.clone();
+    }
+    let policy = read_frequency_policy(core_count);
+    *cached = Some((Instant::now(), policy.clone()));
+    policy
+}
+
+#[cfg(target_os = "linux")]
+fn read_frequency_policy(core_count: usize) -> FrequencyPolicy {
+    let read = |path: &str| std::fs::read_to_string(format!("/sys/devices/system/cpu/{path}")).ok().map(|contents| contents.trim().to_string());
+    // cpufreq counts in kHz
+    let kilohertz = |path: &str| read(path)?.parse::<f64>().ok().map(|khz| Frequency::new::<hertz>(khz * 1000.0));
+    FrequencyPolicy {
+        cores: (0..core_count)
+            .map(|core| CoreFrequency {
+                minimum:  kilohertz(&format!("cpu{core}/cpufreq/cpuinfo_min_freq")),
+                maximum:  kilohertz(&format!("cpu{core}/cpufreq/cpuinfo_max_freq")),
+                base:     kilohertz(&format!("cpu{core}/cpufreq/base_frequency")),
+                governor: read(&format!("cpu{core}/cpufreq/scaling_governor")),
+            })
+            .collect(),
+        turbo: read("intel_pstate/no_turbo").map(|no_turbo| no_turbo == "0").or_else(|| read("cpufreq/boost").map(|boost| boost == "1")),
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn read_frequency_policy(_core_count: usize) -> FrequencyPolicy {
+    let sysctl = |name: &str| {
+        let output = std::process::Command::new("sysctl").args(["-n", name]).output().ok().filter(|output| output.status.success())?;
+        String::from_utf8_lossy(&output.stdout).trim().parse::<f64>().ok().map(Frequency::new::<hertz>)
+    };
+    let low_power_mode = std::process::Command::new("pmset")
+        .arg("-g")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| parse_low_power_mode(&String::from_utf8_lossy(&output.stdout)));
+    FrequencyPolicy {
+        cores: vec![CoreFrequency {
+            minimum:  sysctl("hw.cpufrequency_min"),
+            maximum:  sysctl("hw.cpufrequency_max"),
+            base:     sysctl("hw.cpufrequency"),
+            governor: low_power_mode.map(|enabled| if enabled { "low power" } else { "automatic" }.to_string()),
+        }],
+        turbo: None,
+    }
+}
+
+#[cfg(windows)]
+fn read_frequency_policy(_core_count: usize) -> FrequencyPolicy {
+    let run = |program: &str, args: &[&str]| {
+        let output = std::process::Command::new(program).args(args).output().ok().filter(|output| output.status.success())?;
+        Some(String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+    let base = run("powershell", &["-NoProfile", "-NonInteractive", "-Command", "(Get-CimInstance Win32_Processor | Select-Object -First 1).MaxClockSpeed"])
+        .and_then(|megahertz| megahertz.trim().parse::<f64>().ok())
+        .map(|megahertz| Frequency::new::<hertz>(megahertz * 1_000_000.0));
+    FrequencyPolicy {
+        cores: vec![CoreFrequency {
+            base,
+            governor: run("powercfg", &["/getactivescheme"]).and_then(|scheme| parse_power_schemes(&scheme).into_iter().next()).map(|(_, name)| name),
+            ..Default::default()
+        }],
+        turbo: None,
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+fn read_frequency_policy(_core_count: usize) -> FrequencyPolicy {
+    FrequencyPolicy::default()
+}
+
+/// Sets the governor of every core on Linux, or activates the power plan
+/// with that name on Windows
+#[cfg(all(feature = "cpu-governor", target_os = "linux"))]
+pub fn set_governor(governor: &str) -> bool {
+    let Ok(cores) = std::fs::read_dir("/sys/devices/system/cpu/cpufreq") else {
+        return false;
+    };
+    let policies = cores.flatten().map(|entry| entry.path()).filter(|path| path.join("scaling_governor").exists()).collect::<Vec<std::path::PathBuf>>();
+    // Writing one the driver doesn't know fails anyway, but only after
+    // some of the cores might have been changed
+    let available = |policy: &std::path::PathBuf| {
+        std::fs::read_to_string(policy.join("scaling_available_governors")).is_ok_and(|available| available.split_whitespace().any(|available| available == governor))
+    };
+    !policies.is_empty() && policies.iter().all(available) && policies.iter().all(|policy| std::fs::write(policy.join("scaling_governor"), governor).is_ok())
+}
+
+#[cfg(all(feature = "cpu-governor", windows))]
+pub fn set_governor(governor: &str) -> bool {
+    let Some(schemes) = std::process::Command::new("powercfg").arg("/list").output().ok().filter(|output| output.status.success()) else {
+        return false;
+    };
+    parse_power_schemes(&String::from_utf8_lossy(&schemes.stdout))
+        .into_iter()
+        .find(|(_, name)| name.eq_ignore_ascii_case(governor))
+        .is_some_and(|(guid, _)| std::process::Command::new("powercfg").args(["/setactive", &guid]).output().is_ok_and(|output| output.status.success()))
+}
+
+#[cfg(all(feature = "cpu-governor", not(any(target_os = "linux", windows))))]
+pub const fn set_governor(_governor: &str) -> bool {
+    false
+}
+
+// " lowpowermode         1" among the other settings of the current
+// power source
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_low_power_mode(pmset: &str) -> Option<bool> {
+    pmset.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        (fields.next()? == "lowpowermode").then(|| fields.next() == Some("1"))
+    })
+}
+
+// Both /getactivescheme and /list print lines like
+// "Power Scheme GUID: 381b4222-f694-41f0-9685-ff5bb260df2e  (Balanced)",
+// the active one in /list followed by a *. The labels are translated, so
+// only the shape is relied on
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_power_schemes(powercfg: &str) -> Vec<(String, String)> {
+    powercfg
+        .lines()
+        .filter_map(|line| {
+            let (_, scheme) = line.split_once(": ")?;
+            let (guid, name) = scheme.split_once('(')?;
+            let name = name.trim_end_matches(|chr: char| chr == '*' || chr.is_whitespace()).strip_suffix(')')?;
+            Some((guid.trim().to_string(), name.to_string()))
+        })
+        .collect()
+}
+
+#[test]
+fn parse_frequency_policy() {
+    assert_eq!(parse_power_schemes("Power Scheme GUID: 381b4222-f694-41f0-9685-ff5bb260df2e  (Balanced)\n"), vec![(
+        "381b4222-f694-41f0-9685-ff5bb260df2e".to_string(),
+        "Balanced".to_string()
+    )]);
+    assert_eq!(
+        parse_power_schemes(
+            "Existing Power Schemes (* Active)\n-----------------------------------\nPower Scheme GUID: 381b4222-f694-41f0-9685-ff5bb260df2e  (Balanced) *\nPower Scheme GUID: \
+             8c5e7fda-e8bf-4a96-9a85-a6e23a8c635c  (High performance)\n"
+        )
+        .len(),
+        2
+    );
+    assert_eq!(parse_low_power_mode("System-wide power settings:\nCurrently in use:\n standby              1\n lowpowermode         1\n"), Some(true));
+    assert_eq!(parse_low_power_mode(" standby 1\n"), None);
+}
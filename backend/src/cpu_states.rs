@@ -0,0 +1,160 @@
+// What the CPU spent its time on, since high iowait and high user time
+// call for completely different fixes:
+//
+// - Linux: the tick counters in /proc/stat. They count since boot, so the
+//   Manager keeps the last ones and the breakdown covers the time since
+//   the previous call
+// - Windows: the processor performance counters through WMI, which don't
+//   know iowait or steal
+// - macOS would need host_processor_info, which is only reachable through
+//   unsafe code
+
+/// Percent of the time, adding up to 100
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuStateBreakdown {
+    /// Including niced processes
+    pub user:   f32,
+    /// Including interrupts
+    pub system: f32,
+    /// Waiting for disks, only known on Linux
+    pub iowait: Option<f32>,
+    /// Taken by the hypervisor for other virtual machines, only known on
+    /// Linux
+    pub steal:  Option<f32>,
+    pub idle:   f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuStates {
+    pub total: CpuStateBreakdown,
+    /// In the same order as `Manager::cpu_information`
+    pub cores: Vec<CpuStateBreakdown>,
+}
+
+/// Ticks since boot
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CpuTimes {
+    user:   u64,
+    system: u64,
+    iowait: u64,
+    steal:  u64,
+    idle:   u64,
+}
+
+impl CpuTimes {
+    #[allow(clippy::cast_precision_loss)]
+    fn breakdown_since(self, previous: Self) -> CpuStateBreakdown {
+        let user = self.user.saturating_sub(previous.user) as f32;
+        let system = self.system.saturating_sub(previous.system) as f32;
+        let iowait = self.iowait.saturating_sub(previous.iowait) as f32;
+        let steal = self.steal.saturating_sub(previous.steal) as f32;
+        let idle = self.idle.saturating_sub(previous.idle) as f32;
+        let total = user + system + iowait + steal + idle;
+        if total == 0.0 {
+            return CpuStateBreakdown {
+                idle: 100.0,
+                iowait: Some(0.0),
+                steal: Some(0.0),
+                ..CpuStateBreakdown::default()
+            };
+        }
+        let percent = |ticks: f32| ticks * 100.0 / total;
+        CpuStateBreakdown {
+            user:   percent(user),
+            system: percent(system),
+            iowait: Some(percent(iowait)),
+            steal:  Some(percent(steal)),
+            idle:   percent(idle),
+        }
+    }
+}
+
+/// The aggregate first, then every core
+#[cfg(target_os = "linux")]
+pub fn cpu_times() -> Option<Vec<CpuTimes>> {
+    Some(parse_proc_stat(&std::fs::read_to_string("/proc/stat").ok()?))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn cpu_times() -> Option<Vec<CpuTimes>> {
+    None
+}
+
+// cpu  4705 356 584 3699 23 23 0 0 0 0
+// cpu0 1393 280 283 1003 13 6 0 0 0 0
+//
+// The columns are user, nice, system, idle, iowait, irq, softirq, steal,
+// guest and guest_nice. Guest time is already part of user time.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_stat(stat: &str) -> Vec<CpuTimes> {
+    stat.lines()
+        .filter(|line| line.starts_with("cpu"))
+        .filter_map(|line| {
+            let ticks = line.split_whitespace().skip(1).map(|ticks| ticks.parse::<u64>().ok()).collect::<Option<Vec<u64>>>()?;
+            let tick = |index: usize| ticks.get(index).copied().unwrap_or_default();
+            Some(CpuTimes {
+                user:   tick(0) + tick(1),
+                system: tick(2) + tick(5) + tick(6),
+                iowait: tick(4),
+                steal:  tick(7),
+                idle:   tick(3),
+            })
+        })
+        .collect()
+}
+
+/// `previous` are the times of the last call, which are replaced
+pub fn cpu_states(previous: &mut Option<Vec<CpuTimes>>) -> Option<CpuStates> {
+    if let Some(current) = cpu_times() {
+        let earlier = previous.replace(current.clone()).unwrap_or_default();
+        let mut breakdowns = current.iter().enumerate().map(|(index, times)| times.breakdown_since(earlier.get(index).copied().unwrap_or_default()));
+        return Some(CpuStates {
+            total: breakdowns.next()?,
+            cores: breakdowns.collect(),
+        });
+    }
+    performance_counter_states()
+}
+
+#[cfg(windows)]
+fn performance_counter_states() -> Option<CpuStates> {
+    let rows = crate::performance_counters::processor_times()?;
+    let breakdown = |name: &str| {
+        rows.iter().find_map(|row| match row.as_slice() {
+            [found, user, privileged, idle, _] if found == name => Some(CpuStateBreakdown {
+                user:   user.parse().ok()?,
+                system: privileged.parse().ok()?,
+                iowait: None,
+                steal:  None,
+                idle:   idle.parse().ok()?,
+            }),
+            _ => None,
+        })
+    };
+    Some(CpuStates {
+        total: breakdown("_Total")?,
+        cores: (0..).map_while(|core: usize| breakdown(&core.to_string())).collect(),
+    })
+}
+
+#[cfg(not(windows))]
+const fn performance_counter_states() -> Option<CpuStates> {
+    None
+}
+
+#[test]
+fn cpu_state_breakdown() {
+    let before = parse_proc_stat("cpu  100 0 50 800 50 0 0 0 0 0\ncpu0 100 0 50 800 50 0 0 0 0 0\nintr 1 2 3\n");
+    let after = parse_proc_stat("cpu  150 10 80 880 60 5 5 10 0 0\ncpu0 150 10 80 880 60 5 5 10 0 0\n");
+    assert_eq!(before.len(), 2);
+    let breakdown = after[0].breakdown_since(before[0]);
+    assert_eq!(breakdown, CpuStateBreakdown {
+        user:   30.0,
+        system: 20.0,
+        iowait: Some(5.0),
+        steal:  Some(5.0),
+        idle:   40.0,
+    });
+}
@@ -0,0 +1,132 @@
+// Crashed applications leave a trace behind on every platform: systemd
+// collects core dumps and lists them through `coredumpctl`, macOS writes a
+// report per crash into DiagnosticReports and Windows Error Reporting
+// keeps one directory per crash in its report archive.
+
+use std::time::SystemTime;
+
+#[derive(Debug, Clone)]
+pub struct CrashReport {
+    pub process_name: String,
+    pub pid:          Option<u32>,
+    pub time:         SystemTime,
+    /// The signal that killed the process, e.g. "SIGSEGV". Only known on
+    /// Linux
+    pub signal:       Option<String>,
+    /// The crash report or core dump, if it's still around
+    pub report:       Option<std::path::PathBuf>,
+}
+
+#[cfg(target_os = "linux")]
+pub fn crash_reports() -> Option<Vec<CrashReport>> {
+    let output = std::process::Command::new("coredumpctl").args(["list", "--json=short", "--no-pager", "--quiet"]).output().ok()?;
+    // coredumpctl exits with an error if there are no core dumps at all
+    if !output.status.success() {
+        return output.stdout.is_empty().then(Vec::new);
+    }
+    Some(parse_coredumpctl(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(target_os = "macos")]
+pub fn crash_reports() -> Option<Vec<CrashReport>> {
+    let user_reports = std::env::var("HOME").ok().map(|home| std::path::PathBuf::from(home).join("Library/Logs/DiagnosticReports"));
+    let reports = [user_reports, Some(std::path::PathBuf::from("/Library/Logs/DiagnosticReports"))]
+        .into_iter()
+        .flatten()
+        .filter_map(|directory| std::fs::read_dir(directory).ok())
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path();
+            if !path.extension().is_some_and(|extension| extension == "ips" || extension == "crash") {
+                return None;
+            }
+            // Reports are named "<process>-<yyyy>-<mm>-<dd>-<hhmmss>.ips"
+            let file_name = path.file_stem()?.to_string_lossy().to_string();
+            let process_name = file_name.rsplitn(5, '-').last()?.to_string();
+            Some(CrashReport {
+                process_name,
+                pid: None,
+                time: entry.metadata().ok()?.modified().ok()?,
+                signal: None,
+                report: Some(path),
+            })
+        })
+        .collect();
+    Some(reports)
+}
+
+#[cfg(windows)]
+pub fn crash_reports() -> Option<Vec<CrashReport>> {
+    let archive = std::path::PathBuf::from(std::env::var("ProgramData").ok()?).join("Microsoft\\Windows\\WER\\ReportArchive");
+    let reports = std::fs::read_dir(archive)
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            // Directories are named "AppCrash_<process>.exe_<hash>_<id>"
+            let directory_name = entry.file_name().to_string_lossy().to_string();
+            let (process_name, _) = directory_name.strip_prefix("AppCrash_")?.split_once('_')?;
+            Some(CrashReport {
+                process_name: process_name.to_string(),
+                pid:          None,
+                time:         entry.metadata().ok()?.modified().ok()?,
+                signal:       None,
+                report:       Some(entry.path()),
+            })
+        })
+        .collect();
+    Some(reports)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub const fn crash_reports() -> Option<Vec<CrashReport>> {
+    None
+}
+
+// The output is an array of objects like
+//
+// {"time":1696932000000000,"pid":1234,"sig":11,"exe":"/usr/bin/foo",...}
+//
+// with the time in microseconds.
+#[cfg(target_os = "linux")]
+fn parse_coredumpctl(json: &str) -> Vec<CrashReport> {
+    let Ok(serde_json::Value::Array(dumps)) = serde_json::from_str(json) else {
+        return Vec::new();
+    };
+    dumps
+        .iter()
+        .filter_map(|dump| {
+            let exe = dump.get("exe")?.as_str()?;
+            Some(CrashReport {
+                process_name: exe.rsplit('/').next().unwrap_or(exe).to_string(),
+                pid:          dump.get("pid").and_then(serde_json::Value::as_u64).and_then(|pid| u32::try_from(pid).ok()),
+                time:         std::time::UNIX_EPOCH + std::time::Duration::from_micros(dump.get("time")?.as_u64()?),
+                signal:       dump
+                    .get("sig")
+                    .and_then(serde_json::Value::as_i64)
+                    .and_then(|signal| i32::try_from(signal).ok())
+                    .and_then(|signal| nix::sys::signal::Signal::try_from(signal).ok())
+                    .map(|signal| signal.as_str().to_string()),
+                // The core dump itself is stored in the journal or
+                // compressed in /var/lib/systemd/coredump, neither of which
+                // is directly usable as a path
+                report:       None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn parse_core_dumps() {
+    let reports = parse_coredumpctl(
+        r#"[{"time":1696932000000000,"pid":1234,"uid":1000,"gid":1000,"sig":11,"corefile":"present","exe":"\/opt\/My App, Inc\/app","size":2048},{"time":1696932060000000,"pid":77,"uid":0,"gid":0,"sig":6,"corefile":"missing","exe":"/usr/bin/foo","size":null}]"#,
+    );
+    assert_eq!(reports.len(), 2);
+    assert_eq!(reports[0].process_name, "app");
+    assert_eq!(reports[0].pid, Some(1234));
+    assert_eq!(reports[0].signal.as_deref(), Some("SIGSEGV"));
+    assert_eq!(reports[0].time, std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_696_932_000));
+    assert_eq!((reports[1].process_name.as_str(), reports[1].signal.as_deref()), ("foo", Some("SIGABRT")));
+    assert!(parse_coredumpctl("").is_empty());
+}
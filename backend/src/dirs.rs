@@ -0,0 +1,78 @@
+// Where crossinfo keeps its files, following each platform's conventions:
+//
+//          Linux and BSD          macOS                          Windows
+// config   $XDG_CONFIG_HOME       ~/Library/Application Support  %APPDATA%
+// data     $XDG_DATA_HOME         ~/Library/Application Support
+// %LOCALAPPDATA% cache    $XDG_CACHE_HOME        ~/Library/Caches
+// %LOCALAPPDATA%
+//
+// each with a crossinfo subdirectory. Windows has no separate folder for
+// caches, so there the cache is a subdirectory of the data directory.
+// CROSSINFO_CONFIG_DIR, CROSSINFO_DATA_DIR and CROSSINFO_CACHE_DIR
+// override the whole path, e.g. for portable installs or tests. None of
+// the directories are created here, only when something gets written.
+
+use std::path::PathBuf;
+
+fn environment_path(variable: &str) -> Option<PathBuf> {
+    // The XDG spec says relative paths are invalid and should be ignored
+    std::env::var_os(variable).map(PathBuf::from).filter(|path| path.is_absolute())
+}
+
+fn home() -> Option<PathBuf> {
+    environment_path("HOME")
+}
+
+fn xdg_directory(variable: &str, default: &str) -> Option<PathBuf> {
+    environment_path(variable).or_else(|| Some(home()?.join(default)))
+}
+
+/// Settings the user edits
+#[must_use]
+pub fn config_directory() -> Option<PathBuf> {
+    environment_path("CROSSINFO_CONFIG_DIR").or_else(|| {
+        Some(
+            if cfg!(windows) {
+                environment_path("APPDATA")?
+            } else if cfg!(target_os = "macos") {
+                home()?.join("Library/Application Support")
+            } else {
+                xdg_directory("XDG_CONFIG_HOME", ".config")?
+            }
+            .join("crossinfo"),
+        )
+    })
+}
+
+/// History and baselines that should survive
+#[must_use]
+pub fn data_directory() -> Option<PathBuf> {
+    environment_path("CROSSINFO_DATA_DIR").or_else(|| {
+        Some(
+            if cfg!(windows) {
+                environment_path("LOCALAPPDATA")?
+            } else if cfg!(target_os = "macos") {
+                home()?.join("Library/Application Support")
+            } else {
+                xdg_directory("XDG_DATA_HOME", ".local/share")?
+            }
+            .join("crossinfo"),
+        )
+    })
+}
+
+/// Anything that can be thrown away and rebuilt
+#[must_use]
+pub fn cache_directory() -> Option<PathBuf> {
+    environment_path("CROSSINFO_CACHE_DIR").or_else(|| {
+        Some(
+            if cfg!(windows) {
+                environment_path("LOCALAPPDATA")?.join("crossinfo").join("cache")
+            } else if cfg!(target_os = "macos") {
+                home()?.join("Library/Caches/crossinfo")
+            } else {
+                xdg_directory("XDG_CACHE_HOME", ".cache")?.join("crossinfo")
+            },
+        )
+    })
+}
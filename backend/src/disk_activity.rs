@@ -0,0 +1,205 @@
+// How long IO requests take and how many of them wait. Throughput doesn't
+// show a disk that can't keep up, latency does:
+//
+// - Linux: the counters since boot in /proc/diskstats
+// - Windows: the raw logical disk performance counters through WMI, which
+//   also count since boot
+// - macOS only reports them through IOKit, which needs unsafe code
+//
+// The Manager keeps the last counters, so the averages cover the time
+// since the previous call. The first call averages over the time since
+// boot.
+
+use std::{collections::HashMap, time::Duration};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DiskActivity {
+    /// IO requests in flight right now
+    pub queue_length:        Option<f64>,
+    pub average_queue_depth: Option<f64>,
+    /// Of reads and writes together. `None` if there weren't any
+    pub average_latency:     Option<Duration>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+struct DeviceCounters {
+    /// Reads and writes
+    completed:             u64,
+    /// Summed up over every request
+    waiting_milliseconds:  f64,
+    /// Milliseconds times the requests in flight, which divided by the
+    /// time gives the average queue depth
+    weighted_milliseconds: f64,
+    in_flight:             u64,
+}
+
+/// Counters of every disk, keyed by device name on Linux and by mount
+/// point on Windows
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiskCounters {
+    milliseconds_since_boot: f64,
+    devices:                 HashMap<String, DeviceCounters>,
+}
+
+/// `previous` are the counters of the last call, which are replaced. Keyed
+/// like `DiskCounters`
+pub fn disk_activity(previous: &mut Option<DiskCounters>) -> Option<HashMap<String, DiskActivity>> {
+    let current = disk_counters()?;
+    let earlier = previous.replace(current.clone());
+    Some(
+        current
+            .devices
+            .iter()
+            .filter_map(|(name, counters)| {
+                // A disk that showed up since the last call has no counters
+                // to compare with
+                let (earlier_counters, milliseconds) = earlier.as_ref().map_or_else(
+                    || Some((DeviceCounters::default(), current.milliseconds_since_boot)),
+                    |earlier| Some((*earlier.devices.get(name)?, current.milliseconds_since_boot - earlier.milliseconds_since_boot)),
+                )?;
+                Some((name.clone(), activity(earlier_counters, *counters, milliseconds)))
+            })
+            .collect(),
+    )
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn activity(earlier: DeviceCounters, current: DeviceCounters, milliseconds: f64) -> DiskActivity {
+    let completed = current.completed.saturating_sub(earlier.completed);
+    DiskActivity {
+        queue_length:        Some(current.in_flight as f64),
+        average_queue_depth: (milliseconds > 0.0).then(|| (current.weighted_milliseconds - earlier.weighted_milliseconds).max(0.0) / milliseconds),
+        average_latency:     (completed > 0).then(|| Duration::from_secs_f64((current.waiting_milliseconds - earlier.waiting_milliseconds).max(0.0) / completed as f64 / 1000.0)),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn disk_counters() -> Option<DiskCounters> {
+    let uptime = std::fs::read_to_string("/proc/uptime").ok()?.split_whitespace().next()?.parse::<f64>().ok()?;
+    Some(DiskCounters {
+        milliseconds_since_boot: uptime * 1000.0,
+        devices:                 parse_diskstats(&std::fs::read_to_string("/proc/diskstats").ok()?),
+    })
+}
+
+#[cfg(windows)]
+fn disk_counters() -> Option<DiskCounters> {
+    parse_logical_disks(&crate::performance_counters::logical_disks()?)
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+const fn disk_counters() -> Option<DiskCounters> {
+    None
+}
+
+//    8       0 sda 4711 52 380424 2365 2210 1860 95314 3830 0 3080 6196
+//
+// After major, minor and name: reads completed, merged, sectors read and
+// milliseconds spent reading, the same four for writes, the requests in
+// flight, milliseconds spent doing IO and the weighted milliseconds. Newer
+// kernels append discard and flush counters.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_diskstats(diskstats: &str) -> HashMap<String, DeviceCounters> {
+    diskstats
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            let counter = |index: usize| fields.get(index)?.parse::<u64>().ok();
+            #[allow(clippy::cast_precision_loss)]
+            let counters = DeviceCounters {
+                completed:             counter(3)? + counter(7)?,
+                waiting_milliseconds:  (counter(6)? + counter(10)?) as f64,
+                weighted_milliseconds: counter(13)? as f64,
+                in_flight:             counter(11)?,
+            };
+            Some(((*fields.get(2)?).to_string(), counters))
+        })
+        .collect()
+}
+
+// Name, CurrentDiskQueueLength, AvgDiskSecPerTransfer,
+// AvgDiskSecPerTransfer_Base, AvgDiskQueueLength, Timestamp_PerfTime and
+// Frequency_PerfTime. AvgDiskSecPerTransfer is in ticks of the performance
+// counter frequency, AvgDiskQueueLength in 100 nanoseconds.
+#[cfg_attr(not(windows), allow(dead_code))]
+#[allow(clippy::cast_precision_loss)]
+fn parse_logical_disks(rows: &[Vec<String>]) -> Option<DiskCounters> {
+    let mut milliseconds_since_boot = None;
+    let devices = rows
+        .iter()
+        .filter_map(|row| {
+            let [name, in_flight, waiting_ticks, completed, weighted_100ns, timestamp, frequency] = row.as_slice() else {
+                return None;
+            };
+            let frequency = frequency.parse::<u64>().ok().filter(|frequency| *frequency > 0)? as f64;
+            milliseconds_since_boot = Some(timestamp.parse::<u64>().ok()? as f64 * 1000.0 / frequency);
+            // "_Total" and unmounted volumes like "HarddiskVolume1" have no
+            // drive letter
+            if !name.ends_with(':') {
+                return None;
+            }
+            Some((format!("{name}\\"), DeviceCounters {
+                completed:             completed.parse().ok()?,
+                waiting_milliseconds:  waiting_ticks.parse::<u64>().ok()? as f64 * 1000.0 / frequency,
+                weighted_milliseconds: weighted_100ns.parse::<u64>().ok()? as f64 / 10_000.0,
+                in_flight:             in_flight.parse().ok()?,
+            }))
+        })
+        .collect();
+    Some(DiskCounters {
+        milliseconds_since_boot: milliseconds_since_boot?,
+        devices,
+    })
+}
+
+#[test]
+fn disk_activity_from_counters() {
+    let devices = parse_diskstats("   8       0 sda 4711 52 380424 2365 2210 1860 95314 3830 2 3080 6196 0 0 0 0\n   7       0 loop0 12 0 24 1\n");
+    assert_eq!(devices.len(), 1);
+    let sda = devices["sda"];
+    assert_eq!((sda.completed, sda.in_flight), (6921, 2));
+
+    let later = DeviceCounters {
+        completed:             sda.completed + 100,
+        waiting_milliseconds:  sda.waiting_milliseconds + 250.0,
+        weighted_milliseconds: sda.weighted_milliseconds + 500.0,
+        in_flight:             0,
+    };
+    let activity = activity(sda, later, 1000.0);
+    assert_eq!(activity.average_latency, Some(Duration::from_micros(2500)));
+    assert_eq!(activity.average_queue_depth, Some(0.5));
+    assert_eq!(activity.queue_length, Some(0.0));
+
+    let windows = parse_logical_disks(&[
+        vec![
+            "C:".to_string(),
+            "1".to_string(),
+            "20000000".to_string(),
+            "400".to_string(),
+            "50000".to_string(),
+            "100000000".to_string(),
+            "10000000".to_string(),
+        ],
+        vec![
+            "_Total".to_string(),
+            "1".to_string(),
+            "20000000".to_string(),
+            "400".to_string(),
+            "50000".to_string(),
+            "100000000".to_string(),
+            "10000000".to_string(),
+        ],
+    ]);
+    assert_eq!(
+        windows,
+        Some(DiskCounters {
+            milliseconds_since_boot: 10_000.0,
+            devices:                 HashMap::from([("C:\\".to_string(), DeviceCounters {
+                completed:             400,
+                waiting_milliseconds:  2000.0,
+                weighted_milliseconds: 5.0,
+                in_flight:             1,
+            })]),
+        })
+    );
+}
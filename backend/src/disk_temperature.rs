@@ -0,0 +1,74 @@
+// How hot the drive behind a disk is, so frontends can warn about
+// overheating storage before it throttles or wears out:
+//
+// - Linux: the hwmon device of the drive in sysfs. NVMe drives always have
+//   one, SATA drives once the drivetemp module is loaded. Partitions are
+//   looked up through the drive they are on
+// - Elsewhere drives only report it through S.M.A.R.T., which takes far
+//   too long to read on every refresh. `DiskSmartInfo` has it instead
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiskTemperature {
+    /// Degrees Celsius, like every other temperature
+    pub current:  f32,
+    /// Where the drive starts throttling, if it says
+    pub critical: Option<f32>,
+}
+
+/// `device` as sysinfo names it, e.g. /dev/nvme0n1p2
+#[cfg(target_os = "linux")]
+pub fn disk_temperature(device: &str) -> Option<DiskTemperature> {
+    let device = std::path::Path::new("/sys/block").join(parent_disk(device)?).join("device");
+    // SATA drives have device/hwmon/hwmonN, NVMe controllers device/hwmonN
+    let hwmon = std::fs::read_dir(device.join("hwmon"))
+        .into_iter()
+        .chain(std::fs::read_dir(&device))
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .find(|path| path.file_name().and_then(|name| name.to_str()).is_some_and(|name| name.starts_with("hwmon")) && path.join("temp1_input").exists())?;
+    let read = |name: &str| parse_millidegrees(&std::fs::read_to_string(hwmon.join(name)).ok()?);
+    Some(DiskTemperature {
+        current:  read("temp1_input")?,
+        critical: read("temp1_crit").or_else(|| read("temp1_max")),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn disk_temperature(_device: &str) -> Option<DiskTemperature> {
+    None
+}
+
+/// The whole drive a partition is on, e.g. nvme0n1 for /dev/nvme0n1p2. The
+/// name itself if it's a whole drive already
+#[cfg(target_os = "linux")]
+pub fn parent_disk(device: &str) -> Option<String> {
+    let name = device.trim_start_matches("/dev/");
+    let path = std::path::Path::new("/sys/class/block").join(name);
+    if path.join("partition").exists() {
+        // /sys/class/block/nvme0n1p2 links to .../block/nvme0n1/nvme0n1p2
+        Some(std::fs::canonicalize(path).ok()?.parent()?.file_name()?.to_str()?.to_string())
+    } else if path.exists() {
+        Some(name.to_string())
+    } else {
+        None
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn parent_disk(_device: &str) -> Option<String> {
+    None
+}
+
+// 45850
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_millidegrees(millidegrees: &str) -> Option<f32> {
+    millidegrees.trim().parse::<f32>().ok().map(|millidegrees| millidegrees / 1000.0)
+}
+
+#[test]
+fn parse_disk_temperature() {
+    assert_eq!(parse_millidegrees("45850\n"), Some(45.85));
+    assert_eq!(parse_millidegrees("-1000"), Some(-1.0));
+    assert_eq!(parse_millidegrees(""), None);
+}
@@ -0,0 +1,31 @@
+// The Option returning functions of the Manager can't tell an unsupported
+// platform from a missing device or a failing driver. Their `try_`
+// variants return this instead, wrapping the error of the crate that
+// failed.
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The crate behind the information doesn't support this platform
+    #[error("Not supported on this platform")]
+    Unsupported,
+    /// E.g. a desktop without a battery
+    #[error("There is no {0}")]
+    NotFound(&'static str),
+    #[error("Reading the batteries failed: {0}")]
+    Battery(#[from] battery::Error),
+    #[error("Bluetooth is turned off")]
+    BluetoothOff,
+    #[cfg(feature = "bluetooth")]
+    #[error("Bluetooth failed: {0}")]
+    Bluetooth(#[from] btleplug::Error),
+    /// A request to the internet, e.g. during the speedtest
+    #[cfg(feature = "network-extra")]
+    #[error("A network request failed: {0}")]
+    Network(#[from] reqwest::Error),
+    /// E.g. while benchmarking a disk
+    #[error("Reading or writing failed: {0}")]
+    Io(#[from] std::io::Error),
+    /// display-info only reports its errors as text
+    #[error("Reading the displays failed: {0}")]
+    Display(String),
+}
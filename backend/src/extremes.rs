@@ -0,0 +1,111 @@
+// The coolest and hottest each component got, since peak temperatures
+// under load are what tells about cooling problems. Frontends record every
+// reading and can keep the result in crossinfo's data directory:
+//
+// crossinfo temperatures
+// 32.5 81 CPU Package
+//
+// Component names come last as they contain spaces.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use crate::ComponentInfo;
+
+/// Degrees Celsius
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TemperatureRange {
+    pub min: f32,
+    pub max: f32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TemperatureExtremes {
+    components: BTreeMap<String, TemperatureRange>,
+}
+
+fn path() -> Option<PathBuf> {
+    Some(crate::data_directory()?.join("temperatures.txt"))
+}
+
+impl TemperatureExtremes {
+    /// Empty if nothing was saved yet
+    #[must_use]
+    pub fn load() -> Self {
+        path().and_then(|path| std::fs::read_to_string(path).ok()).and_then(|text| Self::from_text(&text)).unwrap_or_default()
+    }
+
+    /// # Errors
+    ///
+    /// If there is no data directory or it can't be written to
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = path().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "There is no data directory"))?;
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        std::fs::write(path, self.to_text())
+    }
+
+    pub fn record(&mut self, components: &[ComponentInfo]) {
+        // Some sensors report NaN while they're asleep
+        for component in components.iter().filter(|component| component.temperature.is_finite()) {
+            self.components
+                .entry(component.name.clone())
+                .and_modify(|range| {
+                    range.min = range.min.min(component.temperature);
+                    range.max = range.max.max(component.temperature);
+                })
+                .or_insert(TemperatureRange {
+                    min: component.temperature,
+                    max: component.temperature,
+                });
+        }
+    }
+
+    #[must_use]
+    pub fn get(&self, component: &str) -> Option<TemperatureRange> {
+        self.components.get(component).copied()
+    }
+
+    pub fn clear(&mut self) {
+        self.components.clear();
+    }
+
+    fn to_text(&self) -> String {
+        std::iter::once("crossinfo temperatures".to_string())
+            .chain(self.components.iter().map(|(name, range)| format!("{} {} {name}", range.min, range.max)))
+            .map(|line| line + "\n")
+            .collect()
+    }
+
+    fn from_text(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+        if lines.next()? != "crossinfo temperatures" {
+            return None;
+        }
+        Some(Self {
+            components: lines
+                .filter_map(|line| {
+                    let mut fields = line.splitn(3, ' ');
+                    let min = fields.next()?.parse().ok()?;
+                    let max = fields.next()?.parse().ok()?;
+                    Some((fields.next()?.to_string(), TemperatureRange { min, max }))
+                })
+                .collect(),
+        })
+    }
+}
+
+#[test]
+fn temperature_extremes() {
+    let component = |temperature| ComponentInfo {
+        name: "CPU Package".to_string(),
+        temperature,
+        critical_temperature: None,
+    };
+    let mut extremes = TemperatureExtremes::default();
+    extremes.record(&[component(45.0)]);
+    extremes.record(&[component(81.5), component(f32::NAN)]);
+    extremes.record(&[component(32.0)]);
+    assert_eq!(extremes.get("CPU Package"), Some(TemperatureRange { min: 32.0, max: 81.5 }));
+    assert_eq!(TemperatureExtremes::from_text(&extremes.to_text()), Some(extremes));
+}
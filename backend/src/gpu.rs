@@ -0,0 +1,326 @@
+// There is no crate that covers every vendor and OS without unsafe code,
+// so each platform reads what it already exposes:
+//
+// - NVIDIA on Linux and Windows: `nvidia-smi`, which ships with the driver
+//   and reads NVML
+// - Other GPUs on Linux: the DRM devices in sysfs. amdgpu reports VRAM and
+//   utilization, i915 and xe don't
+// - macOS: `system_profiler` for the model and VRAM, `ioreg` for the
+//   utilization of the IOAccelerator
+// - Windows: Win32_VideoController and the GPU engine counters through WMI
+//
+// Whatever a source doesn't report stays `None`.
+
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GpuInfo {
+    /// E.g. "NVIDIA", "AMD", "Intel" or "Apple"
+    pub vendor:             String,
+    pub model:              Option<String>,
+    /// Bytes
+    pub vram_total:         Option<u64>,
+    /// Bytes
+    pub vram_used:          Option<u64>,
+    /// Percent, of the busiest engine
+    pub utilization:        Option<f32>,
+    pub engines:            GpuEngines,
+    /// Percent of the time VRAM was being read or written, i.e. how much
+    /// of its bandwidth is used
+    pub memory_utilization: Option<f32>,
+    /// Degrees Celsius
+    pub temperature:        Option<f32>,
+    pub driver_version:     Option<String>,
+}
+
+/// Utilization of the engines of a GPU in percent, so it's clear whether
+/// e.g. rendering or a video encode is what keeps it busy
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GpuEngines {
+    /// 3D rendering
+    pub graphics: Option<f32>,
+    /// Compute shaders, CUDA and the like
+    pub compute:  Option<f32>,
+    /// Video encoding, e.g. while streaming
+    pub encode:   Option<f32>,
+    /// Video decoding, e.g. while watching a video
+    pub decode:   Option<f32>,
+}
+
+// The engine types of the Windows counters. Some GPUs have several engines
+// of a type, like Compute_0 and Compute_1, and the busiest one counts
+#[cfg_attr(not(windows), allow(dead_code))]
+fn engines_from_counters(engine_types: &BTreeMap<String, f64>) -> GpuEngines {
+    #[allow(clippy::cast_possible_truncation)]
+    let busiest = |prefixes: &[&str]| {
+        engine_types
+            .iter()
+            .filter(|(engine_type, _)| prefixes.iter().any(|prefix| engine_type.starts_with(prefix)))
+            .map(|(_, utilization)| *utilization as f32)
+            .reduce(f32::max)
+    };
+    GpuEngines {
+        graphics: busiest(&["3D", "Graphics"]),
+        compute:  busiest(&["Compute", "Cuda"]),
+        encode:   busiest(&["VideoEncode"]),
+        decode:   busiest(&["VideoDecode"]),
+    }
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn vendor_name(pci_id: &str) -> String {
+    match pci_id.trim().to_lowercase().as_str() {
+        "0x10de" => "NVIDIA".to_string(),
+        "0x1002" => "AMD".to_string(),
+        "0x8086" => "Intel".to_string(),
+        id => id.to_string(),
+    }
+}
+
+#[cfg(any(target_os = "linux", windows))]
+fn nvidia_smi() -> Option<Vec<GpuInfo>> {
+    let output = std::process::Command::new("nvidia-smi")
+        .args([
+            "--query-gpu=name,memory.total,memory.used,utilization.gpu,utilization.memory,utilization.encoder,utilization.decoder,temperature.gpu,driver_version",
+            "--format=csv,noheader,nounits",
+        ])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(parse_nvidia_smi(&String::from_utf8_lossy(&output.stdout)))
+}
+
+// One line per GPU, with memory in MiB and "[N/A]" for anything the card
+// doesn't support:
+//
+// NVIDIA GeForce RTX 3070, 8192, 1034, 7, 3, 0, 12, 45, 550.54.14
+//
+// utilization.gpu is the time any kernel ran, NVML doesn't tell graphics
+// and compute apart.
+#[cfg_attr(not(any(target_os = "linux", windows)), allow(dead_code))]
+fn parse_nvidia_smi(output: &str) -> Vec<GpuInfo> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split(',').map(str::trim).collect::<Vec<&str>>();
+            let [model, vram_total, vram_used, utilization, memory_utilization, encode, decode, temperature, driver_version] = fields.as_slice() else {
+                return None;
+            };
+            let mebibytes = |value: &str| value.parse::<u64>().ok().map(|mebibytes| mebibytes * 1024 * 1024);
+            Some(GpuInfo {
+                vendor:             "NVIDIA".to_string(),
+                model:              Some((*model).to_string()),
+                vram_total:         mebibytes(vram_total),
+                vram_used:          mebibytes(vram_used),
+                utilization:        utilization.parse().ok(),
+                engines:            GpuEngines {
+                    encode: encode.parse().ok(),
+                    decode: decode.parse().ok(),
+                    ..GpuEngines::default()
+                },
+                memory_utilization: memory_utilization.parse().ok(),
+                temperature:        temperature.parse().ok(),
+                driver_version:     Some((*driver_version).to_string()).filter(|version| !version.starts_with('[')),
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+pub fn gpu_information() -> Option<Vec<GpuInfo>> {
+    let nvidia = nvidia_smi();
+    let read = |path: &std::path::Path| std::fs::read_to_string(path).ok().map(|text| text.trim().to_string());
+    let mut gpus = std::fs::read_dir("/sys/class/drm")
+        .ok()?
+        .flatten()
+        // Connectors like card0-HDMI-A-1 are listed next to the cards
+        .filter(|entry| entry.file_name().to_str().is_some_and(|name| name.starts_with("card") && !name.contains('-')))
+        .filter_map(|entry| {
+            let device = entry.path().join("device");
+            let pci_id = read(&device.join("vendor"))?;
+            // nvidia-smi knows a lot more than sysfs
+            if nvidia.is_some() && pci_id == "0x10de" {
+                return None;
+            }
+            let driver = std::fs::read_link(device.join("driver")).ok()?.file_name()?.to_str()?.to_string();
+            let slot = std::fs::canonicalize(&device).ok()?.file_name()?.to_str()?.to_string();
+            Some(GpuInfo {
+                vendor:         vendor_name(&pci_id),
+                model:          pci_device_name(&slot),
+                vram_total:     read(&device.join("mem_info_vram_total")).and_then(|bytes| bytes.parse().ok()),
+                vram_used:      read(&device.join("mem_info_vram_used")).and_then(|bytes| bytes.parse().ok()),
+                utilization:    read(&device.join("gpu_busy_percent")).and_then(|percent| percent.parse().ok()),
+                // amdgpu doesn't split up gpu_busy_percent by engine
+                engines:        GpuEngines::default(),
+                memory_utilization: read(&device.join("mem_busy_percent")).and_then(|percent| percent.parse().ok()),
+                temperature:    std::fs::read_dir(device.join("hwmon"))
+                    .ok()?
+                    .flatten()
+                    .find_map(|hwmon| read(&hwmon.path().join("temp1_input"))?.parse::<f32>().ok())
+                    .map(|millidegrees| millidegrees / 1000.0),
+                // In-tree drivers only have a version if they were built as a module with one
+                driver_version: read(&std::path::PathBuf::from(format!("/sys/module/{driver}/version"))),
+            })
+        })
+        .collect::<Vec<GpuInfo>>();
+    gpus.extend(nvidia.unwrap_or_default());
+    Some(gpus)
+}
+
+/// The marketing name of a PCI device, which only lspci can look up
+#[cfg(target_os = "linux")]
+fn pci_device_name(slot: &str) -> Option<String> {
+    let output = std::process::Command::new("lspci").args(["-vmm", "-s", slot]).output().ok().filter(|output| output.status.success())?;
+    String::from_utf8_lossy(&output.stdout).lines().find_map(|line| Some(line.strip_prefix("Device:")?.trim().to_string()))
+}
+
+#[cfg(target_os = "macos")]
+pub fn gpu_information() -> Option<Vec<GpuInfo>> {
+    let output = std::process::Command::new("system_profiler")
+        .arg("SPDisplaysDataType")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    let mut gpus = parse_system_profiler(&String::from_utf8_lossy(&output.stdout));
+    // Neither ioreg nor powermetrics say which GPU they mean, which only
+    // matters on the few Intel Macs with two
+    if let [gpu] = gpus.as_mut_slice() {
+        gpu.utilization = std::process::Command::new("ioreg")
+            .args(["-r", "-d", "1", "-w", "0", "-c", "IOAccelerator"])
+            .output()
+            .ok()
+            .and_then(|output| {
+                let output = String::from_utf8_lossy(&output.stdout).into_owned();
+                let (_, rest) = output.split_once("\"Device Utilization %\"=")?;
+                rest.split(|character: char| !character.is_ascii_digit()).next()?.parse().ok()
+            });
+        gpu.temperature = crate::sensors::sensor_readings().and_then(|readings| readings.temperatures.into_iter().find_map(|(name, temperature)| name.contains("GPU").then_some(temperature)));
+    }
+    Some(gpus)
+}
+
+// Every GPU starts with its model, followed by indented details and the
+// connected displays:
+//
+//     Apple M1:
+//
+//       Chipset Model: Apple M1
+//       Type: GPU
+//       Vendor: Apple (0x106b)
+//
+// Intel Macs also have "VRAM (Total): 1536 MB" or "VRAM (Dynamic, Max)".
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_system_profiler(output: &str) -> Vec<GpuInfo> {
+    let mut gpus = Vec::<GpuInfo>::new();
+    for line in output.lines() {
+        let Some((key, value)) = line.trim().split_once(": ") else {
+            continue;
+        };
+        if key == "Chipset Model" {
+            gpus.push(GpuInfo {
+                model: Some(value.to_string()),
+                ..GpuInfo::default()
+            });
+        } else if let Some(gpu) = gpus.last_mut() {
+            match key {
+                // The PCI ID follows in parentheses
+                "Vendor" => gpu.vendor = value.split(" (").next().unwrap_or(value).trim_start_matches("sppci_vendor_").to_string(),
+                key if key.starts_with("VRAM") => {
+                    gpu.vram_total = value.split_once(' ').and_then(|(amount, unit)| {
+                        Some(
+                            amount.parse::<u64>().ok()?
+                                * match unit {
+                                    "GB" => 1024 * 1024 * 1024,
+                                    "MB" => 1024 * 1024,
+                                    _ => return None,
+                                },
+                        )
+                    });
+                }
+                _ => {}
+            }
+        }
+    }
+    gpus
+}
+
+#[cfg(windows)]
+pub fn gpu_information() -> Option<Vec<GpuInfo>> {
+    let nvidia = nvidia_smi();
+    let mut gpus = crate::performance_counters::video_controllers()?
+        .into_iter()
+        .filter(|gpu| nvidia.is_none() || gpu.vendor != "NVIDIA")
+        .collect::<Vec<GpuInfo>>();
+    // The engine counters can't be matched to an adapter without its LUID
+    if let [gpu] = gpus.as_mut_slice()
+        && let Some(engine_types) = crate::performance_counters::gpu_engine_utilization()
+    {
+        #[allow(clippy::cast_possible_truncation)]
+        let utilization = engine_types.values().copied().reduce(f64::max).map(|utilization| utilization as f32);
+        gpu.utilization = utilization;
+        gpu.engines = engines_from_counters(&engine_types);
+    }
+    gpus.extend(nvidia.unwrap_or_default());
+    Some(gpus)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub const fn gpu_information() -> Option<Vec<GpuInfo>> {
+    None
+}
+
+#[test]
+fn parse_gpu_tools() {
+    let nvidia = parse_nvidia_smi("NVIDIA GeForce RTX 3070, 8192, 1034, 7, 3, 0, 12, 45, 550.54.14\nTesla K80, 11441, 0, [N/A], [N/A], [N/A], [N/A], [N/A], [N/A]\n");
+    assert_eq!(nvidia[0], GpuInfo {
+        vendor:             "NVIDIA".to_string(),
+        model:              Some("NVIDIA GeForce RTX 3070".to_string()),
+        vram_total:         Some(8192 * 1024 * 1024),
+        vram_used:          Some(1034 * 1024 * 1024),
+        utilization:        Some(7.0),
+        engines:            GpuEngines {
+            graphics: None,
+            compute:  None,
+            encode:   Some(0.0),
+            decode:   Some(12.0),
+        },
+        memory_utilization: Some(3.0),
+        temperature:        Some(45.0),
+        driver_version:     Some("550.54.14".to_string()),
+    });
+    assert_eq!((nvidia[1].utilization, nvidia[1].driver_version.as_ref()), (None, None));
+
+    let apple = parse_system_profiler(
+        "Graphics/Displays:
+
+    Intel Iris Plus Graphics 655:
+
+      Chipset Model: Intel Iris Plus Graphics 655
+      Type: GPU
+      VRAM (Dynamic, Max): 1536 MB
+      Vendor: Intel (0x8086)
+      Displays:
+        Color LCD:
+          Resolution: 2560 x 1600 Retina
+",
+    );
+    assert_eq!(apple.len(), 1);
+    assert_eq!(apple[0].vendor, "Intel");
+    assert_eq!(apple[0].vram_total, Some(1536 * 1024 * 1024));
+
+    let engines = engines_from_counters(&BTreeMap::from([
+        ("3D".to_string(), 40.0),
+        ("Compute_0".to_string(), 5.0),
+        ("Compute_1".to_string(), 25.0),
+        ("Copy".to_string(), 1.0),
+        ("VideoDecode".to_string(), 3.0),
+    ]));
+    assert_eq!(engines, GpuEngines {
+        graphics: Some(40.0),
+        compute:  Some(25.0),
+        encode:   None,
+        decode:   Some(3.0),
+    });
+}
@@ -0,0 +1,199 @@
+// Some information is only readable by root, like the open files of other
+// users' processes. Instead of running the whole UI as root, `cli helper`
+// can be started through sudo or pkexec. It serves a handful of fixed
+// queries over a Unix socket in a directory only root can write to, hangs
+// up on everyone but the user who started it, and the backend falls back
+// to asking it whenever it can't read something itself. The protocol is
+// one request per line:
+//
+// > file_descriptors 1234 1240
+// < 1234 56 1024
+// < 1240 3 -
+// <
+// > smart /dev/sda sat
+// < Device Model:     Samsung SSD 860 EVO 500GB
+// < ...
+// <
+//
+// with the answer ending in an empty line, which is also the whole answer
+// if something couldn't be read. Only supported on Linux, on Windows this
+// would need UAC and named pipes, which std has no API for.
+
+use std::collections::HashMap;
+
+use crate::FileDescriptorUsage;
+
+/// Only root may write here. In a directory the user could write to they
+/// could swap the socket for a symlink while root is still setting it up
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+const SOCKET_DIRECTORY: &str = "/run/crossinfo";
+
+/// Where the helper for the user with `uid` listens
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn socket_path(uid: u32) -> std::path::PathBuf {
+    std::path::Path::new(SOCKET_DIRECTORY).join(format!("helper-{uid}.sock"))
+}
+
+/// Serves the current user until the process is killed. Has to run as
+/// root, and finds out who to serve from the variables sudo and pkexec set
+///
+/// # Errors
+///
+/// If not running as root through sudo or pkexec, or if the socket can't
+/// be created
+#[cfg(target_os = "linux")]
+pub fn run_helper() -> std::io::Result<()> {
+    use std::{
+        fmt::Write as _,
+        io::{BufRead, Write},
+        os::unix::fs::{DirBuilderExt, MetadataExt},
+    };
+
+    use nix::sys::{
+        socket::{getsockopt, sockopt::PeerCredentials},
+        stat::{umask, Mode},
+    };
+
+    if std::fs::metadata("/proc/self")?.uid() != 0 {
+        return Err(std::io::Error::new(std::io::ErrorKind::PermissionDenied, "The helper has to run as root, start it with sudo or pkexec"));
+    }
+    let uid = ["PKEXEC_UID", "SUDO_UID"]
+        .into_iter()
+        .find_map(|variable| std::env::var(variable).ok()?.parse::<u32>().ok())
+        .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "Couldn't find out which user started the helper"))?;
+    if let Err(error) = std::fs::DirBuilder::new().mode(0o755).create(SOCKET_DIRECTORY)
+        && error.kind() != std::io::ErrorKind::AlreadyExists
+    {
+        return Err(error);
+    }
+    let directory = std::fs::symlink_metadata(SOCKET_DIRECTORY)?;
+    if !directory.is_dir() || directory.uid() != 0 || directory.mode() & 0o022 != 0 {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("{SOCKET_DIRECTORY} has to be a directory only root can write to"),
+        ));
+    }
+    let path = socket_path(uid);
+    // Left over if the last helper was killed
+    let _ = std::fs::remove_file(&path);
+    // Anyone may connect, who gets an answer depends on the peer's
+    // credentials. Setting the mode through the umask means the path is
+    // never touched again after binding
+    let previous_umask = umask(Mode::from_bits_truncate(0o111));
+    let listener = std::os::unix::net::UnixListener::bind(&path);
+    umask(previous_umask);
+    let listener = listener?;
+    println!("Serving user {uid} at {}, stop with Ctrl+C", path.display());
+
+    for stream in listener.incoming() {
+        let Ok(stream) = stream else {
+            continue;
+        };
+        if !getsockopt(&stream, PeerCredentials).is_ok_and(|credentials| credentials.uid() == uid) {
+            continue;
+        }
+        let mut writer = &stream;
+        for request in std::io::BufReader::new(&stream).lines() {
+            let Ok(request) = request else {
+                break;
+            };
+            let answer = match request.split_whitespace().collect::<Vec<&str>>().as_slice() {
+                ["file_descriptors", pids @ ..] => pids
+                    .iter()
+                    .filter_map(|pid| {
+                        let pid = pid.parse::<usize>().ok()?;
+                        Some((pid, crate::processes::process_file_descriptors(sysinfo::Pid::from(pid))?))
+                    })
+                    .fold(String::new(), |mut answer, (pid, usage)| {
+                        let limit = usage.limit.map_or_else(|| "-".to_string(), |limit| limit.to_string());
+                        let _ = writeln!(answer, "{pid} {} {limit}", usage.open);
+                        answer
+                    }),
+                // Empty lines would end the answer early
+                ["smart", device, device_type] => crate::smart::scanned_drive(device, device_type)
+                    .unwrap_or_default()
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .fold(String::new(), |mut answer, line| {
+                        let _ = writeln!(answer, "{line}");
+                        answer
+                    }),
+                _ => String::new(),
+            };
+            if writer.write_all(format!("{answer}\n").as_bytes()).is_err() {
+                break;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// # Errors
+///
+/// Always, as the helper is only supported on Linux
+#[cfg(not(target_os = "linux"))]
+pub fn run_helper() -> std::io::Result<()> {
+    Err(std::io::Error::new(std::io::ErrorKind::Unsupported, "The helper is only supported on Linux"))
+}
+
+/// Sends a request to the helper and returns the lines of the answer, or
+/// `None` if no helper is running
+#[cfg(target_os = "linux")]
+fn request(request: &str) -> Option<Vec<String>> {
+    use std::{
+        io::{BufRead, Write},
+        os::unix::fs::MetadataExt,
+    };
+
+    let uid = std::fs::metadata("/proc/self").ok()?.uid();
+    let mut stream = std::os::unix::net::UnixStream::connect(socket_path(uid)).ok()?;
+    stream.set_read_timeout(Some(std::time::Duration::from_secs(2))).ok()?;
+    stream.write_all(format!("{request}\n").as_bytes()).ok()?;
+    Some(std::io::BufReader::new(stream).lines().map_while(Result::ok).take_while(|line| !line.is_empty()).collect())
+}
+
+/// Open file descriptors of the processes with `pids`, keyed by PID
+#[cfg(target_os = "linux")]
+pub fn file_descriptors(pids: &[u32]) -> Option<HashMap<u32, FileDescriptorUsage>> {
+    let pids = pids.iter().map(u32::to_string).collect::<Vec<String>>();
+    Some(parse_file_descriptors(&request(&format!("file_descriptors {}", pids.join(" ")))?))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn file_descriptors(_pids: &[u32]) -> Option<HashMap<u32, FileDescriptorUsage>> {
+    None
+}
+
+/// What smartctl printed for `device` when the helper ran it as root
+#[cfg(target_os = "linux")]
+pub fn smartctl(device: &str, device_type: &str) -> Option<String> {
+    let lines = request(&format!("smart {device} {device_type}"))?;
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn smartctl(_device: &str, _device_type: &str) -> Option<String> {
+    None
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_file_descriptors(lines: &[String]) -> HashMap<u32, FileDescriptorUsage> {
+    lines
+        .iter()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some((fields.next()?.parse().ok()?, FileDescriptorUsage {
+                open:  fields.next()?.parse().ok()?,
+                limit: fields.next()?.parse().ok(),
+            }))
+        })
+        .collect()
+}
+
+#[test]
+fn parse_helper_answer() {
+    let usage = parse_file_descriptors(&["1234 56 1024".to_string(), "1240 3 -".to_string(), "garbage".to_string()]);
+    assert_eq!(usage.len(), 2);
+    assert_eq!(usage[&1234], FileDescriptorUsage { open: 56, limit: Some(1024) });
+    assert_eq!(usage[&1240].limit, None);
+}
@@ -0,0 +1,116 @@
+// What the system settings call a network interface, since names like en0
+// or {4D36E972-E325-11CE-BFC1-08002BE10318} don't tell users which one is
+// the Wi-Fi:
+//
+// - macOS: `networksetup -listallhardwareports`, which reads the hardware
+//   ports from SCNetworkInterface, e.g. "Wi-Fi" for en0 or "Thunderbolt
+//   Bridge" for bridge0
+// - Windows: the connection names of Win32_NetworkAdapter through WMI,
+//   e.g. "Ethernet 2", matched by the GUID pnet names interfaces after
+// - Linux names like wlp2s0 already say what they are, and there is no
+//   other name to show
+
+use std::collections::HashMap;
+
+use crate::Network;
+
+/// Sets `display_name` of every interface the system has another name for
+pub fn add_display_names(networks: &mut [Network]) {
+    let Some(names) = display_names() else {
+        return;
+    };
+    for network in networks {
+        network.display_name = display_name(&network.name, &names);
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn display_names() -> Option<HashMap<String, String>> {
+    let output = std::process::Command::new("networksetup").arg("-listallhardwareports").output().ok().filter(|output| output.status.success())?;
+    Some(parse_hardware_ports(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(windows)]
+fn display_names() -> Option<HashMap<String, String>> {
+    crate::performance_counters::network_adapters().map(|rows| parse_network_adapters(&rows))
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+const fn display_names() -> Option<HashMap<String, String>> {
+    None
+}
+
+/// `names` is keyed by interface name on macOS and by GUID on Windows,
+/// where pnet calls interfaces \Device\NPF_{GUID}. Nothing if the
+/// interface already goes by its display name
+#[cfg_attr(not(any(target_os = "macos", windows)), allow(dead_code))]
+fn display_name(name: &str, names: &HashMap<String, String>) -> Option<String> {
+    let display_name = names.get(name).or_else(|| {
+        let guid = name.get(name.find('{')?..=name.find('}')?)?;
+        names.iter().find(|(key, _)| key.eq_ignore_ascii_case(guid)).map(|(_, display_name)| display_name)
+    })?;
+    (display_name != name).then(|| display_name.clone())
+}
+
+// A block per port:
+//
+// Hardware Port: Wi-Fi
+// Device: en0
+// Ethernet Address: a4:83:e7:12:34:56
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_hardware_ports(ports: &str) -> HashMap<String, String> {
+    let mut names = HashMap::new();
+    let mut port = None;
+    for line in ports.lines() {
+        if let Some(name) = line.strip_prefix("Hardware Port:") {
+            port = Some(name.trim().to_string());
+        } else if let Some(device) = line.strip_prefix("Device:")
+            && let Some(port) = port.take()
+        {
+            names.insert(device.trim().to_string(), port);
+        }
+    }
+    names
+}
+
+// GUID and NetConnectionID, e.g.
+// "{8E2A0F7C-52B1-4C3A-9D4E-1F2A3B4C5D6E}","Ethernet 2"
+// Adapters that were never connected have neither
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_network_adapters(rows: &[Vec<String>]) -> HashMap<String, String> {
+    rows.iter()
+        .filter_map(|row| match row.as_slice() {
+            [guid, name] if !guid.is_empty() && !name.is_empty() => Some((guid.clone(), name.clone())),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn parse_interface_names() {
+    let names = parse_hardware_ports(
+        "
+Hardware Port: Ethernet Adapter (en4)
+Device: en4
+Ethernet Address: 1a:2b:3c:4d:5e:6f
+
+Hardware Port: Wi-Fi
+Device: en0
+Ethernet Address: a4:83:e7:12:34:56
+
+VLAN Configurations
+===================
+",
+    );
+    assert_eq!(names.len(), 2);
+    assert_eq!(display_name("en0", &names).as_deref(), Some("Wi-Fi"));
+    assert_eq!(display_name("utun3", &names), None);
+
+    let names = parse_network_adapters(&[
+        vec!["{8E2A0F7C-52B1-4C3A-9D4E-1F2A3B4C5D6E}".to_string(), "Ethernet 2".to_string()],
+        vec![String::new(), String::new()],
+    ]);
+    assert_eq!(names.len(), 1);
+    assert_eq!(display_name(r"\Device\NPF_{8e2a0f7c-52b1-4c3a-9d4e-1f2a3b4c5d6e}", &names).as_deref(), Some("Ethernet 2"));
+    assert_eq!(display_name("Ethernet 2", &names), None);
+}
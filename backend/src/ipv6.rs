@@ -0,0 +1,304 @@
+// IPv6 details the interface list leaves out. On a dual stack network one
+// protocol can work while the other doesn't, so the addresses, the
+// default gateway and the privacy extensions are read on their own:
+//
+// - Linux: /proc/net/if_inet6, /proc/net/ipv6_route and the use_tempaddr
+//   sysctl of every interface
+// - macOS: ifconfig, `netstat -rn` and the use_tempaddr sysctl, which
+//   applies to every interface
+// - Windows: only the addresses of the interface list, without prefix
+//   lengths or whether they are temporary
+//
+// The scope follows from the address itself on every platform.
+
+use std::{collections::HashMap, net::Ipv6Addr};
+
+use crate::Network;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Ipv6Scope {
+    /// Reachable from the internet
+    Global,
+    /// fc00::/7, the IPv6 equivalent of private IPv4 ranges
+    UniqueLocal,
+    /// fe80::/10, only valid on the link, e.g. to reach the router
+    LinkLocal,
+    Loopback,
+}
+
+impl From<Ipv6Addr> for Ipv6Scope {
+    fn from(address: Ipv6Addr) -> Self {
+        let first_segment = address.segments()[0];
+        if address.is_loopback() {
+            Self::Loopback
+        } else if first_segment & 0xffc0 == 0xfe80 {
+            Self::LinkLocal
+        } else if first_segment & 0xfe00 == 0xfc00 {
+            Self::UniqueLocal
+        } else {
+            Self::Global
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Ipv6Address {
+    pub address:       Ipv6Addr,
+    pub prefix_length: Option<u8>,
+    pub scope:         Ipv6Scope,
+    /// A privacy extension address (RFC 4941), which is replaced
+    /// regularly so websites can't follow the device around. `None` if
+    /// unknown
+    pub temporary:     Option<bool>,
+}
+
+impl From<Ipv6Addr> for Ipv6Address {
+    fn from(address: Ipv6Addr) -> Self {
+        Self {
+            address,
+            prefix_length: None,
+            scope: address.into(),
+            temporary: None,
+        }
+    }
+}
+
+/// Fills in the IPv6 addresses and privacy extensions of every interface
+pub fn add_ipv6_details(networks: &mut [Network]) {
+    let mut addresses = interface_addresses().unwrap_or_default();
+    for network in networks {
+        // Without anything better, the addresses pnet found
+        let ipv6_addresses = addresses.remove(&network.name).or_else(|| {
+            network.ips.as_ref().map(|ips| {
+                ips.iter()
+                    .filter_map(|ip| match ip {
+                        std::net::IpAddr::V6(address) => Some(Ipv6Address::from(*address)),
+                        std::net::IpAddr::V4(_) => None,
+                    })
+                    .collect()
+            })
+        });
+        network.ipv6_privacy_extensions = privacy_extensions(&network.name).or_else(|| ipv6_addresses.as_ref()?.iter().any(|address| address.temporary == Some(true)).then_some(true));
+        network.ipv6_addresses = ipv6_addresses;
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn interface_addresses() -> Option<HashMap<String, Vec<Ipv6Address>>> {
+    Some(parse_if_inet6(&std::fs::read_to_string("/proc/net/if_inet6").ok()?))
+}
+
+#[cfg(target_os = "macos")]
+fn interface_addresses() -> Option<HashMap<String, Vec<Ipv6Address>>> {
+    let output = std::process::Command::new("ifconfig").output().ok().filter(|output| output.status.success())?;
+    Some(parse_ifconfig(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+const fn interface_addresses() -> Option<HashMap<String, Vec<Ipv6Address>>> {
+    None
+}
+
+// 0 turns them off, 1 creates temporary addresses but prefers the public
+// one and 2 prefers the temporary ones
+#[cfg(target_os = "linux")]
+fn privacy_extensions(interface: &str) -> Option<bool> {
+    let use_tempaddr = std::fs::read_to_string(format!("/proc/sys/net/ipv6/conf/{interface}/use_tempaddr")).ok()?;
+    Some(use_tempaddr.trim().parse::<i32>().ok()? > 0)
+}
+
+#[cfg(target_os = "macos")]
+fn privacy_extensions(_interface: &str) -> Option<bool> {
+    let output = std::process::Command::new("sysctl")
+        .args(["-n", "net.inet6.ip6.use_tempaddr"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).trim().parse::<i32>().ok()? > 0)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+const fn privacy_extensions(_interface: &str) -> Option<bool> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+pub fn default_gateway() -> Option<Ipv6Addr> {
+    parse_ipv6_route(&std::fs::read_to_string("/proc/net/ipv6_route").ok()?)
+}
+
+#[cfg(target_os = "macos")]
+pub fn default_gateway() -> Option<Ipv6Addr> {
+    let output = std::process::Command::new("netstat")
+        .args(["-rn", "-f", "inet6"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    parse_netstat(&String::from_utf8_lossy(&output.stdout))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub const fn default_gateway() -> Option<Ipv6Addr> {
+    None
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn hex_address(hex: &str) -> Option<Ipv6Addr> {
+    Some(Ipv6Addr::from(u128::from_str_radix(hex, 16).ok()?))
+}
+
+// The address, the interface index, prefix length, scope and flags in hex
+// and the interface name:
+//
+// fe80000000000000021a2bfffe3c4d5e 02 40 20 80     eth0
+//
+// 0x01 in the flags marks temporary addresses.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_if_inet6(if_inet6: &str) -> HashMap<String, Vec<Ipv6Address>> {
+    let mut interfaces = HashMap::<String, Vec<Ipv6Address>>::new();
+    for line in if_inet6.lines() {
+        let [address, _, prefix_length, _, flags, name] = line.split_whitespace().collect::<Vec<&str>>()[..] else {
+            continue;
+        };
+        let (Some(address), Ok(prefix_length), Ok(flags)) = (hex_address(address), u8::from_str_radix(prefix_length, 16), u32::from_str_radix(flags, 16)) else {
+            continue;
+        };
+        interfaces.entry(name.to_string()).or_default().push(Ipv6Address {
+            address,
+            prefix_length: Some(prefix_length),
+            scope: address.into(),
+            temporary: Some(flags & 0x01 != 0),
+        });
+    }
+    interfaces
+}
+
+// Destination and its prefix length, source and its prefix length, next
+// hop, metric, reference count, use count, flags and interface, all in
+// hex. The default route goes to ::/0 through a next hop that isn't ::
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_ipv6_route(ipv6_route: &str) -> Option<Ipv6Addr> {
+    ipv6_route
+        .lines()
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            let [destination, destination_length, _, _, next_hop, metric, ..] = fields[..] else {
+                return None;
+            };
+            let next_hop = hex_address(next_hop)?;
+            (hex_address(destination)?.is_unspecified() && destination_length == "00" && !next_hop.is_unspecified()).then_some((u32::from_str_radix(metric, 16).ok()?, next_hop))
+        })
+        .min()
+        .map(|(_, next_hop)| next_hop)
+}
+
+// An interface starts at the beginning of a line, its addresses are
+// indented:
+//
+// en0: flags=8863<UP,BROADCAST,SMART,RUNNING,SIMPLEX,MULTICAST> mtu 1500
+// 	inet6 fe80::1c2b:3c4d:5e6f:7a8b%en0 prefixlen 64 secured scopeid 0x6
+// 	inet6 2001:db8::abcd prefixlen 64 autoconf temporary
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_ifconfig(ifconfig: &str) -> HashMap<String, Vec<Ipv6Address>> {
+    let mut interfaces = HashMap::<String, Vec<Ipv6Address>>::new();
+    let mut interface = None;
+    for line in ifconfig.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            interface = line.split_once(':').map(|(name, _)| name.to_string());
+            continue;
+        }
+        let words = line.split_whitespace().collect::<Vec<&str>>();
+        if let (Some(interface), ["inet6", address, rest @ ..]) = (&interface, words.as_slice())
+            // Link-local addresses have the interface appended as the zone
+            && let Ok(address) = address.split('%').next().unwrap_or_default().parse::<Ipv6Addr>()
+        {
+            interfaces.entry(interface.clone()).or_default().push(Ipv6Address {
+                address,
+                prefix_length: rest.iter().skip_while(|word| **word != "prefixlen").nth(1).and_then(|length| length.parse().ok()),
+                scope: address.into(),
+                temporary: Some(rest.contains(&"temporary")),
+            });
+        }
+    }
+    interfaces
+}
+
+// Destination, gateway, flags and interface. Tunnels like utun0 have a
+// default route too, through a gateway without a host part:
+//
+// default                                 fe80::1%en0
+// UGcg                en0 default
+// fe80::%utun0                    UGcIg             utun0
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_netstat(netstat: &str) -> Option<Ipv6Addr> {
+    netstat.lines().find_map(|line| {
+        let mut fields = line.split_whitespace();
+        if fields.next()? != "default" {
+            return None;
+        }
+        let gateway = fields.next()?.split('%').next()?;
+        if gateway.ends_with("::") {
+            return None;
+        }
+        gateway.parse().ok()
+    })
+}
+
+#[test]
+fn parse_ipv6_details() {
+    let linux = parse_if_inet6(
+        "00000000000000000000000000000001 01 80 10 80       lo
+fe80000000000000021a2bfffe3c4d5e 02 40 20 80     eth0
+20010db8000000001a2b3c4d5e6f7a8b 02 40 00 01     eth0
+",
+    );
+    assert_eq!(linux["lo"][0].scope, Ipv6Scope::Loopback);
+    assert_eq!(linux["eth0"], vec![
+        Ipv6Address {
+            address:       "fe80::21a:2bff:fe3c:4d5e".parse().unwrap_or(Ipv6Addr::UNSPECIFIED),
+            prefix_length: Some(64),
+            scope:         Ipv6Scope::LinkLocal,
+            temporary:     Some(false),
+        },
+        Ipv6Address {
+            address:       "2001:db8::1a2b:3c4d:5e6f:7a8b".parse().unwrap_or(Ipv6Addr::UNSPECIFIED),
+            prefix_length: Some(64),
+            scope:         Ipv6Scope::Global,
+            temporary:     Some(true),
+        },
+    ]);
+    assert_eq!(Ipv6Scope::from("fd12:3456::1".parse::<Ipv6Addr>().unwrap_or(Ipv6Addr::UNSPECIFIED)), Ipv6Scope::UniqueLocal);
+
+    assert_eq!(
+        parse_ipv6_route(
+            "fe800000000000000000000000000000 40 00000000000000000000000000000000 00 00000000000000000000000000000000 00000100 00000001 00000000 00000001     eth0
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 fe800000000000000000000000000001 00000400 00000001 00000000 00450003     eth0
+00000000000000000000000000000000 00 00000000000000000000000000000000 00 fe800000000000000000000000000002 00000064 00000001 00000000 00450003    wlan0
+"
+        ),
+        "fe80::2".parse().ok()
+    );
+
+    let macos = parse_ifconfig(
+        "lo0: flags=8049<UP,LOOPBACK,RUNNING,MULTICAST> mtu 16384
+	inet 127.0.0.1 netmask 0xff000000
+	inet6 ::1 prefixlen 128
+en0: flags=8863<UP,BROADCAST,SMART,RUNNING,SIMPLEX,MULTICAST> mtu 1500
+	inet6 fe80::1c2b:3c4d:5e6f:7a8b%en0 prefixlen 64 secured scopeid 0x6
+	inet6 2001:db8::abcd prefixlen 64 autoconf temporary
+",
+    );
+    assert_eq!(macos["en0"].len(), 2);
+    assert_eq!(macos["en0"][0].scope, Ipv6Scope::LinkLocal);
+    assert_eq!((macos["en0"][1].prefix_length, macos["en0"][1].temporary), (Some(64), Some(true)));
+
+    assert_eq!(
+        parse_netstat(
+            "Routing tables\n\nInternet6:\nDestination        Gateway        Flags         Netif Expire\ndefault            fe80::%utun0   UGcIg         utun0\ndefault            fe80::1%en0    \
+             UGcg            en0\n"
+        ),
+        "fe80::1".parse().ok()
+    );
+}
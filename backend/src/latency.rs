@@ -0,0 +1,171 @@
+// Block IO and run queue latency, traced with eBPF. Utilization alone
+// doesn't explain stutter, a disk at 30% can still make a process wait
+// 200ms for a read. Loading BPF programs from Rust needs either unsafe
+// code or a whole toolchain for compiling them, so this hands small
+// scripts to `bpftrace` and parses the histograms it prints. Needs root
+// and is only compiled in with the `ebpf` feature.
+
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LatencyBucket {
+    pub low:   Duration,
+    /// Exclusive
+    pub high:  Duration,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LatencyHistogram {
+    /// The process the histogram belongs to, `None` for system wide ones
+    pub name:    Option<String>,
+    pub buckets: Vec<LatencyBucket>,
+}
+
+impl LatencyHistogram {
+    #[must_use]
+    pub fn count(&self) -> u64 {
+        self.buckets.iter().map(|bucket| bucket.count).sum()
+    }
+
+    /// Upper bound of the bucket containing the given percentile, e.g.
+    /// 0.99 for the p99 latency
+    #[must_use]
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+    pub fn percentile(&self, percentile: f64) -> Option<Duration> {
+        let target = (self.count() as f64 * percentile.clamp(0.0, 1.0)).ceil() as u64;
+        let mut seen = 0;
+        self.buckets.iter().find_map(|bucket| {
+            seen += bucket.count;
+            (seen >= target.max(1)).then_some(bucket.high)
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct LatencyInfo {
+    /// Time from issuing a block IO request to its completion, per process
+    pub block_io:  Vec<LatencyHistogram>,
+    /// Time from a task becoming runnable to it actually running
+    pub run_queue: Option<LatencyHistogram>,
+}
+
+// Based on biolatency.bt and runqlat.bt from the bpftrace tools. A
+// prev_state of 0 is TASK_RUNNING, i.e. the task got preempted and is
+// still waiting to run.
+#[cfg(target_os = "linux")]
+const SCRIPT: &str = r"
+tracepoint:block:block_rq_issue { @start[args->dev, args->sector] = nsecs; @issuer[args->dev, args->sector] = comm; }
+tracepoint:block:block_rq_complete /@start[args->dev, args->sector]/ {
+    @block_io[@issuer[args->dev, args->sector]] = hist((nsecs - @start[args->dev, args->sector]) / 1000);
+    delete(@start[args->dev, args->sector]);
+    delete(@issuer[args->dev, args->sector]);
+}
+tracepoint:sched:sched_wakeup, tracepoint:sched:sched_wakeup_new { @queued[args->pid] = nsecs; }
+tracepoint:sched:sched_switch {
+    if (args->prev_state == 0) { @queued[args->prev_pid] = nsecs; }
+    $queued = @queued[args->next_pid];
+    if ($queued) { @run_queue = hist((nsecs - $queued) / 1000); }
+    delete(@queued[args->next_pid]);
+}
+END { clear(@start); clear(@issuer); clear(@queued); }
+";
+
+/// Traces for `duration` and blocks until then
+#[cfg(target_os = "linux")]
+pub fn latency(duration: Duration) -> Option<LatencyInfo> {
+    let script = format!("{SCRIPT}interval:ms:{} {{ exit(); }}\n", duration.as_millis().max(1));
+    let output = std::process::Command::new("bpftrace").args(["-e", &script]).output().ok().filter(|output| output.status.success())?;
+    let mut histograms = parse_histograms(&String::from_utf8_lossy(&output.stdout));
+    let run_queue = histograms.iter().position(|(map, _)| map == "run_queue").map(|index| histograms.remove(index).1);
+    Some(LatencyInfo {
+        block_io: histograms.into_iter().filter(|(map, _)| map == "block_io").map(|(_, histogram)| histogram).collect(),
+        run_queue,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn latency(_duration: Duration) -> Option<LatencyInfo> {
+    None
+}
+
+// Buckets are labelled "[0]", "[1]", "[2, 4)" up to "[1K, 2K)" or
+// "[1M, 2M)", with powers of 1024
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_bucket_bound(bound: &str) -> Option<u64> {
+    let bound = bound.trim();
+    let (number, multiplier) = match bound.chars().last()? {
+        'K' => (&bound[..bound.len() - 1], 1 << 10),
+        'M' => (&bound[..bound.len() - 1], 1 << 20),
+        'G' => (&bound[..bound.len() - 1], 1 << 30),
+        _ => (bound, 1),
+    };
+    number.parse::<u64>().ok().map(|number| number * multiplier)
+}
+
+// bpftrace prints every histogram as a header followed by its buckets:
+//
+// @block_io[postgres]:
+// [16, 32)     3 |@@@@@            |
+// [32, 64)    12 |@@@@@@@@@@@@@@@@@|
+//
+// Returns the name of the map along with the histogram
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_histograms(output: &str) -> Vec<(String, LatencyHistogram)> {
+    let mut histograms: Vec<(String, LatencyHistogram)> = Vec::new();
+    for line in output.lines() {
+        if let Some(header) = line.strip_prefix('@').and_then(|header| header.trim_end().strip_suffix(':')) {
+            let (map, name) = match header.split_once('[') {
+                Some((map, name)) => (map, name.strip_suffix(']').map(ToString::to_string)),
+                None => (header, None),
+            };
+            histograms.push((map.to_string(), LatencyHistogram { name, buckets: Vec::new() }));
+        } else if let Some((_, histogram)) = histograms.last_mut()
+            && let Some((range, rest)) = line.strip_prefix('[').and_then(|line| line.split_once([')', ']']))
+            && let Some(count) = rest.split_whitespace().next().and_then(|count| count.parse().ok())
+        {
+            let (low, high) = match range.split_once(',') {
+                Some((low, high)) => (parse_bucket_bound(low), parse_bucket_bound(high)),
+                None => (parse_bucket_bound(range), parse_bucket_bound(range).map(|low| low + 1)),
+            };
+            if let (Some(low), Some(high)) = (low, high) {
+                histogram.buckets.push(LatencyBucket {
+                    low: Duration::from_micros(low),
+                    high: Duration::from_micros(high),
+                    count,
+                });
+            }
+        }
+    }
+    histograms
+}
+
+#[test]
+fn parse_bpftrace_histograms() {
+    let histograms = parse_histograms(
+        "Attaching 6 probes...
+
+
+@block_io[postgres]:
+[16, 32)               3 |@@@@@                                               |
+[32, 64)              12 |@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@|
+[1K, 2K)               1 |@@@@                                                |
+
+@run_queue:
+[0]                   40 |@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@@|
+[1]                   10 |@@@@@@@@@@@@@                                       |
+",
+    );
+    assert_eq!(histograms.len(), 2);
+    let (map, block_io) = &histograms[0];
+    assert_eq!(map, "block_io");
+    assert_eq!(block_io.name.as_deref(), Some("postgres"));
+    assert_eq!(block_io.count(), 16);
+    assert_eq!(block_io.buckets[2].low, Duration::from_micros(1024));
+    assert_eq!(block_io.percentile(0.5), Some(Duration::from_micros(64)));
+    assert_eq!(block_io.percentile(1.0), Some(Duration::from_micros(2048)));
+    let (map, run_queue) = &histograms[1];
+    assert_eq!(map, "run_queue");
+    assert_eq!(run_queue.name, None);
+    assert_eq!(run_queue.buckets[1].high, Duration::from_micros(2));
+}
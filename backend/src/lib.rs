@@ -21,70 +21,121 @@ Frontend checklist: These things should be in any crossinfo-frontend
 use std::{
     hash::Hash,
     sync::atomic::{AtomicBool, Ordering},
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 use battery::units::{electric_potential::volt, energy::watt_hour};
-use btleplug::api::{Central as _, Manager as _, Peripheral as _};
 pub use strum::{EnumCount, IntoEnumIterator};
 pub use strum_macros::{EnumCount as EnumCountMacro, EnumIter};
-use sysinfo::{Components, Disks, Networks, System, Users};
-use uom::si::{f64::Frequency, frequency::megahertz};
-
-#[derive(EnumIter, EnumCountMacro, Debug, Copy, Clone)]
-pub enum Tab {
-    /// OS information, Users, Kernel version,
-    /// etc.
-    System,
-    /// CPU usage, model, manufacturer, specs
-    Cpu,
-    /// RAM amount, usage, model, SWAP (specs
-    /// maybe?)
-    Memory,
-    /// Disk amount, usage (specs maybe? disk
-    /// speed benchmark maybe?)
-    Disk,
-    // One day, there doesn't seem to be a good crate or unified method to get GPU info like usage
-    // and model
-    // Gpu,
-    /// Installed battery/batteries info like
-    /// charge, capacity, cycles, state
-    /// (charching, etc.), health
-    Battery,
-    /// Speedtest using reqwest and speedtest.net
-    /// api, Network usage, available WiFi
-    /// connections (LAN detection maybe?)
-    Network,
-    /// CPU/RAM/SWAP/Disk usage, killing the
-    /// process, extra nerd info like PID, exe
-    /// path, etc
-    Processes,
-    /// Name, temperature, sometimes critical
-    /// temperatures
-    Components,
-    /// ID, display resolution, rotation and scale factor
-    Display,
-    /// ID-String, address, name, transmission strength, signal strength,
-    /// connection status
-    Bluetooth,
-}
+use sysinfo::{Components, Disks, Groups, Networks, ProcessRefreshKind, System, UpdateKind, Users};
+use uom::si::{
+    f64::{ElectricPotential, Energy, Frequency, Power},
+    frequency::megahertz,
+};
 
-impl std::fmt::Display for Tab {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "{}", match self {
-            Self::System => "System",
-            Self::Cpu => "CPU",
-            Self::Memory => "Memory/SWAP",
-            Self::Disk => "Disks",
-            Self::Battery => "Battery",
-            Self::Network => "Networks",
-            Self::Processes => "Processes",
-            Self::Components => "Components",
-            Self::Display => "Display",
-            Self::Bluetooth => "Bluetooth",
-        })
-    }
-}
+mod alerts;
+#[cfg(feature = "async")]
+mod async_manager;
+mod audio;
+mod batteries;
+mod benchmark;
+mod bluetooth;
+mod boot;
+mod categories;
+mod connectivity;
+mod cpu_features;
+mod cpu_frequency;
+mod cpu_states;
+mod crashes;
+mod dirs;
+mod disk_activity;
+mod disk_temperature;
+mod error;
+mod extremes;
+mod gpu;
+mod helper;
+mod interface_names;
+mod ipv6;
+#[cfg(feature = "ebpf")]
+mod latency;
+mod memory;
+mod network_extra;
+mod numa;
+mod open_files;
+mod performance_counters;
+mod physical_disks;
+#[cfg(feature = "plugins")]
+mod plugins;
+mod processes;
+#[cfg(feature = "remote")]
+mod remote;
+mod sandbox;
+mod scheduler;
+mod security;
+mod sensors;
+#[cfg(feature = "serde")]
+mod serialization;
+#[cfg(feature = "server")]
+mod server;
+mod session;
+mod smart;
+mod snapshot;
+mod sockets;
+mod speedtest;
+mod storage_pools;
+mod subscription;
+mod threads;
+#[cfg(any(feature = "server", feature = "remote"))]
+mod token;
+mod traffic;
+mod units;
+mod wifi_profiles;
+
+pub use alerts::{Alert, AlertSeverity, BatteryAlertRules, BatteryAlerts};
+#[cfg(feature = "async")]
+pub use async_manager::AsyncManager;
+pub use audio::{AudioDevice, AudioDirection, AudioInfo, AudioStream};
+pub use batteries::{combine_batteries, CombinedBattery};
+pub use benchmark::{BenchmarkConfig, CpuBenchmark, DiskBenchmark, MemoryBenchmark};
+pub use bluetooth::{BDAddr, BluetoothAdapterInfo};
+pub use boot::{BootRecord, BootTimes};
+pub use categories::{category, InfoCategory, CATEGORIES};
+pub use connectivity::{ConnectivityCheck, ConnectivityState};
+pub use cpu_features::{CacheInfo, CacheKind, CpuFeatures};
+pub use cpu_states::{CpuStateBreakdown, CpuStates};
+pub use crashes::CrashReport;
+pub use dirs::{cache_directory, config_directory, data_directory};
+pub use error::Error;
+pub use extremes::{TemperatureExtremes, TemperatureRange};
+pub use gpu::{GpuEngines, GpuInfo};
+pub use helper::run_helper;
+pub use ipv6::{Ipv6Address, Ipv6Scope};
+#[cfg(feature = "ebpf")]
+pub use latency::{LatencyBucket, LatencyHistogram, LatencyInfo};
+pub use memory::{OomKillEvent, SwapDevice, SwapKind};
+pub use numa::{HugePages, NumaInfo, NumaNode};
+pub use open_files::{OpenFile, OpenFileKind};
+pub use physical_disks::{DiskBus, PhysicalDiskInfo};
+#[cfg(feature = "plugins")]
+pub use plugins::{PluginInfo, PluginItem, PluginReading};
+pub use processes::{aggregate_children, FileDescriptorUsage, IoPriority, IoPriorityClass, OwnUsage, ProcessExit, ProcessId, Termination, PRIORITY_RANGE};
+#[cfg(feature = "remote")]
+pub use remote::{AgentCapability, AgentConfig, RemoteManager, TlsIdentity};
+pub use sandbox::{SandboxInfo, SandboxKind, SandboxedApplication};
+pub use scheduler::{ContextSwitches, SchedulerActivity};
+pub use security::{CpuSecurityInfo, CpuVulnerability, MandatoryAccessControlInfo, MandatoryAccessControlMode, MandatoryAccessControlProfile, MandatoryAccessControlSystem, MitigationStatus};
+pub use sensors::{FanInfo, PowerRailInfo};
+pub use session::SessionActivity;
+pub use smart::{DiskSmartInfo, SmartAttribute, SmartHealth};
+pub use snapshot::{ListeningSocket, Snapshot, SnapshotDiff};
+pub use sockets::TopTalker;
+pub use speedtest::{Speedtest, SpeedtestPhase, SpeedtestProgress, SpeedtestServer};
+pub use storage_pools::{StoragePoolHealth, StoragePoolInfo, StoragePoolKind, StoragePoolMember};
+pub use subscription::{RefreshSpec, Update};
+pub use threads::{ThreadInfo, ThreadKind};
+pub use traffic::{TrafficHistory, TrafficUsage};
+pub use units::{format_duration, format_energy, format_voltage, ByteUnit, NumberFormat, TemperatureUnit};
+pub use wifi_profiles::WifiProfile;
 
 // constants to indicate if there is support for
 // the crates used for the information
@@ -107,20 +158,47 @@ fn populate_battery_support() {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SystemInfo {
     pub os:             Option<String>,
     pub os_version:     Option<String>,
     pub kernel_version: Option<String>,
     pub users:          Vec<String>,
     pub uptime:         Duration,
+    /// Not available on Windows
+    pub load_average:   Option<LoadAverage>,
+}
+
+/// How many processes were running or waiting to run, averaged over the
+/// last one, five and fifteen minutes. On Linux this includes processes
+/// waiting for IO
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LoadAverage {
+    pub one:     f64,
+    pub five:    f64,
+    pub fifteen: f64,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CpuInfo {
-    pub usage:        f32,
-    pub model:        String,
-    pub manufacturer: String,
-    pub frequency:    Frequency,
+    pub usage:             f32,
+    pub model:             String,
+    pub manufacturer:      String,
+    pub frequency:         Frequency,
+    /// The range the core can be clocked in. Only on Linux and Intel Macs
+    pub minimum_frequency: Option<Frequency>,
+    pub maximum_frequency: Option<Frequency>,
+    /// The advertised frequency without turbo. Only with intel_pstate on
+    /// Linux, on Intel Macs and on Windows
+    pub base_frequency:    Option<Frequency>,
+    /// The cpufreq governor on Linux, the power plan on Windows and
+    /// whether Low Power Mode is on on macOS
+    pub governor:          Option<String>,
+    /// Whether the cores may clock above the base frequency. The same for
+    /// every core, only available on Linux
+    pub turbo:             Option<bool>,
 }
 
 impl Hash for CpuInfo {
@@ -141,38 +219,175 @@ impl Eq for CpuInfo {}
 // TODO: Find a way to get more info about RAM
 // like frequency, DDR(N), manufacturer
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MemoryInfo {
-    pub total_memory: u64,
-    pub used_memory:  u64,
-    pub total_swap:   u64,
-    pub used_swap:    u64,
+    pub total_memory:      u64,
+    pub used_memory:       u64,
+    /// What could be handed out without swapping, including caches the
+    /// kernel would drop for it. A better measure of how much is left than
+    /// `free_memory`
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub available_memory:  u64,
+    /// Not used for anything, not even caches
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub free_memory:       u64,
+    /// File contents kept around in case they're read again. Counts as
+    /// used but is given up whenever memory runs short. Only available on
+    /// Linux and macOS
+    pub cached_memory:     Option<u64>,
+    /// Metadata of block devices. Only available on Linux
+    pub buffer_memory:     Option<u64>,
+    /// What processes have allocated, whether they've touched it yet or
+    /// not, and what the kernel allows that to grow to before refusing
+    /// allocations with strict overcommit. Only available on Linux
+    pub committed_memory:  Option<u64>,
+    pub commit_limit:      Option<u64>,
+    /// Pages read from and written to SWAP since boot. Only available on
+    /// Linux and macOS
+    pub swap_ins:          Option<u64>,
+    pub swap_outs:         Option<u64>,
+    pub total_swap:        u64,
+    pub used_swap:         u64,
+    /// Every SWAP partition, file and zram device making up `total_swap`
+    pub swap_devices:      Option<Vec<SwapDevice>>,
+    pub zswap_enabled:     Option<bool>,
+    /// What compressed pages take up in RAM: the compressor on macOS, the
+    /// "Memory Compression" process on Windows and zswap on Linux
+    pub compressed_memory: Option<u64>,
+    /// Whether SWAP is allocated as it's needed, like the swap files of
+    /// macOS and the page files of Windows, so `total_swap` is only what
+    /// there is right now
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub dynamic_swap:      bool,
+    /// What dynamic SWAP can grow to. None if it isn't dynamic or only
+    /// limited by the free disk space
+    pub swap_limit:        Option<u64>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DiskInfo {
-    pub total:       u64,
-    pub used:        u64,
-    pub name:        String,
-    pub file_system: Option<String>,
-    pub mount_point: String,
+    pub total:                u64,
+    pub used:                 u64,
+    pub name:                 String,
+    pub file_system:          Option<String>,
+    pub mount_point:          String,
+    /// Options like ro, noatime or nosuid the filesystem was mounted with
+    pub mount_options:        Option<Vec<String>>,
+    /// How many files and directories the filesystem can hold, from
+    /// statvfs. A full inode table makes a disk with free space just as
+    /// full. None on Windows, where NTFS grows its file table as needed,
+    /// and for filesystems without a fixed table like btrfs or FAT
+    pub inodes_total:         Option<u64>,
+    pub inodes_used:          Option<u64>,
+    /// IO requests in flight right now. Only available on Linux and
+    /// Windows, like the averages
+    pub queue_length:         Option<f64>,
+    /// Since the last call to `disk_information`
+    pub average_queue_depth:  Option<f64>,
+    /// How long a read or write took on average since the last call to
+    /// `disk_information`. `None` if there weren't any
+    pub average_latency:      Option<Duration>,
+    /// Of the drive the disk is on, in degrees Celsius. Only on Linux, see
+    /// `DiskSmartInfo::temperature` for the other platforms
+    pub temperature:          Option<f32>,
+    /// Where the drive starts throttling, if it says
+    pub critical_temperature: Option<f32>,
+    /// The `PhysicalDiskInfo::name` of the drive the disk is on. Only on
+    /// Linux
+    pub physical_disk:        Option<String>,
+}
+
+// /proc/self/mounts escapes whitespace in paths with octal sequences like
+// \040, so the mount points have to be unescaped before they can be
+// compared to the ones sysinfo reports
+#[cfg(target_os = "linux")]
+fn mount_options() -> std::collections::HashMap<String, Vec<String>> {
+    std::fs::read_to_string("/proc/self/mounts")
+        .unwrap_or_default()
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace().skip(1);
+            let mount_point = fields.next()?.replace("\\040", " ").replace("\\011", "\t").replace("\\012", "\n").replace("\\134", "\\");
+            let options = fields.nth(1)?.split(',').map(ToString::to_string).collect();
+            Some((mount_point, options))
+        })
+        .collect()
+}
+
+#[cfg(not(target_os = "linux"))]
+fn mount_options() -> std::collections::HashMap<String, Vec<String>> {
+    std::collections::HashMap::new()
+}
+
+// Returns (total, used) inodes. Filesystems without a fixed inode table
+// (btrfs, FAT, most network filesystems) report zero, which isn't useful.
+#[cfg(unix)]
+#[allow(clippy::useless_conversion)]
+fn inode_usage(mount_point: &str) -> Option<(u64, u64)> {
+    let stats = nix::sys::statvfs::statvfs(mount_point).ok()?;
+    let total = u64::from(stats.files());
+    let free = u64::from(stats.files_free());
+    (total != 0).then(|| (total, total.saturating_sub(free)))
+}
+
+#[cfg(not(unix))]
+const fn inode_usage(_mount_point: &str) -> Option<(u64, u64)> {
+    None
+}
+
+// Used where the mount table can't be read, which only covers the flags
+// statvfs knows about
+#[cfg(unix)]
+fn statvfs_mount_options(mount_point: &str) -> Option<Vec<String>> {
+    use nix::sys::statvfs::FsFlags;
+
+    let flags = nix::sys::statvfs::statvfs(mount_point).ok()?.flags();
+    let mut options = vec![if flags.contains(FsFlags::ST_RDONLY) { "ro" } else { "rw" }.to_string()];
+    if flags.contains(FsFlags::ST_NOSUID) {
+        options.push("nosuid".to_string());
+    }
+    Some(options)
+}
+
+#[cfg(not(unix))]
+const fn statvfs_mount_options(_mount_point: &str) -> Option<Vec<String>> {
+    None
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BatteryInfo {
-    pub charge:          f32,
-    pub capacity_wh:     f32,
-    pub capacity_new_wh: f32,
-    pub health:          f32,
-    pub voltage:         f32,
-    pub state:           battery::State,
-    pub technology:      battery::Technology,
-    pub cycle_count:     Option<u32>,
-    pub manufacturer:    Option<String>,
-    pub model:           Option<String>,
+    /// Between 0 and 1
+    pub charge:       f32,
+    /// What the battery holds when full now
+    #[cfg_attr(feature = "serde", serde(rename = "capacity_wh", with = "serialization"))]
+    pub capacity:     Energy,
+    /// What the battery held when full while it was new
+    #[cfg_attr(feature = "serde", serde(rename = "capacity_new_wh", with = "serialization"))]
+    pub capacity_new: Energy,
+    /// What the battery holds right now
+    #[cfg_attr(feature = "serde", serde(rename = "energy_wh", default, with = "serialization"))]
+    pub energy:       Option<Energy>,
+    /// How fast it's being charged or discharged, depending on `state`
+    #[cfg_attr(feature = "serde", serde(rename = "power_w", default, with = "serialization"))]
+    pub power:        Option<Power>,
+    /// Percent
+    pub health:       f32,
+    #[cfg_attr(feature = "serde", serde(with = "serialization"))]
+    pub voltage:      ElectricPotential,
+    #[cfg_attr(feature = "serde", serde(with = "serialization"))]
+    pub state:        battery::State,
+    #[cfg_attr(feature = "serde", serde(with = "serialization"))]
+    pub technology:   battery::Technology,
+    pub cycle_count:  Option<u32>,
+    pub manufacturer: Option<String>,
+    pub model:        Option<String>,
 }
 
 #[allow(clippy::struct_excessive_bools)]
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NetworkFlags {
     pub raw:               u32,
     pub is_up:             bool,
@@ -186,8 +401,13 @@ pub struct NetworkFlags {
 }
 
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Network {
     pub name:                         String,
+    /// What the system settings call the interface, e.g. Wi-Fi for en0 on
+    /// macOS. None where the name is all there is
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub display_name:                 Option<String>,
     pub description:                  Option<String>,
     pub index:                        Option<u32>,
     pub ips:                          Option<Vec<std::net::IpAddr>>,
@@ -200,32 +420,114 @@ pub struct Network {
     pub packets_received_total:       Option<u64>,
     pub packets_transmitted_recently: Option<u64>,
     pub packets_transmitted_total:    Option<u64>,
+    #[cfg_attr(feature = "serde", serde(with = "serialization"))]
     pub mac_address:                  Option<sysinfo::MacAddr>,
+    /// With their scope and whether they are temporary
+    pub ipv6_addresses:               Option<Vec<Ipv6Address>>,
+    /// Whether temporary addresses are created for outgoing connections
+    pub ipv6_privacy_extensions:      Option<bool>,
+}
+
+#[cfg(feature = "wifi")]
+pub use wifiscanner::Wifi;
+
+/// Stands in for wifiscanner's without the `wifi` feature, which scans
+/// for nothing
+#[cfg(not(feature = "wifi"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Wifi {
+    pub mac:          String,
+    pub ssid:         String,
+    pub channel:      String,
+    pub signal_level: String,
+    pub security:     String,
+}
+
+#[cfg(feature = "wifi")]
+fn scan_wifis() -> Option<Vec<Wifi>> {
+    wifiscanner::scan().ok()
+}
+
+#[cfg(not(feature = "wifi"))]
+const fn scan_wifis() -> Option<Vec<Wifi>> {
+    None
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NetworkInfo {
-    pub connected:     bool,
-    pub wifis:         Option<Vec<wifiscanner::Wifi>>,
+    #[cfg_attr(feature = "serde", serde(flatten, with = "serialization::connectivity"))]
+    pub connectivity:  ConnectivityState,
+    #[cfg_attr(feature = "serde", serde(with = "serialization"))]
+    pub wifis:         Option<Vec<Wifi>>,
     pub networks:      Option<Vec<Network>>,
     pub ip_address_v4: Option<std::net::IpAddr>,
     pub ip_address_v6: Option<std::net::IpAddr>,
+    /// Whether a public IPv6 address can be reached, independently of
+    /// `connectivity`
+    pub connected_v6:  bool,
+    pub gateway_v6:    Option<std::net::Ipv6Addr>,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ProcessInfo {
-    pub name:         String,
-    pub path:         Option<String>,
-    pub memory_usage: u64,
-    pub swap_usage:   u64,
-    pub cpu_usage:    f32,
+    pub name:             String,
+    pub path:             Option<String>,
+    /// The command line the process was started with, including the
+    /// executable
+    pub cmd:              Vec<String>,
+    pub cwd:              Option<std::path::PathBuf>,
+    pub user:             Option<String>,
+    #[cfg_attr(feature = "serde", serde(with = "serialization"))]
+    pub uid:              Option<sysinfo::Uid>,
+    /// The real group. Windows has no primary group, so it is always
+    /// `None` there
+    pub group:            Option<String>,
+    #[cfg_attr(feature = "serde", serde(with = "serialization"))]
+    pub status:           sysinfo::ProcessStatus,
+    pub memory_usage:     u64,
+    pub swap_usage:       u64,
+    pub cpu_usage:        f32,
+    /// The nice value, see `set_priority`. On Windows the priority class
+    /// mapped onto it
+    pub nice:             Option<i32>,
     // TODO: add disk usage
-    pub run_time:     Duration,
-    pub pid:          sysinfo::Pid,
-    pub parent:       Option<sysinfo::Pid>,
+    pub run_time:         Duration,
+    /// How much CPU time the process used since it started, as opposed to
+    /// `cpu_usage` which only covers the last refresh. Only available on
+    /// Linux and Windows
+    pub cpu_time:         Option<Duration>,
+    pub start_time:       std::time::SystemTime,
+    /// Left out by `Manager::process_information`, which would have to
+    /// read a directory per process every refresh, and filled in by
+    /// `Manager::add_file_descriptors`. Only available on Linux, and for
+    /// other users' processes only when running as root or through the
+    /// helper. On Windows these are the open handles, which have no
+    /// practical limit
+    pub file_descriptors: Option<FileDescriptorUsage>,
+    /// Only available on Linux
+    pub context_switches: Option<ContextSwitches>,
+    /// Set for Flatpaks, snaps, AppImages and Microsoft Store apps
+    pub sandbox:          Option<SandboxInfo>,
+    #[cfg_attr(feature = "serde", serde(with = "serialization"))]
+    pub pid:              sysinfo::Pid,
+    #[cfg_attr(feature = "serde", serde(with = "serialization"))]
+    pub parent:           Option<sysinfo::Pid>,
+}
+
+impl ProcessInfo {
+    #[must_use]
+    pub const fn id(&self) -> ProcessId {
+        ProcessId {
+            pid:        self.pid,
+            start_time: self.start_time,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComponentInfo {
     pub name:                 String,
     pub temperature:          f32,
@@ -233,12 +535,14 @@ pub struct ComponentInfo {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DisplaySize {
     pub width:  u32,
     pub height: u32,
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DisplayInfo {
     pub id:           u32,
     pub size:         DisplaySize,
@@ -248,9 +552,10 @@ pub struct DisplayInfo {
 }
 
 #[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BluetoothInfo {
     pub id:                       String,
-    pub address:                  btleplug::api::BDAddr,
+    pub address:                  BDAddr,
     pub local_name:               Option<String>,
     pub transmission_power_level: Option<i16>,
     pub signal_strength:          Option<i16>,
@@ -258,51 +563,149 @@ pub struct BluetoothInfo {
 }
 
 pub struct Manager {
-    system:           Option<System>,
-    components:       Option<Components>,
-    users:            Option<Users>,
-    networks:         Option<Networks>,
-    disks:            Option<Disks>,
-    battery_manager:  Option<battery::Manager>,
-    btleplug_adapter: Option<btleplug::platform::Adapter>,
-    tokio_runtime:    tokio::runtime::Runtime,
+    system:             Option<System>,
+    components:         Option<Components>,
+    users:              Option<Users>,
+    groups:             Option<Groups>,
+    networks:           Option<Networks>,
+    disks:              Option<Disks>,
+    battery_manager:    Option<battery::Manager>,
+    bluetooth:          bluetooth::Adapters,
+    // None inside an AsyncManager, which runs on the caller's runtime
+    tokio_runtime:      Option<tokio::runtime::Runtime>,
+    // The OOM kill counter at the time the kernel log was last read,
+    // together with when and what was found. Not being allowed to read it
+    // is kept as well, so the log isn't read on every call
+    oom_kills:          Option<(Option<u64>, Instant, Option<Vec<OomKillEvent>>)>,
+    // The TCP byte counters at the time top_talkers was last called
+    socket_counters:    Option<sockets::SocketCounters>,
+    // Scanning for Wi-Fi networks takes seconds and makes some drivers drop
+    // packets, so the result is reused for a while
+    wifi_scan:          Option<(Instant, Option<Vec<Wifi>>)>,
+    wifi_interval:      Duration,
+    connectivity_check: ConnectivityCheck,
+    // The CPU tick counters at the time cpu_state_information was last
+    // called
+    cpu_times:          Option<Vec<cpu_states::CpuTimes>>,
+    // The same for scheduler_activity
+    scheduler:          Option<scheduler::SchedulerCounters>,
+    // And for the disk latencies of disk_information
+    disk_counters:      Option<disk_activity::DiskCounters>,
+    // And for the thread CPU usage of process_threads
+    thread_times:       Option<threads::ThreadTimes>,
+    // Frequency limits and governors, which are read less often than the
+    // CPU usage
+    frequency_policy:   Option<(Instant, cpu_frequency::FrequencyPolicy)>,
+    // Started the first time they're asked for
+    #[cfg(feature = "plugins")]
+    plugins:            Option<plugins::Plugins>,
 }
 
 impl Default for Manager {
     fn default() -> Self {
         let tokio_runtime = tokio::runtime::Runtime::new().expect("Constructing a tokio Runtime failed");
-        populate_battery_support();
         Self {
-            system: if SYSINFO_SUPPORT { Some(System::new_all()) } else { None },
-            components: if SYSINFO_SUPPORT { Some(Components::new()) } else { None },
-            users: if SYSINFO_SUPPORT { Some(Users::new_with_refreshed_list()) } else { None },
-            networks: if SYSINFO_SUPPORT { Some(Networks::new()) } else { None },
-            disks: if SYSINFO_SUPPORT { Some(Disks::new()) } else { None },
-            battery_manager: if BATTERY_SUPPORT.load(Ordering::Relaxed) { battery::Manager::new().ok() } else { None },
-            btleplug_adapter: tokio_runtime
-                .block_on(btleplug::platform::Manager::new())
-                .map(|manager| tokio_runtime.block_on(manager.adapters()).ok().map(|adapters| adapters.into_iter().nth(0)))
-                .ok()
-                .flatten()
-                .flatten(),
-            tokio_runtime,
+            bluetooth: tokio_runtime.block_on(bluetooth::Adapters::new()),
+            tokio_runtime: Some(tokio_runtime),
+            ..Self::without_runtime()
         }
     }
 }
 
 impl Manager {
+    const DEFAULT_WIFI_INTERVAL: Duration = Duration::from_secs(45);
+
+    /// Everything that doesn't need an async runtime. The Bluetooth
+    /// adapters are left to whoever runs the futures
+    fn without_runtime() -> Self {
+        populate_battery_support();
+        Self {
+            system:             if SYSINFO_SUPPORT { Some(System::new_all()) } else { None },
+            components:         if SYSINFO_SUPPORT { Some(Components::new()) } else { None },
+            users:              if SYSINFO_SUPPORT { Some(Users::new_with_refreshed_list()) } else { None },
+            groups:             if SYSINFO_SUPPORT { Some(Groups::new_with_refreshed_list()) } else { None },
+            networks:           if SYSINFO_SUPPORT { Some(Networks::new()) } else { None },
+            disks:              if SYSINFO_SUPPORT { Some(Disks::new()) } else { None },
+            battery_manager:    if BATTERY_SUPPORT.load(Ordering::Relaxed) { battery::Manager::new().ok() } else { None },
+            bluetooth:          bluetooth::Adapters::default(),
+            tokio_runtime:      None,
+            oom_kills:          None,
+            socket_counters:    None,
+            wifi_scan:          None,
+            wifi_interval:      Self::DEFAULT_WIFI_INTERVAL,
+            connectivity_check: ConnectivityCheck::default(),
+            cpu_times:          None,
+            scheduler:          None,
+            disk_counters:      None,
+            thread_times:       None,
+            frequency_policy:   None,
+            #[cfg(feature = "plugins")]
+            plugins:            None,
+        }
+    }
+
     #[must_use]
     pub fn new() -> Self {
-        let new_self = Self::default();
-        new_self
-            .btleplug_adapter
-            .as_ref()
-            .map(|adapter| new_self.tokio_runtime.block_on(adapter.start_scan(btleplug::api::ScanFilter::default())));
-        new_self
+        Self::default()
+    }
+
+    /// Sends the readings in `spec` every `interval` until the receiver is
+    /// dropped. They come from a new Manager on its own thread, so this
+    /// one can keep being used for everything else
+    #[must_use]
+    pub fn subscribe(spec: RefreshSpec, interval: Duration) -> std::sync::mpsc::Receiver<Update> {
+        subscription::subscribe(spec, interval)
+    }
+
+    /// Answers HTTP requests on `address` (e.g. "0.0.0.0:8080") with this
+    /// Manager's readings as JSON, see the server module for the routes.
+    /// Killing processes with `DELETE /processes/{pid}` is only allowed
+    /// with a `kill_token`, which the request has to send as
+    /// `Authorization: Bearer <token>`
+    ///
+    /// # Errors
+    ///
+    /// If the address can't be bound. Otherwise this never returns
+    #[cfg(feature = "server")]
+    pub fn serve(self, address: &str, kill_token: Option<&str>) -> std::io::Result<()> {
+        server::serve(self, address, kill_token)
+    }
+
+    /// Sends this Manager's readings every `config.interval` to every
+    /// `RemoteManager` that connects to `address` with one of the tokens,
+    /// see the remote module for the protocol
+    ///
+    /// # Errors
+    ///
+    /// If the address can't be bound or the TLS files can't be read.
+    /// Otherwise this never returns
+    #[cfg(feature = "remote")]
+    pub fn run_agent(self, address: &str, config: &AgentConfig) -> std::io::Result<()> {
+        remote::run_agent(self, address, config)
+    }
+
+    /// The latest reading of every plugin, see the plugins module for how
+    /// to write one. The first call starts them, so they've usually not
+    /// printed anything yet
+    #[cfg(feature = "plugins")]
+    pub fn plugin_information(&mut self) -> Vec<PluginInfo> {
+        self.plugins.get_or_insert_with(plugins::Plugins::start).information()
+    }
+
+    fn block_on<F: std::future::Future>(&self, future: F) -> Option<F::Output> {
+        self.tokio_runtime.as_ref().map(|runtime| runtime.block_on(future))
     }
 
     pub fn system_information(&mut self) -> Option<SystemInfo> {
-        self.users.as_mut().map(|users| {
+        self.try_system_information().ok()
+    }
+
+    /// # Errors
+    ///
+    /// `Error::Unsupported` if sysinfo doesn't support the platform
+    pub fn try_system_information(&mut self) -> Result<SystemInfo, Error> {
+        let load_average = self.load_average();
+        self.users.as_mut().ok_or(Error::Unsupported).map(|users| {
             users.refresh_list();
             SystemInfo {
                 os:             System::name(),
@@ -310,90 +713,403 @@ impl Manager {
                 kernel_version: System::kernel_version(),
                 users:          users.list().iter().map(|v| v.name().to_string()).collect(),
                 uptime:         Duration::from_secs(System::uptime()),
+                load_average,
             }
         })
     }
 
+    /// Cheaper than all of the system information, for reading it often.
+    /// Windows doesn't keep a load average
+    pub fn load_average(&self) -> Option<LoadAverage> {
+        if cfg!(windows) {
+            return None;
+        }
+        let load = System::load_average();
+        Some(LoadAverage {
+            one:     load.one,
+            five:    load.five,
+            fifteen: load.fifteen,
+        })
+    }
+
+    /// Memory and CPUs of every NUMA node, and the huge pages set aside.
+    /// Only supported on Linux
+    pub fn numa_information(&self) -> Option<NumaInfo> {
+        numa::numa_information()
+    }
+
+    /// The last `count` applications that crashed, newest first
+    pub fn crash_reports(&self, count: usize) -> Option<Vec<CrashReport>> {
+        crashes::crash_reports().map(|mut reports| {
+            reports.sort_by_key(|report| std::cmp::Reverse(report.time));
+            reports.truncate(count);
+            reports
+        })
+    }
+
+    /// Flatpaks, snaps, AppImages and Microsoft Store apps, along with
+    /// their permissions where the platform has them. Only supported on
+    /// Linux and Windows. Runs a few commands, so this takes a moment
+    pub fn sandboxed_applications(&self) -> Option<Vec<SandboxedApplication>> {
+        sandbox::sandboxed_applications()
+    }
+
+    /// Block IO latency per process and run queue latency, traced for
+    /// `duration`. Blocks until then. Only supported on Linux, needs root
+    /// and `bpftrace`
+    #[cfg(feature = "ebpf")]
+    pub fn latency_information(&self, duration: Duration) -> Option<LatencyInfo> {
+        latency::latency(duration)
+    }
+
+    /// Idle and screen lock state of the current session. Frontends can
+    /// use this to refresh less often while nobody is looking. Not
+    /// supported on Windows, and macOS can't tell whether the screen
+    /// is locked
+    pub fn session_activity(&self) -> Option<SessionActivity> {
+        session::session_activity()
+    }
+
+    /// The last `count` boots, newest (i.e. the current one) first. Runs
+    /// `journalctl` or PowerShell, so this shouldn't be called every frame
+    pub fn boot_history(&self, count: usize) -> Option<Vec<BootRecord>> {
+        boot::boot_history(count)
+    }
+
+    /// How long the last boot took per phase, plus the `unit_count`
+    /// slowest systemd units
+    pub fn boot_times(&self, unit_count: usize) -> Option<BootTimes> {
+        boot::boot_times(unit_count)
+    }
+
+    /// SELinux and AppArmor on Linux, System Integrity Protection on
+    /// macOS. `None` if none of them are active
+    pub fn mandatory_access_control_information(&self) -> Option<Vec<MandatoryAccessControlInfo>> {
+        let systems = security::mandatory_access_control();
+        match systems.len() {
+            0 => None,
+            _ => Some(systems),
+        }
+    }
+
+    /// Speculative execution vulnerabilities like Spectre and Meltdown and
+    /// whether the kernel mitigates them. Only supported on Linux
+    pub fn cpu_security_information(&self) -> Option<CpuSecurityInfo> {
+        security::cpu_security()
+    }
+
+    /// Takes a few seconds and keeps every core busy. Doesn't need any of
+    /// the `Manager`'s state, so it can be run on another thread while the
+    /// `Manager` is in use. `progress` is called regularly with a value
+    /// between 0 and 1
+    pub fn benchmark_cpu(progress: impl Fn(f32)) -> CpuBenchmark {
+        benchmark::benchmark_cpu(progress)
+    }
+
+    /// Allocates 512 MB for the duration of the benchmark
+    pub fn benchmark_memory(progress: impl Fn(f32)) -> MemoryBenchmark {
+        benchmark::benchmark_memory(progress)
+    }
+
+    /// Writes a temporary file of `config.file_size` to the disk mounted at
+    /// `mount_point` and reads it back, sequentially and in random blocks.
+    /// Like the other benchmarks it can run on another thread
+    ///
+    /// # Errors
+    ///
+    /// `Error::Io` if the file can't be created, e.g. because the disk is
+    /// read only, full or not writable by this user, or reading or writing
+    /// it fails
+    pub fn disk_benchmark(mount_point: &std::path::Path, config: BenchmarkConfig, progress: impl Fn(f32)) -> Result<DiskBenchmark, Error> {
+        Ok(benchmark::benchmark_disk(mount_point, config, progress)?)
+    }
+
     pub fn cpu_information(&mut self) -> Option<Vec<CpuInfo>> {
-        self.system.as_mut().map(|sys| {
+        self.try_cpu_information().ok()
+    }
+
+    /// # Errors
+    ///
+    /// `Error::Unsupported` if sysinfo doesn't support the platform
+    pub fn try_cpu_information(&mut self) -> Result<Vec<CpuInfo>, Error> {
+        let frequency_policy = &mut self.frequency_policy;
+        self.system.as_mut().ok_or(Error::Unsupported).map(|sys| {
             sys.refresh_cpu();
+            let policy = cpu_frequency::frequency_policy(frequency_policy, sys.cpus().len());
             #[allow(clippy::cast_precision_loss)]
             sys.cpus()
                 .iter()
-                .map(|cpu| CpuInfo {
-                    usage:        cpu.cpu_usage(),
-                    model:        cpu.name().to_string(),
-                    manufacturer: cpu.brand().to_string(),
-                    frequency:    Frequency::new::<megahertz>(cpu.frequency() as f64), /* TODO: figure out how to
-                                                                                        * use uom for this */
+                .enumerate()
+                .map(|(index, cpu)| {
+                    let core = policy.core(index);
+                    CpuInfo {
+                        usage:             cpu.cpu_usage(),
+                        model:             cpu.name().to_string(),
+                        manufacturer:      cpu.brand().to_string(),
+                        frequency:         Frequency::new::<megahertz>(cpu.frequency() as f64), /* TODO: figure out how to
+                                                                                                 * use uom for this */
+                        minimum_frequency: core.minimum,
+                        maximum_frequency: core.maximum,
+                        base_frequency:    core.base,
+                        governor:          core.governor,
+                        turbo:             policy.turbo,
+                    }
                 })
                 .collect()
         })
     }
 
+    /// Instruction set extensions, virtualization extensions and cache
+    /// sizes of the CPU. Multi CPU machines are assumed to have the same
+    /// model in every socket
+    pub fn cpu_features(&self) -> CpuFeatures {
+        cpu_features::cpu_features()
+    }
+
+    /// What the CPU spent its time on since the last call, aggregated and
+    /// per core. The first call counts since boot on Linux. Not supported
+    /// on macOS
+    pub fn cpu_state_information(&mut self) -> Option<CpuStates> {
+        cpu_states::cpu_states(&mut self.cpu_times)
+    }
+
+    /// Context switches and interrupts per second since the last call. The
+    /// first call averages over the time since boot on Linux. Not
+    /// supported on macOS
+    pub fn scheduler_activity(&mut self) -> Option<SchedulerActivity> {
+        scheduler::scheduler_activity(&mut self.scheduler, Duration::from_secs(System::uptime()))
+    }
+
+    /// Switches every core to a cpufreq governor like "powersave" on
+    /// Linux, or activates the power plan with that name on Windows. Needs
+    /// root or an elevated prompt
+    #[cfg(feature = "cpu-governor")]
+    pub fn set_governor(&mut self, governor: &str) -> bool {
+        // So the next cpu_information shows the new one right away
+        self.frequency_policy = None;
+        cpu_frequency::set_governor(governor)
+    }
+
     pub fn memory_information(&mut self) -> Option<MemoryInfo> {
-        self.system.as_mut().map(|sys| {
+        self.try_memory_information().ok()
+    }
+
+    /// # Errors
+    ///
+    /// `Error::Unsupported` if sysinfo doesn't support the platform
+    pub fn try_memory_information(&mut self) -> Result<MemoryInfo, Error> {
+        self.system.as_mut().ok_or(Error::Unsupported).map(|sys| {
             sys.refresh_memory();
+            let breakdown = memory::memory_breakdown();
             MemoryInfo {
-                total_memory: sys.total_memory(),
-                used_memory:  sys.used_memory(),
-                total_swap:   sys.total_swap(),
-                used_swap:    sys.used_swap(),
+                total_memory:      sys.total_memory(),
+                used_memory:       sys.used_memory(),
+                available_memory:  sys.available_memory(),
+                free_memory:       sys.free_memory(),
+                cached_memory:     breakdown.cached,
+                buffer_memory:     breakdown.buffers,
+                committed_memory:  breakdown.committed,
+                commit_limit:      breakdown.commit_limit,
+                swap_ins:          breakdown.swap_ins,
+                swap_outs:         breakdown.swap_outs,
+                total_swap:        sys.total_swap(),
+                used_swap:         sys.used_swap(),
+                swap_devices:      memory::swap_devices(),
+                zswap_enabled:     memory::zswap_enabled(),
+                compressed_memory: breakdown.compressed,
+                dynamic_swap:      breakdown.dynamic_swap,
+                swap_limit:        breakdown.swap_limit,
             }
         })
     }
 
+    /// Processes killed by the kernel for running out of memory, oldest
+    /// first. The kernel log is only read again once the kernel has killed
+    /// another process, or every minute where it doesn't count them
+    pub fn oom_kill_information(&mut self) -> Option<Vec<OomKillEvent>> {
+        const UNCOUNTED_INTERVAL: Duration = Duration::from_secs(60);
+        let count = memory::oom_kill_count();
+        if let Some((cached_count, read_at, events)) = &self.oom_kills
+            && *cached_count == count
+            && (count.is_some() || read_at.elapsed() < UNCOUNTED_INTERVAL)
+        {
+            return events.clone();
+        }
+        let events = memory::oom_kill_events();
+        self.oom_kills = Some((count, Instant::now(), events.clone()));
+        events
+    }
+
+    /// Remote hosts by how much they transferred over TCP since the last
+    /// call, busiest first. The first call counts from the start of every
+    /// connection. Only supported on Linux
+    pub fn top_talkers(&mut self) -> Option<Vec<TopTalker>> {
+        let current = sockets::socket_counters()?;
+        let previous = self.socket_counters.replace(current.clone()).unwrap_or_default();
+        Some(sockets::top_talkers(&previous, &current))
+    }
+
     pub fn disk_information(&mut self) -> Option<Vec<DiskInfo>> {
-        self.disks.as_mut().map(|disks| {
+        self.try_disk_information().ok()
+    }
+
+    /// # Errors
+    ///
+    /// `Error::Unsupported` if sysinfo doesn't support the platform
+    pub fn try_disk_information(&mut self) -> Result<Vec<DiskInfo>, Error> {
+        let activity = disk_activity::disk_activity(&mut self.disk_counters).unwrap_or_default();
+        self.disks.as_mut().ok_or(Error::Unsupported).map(|disks| {
             disks.refresh_list();
+            let mut mount_options = mount_options();
             disks
                 .list()
                 .iter()
-                .map(|disk| DiskInfo {
-                    total:       disk.total_space(),
-                    used:        (disk.total_space() - disk.available_space()),
-                    name:        disk.name().to_string_lossy().to_string(),
-                    file_system: disk.file_system().to_str().map(ToString::to_string),
-                    mount_point: disk.mount_point().to_string_lossy().to_string(),
+                .map(|disk| {
+                    let mount_point = disk.mount_point().to_string_lossy().to_string();
+                    let inodes = inode_usage(&mount_point);
+                    let name = disk.name().to_string_lossy().to_string();
+                    // Linux counts per device, Windows per drive letter
+                    let activity = activity.get(name.trim_start_matches("/dev/")).or_else(|| activity.get(&mount_point)).copied().unwrap_or_default();
+                    let temperature = disk_temperature::disk_temperature(&name);
+                    let physical_disk = disk_temperature::parent_disk(&name);
+                    DiskInfo {
+                        total: disk.total_space(),
+                        used: (disk.total_space() - disk.available_space()),
+                        name,
+                        file_system: disk.file_system().to_str().map(ToString::to_string),
+                        mount_options: mount_options.remove(&mount_point).or_else(|| statvfs_mount_options(&mount_point)),
+                        inodes_total: inodes.map(|(total, _)| total),
+                        inodes_used: inodes.map(|(_, used)| used),
+                        queue_length: activity.queue_length,
+                        average_queue_depth: activity.average_queue_depth,
+                        average_latency: activity.average_latency,
+                        temperature: temperature.map(|temperature| temperature.current),
+                        critical_temperature: temperature.and_then(|temperature| temperature.critical),
+                        physical_disk,
+                        mount_point,
+                    }
                 })
                 .collect()
         })
     }
 
+    /// Records startup items, listening sockets, disk usage and installed
+    /// packages so they can later be compared with `Snapshot::diff`. Runs
+    /// the package manager, so this takes a moment
+    pub fn snapshot(&mut self) -> Snapshot {
+        Snapshot {
+            taken_at:          std::time::SystemTime::now(),
+            startup_items:     snapshot::startup_items(),
+            listening_sockets: snapshot::listening_sockets(),
+            disk_usage:        self.disk_information().unwrap_or_default().into_iter().map(|disk| (disk.mount_point, disk.used)).collect(),
+            packages:          snapshot::packages(),
+        }
+    }
+
+    /// The drives themselves, with the partitions on them, where
+    /// `disk_information` lists the mounted volumes
+    pub fn physical_disk_information(&self) -> Option<Vec<PhysicalDiskInfo>> {
+        physical_disks::physical_disks()
+    }
+
+    /// mdraid arrays, btrfs multi-device filesystems, LVM volume groups and
+    /// ZFS pools. `None` if there are no pools on this system
+    pub fn storage_pool_information(&self) -> Option<Vec<StoragePoolInfo>> {
+        let pools = storage_pools::storage_pools();
+        match pools.len() {
+            0 => None,
+            _ => Some(pools),
+        }
+    }
+
+    /// Health, wear and the S.M.A.R.T. attributes of every SATA and NVMe
+    /// drive, through smartctl. `None` if smartctl isn't installed. Drives
+    /// that can't be read, usually for lack of root, are still listed with
+    /// their error
+    pub fn disk_smart_information(&self) -> Option<Vec<DiskSmartInfo>> {
+        smart::smart_information()
+    }
+
+    /// All batteries as if they were one, for laptops with more than one.
+    /// See `combine_batteries`
+    pub fn combined_battery_information(&self) -> Option<CombinedBattery> {
+        combine_batteries(&self.battery_information()?)
+    }
+
+    pub fn battery_information(&self) -> Option<Vec<BatteryInfo>> {
+        self.try_battery_information().ok()
+    }
+
     // TODO: potential error source: batteries may
     // need to be stored in the Manager struct and
     // refreshed every time
-    pub fn battery_information(&self) -> Option<Vec<BatteryInfo>> {
-        self.battery_manager.as_ref().and_then(|battery_manager| {
-            let batteries_res = battery_manager.batteries();
-            batteries_res.map_or(None, |batteries| {
-                Some(
-                    batteries
-                        .filter_map(|battery_res| {
-                            let mut battery = battery_res.ok()?;
-                            let _ = battery_manager.refresh(&mut battery);
-                            Some(BatteryInfo {
-                                charge:          f32::from(battery.state_of_charge()),
-                                capacity_wh:     battery.energy_full().get::<watt_hour>(),
-                                capacity_new_wh: battery.energy_full_design().get::<watt_hour>(),
-                                health:          100.0 * f32::from(battery.state_of_health()),
-                                voltage:         battery.voltage().get::<volt>(),
-                                state:           battery.state(),
-                                technology:      battery.technology(),
-                                cycle_count:     battery.cycle_count(),
-                                manufacturer:    battery.vendor().map(std::string::ToString::to_string),
-                                model:           battery.model().map(std::string::ToString::to_string),
-                            })
-                        })
-                        .collect(),
-                )
+    /// # Errors
+    ///
+    /// `Error::NotFound` if there is no battery, `Error::Battery` if they
+    /// can't be listed. Single batteries that can't be read are left out
+    pub fn try_battery_information(&self) -> Result<Vec<BatteryInfo>, Error> {
+        let battery_manager = self.battery_manager.as_ref().ok_or(Error::NotFound("battery"))?;
+        Ok(battery_manager
+            .batteries()?
+            .filter_map(|battery_res| {
+                let mut battery = battery_res.ok()?;
+                let _ = battery_manager.refresh(&mut battery);
+                // The battery crate brings its own version of uom along
+                Some(BatteryInfo {
+                    charge:       f32::from(battery.state_of_charge()),
+                    capacity:     Energy::new::<uom::si::energy::watt_hour>(f64::from(battery.energy_full().get::<watt_hour>())),
+                    capacity_new: Energy::new::<uom::si::energy::watt_hour>(f64::from(battery.energy_full_design().get::<watt_hour>())),
+                    energy:       Some(Energy::new::<uom::si::energy::watt_hour>(f64::from(battery.energy().get::<watt_hour>()))),
+                    power:        Some(Power::new::<uom::si::power::watt>(f64::from(battery.energy_rate().get::<battery::units::power::watt>()))),
+                    health:       100.0 * f32::from(battery.state_of_health()),
+                    voltage:      ElectricPotential::new::<uom::si::electric_potential::volt>(f64::from(battery.voltage().get::<volt>())),
+                    state:        battery.state(),
+                    technology:   battery.technology(),
+                    cycle_count:  battery.cycle_count(),
+                    manufacturer: battery.vendor().map(std::string::ToString::to_string),
+                    model:        battery.model().map(std::string::ToString::to_string),
+                })
             })
-        })
+            .collect())
+    }
+
+    pub fn network_information(&mut self) -> NetworkInfo {
+        let interfaces = self.interface_information();
+        let connectivity = self
+            .block_on(connectivity::connectivity_state(&self.connectivity_check, interfaces.networks.as_deref()))
+            .unwrap_or_default();
+        let connected_v6 = self.block_on(connectivity::is_connected_v6()).unwrap_or_default();
+        let wifis = self.wifi_networks();
+        NetworkInfo {
+            connectivity,
+            wifis,
+            connected_v6,
+            ..interfaces
+        }
+    }
+
+    /// Measures latency, download and upload against the closest
+    /// speedtest.net server. Takes about half a minute and transfers a lot
+    /// of data. `progress` is called whenever data arrives or was sent
+    pub fn speedtest(&self, progress: impl Fn(SpeedtestProgress)) -> Option<Speedtest> {
+        self.try_speedtest(progress).ok()
+    }
+
+    /// # Errors
+    ///
+    /// `Error::Unsupported` without the `network-extra` feature or inside
+    /// an `AsyncManager`, whose `speedtest` has to be used instead.
+    /// `Error::NotFound` if no server could be reached and
+    /// `Error::Network` if a request failed during the test
+    pub fn try_speedtest(&self, progress: impl Fn(SpeedtestProgress)) -> Result<Speedtest, Error> {
+        self.block_on(speedtest::speedtest(progress)).ok_or(Error::Unsupported)?
     }
 
     // This is quite a complex function and I do not
     // see many advantages to refactoring it to if let
-    pub fn network_information(&mut self) -> NetworkInfo {
+    /// Everything in `NetworkInfo` except for the connectivity check and
+    /// the Wi-Fi networks, which have to wait for the network
+    fn interface_information(&mut self) -> NetworkInfo {
         if let Some(networks) = self.networks.as_mut() {
             networks.refresh();
             networks.refresh_list();
@@ -418,73 +1134,271 @@ impl Manager {
                 .collect::<Vec<Network>>()
         });
 
-        for interface in pnet_datalink::interfaces() {
-            let network_flags = NetworkFlags {
-                raw:               interface.flags,
-                is_up:             interface.is_up(),
-                is_broadcast:      interface.is_broadcast(),
-                is_loopback:       interface.is_loopback(),
-                is_point_to_point: interface.is_point_to_point(),
-                is_multicast:      interface.is_multicast(),
-            };
-            if let Some(network_index) = networks.iter().position(|network| network.name == interface.name) {
-                networks[network_index].description = Some(interface.description);
-                networks[network_index].index = Some(interface.index);
-                networks[network_index].ips = Some(interface.ips.iter().map(ipnetwork::IpNetwork::ip).collect());
-                networks[network_index].flags = Some(network_flags);
-            } else {
-                networks.push(Network {
-                    name: interface.name,
-                    description: Some(interface.description),
-                    index: Some(interface.index),
-                    ips: Some(interface.ips.iter().map(ipnetwork::IpNetwork::ip).collect()),
-                    flags: Some(network_flags),
-                    ..Default::default()
-                });
-            }
-        }
+        network_extra::add_interface_details(&mut networks);
+        ipv6::add_ipv6_details(&mut networks);
+        interface_names::add_display_names(&mut networks);
 
+        let (ip_address_v4, ip_address_v6) = network_extra::local_ip_addresses();
         NetworkInfo {
-            connected:     self.tokio_runtime.block_on(reqwest::get("https://google.com")).is_ok(),
-            wifis:         wifiscanner::scan().ok(),
-            networks:      match networks.len() {
+            connectivity: ConnectivityState::Unknown,
+            wifis: None,
+            networks: match networks.len() {
                 0 => None,
                 _ => Some(networks),
             },
-            ip_address_v4: local_ip_address::local_ip().ok(),
-            ip_address_v6: local_ip_address::local_ipv6().ok(),
+            ip_address_v4,
+            ip_address_v6,
+            connected_v6: false,
+            gateway_v6: ipv6::default_gateway(),
+        }
+    }
+
+    /// How old the Wi-Fi networks in `network_information` may get before
+    /// scanning again
+    pub const fn set_wifi_interval(&mut self, interval: Duration) {
+        self.wifi_interval = interval;
+    }
+
+    /// How `network_information` decides whether the internet is
+    /// reachable, an HTTPS request to google.com by default. A captive
+    /// portal is detected either way
+    pub fn set_connectivity_check(&mut self, check: ConnectivityCheck) {
+        self.connectivity_check = check;
+    }
+
+    /// The Wi-Fi networks the system remembers, including the ones out of
+    /// range. macOS only lists their SSIDs
+    pub fn wifi_profiles(&self) -> Option<Vec<WifiProfile>> {
+        wifi_profiles::wifi_profiles()
+    }
+
+    /// The last Wi-Fi scan, unless it is older than the interval
+    fn current_wifi_scan(&self) -> Option<&(Instant, Option<Vec<Wifi>>)> {
+        self.wifi_scan.as_ref().filter(|(scanned, _)| scanned.elapsed() < self.wifi_interval)
+    }
+
+    fn wifi_networks(&mut self) -> Option<Vec<Wifi>> {
+        if let Some((_, wifis)) = self.current_wifi_scan() {
+            return wifis.clone();
         }
+        let wifis = scan_wifis();
+        self.wifi_scan = Some((Instant::now(), wifis.clone()));
+        wifis
     }
 
     pub fn process_information(&mut self) -> Option<Vec<ProcessInfo>> {
-        self.system.as_mut().map(|sys| {
-            sys.refresh_processes();
+        self.try_process_information().ok()
+    }
+
+    /// # Errors
+    ///
+    /// `Error::Unsupported` if sysinfo doesn't support the platform
+    pub fn try_process_information(&mut self) -> Result<Vec<ProcessInfo>, Error> {
+        let users = self.users.as_ref();
+        let groups = self.groups.as_ref();
+        let cpu_times = performance_counters::process_cpu_times();
+        let priorities = processes::priorities();
+        self.system.as_mut().ok_or(Error::Unsupported).map(|sys| {
+            sys.refresh_processes_specifics(
+                ProcessRefreshKind::new()
+                    .with_memory()
+                    .with_cpu()
+                    .with_disk_usage()
+                    .with_exe(UpdateKind::OnlyIfNotSet)
+                    .with_cmd(UpdateKind::OnlyIfNotSet)
+                    .with_cwd(UpdateKind::OnlyIfNotSet)
+                    .with_user(UpdateKind::OnlyIfNotSet),
+            );
             sys.processes()
                 .iter()
                 .map(|(pid, process)| ProcessInfo {
-                    name:         process.name().to_string(),
-                    path:         process.exe().map(|p| p.to_string_lossy().into_owned()),
-                    memory_usage: process.memory(),
-                    swap_usage:   process.virtual_memory(),
-                    cpu_usage:    process.cpu_usage(),
-                    run_time:     Duration::from_secs(process.run_time()),
-                    pid:          *pid,
-                    parent:       process.parent(),
+                    name:             process.name().to_string(),
+                    path:             process.exe().map(|p| p.to_string_lossy().into_owned()),
+                    cmd:              process.cmd().to_vec(),
+                    cwd:              process.cwd().map(std::path::Path::to_path_buf),
+                    user:             process.user_id().and_then(|uid| users?.get_user_by_id(uid)).map(|user| user.name().to_string()),
+                    uid:              process.user_id().cloned(),
+                    group:            process.group_id().and_then(|gid| groups?.iter().find(|group| *group.id() == gid)).map(|group| group.name().to_string()),
+                    status:           process.status(),
+                    memory_usage:     process.memory(),
+                    swap_usage:       process.virtual_memory(),
+                    cpu_usage:        process.cpu_usage(),
+                    nice:             processes::process_nice(*pid).or_else(|| priorities.as_ref()?.get(&pid.as_u32()).copied()),
+                    run_time:         Duration::from_secs(process.run_time()),
+                    cpu_time:         processes::process_cpu_time(*pid).or_else(|| cpu_times.as_ref()?.get(&pid.as_u32()).copied()),
+                    start_time:       std::time::UNIX_EPOCH + Duration::from_secs(process.start_time()),
+                    file_descriptors: None,
+                    context_switches: scheduler::process_context_switches(*pid),
+                    sandbox:          sandbox::process_sandbox(*pid, process.exe().and_then(std::path::Path::to_str)),
+                    pid:              *pid,
+                    parent:           process.parent(),
                 })
                 .collect()
         })
     }
 
+    /// Only the processes that exited but weren't reaped by their parent
+    /// yet. Lots of them mean the parent is stuck or buggy
+    pub fn zombie_processes(&mut self) -> Option<Vec<ProcessInfo>> {
+        let mut processes = self.process_information()?;
+        processes.retain(|process| process.status == sysinfo::ProcessStatus::Zombie);
+        Some(processes)
+    }
+
+    /// Sets `ProcessInfo::file_descriptors` of `processes`. Reads a
+    /// directory per process, so frontends should only do this for the
+    /// ones they show it for
+    pub fn add_file_descriptors(&self, processes: &mut [ProcessInfo]) {
+        let usage = processes::file_descriptors(&processes.iter().map(|process| process.pid).collect::<Vec<sysinfo::Pid>>());
+        for process in processes {
+            process.file_descriptors = usage.get(&process.pid.as_u32()).copied();
+        }
+    }
+
+    /// Open file descriptors of a single process, see
+    /// `add_file_descriptors`
+    pub fn process_file_descriptors(&self, pid: sysinfo::Pid) -> Option<FileDescriptorUsage> {
+        processes::file_descriptors(&[pid]).remove(&pid.as_u32())
+    }
+
+    /// Every file, socket and pipe the process has open, by descriptor.
+    /// `ProcessInfo::file_descriptors` has how many there are. Only
+    /// supported on Linux and macOS, and for other users' processes only
+    /// when running as root
+    pub fn process_open_files(&self, pid: sysinfo::Pid) -> Option<Vec<OpenFile>> {
+        open_files::open_files(pid)
+    }
+
+    /// The threads of a process with their names and kind. The CPU usage
+    /// counts since the last call for the same process, so it is missing
+    /// on the first one. Only supported on Linux
+    pub fn process_threads(&mut self, pid: sysinfo::Pid) -> Option<Vec<ThreadInfo>> {
+        threads::process_threads(pid, &mut self.thread_times)
+    }
+
+    /// Open file handles of the whole system compared to the kernel wide
+    /// maximum. Only supported on Linux
+    pub fn file_descriptor_information(&self) -> Option<FileDescriptorUsage> {
+        processes::system_file_descriptors()
+    }
+
+    /// Vendor, model, VRAM, utilization of the engines and of the VRAM
+    /// bandwidth, temperature and driver of every GPU. Runs `nvidia-smi`
+    /// for NVIDIA cards and `system_profiler` on macOS, which takes a
+    /// moment
+    pub fn gpu_information(&self) -> Option<Vec<GpuInfo>> {
+        gpu::gpu_information()
+    }
+
+    /// Utilization of every GPU engine type, like 3D, Copy or VideoDecode,
+    /// in percent. Only supported on Windows
+    #[cfg_attr(not(windows), allow(clippy::missing_const_for_fn))]
+    pub fn gpu_engine_utilization(&self) -> Option<std::collections::BTreeMap<String, f64>> {
+        performance_counters::gpu_engine_utilization()
+    }
+
+    /// Whether the process is still running, and not some other one that
+    /// got its PID since. Only refreshes this one process
+    pub fn is_running(&mut self, id: ProcessId) -> bool {
+        self.system.as_mut().is_some_and(|sys| {
+            sys.refresh_process_specifics(id.pid, ProcessRefreshKind::new())
+                && sys.process(id.pid).is_some_and(|process| std::time::UNIX_EPOCH + Duration::from_secs(process.start_time()) == id.start_time)
+        })
+    }
+
     pub fn kill_process(&self, pid: sysinfo::Pid) -> bool {
         self.system.as_ref().map_or(false, |sys| sys.process(pid).is_some_and(sysinfo::Process::kill))
     }
 
+    /// Waits up to `timeout` for a process to exit, e.g. after killing it.
+    /// Only the parent of a process can collect its exit code, which
+    /// crossinfo never is, so this can only tell whether it is gone
+    pub fn wait_process(&mut self, pid: sysinfo::Pid, timeout: Duration) -> Option<ProcessExit> {
+        const POLL_INTERVAL: Duration = Duration::from_millis(50);
+        let sys = self.system.as_mut()?;
+        let started = Instant::now();
+        loop {
+            // Returns false once the process doesn't exist anymore
+            if !sys.refresh_process_specifics(pid, ProcessRefreshKind::new()) || sys.process(pid).is_some_and(|process| process.status() == sysinfo::ProcessStatus::Zombie) {
+                return Some(ProcessExit::Exited);
+            }
+            let remaining = timeout.saturating_sub(started.elapsed());
+            if remaining.is_zero() {
+                return Some(ProcessExit::TimedOut);
+            }
+            std::thread::sleep(remaining.min(POLL_INTERVAL));
+        }
+    }
+
+    /// Asks a process to quit with SIGTERM so it can save its work, and
+    /// kills it if it is still running after `timeout`. Windows has no
+    /// equivalent for console programs, so there it is killed right away.
+    /// Blocks until the process is gone or killing it failed, `None` if
+    /// it doesn't exist
+    pub fn terminate_gracefully(&mut self, pid: sysinfo::Pid, timeout: Duration) -> Option<Termination> {
+        // SIGKILL can't be ignored, but the kernel may still need a moment
+        const KILL_TIMEOUT: Duration = Duration::from_secs(1);
+        let process = self.system.as_ref()?.process(pid)?;
+        if process.kill_with(sysinfo::Signal::Term) == Some(true) && self.wait_process(pid, timeout)? == ProcessExit::Exited {
+            return Some(Termination::Terminated);
+        }
+        if !self.kill_process(pid) {
+            return Some(Termination::Failed);
+        }
+        Some(match self.wait_process(pid, KILL_TIMEOUT)? {
+            ProcessExit::Exited => Termination::Killed,
+            ProcessExit::TimedOut => Termination::Failed,
+        })
+    }
+
+    /// The IO scheduling class and level of a process. Only supported on
+    /// Linux
+    pub fn io_priority(&self, pid: sysinfo::Pid) -> Option<IoPriority> {
+        processes::io_priority(pid)
+    }
+
+    /// Returns whether the IO priority was changed. Lowering the priority
+    /// of your own processes is always allowed, raising it or touching
+    /// other users' processes needs root. Does nothing outside of
+    /// Linux
+    pub fn set_io_priority(&self, pid: sysinfo::Pid, priority: IoPriority) -> bool {
+        processes::set_io_priority(pid, priority)
+    }
+
+    /// Returns whether the nice value was changed. It is clamped to
+    /// `PRIORITY_RANGE`, and like with the IO priority only root may raise
+    /// the priority or touch other users' processes. Windows rounds it to
+    /// one of its priority classes
+    pub fn set_priority(&self, pid: sysinfo::Pid, nice: i32) -> bool {
+        processes::set_priority(pid, nice)
+    }
+
+    /// CPU and memory usage of this process, so a monitor can show what
+    /// it costs itself. Only refreshes this one process, unlike
+    /// `process_information`. The CPU usage counts since the last call
+    pub fn own_usage(&mut self) -> Option<OwnUsage> {
+        let pid = sysinfo::get_current_pid().ok()?;
+        let sys = self.system.as_mut()?;
+        sys.refresh_process_specifics(pid, ProcessRefreshKind::new().with_cpu().with_memory());
+        sys.process(pid).map(|process| OwnUsage {
+            cpu_usage:    process.cpu_usage(),
+            memory_usage: process.memory(),
+        })
+    }
+
     pub fn get_process(&self, pid: sysinfo::Pid) -> Option<&sysinfo::Process> {
         self.system.as_ref().and_then(|sys| sys.process(pid))
     }
 
     pub fn component_information(&mut self) -> Option<Vec<ComponentInfo>> {
-        self.components.as_mut().map(|components| {
+        self.try_component_information().ok()
+    }
+
+    /// # Errors
+    ///
+    /// `Error::Unsupported` if sysinfo doesn't support the platform
+    pub fn try_component_information(&mut self) -> Result<Vec<ComponentInfo>, Error> {
+        self.components.as_mut().ok_or(Error::Unsupported).map(|components| {
             components.refresh();
             components.refresh_list();
             components
@@ -495,12 +1409,62 @@ impl Manager {
                     temperature:          component.temperature(),
                     critical_temperature: component.critical(),
                 })
+                .chain(
+                    sensors::sensor_readings()
+                        .map(|readings| readings.temperatures)
+                        .unwrap_or_default()
+                        .into_iter()
+                        .map(|(name, temperature)| ComponentInfo {
+                            name,
+                            temperature,
+                            critical_temperature: None,
+                        }),
+                )
                 .collect()
         })
     }
 
+    /// Fans and their current speed. Only supported on macOS, where it
+    /// needs root
+    pub fn fan_information(&self) -> Option<Vec<FanInfo>> {
+        sensors::sensor_readings().map(|readings| readings.fans)
+    }
+
+    /// Power drawn by the CPU, GPU and other rails. Only supported on
+    /// macOS, where it needs root
+    pub fn power_information(&self) -> Option<Vec<PowerRailInfo>> {
+        sensors::sensor_readings().map(|readings| readings.power_rails)
+    }
+
+    /// Uses `pactl` on Linux, which works with both PulseAudio and
+    /// PipeWire. macOS only reports the volume of the default devices
+    pub fn audio_information(&self) -> Option<AudioInfo> {
+        audio::audio_information()
+    }
+
+    /// Returns whether the volume was changed. 1.0 is 100%
+    pub fn set_volume(&self, device: &AudioDevice, volume: f32) -> bool {
+        audio::set_volume(device, volume)
+    }
+
+    /// Makes the device with the `AudioDevice::id` the default output or
+    /// input, depending on its direction. Returns whether that worked,
+    /// which it never does outside of Linux: macOS only shows the default
+    /// devices, and Windows none at all
+    pub fn set_default_audio_device(&self, id: &str) -> bool {
+        audio::set_default_device(id)
+    }
+
     pub fn display_information(&self) -> Option<Vec<DisplayInfo>> {
-        display_info::DisplayInfo::all().ok().map(|monitors| {
+        self.try_display_information().ok()
+    }
+
+    /// # Errors
+    ///
+    /// If display-info can't list the displays
+    #[cfg(feature = "display")]
+    pub fn try_display_information(&self) -> Result<Vec<DisplayInfo>, Error> {
+        display_info::DisplayInfo::all().map_err(|error| Error::Display(error.to_string())).map(|monitors| {
             monitors
                 .iter()
                 .map(|monitor| DisplayInfo {
@@ -517,29 +1481,75 @@ impl Manager {
         })
     }
 
+    /// # Errors
+    ///
+    /// Always `Error::Unsupported`, as there is no display-info without
+    /// the `display` feature
+    #[cfg(not(feature = "display"))]
+    pub const fn try_display_information(&self) -> Result<Vec<DisplayInfo>, Error> {
+        Err(Error::Unsupported)
+    }
+
+    /// The devices seen by the selected adapter
     pub fn bluetooth_information(&self) -> Option<Vec<BluetoothInfo>> {
-        if let Some(adapter) = self.btleplug_adapter.as_ref() {
-            Some(
-                self.tokio_runtime
-                    .block_on(adapter.peripherals())
-                    .ok()?
-                    .iter()
-                    .map(|peripheral| {
-                        let properties = self.tokio_runtime.block_on(peripheral.properties()).ok().flatten();
-                        BluetoothInfo {
-                            id:                       peripheral.id().to_string(),
-                            address:                  peripheral.address(),
-                            local_name:               properties.as_ref().and_then(|props| props.local_name.clone()),
-                            transmission_power_level: properties.as_ref().and_then(|props| props.tx_power_level),
-                            signal_strength:          properties.as_ref().and_then(|props| props.rssi),
-                            is_connected:             self.tokio_runtime.block_on(peripheral.is_connected()).is_ok_and(|is_connected| is_connected),
-                        }
-                    })
-                    .collect(),
-            )
-        } else {
-            None
-        }
+        self.try_bluetooth_information().ok()
+    }
+
+    /// # Errors
+    ///
+    /// Like `try_bluetooth_information_on`
+    pub fn try_bluetooth_information(&self) -> Result<Vec<BluetoothInfo>, Error> {
+        self.try_bluetooth_information_on(self.bluetooth.selected())
+    }
+
+    /// `adapter` is an index into `bluetooth_adapters`. Only the selected
+    /// adapter scans, the others just know their connected and paired
+    /// devices
+    pub fn bluetooth_information_on(&self, adapter: usize) -> Option<Vec<BluetoothInfo>> {
+        self.try_bluetooth_information_on(adapter).ok()
+    }
+
+    /// # Errors
+    ///
+    /// `Error::NotFound` if there is no such Bluetooth adapter,
+    /// `Error::BluetoothOff` if it's turned off and `Error::Bluetooth` if
+    /// the devices can't be listed
+    pub fn try_bluetooth_information_on(&self, adapter: usize) -> Result<Vec<BluetoothInfo>, Error> {
+        self.block_on(self.bluetooth.devices(adapter)).ok_or(Error::Unsupported)?
+    }
+
+    /// Every Bluetooth adapter, also while they're turned off. Their names
+    /// and addresses need `bluetoothctl` on Linux and aren't known on
+    /// Windows
+    pub fn bluetooth_adapters(&self) -> Vec<BluetoothAdapterInfo> {
+        self.block_on(self.bluetooth.information()).unwrap_or_default()
+    }
+
+    /// The selected one of `bluetooth_adapters`
+    pub fn bluetooth_adapter_information(&self) -> Option<BluetoothAdapterInfo> {
+        self.bluetooth_adapters().into_iter().nth(self.bluetooth.selected())
+    }
+
+    /// The index of the adapter that scans, the first one unless another
+    /// was selected
+    pub const fn selected_bluetooth_adapter(&self) -> usize {
+        self.bluetooth.selected()
+    }
+
+    /// Scans with another adapter, e.g. a USB dongle instead of the
+    /// built-in one. Returns whether there is an adapter with that index
+    pub fn select_bluetooth_adapter(&mut self, adapter: usize) -> bool {
+        let Some(runtime) = self.tokio_runtime.as_ref() else {
+            return false;
+        };
+        runtime.block_on(self.bluetooth.select(adapter))
+    }
+
+    /// Turns the selected Bluetooth adapter on or off and returns whether
+    /// that worked. Needs `bluetoothctl` on Linux and `blueutil` on macOS,
+    /// never works on Windows
+    pub fn set_bluetooth_power(&self, powered: bool) -> bool {
+        self.block_on(self.bluetooth.set_power(powered)).unwrap_or_default()
     }
 }
 
@@ -0,0 +1,370 @@
+// sysinfo only reports a single aggregated SWAP figure. Linux lists every
+// active swap area in /proc/swaps, which also makes it possible to tell
+// compressed RAM (zram) apart from actual disk backed SWAP.
+//
+// What the used memory is made of comes from /proc/meminfo and
+// /proc/vmstat on Linux and from vm_stat on macOS. Windows only has it in
+// performance counters that aren't read.
+//
+// macOS and Windows compress memory before swapping it out, and neither
+// has a fixed amount of SWAP: macOS creates swap files as it needs them,
+// Windows grows the page files up to the limit they're configured with,
+// which WMI knows about along with the working set of the "Memory
+// Compression" process.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SwapKind {
+    Partition,
+    File,
+    /// Compressed block device in RAM
+    Zram,
+    Unknown,
+}
+
+impl std::fmt::Display for SwapKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Partition => "Partition",
+            Self::File => "File",
+            Self::Zram => "zram",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SwapDevice {
+    pub name:     String,
+    pub kind:     SwapKind,
+    pub size:     u64,
+    pub used:     u64,
+    /// Higher priority devices are used first
+    pub priority: i32,
+}
+
+#[cfg(target_os = "linux")]
+pub fn swap_devices() -> Option<Vec<SwapDevice>> {
+    std::fs::read_to_string("/proc/swaps").ok().map(|swaps| parse_swaps(&swaps))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn swap_devices() -> Option<Vec<SwapDevice>> {
+    None
+}
+
+/// zswap is a compressed cache in front of the regular SWAP devices and
+/// doesn't show up in /proc/swaps
+#[cfg(target_os = "linux")]
+pub fn zswap_enabled() -> Option<bool> {
+    std::fs::read_to_string("/sys/module/zswap/parameters/enabled").ok().map(|enabled| enabled.trim() == "Y")
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn zswap_enabled() -> Option<bool> {
+    None
+}
+
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_swaps(swaps: &str) -> Vec<SwapDevice> {
+    swaps
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            // Paths containing whitespace are escaped the same way as in /proc/mounts
+            let name = fields.next()?.replace("\\040", " ");
+            let kind = match fields.next()? {
+                _ if name.starts_with("/dev/zram") => SwapKind::Zram,
+                "partition" => SwapKind::Partition,
+                "file" => SwapKind::File,
+                _ => SwapKind::Unknown,
+            };
+            // Sizes are in KiB
+            let size = fields.next()?.parse::<u64>().ok()? * 1024;
+            let used = fields.next()?.parse::<u64>().ok()? * 1024;
+            let priority = fields.next()?.parse().ok()?;
+            Some(SwapDevice { name, kind, size, used, priority })
+        })
+        .collect()
+}
+
+/// The parts of `MemoryInfo` sysinfo doesn't know about
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemoryBreakdown {
+    pub cached:       Option<u64>,
+    pub buffers:      Option<u64>,
+    pub committed:    Option<u64>,
+    pub commit_limit: Option<u64>,
+    pub swap_ins:     Option<u64>,
+    pub swap_outs:    Option<u64>,
+    pub compressed:   Option<u64>,
+    pub dynamic_swap: bool,
+    pub swap_limit:   Option<u64>,
+}
+
+#[cfg(target_os = "linux")]
+pub fn memory_breakdown() -> MemoryBreakdown {
+    let mut breakdown = std::fs::read_to_string("/proc/meminfo").map(|meminfo| parse_meminfo(&meminfo)).unwrap_or_default();
+    if let Ok(vmstat) = std::fs::read_to_string("/proc/vmstat") {
+        let counter = |name: &str| vmstat.lines().find_map(|line| line.strip_prefix(name)?.trim().parse().ok());
+        breakdown.swap_ins = counter("pswpin ");
+        breakdown.swap_outs = counter("pswpout ");
+    }
+    breakdown
+}
+
+#[cfg(target_os = "macos")]
+pub fn memory_breakdown() -> MemoryBreakdown {
+    let breakdown = std::process::Command::new("vm_stat")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| parse_vm_stat(&String::from_utf8_lossy(&output.stdout)))
+        .unwrap_or_default();
+    // Only the free space of the boot volume limits the swap files
+    MemoryBreakdown { dynamic_swap: true, ..breakdown }
+}
+
+#[cfg(windows)]
+pub fn memory_breakdown() -> MemoryBreakdown {
+    let (dynamic_swap, swap_limit) = crate::performance_counters::page_files().map_or((false, None), |(settings, automatic)| parse_page_files(&settings, automatic));
+    MemoryBreakdown {
+        compressed: crate::performance_counters::memory_compression(),
+        dynamic_swap,
+        swap_limit,
+        ..MemoryBreakdown::default()
+    }
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn memory_breakdown() -> MemoryBreakdown {
+    MemoryBreakdown::default()
+}
+
+// Cached:          2150348 kB
+// Buffers:          123456 kB
+// Committed_AS:    9876543 kB
+// Zswap:             65536 kB
+//
+// Cached doesn't include SwapCached, which is memory that is in SWAP too.
+// Zswap is what the compressed pages take up, only since Linux 5.19
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_meminfo(meminfo: &str) -> MemoryBreakdown {
+    let size = |name: &str| {
+        meminfo
+            .lines()
+            .find_map(|line| line.strip_prefix(name)?.trim().strip_suffix(" kB")?.trim().parse::<u64>().ok())
+            .map(|size| size * 1024)
+    };
+    MemoryBreakdown {
+        cached:       size("Cached:"),
+        buffers:      size("Buffers:"),
+        committed:    size("Committed_AS:"),
+        commit_limit: size("CommitLimit:"),
+        compressed:   size("Zswap:"),
+        ..MemoryBreakdown::default()
+    }
+}
+
+// Mach Virtual Memory Statistics: (page size of 16384 bytes)
+// Pages free:                               12345.
+// File-backed pages:                       234567.
+// Swapins:                                      0.
+// Pages occupied by compressor:             54321.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_vm_stat(vm_stat: &str) -> MemoryBreakdown {
+    let page_size = vm_stat
+        .lines()
+        .next()
+        .and_then(|header| header.split("page size of ").nth(1)?.split_whitespace().next()?.parse::<u64>().ok())
+        .unwrap_or(4096);
+    let pages = |name: &str| vm_stat.lines().find_map(|line| line.strip_prefix(name)?.trim().trim_end_matches('.').parse::<u64>().ok());
+    MemoryBreakdown {
+        cached:     pages("File-backed pages:").map(|pages| pages * page_size),
+        swap_ins:   pages("Swapins:"),
+        swap_outs:  pages("Swapouts:"),
+        compressed: pages("Pages occupied by compressor:").map(|pages| pages * page_size),
+        ..MemoryBreakdown::default()
+    }
+}
+
+// Name, InitialSize and MaximumSize of every Win32_PageFileSetting, in MB,
+// and whether Windows manages the page file itself. A maximum of 0 lets
+// the page file grow as Windows sees fit, as does managing it
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_page_files(settings: &[Vec<String>], automatic: bool) -> (bool, Option<u64>) {
+    let sizes = settings
+        .iter()
+        .filter_map(|row| match row.as_slice() {
+            [_, initial, maximum] => Some((initial.parse::<u64>().ok()?, maximum.parse::<u64>().ok()?)),
+            _ => None,
+        })
+        .collect::<Vec<(u64, u64)>>();
+    let unlimited = automatic || sizes.iter().any(|(_, maximum)| *maximum == 0);
+    let dynamic = unlimited || sizes.iter().any(|(initial, maximum)| maximum > initial);
+    let limit = (dynamic && !unlimited).then(|| sizes.iter().map(|(_, maximum)| maximum * 1024 * 1024).sum());
+    (dynamic, limit)
+}
+
+#[derive(Debug, Clone)]
+pub struct OomKillEvent {
+    pub time:         std::time::SystemTime,
+    pub pid:          u32,
+    pub process_name: String,
+    /// Resident anonymous memory of the process at the time it was killed
+    pub anon_rss:     Option<u64>,
+}
+
+/// The kernel counts OOM kills in /proc/vmstat, which is a lot cheaper to
+/// watch than the kernel log
+#[cfg(target_os = "linux")]
+pub fn oom_kill_count() -> Option<u64> {
+    std::fs::read_to_string("/proc/vmstat")
+        .ok()?
+        .lines()
+        .find_map(|line| line.strip_prefix("oom_kill "))
+        .and_then(|count| count.trim().parse().ok())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn oom_kill_count() -> Option<u64> {
+    None
+}
+
+// journald keeps the kernel log across reboots and has absolute
+// timestamps. Without it dmesg is the fallback, which only has timestamps
+// relative to the boot and usually needs root.
+#[cfg(target_os = "linux")]
+pub fn oom_kill_events() -> Option<Vec<OomKillEvent>> {
+    if let Ok(output) = std::process::Command::new("journalctl")
+        .args(["--dmesg", "--output=short-unix", "--no-pager", "--quiet", "--grep=Killed process"])
+        .output()
+        && output.status.success()
+    {
+        return Some(
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .filter_map(|line| {
+                    let (timestamp, message) = line.split_once(' ')?;
+                    let time = std::time::UNIX_EPOCH + std::time::Duration::try_from_secs_f64(timestamp.parse().ok()?).ok()?;
+                    parse_oom_kill(time, message)
+                })
+                .collect(),
+        );
+    }
+    let output = std::process::Command::new("dmesg").output().ok().filter(|output| output.status.success())?;
+    let boot_time = std::time::UNIX_EPOCH + std::time::Duration::from_secs(sysinfo::System::boot_time());
+    Some(
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (timestamp, message) = line.strip_prefix('[')?.split_once(']')?;
+                let time = boot_time + std::time::Duration::try_from_secs_f64(timestamp.trim().parse().ok()?).ok()?;
+                parse_oom_kill(time, message)
+            })
+            .collect(),
+    )
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn oom_kill_events() -> Option<Vec<OomKillEvent>> {
+    None
+}
+
+// Parses the part of the log line that looks like
+// "Out of memory: Killed process 1234 (firefox) total-vm:123kB,
+// anon-rss:45kB, ..."
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_oom_kill(time: std::time::SystemTime, message: &str) -> Option<OomKillEvent> {
+    let (_, process) = message.split_once("Killed process ")?;
+    let (pid, rest) = process.split_once(' ')?;
+    let (process_name, rest) = rest.strip_prefix('(')?.split_once(')')?;
+    let anon_rss = rest
+        .split([',', ' '])
+        .find_map(|field| field.strip_prefix("anon-rss:"))
+        .and_then(|rss| rss.strip_suffix("kB"))
+        .and_then(|rss| rss.parse::<u64>().ok())
+        .map(|rss| rss * 1024);
+    Some(OomKillEvent {
+        time,
+        pid: pid.parse().ok()?,
+        process_name: process_name.to_string(),
+        anon_rss,
+    })
+}
+
+#[test]
+fn parse_memory_breakdown() {
+    let breakdown = parse_meminfo(
+        "MemTotal:       16318412 kB\nBuffers:          123456 kB\nCached:          2150348 kB\nSwapCached:            0 kB\nCommitLimit:    8159204 kB\nCommitted_AS:    9876543 kB\nZswap:             65536 kB\n",
+    );
+    assert_eq!(breakdown, MemoryBreakdown {
+        cached:       Some(2_150_348 * 1024),
+        buffers:      Some(123_456 * 1024),
+        committed:    Some(9_876_543 * 1024),
+        commit_limit: Some(8_159_204 * 1024),
+        swap_ins:     None,
+        swap_outs:    None,
+        compressed:   Some(65_536 * 1024),
+        dynamic_swap: false,
+        swap_limit:   None,
+    });
+
+    let breakdown = parse_vm_stat(
+        "Mach Virtual Memory Statistics: (page size of 16384 bytes)\nPages free:                               12345.\nFile-backed pages:                          100.\nSwapins:                                      7.\nSwapouts:                                    42.\nPages occupied by compressor:                 10.\n",
+    );
+    assert_eq!(breakdown.cached, Some(100 * 16384));
+    assert_eq!((breakdown.swap_ins, breakdown.swap_outs), (Some(7), Some(42)));
+    assert_eq!(breakdown.compressed, Some(10 * 16384));
+
+    let row = |initial: &str, maximum: &str| vec![r"C:\pagefile.sys".to_string(), initial.to_string(), maximum.to_string()];
+    assert_eq!(parse_page_files(&[], true), (true, None));
+    assert_eq!(parse_page_files(&[row("1024", "4096")], false), (true, Some(4096 * 1024 * 1024)));
+    assert_eq!(parse_page_files(&[row("2048", "2048")], false), (false, None));
+    assert_eq!(parse_page_files(&[row("0", "0")], false), (true, None));
+}
+
+#[test]
+fn parse_swap_devices() {
+    let swaps = parse_swaps(
+        "Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority
+/dev/nvme0n1p3                          partition\t8388604\t\t1048576\t\t-2
+/swap\\040file                          file\t\t2097148\t\t0\t\t-3
+/dev/zram0                              partition\t4194300\t\t524288\t\t100
+",
+    );
+    assert_eq!(swaps.len(), 3);
+    assert_eq!(swaps[0].kind, SwapKind::Partition);
+    assert_eq!((swaps[0].size, swaps[0].used, swaps[0].priority), (8_388_604 * 1024, 1_048_576 * 1024, -2));
+    assert_eq!(swaps[1].name, "/swap file");
+    assert_eq!(swaps[1].kind, SwapKind::File);
+    // zram shows up as a partition
+    assert_eq!(swaps[2].kind, SwapKind::Zram);
+    assert_eq!(swaps[2].priority, 100);
+    assert!(parse_swaps("Filename\t\t\t\tType\t\tSize\t\tUsed\t\tPriority\n").is_empty());
+}
+
+#[test]
+fn parse_oom_kill_lines() {
+    let time = std::time::SystemTime::UNIX_EPOCH;
+    let event = parse_oom_kill(
+        time,
+        "Out of memory: Killed process 48213 (firefox) total-vm:12541236kB, anon-rss:6273820kB, file-rss:0kB, shmem-rss:1024kB, UID:1000 pgtables:18432kB oom_score_adj:0",
+    )
+    .expect("The line is an OOM kill");
+    assert_eq!(event.pid, 48_213);
+    assert_eq!(event.process_name, "firefox");
+    assert_eq!(event.anon_rss, Some(6_273_820 * 1024));
+
+    // Names with spaces, and the cgroup variant of the message
+    let event = parse_oom_kill(
+        time,
+        "Memory cgroup out of memory: Killed process 901 (Web Content) total-vm:2456780kB, anon-rss:1203400kB, file-rss:81920kB, shmem-rss:0kB, UID:1000 pgtables:4096kB oom_score_adj:100",
+    )
+    .expect("The line is an OOM kill");
+    assert_eq!((event.pid, event.process_name.as_str()), (901, "Web Content"));
+
+    assert!(parse_oom_kill(time, "oom-kill:constraint=CONSTRAINT_NONE,nodemask=(null),cpuset=/,mems_allowed=0,task=firefox,pid=48213,uid=1000").is_none());
+}
@@ -0,0 +1,193 @@
+// What `network_information` adds to sysinfo's interface counters: the
+// HTTPS connectivity check through reqwest, interface details through
+// pnet_datalink and the local IP addresses. These pull in a lot, so they
+// are behind the `network-extra` feature and unknown without it.
+
+use std::net::IpAddr;
+
+use crate::Network;
+
+#[cfg(feature = "network-extra")]
+pub async fn is_reachable(url: &str) -> bool {
+    reqwest::get(url).await.is_ok()
+}
+
+#[cfg(not(feature = "network-extra"))]
+#[allow(clippy::unused_async)]
+pub async fn is_reachable(_url: &str) -> bool {
+    false
+}
+
+/// Fills in description, index, addresses and flags, adding the
+/// interfaces sysinfo doesn't know
+#[cfg(feature = "network-extra")]
+pub fn add_interface_details(networks: &mut Vec<Network>) {
+    let interfaces = pnet_datalink::interfaces()
+        .into_iter()
+        .map(|interface| InterfaceDetails {
+            flags:       crate::NetworkFlags {
+                raw:               interface.flags,
+                is_up:             interface.is_up(),
+                is_broadcast:      interface.is_broadcast(),
+                is_loopback:       interface.is_loopback(),
+                is_point_to_point: interface.is_point_to_point(),
+                is_multicast:      interface.is_multicast(),
+            },
+            name:        interface.name,
+            description: interface.description,
+            index:       interface.index,
+            mac_address: interface.mac.map(|mac| sysinfo::MacAddr(mac.octets())),
+            ips:         interface.ips.iter().map(ipnetwork::IpNetwork::ip).collect(),
+        })
+        .collect();
+    merge_interface_details(networks, interfaces);
+}
+
+#[cfg(not(feature = "network-extra"))]
+pub const fn add_interface_details(_networks: &mut Vec<Network>) {}
+
+/// What pnet knows about an interface, without pnet's types
+#[cfg_attr(not(feature = "network-extra"), allow(dead_code))]
+struct InterfaceDetails {
+    name:        String,
+    description: String,
+    index:       u32,
+    mac_address: Option<sysinfo::MacAddr>,
+    ips:         Vec<IpAddr>,
+    flags:       crate::NetworkFlags,
+}
+
+// sysinfo and pnet don't always agree on names. On Windows sysinfo uses
+// the connection name ("Ethernet 2") and pnet \Device\NPF_{GUID}, and
+// interfaces renamed while running keep their old name in one of them
+// until the next refresh. Interfaces are matched by name first, then by
+// index for interfaces pnet lists more than once, and last by MAC address
+// where exactly one of sysinfo's interfaces has it, since bridges, VLANs
+// and bonds share the MAC address of the interfaces they are made of.
+#[cfg_attr(not(feature = "network-extra"), allow(dead_code))]
+fn merge_interface_details(networks: &mut Vec<Network>, interfaces: Vec<InterfaceDetails>) {
+    let mut unmatched = Vec::new();
+    for interface in interfaces {
+        match networks.iter().position(|network| network.name == interface.name || network.index == Some(interface.index)) {
+            Some(position) => merge_interface(&mut networks[position], interface),
+            None => unmatched.push(interface),
+        }
+    }
+    for interface in unmatched {
+        let position = networks.iter().position(|network| network.index == Some(interface.index)).or_else(|| {
+            let candidates = networks
+                .iter()
+                .enumerate()
+                .filter(|(_, network)| network.index.is_none() && interface.mac_address.is_some_and(|mac| !mac.is_unspecified()) && network.mac_address == interface.mac_address)
+                .map(|(position, _)| position)
+                .collect::<Vec<usize>>();
+            match candidates.as_slice() {
+                [position] => Some(*position),
+                _ => None,
+            }
+        });
+        if let Some(position) = position {
+            merge_interface(&mut networks[position], interface);
+        } else {
+            let mut network = Network {
+                name: interface.name.clone(),
+                ..Default::default()
+            };
+            merge_interface(&mut network, interface);
+            networks.push(network);
+        }
+    }
+}
+
+/// Keeps the name sysinfo uses, which is the friendlier one on Windows
+#[cfg_attr(not(feature = "network-extra"), allow(dead_code))]
+fn merge_interface(network: &mut Network, interface: InterfaceDetails) {
+    if !interface.description.is_empty() || network.description.is_none() {
+        network.description = Some(interface.description);
+    }
+    network.index = Some(interface.index);
+    let mac_address = network.mac_address;
+    network.mac_address = mac_address.filter(|mac| !mac.is_unspecified()).or(interface.mac_address).or(mac_address);
+    let ips = network.ips.get_or_insert_with(Vec::new);
+    for ip in interface.ips {
+        if !ips.contains(&ip) {
+            ips.push(ip);
+        }
+    }
+    network.flags = Some(interface.flags);
+}
+
+/// IPv4 and IPv6
+#[cfg(feature = "network-extra")]
+pub fn local_ip_addresses() -> (Option<IpAddr>, Option<IpAddr>) {
+    (local_ip_address::local_ip().ok(), local_ip_address::local_ipv6().ok())
+}
+
+#[cfg(not(feature = "network-extra"))]
+pub const fn local_ip_addresses() -> (Option<IpAddr>, Option<IpAddr>) {
+    (None, None)
+}
+
+#[test]
+fn merge_interfaces() {
+    let flags = crate::NetworkFlags {
+        raw:               0,
+        is_up:             true,
+        is_broadcast:      true,
+        is_loopback:       false,
+        is_point_to_point: false,
+        is_multicast:      true,
+    };
+    let interface = |name: &str, index, mac: Option<[u8; 6]>, ip: &str| InterfaceDetails {
+        name: name.to_string(),
+        description: String::new(),
+        index,
+        mac_address: mac.map(sysinfo::MacAddr),
+        ips: vec![ip.parse().expect("The address is valid")],
+        flags,
+    };
+    let network = |name: &str, mac: [u8; 6]| Network {
+        name: name.to_string(),
+        mac_address: Some(sysinfo::MacAddr(mac)),
+        ..Default::default()
+    };
+    let ethernet = [0x1a, 0x2b, 0x3c, 0x4d, 0x5e, 0x6f];
+    let wifi = [0xa4, 0x83, 0xe7, 0x12, 0x34, 0x56];
+
+    // Linux: the bridge has the MAC address of the interface in it, but the
+    // names match
+    let mut networks = vec![network("eth0", ethernet), network("br0", ethernet), network("lo", [0; 6])];
+    merge_interface_details(&mut networks, vec![
+        interface("lo", 1, Some([0; 6]), "127.0.0.1"),
+        interface("br0", 3, Some(ethernet), "192.168.1.2"),
+        interface("eth0", 2, Some(ethernet), "fe80::1"),
+    ]);
+    assert_eq!(networks.len(), 3);
+    assert_eq!(networks.iter().map(|network| network.index).collect::<Vec<_>>(), vec![Some(2), Some(3), Some(1)]);
+
+    // Windows: sysinfo has connection names, pnet GUIDs, and pnet lists the
+    // IPv4 and IPv6 addresses of an adapter separately
+    let mut networks = vec![network("Ethernet 2", ethernet), network("Wi-Fi", wifi)];
+    merge_interface_details(&mut networks, vec![
+        interface(r"\Device\NPF_{8E2A0F7C-52B1-4C3A-9D4E-1F2A3B4C5D6E}", 12, Some(wifi), "192.168.1.3"),
+        interface(r"\Device\NPF_{8E2A0F7C-52B1-4C3A-9D4E-1F2A3B4C5D6E}", 12, Some(wifi), "fe80::2"),
+        interface(r"\Device\NPF_Loopback", 1, None, "127.0.0.1"),
+    ]);
+    assert_eq!(networks.len(), 3);
+    assert_eq!(networks[0].index, None);
+    assert_eq!(networks[1].name, "Wi-Fi");
+    assert_eq!(networks[1].index, Some(12));
+    assert_eq!(networks[1].ips.as_ref().map(Vec::len), Some(2));
+    assert_eq!(networks[2].name, r"\Device\NPF_Loopback");
+
+    // macOS: tunnels have no MAC address and sysinfo doesn't know about
+    // every one of them
+    let mut networks = vec![network("en0", wifi), network("utun0", [0; 6])];
+    merge_interface_details(&mut networks, vec![
+        interface("en0", 4, Some(wifi), "192.168.1.4"),
+        interface("utun0", 14, None, "fe80::3"),
+        interface("utun1", 15, None, "fe80::4"),
+    ]);
+    assert_eq!(networks.iter().map(|network| network.name.as_str()).collect::<Vec<_>>(), vec!["en0", "utun0", "utun1"]);
+    assert_eq!(networks[2].mac_address, None);
+}
@@ -0,0 +1,150 @@
+// How memory and CPUs are split into NUMA nodes, and how many huge pages
+// are set aside. Memory of another node takes longer to reach, so servers
+// running NUMA-aware workloads pin them to the CPUs of one node:
+//
+// - Linux: /sys/devices/system/node for the nodes and
+//   /sys/kernel/mm/hugepages for the huge pages of the whole system
+// - Windows and macOS would need unsafe system calls, and Apple silicon
+//   doesn't have more than one node anyway
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HugePages {
+    /// Of a single page, e.g. 2 MiB or 1 GiB on x86
+    pub page_size: u64,
+    pub total:     u64,
+    pub free:      u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NumaNode {
+    pub id:           u32,
+    pub total_memory: u64,
+    pub used_memory:  u64,
+    /// The logical CPUs belonging to the node
+    pub cpus:         Vec<usize>,
+    /// Per page size
+    pub hugepages:    Vec<HugePages>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NumaInfo {
+    /// A single node on most desktops
+    pub nodes:                 Vec<NumaNode>,
+    /// Per page size, over all nodes
+    pub hugepages:             Vec<HugePages>,
+    /// always, madvise or never
+    pub transparent_hugepages: Option<String>,
+}
+
+#[cfg(target_os = "linux")]
+pub fn numa_information() -> Option<NumaInfo> {
+    let read = |path: &std::path::Path| std::fs::read_to_string(path).ok();
+    let mut nodes = std::fs::read_dir("/sys/devices/system/node")
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let id = entry.file_name().to_str()?.strip_prefix("node")?.parse().ok()?;
+            let path = entry.path();
+            let (total_memory, used_memory) = parse_node_meminfo(&read(&path.join("meminfo"))?)?;
+            Some(NumaNode {
+                id,
+                total_memory,
+                used_memory,
+                cpus: read(&path.join("cpulist")).map(|list| parse_cpu_list(&list)).unwrap_or_default(),
+                hugepages: hugepages(&path.join("hugepages")),
+            })
+        })
+        .collect::<Vec<NumaNode>>();
+    nodes.sort_by_key(|node| node.id);
+    Some(NumaInfo {
+        nodes,
+        hugepages: hugepages(std::path::Path::new("/sys/kernel/mm/hugepages")),
+        transparent_hugepages: read(std::path::Path::new("/sys/kernel/mm/transparent_hugepage/enabled")).and_then(|enabled| parse_selected(&enabled)),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn numa_information() -> Option<NumaInfo> {
+    None
+}
+
+/// Every hugepages-<size>kB directory in `directory`
+#[cfg(target_os = "linux")]
+fn hugepages(directory: &std::path::Path) -> Vec<HugePages> {
+    let count = |path: std::path::PathBuf| std::fs::read_to_string(path).ok()?.trim().parse::<u64>().ok();
+    let mut hugepages = std::fs::read_dir(directory)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .filter_map(|entry| {
+            Some(HugePages {
+                page_size: parse_page_size(entry.file_name().to_str()?)?,
+                total:     count(entry.path().join("nr_hugepages"))?,
+                free:      count(entry.path().join("free_hugepages"))?,
+            })
+        })
+        .collect::<Vec<HugePages>>();
+    hugepages.sort_by_key(|hugepages| hugepages.page_size);
+    hugepages
+}
+
+// Node 0 MemTotal:       16318412 kB
+// Node 0 MemFree:         8159204 kB
+// Node 0 MemUsed:         8159208 kB
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_node_meminfo(meminfo: &str) -> Option<(u64, u64)> {
+    let size = |name: &str| {
+        meminfo.lines().find_map(|line| {
+            let mut fields = line.split_whitespace().skip(2);
+            if fields.next()? != name {
+                return None;
+            }
+            fields.next()?.parse::<u64>().ok().map(|size| size * 1024)
+        })
+    };
+    Some((size("MemTotal:")?, size("MemUsed:")?))
+}
+
+// 0-7,16-23
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_cpu_list(list: &str) -> Vec<usize> {
+    list.trim()
+        .split(',')
+        .filter_map(|range| {
+            if let Some((start, end)) = range.split_once('-') {
+                Some(start.parse::<usize>().ok()?..=end.parse().ok()?)
+            } else {
+                let cpu = range.parse().ok()?;
+                Some(cpu..=cpu)
+            }
+        })
+        .flatten()
+        .collect()
+}
+
+// hugepages-2048kB
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_page_size(name: &str) -> Option<u64> {
+    name.strip_prefix("hugepages-")?.strip_suffix("kB")?.parse::<u64>().ok().map(|size| size * 1024)
+}
+
+// always [madvise] never
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_selected(options: &str) -> Option<String> {
+    options.split_whitespace().find_map(|option| Some(option.strip_prefix('[')?.strip_suffix(']')?.to_string()))
+}
+
+#[test]
+fn parse_numa() {
+    assert_eq!(
+        parse_node_meminfo("Node 0 MemTotal:       16318412 kB\nNode 0 MemFree:         8159204 kB\nNode 0 MemUsed:         8159208 kB\n"),
+        Some((16_318_412 * 1024, 8_159_208 * 1024))
+    );
+    assert_eq!(parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+    assert_eq!(parse_cpu_list("\n"), Vec::<usize>::new());
+    assert_eq!(parse_page_size("hugepages-2048kB"), Some(2 * 1024 * 1024));
+    assert_eq!(parse_selected("always [madvise] never\n"), Some("madvise".to_string()));
+}
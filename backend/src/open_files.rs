@@ -0,0 +1,188 @@
+// What a process has open, for tracking down descriptor leaks:
+//
+// - Linux: the links in /proc/<pid>/fd. Network sockets only show their
+//   inode there, which is looked up in the socket tables of /proc/net
+// - macOS: lsof, which comes with the system
+// - Windows would need NtQuerySystemInformation, so nothing is listed
+//
+// Other users' processes can only be read by root.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum OpenFileKind {
+    File,
+    Directory,
+    /// Network and Unix domain sockets
+    Socket,
+    Pipe,
+    /// Terminals, /dev/null and the like
+    Device,
+    /// eventfd, epoll, inotify and other kernel objects without a path
+    Other,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OpenFile {
+    pub descriptor: u32,
+    pub kind:       OpenFileKind,
+    /// The path, the addresses of a network socket like "tcp
+    /// 127.0.0.1:5432 -> 127.0.0.1:41234", or what the kernel calls the
+    /// object, e.g. "anon_inode:[eventfd]"
+    pub name:       String,
+}
+
+#[cfg(target_os = "linux")]
+pub fn open_files(pid: sysinfo::Pid) -> Option<Vec<OpenFile>> {
+    use std::os::unix::fs::FileTypeExt;
+
+    let sockets = network_sockets();
+    let mut files = std::fs::read_dir(format!("/proc/{pid}/fd"))
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let descriptor = entry.file_name().to_str()?.parse().ok()?;
+            let target = std::fs::read_link(entry.path()).ok()?.to_string_lossy().into_owned();
+            let (kind, name) = if let Some(inode) = target.strip_prefix("socket:[").and_then(|inode| inode.strip_suffix(']')) {
+                (OpenFileKind::Socket, sockets.get(inode).cloned().unwrap_or(target))
+            } else if target.starts_with("pipe:") {
+                (OpenFileKind::Pipe, target)
+            } else if target.starts_with('/') {
+                // Follows the link, unlike the entry's own metadata
+                let file_type = std::fs::metadata(entry.path()).ok().map(|metadata| metadata.file_type());
+                let kind = match file_type {
+                    Some(file_type) if file_type.is_dir() => OpenFileKind::Directory,
+                    Some(file_type) if file_type.is_char_device() || file_type.is_block_device() => OpenFileKind::Device,
+                    Some(file_type) if file_type.is_fifo() => OpenFileKind::Pipe,
+                    Some(file_type) if file_type.is_socket() => OpenFileKind::Socket,
+                    _ => OpenFileKind::File,
+                };
+                (kind, target)
+            } else {
+                (OpenFileKind::Other, target)
+            };
+            Some(OpenFile { descriptor, kind, name })
+        })
+        .collect::<Vec<OpenFile>>();
+    files.sort_by_key(|file| file.descriptor);
+    Some(files)
+}
+
+/// Descriptions of the TCP and UDP sockets of the whole system, keyed by
+/// inode
+#[cfg(target_os = "linux")]
+fn network_sockets() -> std::collections::HashMap<String, String> {
+    // The IPv6 tables are missing if IPv6 is disabled
+    ["tcp", "tcp6", "udp", "udp6"]
+        .into_iter()
+        .filter_map(|file| Some((file.trim_end_matches('6'), std::fs::read_to_string(format!("/proc/net/{file}")).ok()?)))
+        .flat_map(|(protocol, table)| parse_socket_table(&table, protocol))
+        .collect()
+}
+
+// After the header every line has the local and the remote address,
+// followed by the state, queues, timers, retransmits, UID, timeout and
+// the inode:
+//
+//   1: 0100007F:1538 0100007F:A262 01 ... 1000 0 52137 ...
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_socket_table(table: &str, protocol: &str) -> Vec<(String, String)> {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            let local = crate::snapshot::parse_proc_net_address(fields.get(1)?)?;
+            let remote = crate::snapshot::parse_proc_net_address(fields.get(2)?)?;
+            let description = if remote.ip().is_unspecified() && remote.port() == 0 {
+                format!("{protocol} {local}")
+            } else {
+                format!("{protocol} {local} -> {remote}")
+            };
+            Some(((*fields.get(9)?).to_string(), description))
+        })
+        .collect()
+}
+
+#[cfg(target_os = "macos")]
+pub fn open_files(pid: sysinfo::Pid) -> Option<Vec<OpenFile>> {
+    let output = std::process::Command::new("lsof")
+        .args(["-n", "-P", "-F", "ftn", "-p", &pid.to_string()])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(parse_lsof(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub const fn open_files(_pid: sysinfo::Pid) -> Option<Vec<OpenFile>> {
+    None
+}
+
+// One field per line, starting with its name: p for the process, then f
+// for the descriptor, t for the type and n for the name of every file.
+// Descriptors like cwd and txt are the working directory and the
+// executable, which aren't open descriptors.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_lsof(lsof: &str) -> Vec<OpenFile> {
+    let mut files = Vec::<OpenFile>::new();
+    let mut current = None;
+    for line in lsof.lines() {
+        let (field, value) = line.split_at(line.len().min(1));
+        match field {
+            "f" => {
+                files.extend(current.take());
+                current = value.parse().ok().map(|descriptor| OpenFile {
+                    descriptor,
+                    kind: OpenFileKind::Other,
+                    name: String::new(),
+                });
+            }
+            "t" => {
+                if let Some(file) = &mut current {
+                    file.kind = match value {
+                        "REG" => OpenFileKind::File,
+                        "DIR" => OpenFileKind::Directory,
+                        "IPv4" | "IPv6" | "unix" | "systm" => OpenFileKind::Socket,
+                        "PIPE" | "FIFO" => OpenFileKind::Pipe,
+                        "CHR" | "BLK" => OpenFileKind::Device,
+                        _ => OpenFileKind::Other,
+                    };
+                }
+            }
+            "n" => {
+                if let Some(file) = &mut current {
+                    file.name = value.to_string();
+                }
+            }
+            _ => {}
+        }
+    }
+    files.extend(current);
+    files
+}
+
+#[test]
+fn parse_open_files() {
+    let sockets = parse_socket_table(
+        "  sl  local_address rem_address   st tx_queue rx_queue tr tm->when retrnsmt   uid  timeout inode
+   0: 0100007F:1538 00000000:0000 0A 00000000:00000000 00:00000000 00000000   113        0 21044 1 0000000000000000 100 0 0 10 0
+   1: 0100007F:1538 0100007F:A262 01 00000000:00000000 00:00000000 00000000  1000        0 52137 1 0000000000000000 20 4 30 10 -1
+",
+        "tcp",
+    );
+    assert_eq!(sockets, vec![
+        ("21044".to_string(), "tcp 127.0.0.1:5432".to_string()),
+        ("52137".to_string(), "tcp 127.0.0.1:5432 -> 127.0.0.1:41570".to_string()),
+    ]);
+
+    let files = parse_lsof("p412\nfcwd\ntDIR\nn/\nf0\ntCHR\nn/dev/null\nf5\ntIPv4\nn127.0.0.1:5432->127.0.0.1:41570\nf6\ntREG\nn/var/log/system.log\n");
+    assert_eq!(files.len(), 3);
+    assert_eq!(files[0], OpenFile {
+        descriptor: 0,
+        kind:       OpenFileKind::Device,
+        name:       "/dev/null".to_string(),
+    });
+    assert_eq!(files[1].kind, OpenFileKind::Socket);
+    assert_eq!(files[2].name, "/var/log/system.log");
+}
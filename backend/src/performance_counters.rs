@@ -0,0 +1,282 @@
+// Windows exposes a lot more through its performance counters than sysinfo
+// reads, e.g. disk queue lengths, GPU engine utilization and per-process
+// handle counts. The backend forbids unsafe code, so instead of calling
+// PDH directly the formatted counters are read through WMI with
+// PowerShell. Starting PowerShell takes a while, so results are cached for
+// a few seconds.
+
+use std::collections::{BTreeMap, HashMap};
+
+#[cfg(windows)]
+const CACHE_DURATION: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[cfg(windows)]
+fn query(class: &'static str, properties: &[&str]) -> Option<Vec<Vec<String>>> {
+    type Cache = BTreeMap<&'static str, (std::time::Instant, Vec<Vec<String>>)>;
+    static CACHE: std::sync::Mutex<Cache> = std::sync::Mutex::new(BTreeMap::new());
+
+    let mut cache = CACHE.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let Some((queried_at, rows)) = cache.get(class)
+        && queried_at.elapsed() < CACHE_DURATION
+    {
+        return Some(rows.clone());
+    }
+    let output = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            &format!("Get-CimInstance -ClassName {class} | Select-Object {} | ConvertTo-Csv -NoTypeInformation", properties.join(",")),
+        ])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    // The first row is the header
+    let rows = parse_csv(&String::from_utf8_lossy(&output.stdout)).into_iter().skip(1).collect::<Vec<Vec<String>>>();
+    cache.insert(class, (std::time::Instant::now(), rows.clone()));
+    drop(cache);
+    Some(rows)
+}
+
+/// The raw counters of every logical disk, for
+/// `disk_activity::parse_logical_disks`
+#[cfg(windows)]
+pub fn logical_disks() -> Option<Vec<Vec<String>>> {
+    query("Win32_PerfRawData_PerfDisk_LogicalDisk", &[
+        "Name",
+        "CurrentDiskQueueLength",
+        "AvgDiskSecPerTransfer",
+        "AvgDiskSecPerTransfer_Base",
+        "AvgDiskQueueLength",
+        "Timestamp_PerfTime",
+        "Frequency_PerfTime",
+    ])
+}
+
+/// Device ID, model, serial number, interface and size of every drive, for
+/// `physical_disks::parse_disk_drives`
+#[cfg(windows)]
+pub fn disk_drives() -> Option<Vec<Vec<String>>> {
+    query("Win32_DiskDrive", &["DeviceID", "Model", "SerialNumber", "InterfaceType", "Size"])
+}
+
+/// GUID and connection name of every network adapter, for
+/// `interface_names::parse_network_adapters`
+#[cfg(windows)]
+pub fn network_adapters() -> Option<Vec<Vec<String>>> {
+    query("Win32_NetworkAdapter", &["GUID", "NetConnectionID"])
+}
+
+// The cache is per class, so everything read from Win32_Process has to be
+// queried at once
+#[cfg(windows)]
+const PROCESS_PROPERTIES: [&str; 7] = ["ProcessId", "HandleCount", "KernelModeTime", "UserModeTime", "Name", "WorkingSetSize", "Priority"];
+
+/// Open handles of every process, keyed by PID
+#[cfg(windows)]
+pub fn process_handles() -> Option<HashMap<u32, u64>> {
+    let rows = query("Win32_Process", &PROCESS_PROPERTIES)?;
+    Some(
+        rows.into_iter()
+            .filter_map(|row| match row.as_slice() {
+                [pid, handles, ..] => Some((pid.parse().ok()?, handles.parse().ok()?)),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(not(windows))]
+pub const fn process_handles() -> Option<HashMap<u32, u64>> {
+    None
+}
+
+/// CPU time every process used so far, in user and kernel mode together,
+/// keyed by PID
+#[cfg(windows)]
+pub fn process_cpu_times() -> Option<HashMap<u32, std::time::Duration>> {
+    let rows = query("Win32_Process", &PROCESS_PROPERTIES)?;
+    Some(
+        rows.into_iter()
+            .filter_map(|row| match row.as_slice() {
+                // Both are in units of 100 nanoseconds
+                [pid, _, kernel_time, user_time, ..] => Some((
+                    pid.parse().ok()?,
+                    std::time::Duration::from_nanos(kernel_time.parse::<u64>().ok()?.saturating_add(user_time.parse().ok()?).saturating_mul(100)),
+                )),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(not(windows))]
+pub const fn process_cpu_times() -> Option<HashMap<u32, std::time::Duration>> {
+    None
+}
+
+/// The priority class of every process as a nice value, keyed by PID.
+/// WMI only has the base priority of the class: 4 for idle, 6 below
+/// normal, 8 normal, 10 above normal, 13 high and 24 realtime
+#[cfg(windows)]
+pub fn process_priorities() -> Option<HashMap<u32, i32>> {
+    let rows = query("Win32_Process", &PROCESS_PROPERTIES)?;
+    Some(
+        rows.into_iter()
+            .filter_map(|row| match row.as_slice() {
+                [pid, .., priority] => Some((pid.parse().ok()?, match priority.parse::<u32>().ok()? {
+                    ..=4 => 19,
+                    5..=6 => 10,
+                    7..=8 => 0,
+                    9..=10 => -5,
+                    11..=13 => -10,
+                    _ => -20,
+                })),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+/// What the compressed memory takes up, which Windows accounts to the
+/// working set of the "Memory Compression" process
+#[cfg(windows)]
+pub fn memory_compression() -> Option<u64> {
+    let rows = query("Win32_Process", &PROCESS_PROPERTIES)?;
+    rows.into_iter().find_map(|row| match row.as_slice() {
+        [_, _, _, _, name, working_set, _] if name == "Memory Compression" => working_set.parse().ok(),
+        _ => None,
+    })
+}
+
+/// Name, initial and maximum size of every configured page file, along
+/// with whether Windows manages the page file itself, for
+/// `memory::parse_page_files`
+#[cfg(windows)]
+pub fn page_files() -> Option<(Vec<Vec<String>>, bool)> {
+    let automatic = query("Win32_ComputerSystem", &["AutomaticManagedPagefile"])?.first()?.first()? == "True";
+    Some((query("Win32_PageFileSetting", &["Name", "InitialSize", "MaximumSize"]).unwrap_or_default(), automatic))
+}
+
+/// Model, vendor, VRAM and driver of every graphics adapter
+#[cfg(windows)]
+pub fn video_controllers() -> Option<Vec<crate::GpuInfo>> {
+    let rows = query("Win32_VideoController", &["Name", "AdapterCompatibility", "AdapterRAM", "DriverVersion"])?;
+    Some(
+        rows.into_iter()
+            .filter_map(|row| match row.as_slice() {
+                [name, vendor, vram, driver_version] => Some(crate::GpuInfo {
+                    // E.g. "Advanced Micro Devices, Inc." or "Intel Corporation"
+                    vendor: match vendor.as_str() {
+                        vendor if vendor.starts_with("Advanced Micro Devices") => "AMD".to_string(),
+                        vendor => vendor.trim_end_matches(" Corporation").to_string(),
+                    },
+                    model: Some(name.clone()),
+                    // A 32 bit number, so it stops at 4 GiB
+                    vram_total: vram.parse().ok(),
+                    driver_version: Some(driver_version.clone()).filter(|version| !version.is_empty()),
+                    ..crate::GpuInfo::default()
+                }),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(windows)]
+pub fn gpu_engine_utilization() -> Option<BTreeMap<String, f64>> {
+    query("Win32_PerfFormattedData_GPUPerformanceCounters_GPUEngine", &["Name", "UtilizationPercentage"]).map(|rows| parse_gpu_engines(&rows))
+}
+
+#[cfg(not(windows))]
+pub const fn gpu_engine_utilization() -> Option<BTreeMap<String, f64>> {
+    None
+}
+
+/// Name, user, privileged and idle percent and interrupts per second of
+/// every logical processor and of "_Total"
+#[cfg(windows)]
+pub fn processor_times() -> Option<Vec<Vec<String>>> {
+    query("Win32_PerfFormattedData_PerfOS_Processor", &[
+        "Name",
+        "PercentUserTime",
+        "PercentPrivilegedTime",
+        "PercentIdleTime",
+        "InterruptsPersec",
+    ])
+}
+
+/// Context switches per second of the whole system
+#[cfg(windows)]
+pub fn context_switches() -> Option<f64> {
+    query("Win32_PerfFormattedData_PerfOS_System", &["ContextSwitchesPersec"])?.first()?.first()?.parse().ok()
+}
+
+// There is one counter per process and engine, named like
+// "pid_1234_luid_0x0_0xd1a5_phys_0_eng_0_engtype_3D". The processes are
+// summed up per engine, and if there are several engines of the same type
+// the busiest one counts.
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_gpu_engines(rows: &[Vec<String>]) -> BTreeMap<String, f64> {
+    let mut engines = BTreeMap::<&str, f64>::new();
+    for row in rows {
+        if let [name, utilization] = row.as_slice()
+            && let Some((_, engine)) = name.split_once("_luid_")
+            && let Ok(utilization) = utilization.parse::<f64>()
+        {
+            *engines.entry(engine).or_default() += utilization;
+        }
+    }
+    let mut engine_types = BTreeMap::<String, f64>::new();
+    for (engine, utilization) in engines {
+        if let Some((_, engine_type)) = engine.split_once("_engtype_") {
+            let busiest = engine_types.entry(engine_type.to_string()).or_default();
+            *busiest = busiest.max(utilization.min(100.0));
+        }
+    }
+    engine_types
+}
+
+// ConvertTo-Csv quotes every value and escapes quotes by doubling them
+#[cfg_attr(not(windows), allow(dead_code))]
+pub fn parse_csv(csv: &str) -> Vec<Vec<String>> {
+    csv.lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let mut values = Vec::new();
+            let mut value = String::new();
+            let mut quoted = false;
+            let mut characters = line.trim_end().chars().peekable();
+            while let Some(character) = characters.next() {
+                match character {
+                    '"' if quoted && characters.peek() == Some(&'"') => {
+                        value.push('"');
+                        characters.next();
+                    }
+                    '"' => quoted = !quoted,
+                    ',' if !quoted => values.push(std::mem::take(&mut value)),
+                    _ => value.push(character),
+                }
+            }
+            values.push(value);
+            values
+        })
+        .collect()
+}
+
+#[test]
+fn parse_gpu_engine_counters() {
+    let csv = "\"Name\",\"UtilizationPercentage\"\r
+\"pid_4_luid_0x0_0xd1a5_phys_0_eng_0_engtype_3D\",\"20\"\r
+\"pid_8_luid_0x0_0xd1a5_phys_0_eng_0_engtype_3D\",\"15\"\r
+\"pid_8_luid_0x0_0xd1a5_phys_0_eng_1_engtype_3D\",\"40\"\r
+\"pid_8_luid_0x0_0xd1a5_phys_0_eng_4_engtype_VideoDecode\",\"3\"\r
+\"a \"\"quoted\"\", name\",\"7\"\r
+";
+    let rows = parse_csv(csv);
+    assert_eq!(rows[5], vec!["a \"quoted\", name".to_string(), "7".to_string()]);
+    let engines = parse_gpu_engines(&rows[1..]);
+    assert_eq!(engines.len(), 2);
+    assert!((engines["3D"] - 40.0).abs() < f64::EPSILON);
+    assert!((engines["VideoDecode"] - 3.0).abs() < f64::EPSILON);
+}
@@ -0,0 +1,268 @@
+// The drives themselves, as opposed to the mounted volumes `DiskInfo`
+// lists, so frontends can show which partitions live on which drive:
+//
+// - Linux: /sys/block, leaving out loop, device mapper and RAM devices,
+//   which have no device behind them. udev knows the serial number of
+//   drives whose driver doesn't put it in sysfs
+// - macOS: `diskutil info -all`, leaving out the disks APFS synthesizes
+// - Windows: Win32_DiskDrive through WMI, which doesn't say whether a drive
+//   spins or which drive letters are on it
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum DiskBus {
+    Sata,
+    Nvme,
+    Scsi,
+    Usb,
+    Mmc,
+    /// Disks of virtual machines
+    Virtual,
+    Unknown,
+}
+
+impl std::fmt::Display for DiskBus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Sata => "SATA",
+            Self::Nvme => "NVMe",
+            Self::Scsi => "SCSI",
+            Self::Usb => "USB",
+            Self::Mmc => "MMC",
+            Self::Virtual => "virtual",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PhysicalDiskInfo {
+    /// e.g. sda, nvme0n1, disk0 or PHYSICALDRIVE0. `DiskInfo::physical_disk`
+    /// refers to the drive by it
+    pub name:          String,
+    pub model:         Option<String>,
+    pub serial:        Option<String>,
+    pub bus:           DiskBus,
+    /// Whether it's a spinning hard drive rather than an SSD. None where
+    /// the OS doesn't say
+    pub is_rotational: Option<bool>,
+    /// Bytes
+    pub total:         u64,
+    /// Names of the partitions on the drive, e.g. nvme0n1p1 or disk0s1
+    pub partitions:    Vec<String>,
+}
+
+#[cfg(target_os = "linux")]
+pub fn physical_disks() -> Option<Vec<PhysicalDiskInfo>> {
+    let read = |path: std::path::PathBuf| std::fs::read_to_string(path).ok().map(|text| text.trim().to_string()).filter(|text| !text.is_empty());
+    let mut disks = std::fs::read_dir("/sys/block")
+        .ok()?
+        .flatten()
+        .filter(|entry| entry.path().join("device").exists())
+        .filter_map(|entry| {
+            let name = entry.file_name().to_str()?.to_string();
+            let path = entry.path();
+            let udev = read(path.join("dev")).and_then(|device| std::fs::read_to_string(format!("/run/udev/data/b{device}")).ok());
+            let mut partitions = std::fs::read_dir(&path)
+                .into_iter()
+                .flatten()
+                .flatten()
+                .filter(|partition| partition.path().join("partition").exists())
+                .filter_map(|partition| partition.file_name().to_str().map(ToString::to_string))
+                .collect::<Vec<String>>();
+            partitions.sort();
+            Some(PhysicalDiskInfo {
+                model: read(path.join("device/model")),
+                serial: read(path.join("device/serial")).or_else(|| parse_udev_property(udev.as_deref()?, "ID_SERIAL_SHORT")),
+                bus: parse_bus(&name, &std::fs::canonicalize(&path).ok()?.to_string_lossy()),
+                is_rotational: read(path.join("queue/rotational")).map(|rotational| rotational == "1"),
+                // Always in 512 byte sectors, whatever the drive uses
+                total: read(path.join("size"))?.parse::<u64>().ok()? * 512,
+                partitions,
+                name,
+            })
+        })
+        // Empty card readers and optical drives
+        .filter(|disk| disk.total > 0)
+        .collect::<Vec<PhysicalDiskInfo>>();
+    disks.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(disks)
+}
+
+#[cfg(target_os = "macos")]
+pub fn physical_disks() -> Option<Vec<PhysicalDiskInfo>> {
+    let output = std::process::Command::new("diskutil").args(["info", "-all"]).output().ok().filter(|output| output.status.success())?;
+    Some(parse_diskutil_info(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(windows)]
+pub fn physical_disks() -> Option<Vec<PhysicalDiskInfo>> {
+    crate::performance_counters::disk_drives().map(|rows| parse_disk_drives(&rows))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub const fn physical_disks() -> Option<Vec<PhysicalDiskInfo>> {
+    None
+}
+
+// The path /sys/block/<name> links to, e.g.
+// /sys/devices/pci0000:00/0000:00:17.0/ata1/host0/target0:0:0/0:0:0:0/block/sda
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_bus(name: &str, path: &str) -> DiskBus {
+    if path.contains("/usb") {
+        DiskBus::Usb
+    } else if name.starts_with("nvme") {
+        DiskBus::Nvme
+    } else if name.starts_with("mmcblk") {
+        DiskBus::Mmc
+    } else if path.contains("/virtio") || name.starts_with("vd") || name.starts_with("xvd") {
+        DiskBus::Virtual
+    } else if path.contains("/ata") {
+        DiskBus::Sata
+    } else if path.contains("/host") {
+        DiskBus::Scsi
+    } else {
+        DiskBus::Unknown
+    }
+}
+
+// E:ID_SERIAL_SHORT=S4EWNX0R123456
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_udev_property(data: &str, key: &str) -> Option<String> {
+    data.lines()
+        .find_map(|line| line.strip_prefix("E:")?.strip_prefix(key)?.strip_prefix('='))
+        .map(ToString::to_string)
+        .filter(|value| !value.is_empty())
+}
+
+// A block per disk and partition, separated by a line of asterisks:
+//
+//    Device Identifier:         disk0
+//    Device / Media Name:       APPLE SSD AP0512Q
+//    Whole:                     Yes
+//    Part of Whole:             disk0
+//    Protocol:                  Apple Fabric
+//    Solid State:               Yes
+//    Disk Size:                 500.3 GB (500277792768 Bytes) (exactly 977105064 512-Byte-Units)
+//    Virtual:                   No
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_diskutil_info(info: &str) -> Vec<PhysicalDiskInfo> {
+    let blocks = info
+        .split("**********")
+        .map(|block| {
+            block
+                .lines()
+                .filter_map(|line| line.split_once(':'))
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .collect::<std::collections::HashMap<&str, &str>>()
+        })
+        .collect::<Vec<_>>();
+    blocks
+        .iter()
+        .filter(|block| block.get("Whole") == Some(&"Yes") && block.get("Virtual") != Some(&"Yes"))
+        .filter_map(|block| {
+            let name = (*block.get("Device Identifier")?).to_string();
+            Some(PhysicalDiskInfo {
+                model: block.get("Device / Media Name").map(|model| (*model).to_string()),
+                serial: None,
+                bus: match block.get("Protocol").copied() {
+                    Some("SATA") => DiskBus::Sata,
+                    Some("PCI-Express" | "Apple Fabric" | "NVMExpress") => DiskBus::Nvme,
+                    Some("USB") => DiskBus::Usb,
+                    Some("SAS" | "SCSI") => DiskBus::Scsi,
+                    Some("Secure Digital") => DiskBus::Mmc,
+                    _ => DiskBus::Unknown,
+                },
+                is_rotational: block.get("Solid State").map(|solid_state| *solid_state == "No"),
+                total: block.get("Disk Size").and_then(|size| size.split_once('(')?.1.split_whitespace().next()?.parse().ok()).unwrap_or_default(),
+                partitions: blocks
+                    .iter()
+                    .filter(|partition| partition.get("Whole") == Some(&"No") && partition.get("Part of Whole") == Some(&name.as_str()))
+                    .filter_map(|partition| partition.get("Device Identifier").map(|partition| (*partition).to_string()))
+                    .collect(),
+                name,
+            })
+        })
+        .collect()
+}
+
+// DeviceID, Model, SerialNumber, InterfaceType and Size, e.g.
+// "\\.\PHYSICALDRIVE0","Samsung SSD 970 EVO Plus 1TB","0025_3853_91B0_1234.","SCSI","1000202273280"
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_disk_drives(rows: &[Vec<String>]) -> Vec<PhysicalDiskInfo> {
+    let non_empty = |value: &String| Some(value.trim().to_string()).filter(|value| !value.is_empty());
+    rows.iter()
+        .filter_map(|row| match row.as_slice() {
+            [device_id, model, serial, interface, size] => Some(PhysicalDiskInfo {
+                name:          device_id.trim_start_matches(r"\\.\").to_string(),
+                model:         non_empty(model),
+                serial:        non_empty(serial),
+                // NVMe drives show up as SCSI
+                bus:           match interface.as_str() {
+                    "IDE" => DiskBus::Sata,
+                    "SCSI" => DiskBus::Scsi,
+                    "USB" => DiskBus::Usb,
+                    _ => DiskBus::Unknown,
+                },
+                is_rotational: None,
+                total:         size.parse().unwrap_or_default(),
+                partitions:    Vec::new(),
+            }),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn parse_physical_disks() {
+    assert_eq!(parse_bus("sda", "/sys/devices/pci0000:00/0000:00:17.0/ata1/host0/target0:0:0/0:0:0:0/block/sda"), DiskBus::Sata);
+    assert_eq!(parse_bus("sdb", "/sys/devices/pci0000:00/0000:00:14.0/usb2/2-1/2-1:1.0/host6/target6:0:0/6:0:0:0/block/sdb"), DiskBus::Usb);
+    assert_eq!(parse_bus("nvme0n1", "/sys/devices/pci0000:00/0000:00:1d.0/0000:3d:00.0/nvme/nvme0/nvme0n1"), DiskBus::Nvme);
+    assert_eq!(parse_bus("vda", "/sys/devices/pci0000:00/0000:00:04.0/virtio1/block/vda"), DiskBus::Virtual);
+    assert_eq!(parse_udev_property("S:disk/by-id/ata-foo\nE:ID_SERIAL=foo_123\nE:ID_SERIAL_SHORT=123\n", "ID_SERIAL_SHORT"), Some("123".to_string()));
+    assert_eq!(parse_udev_property("E:ID_SERIAL=foo_123\n", "ID_SERIAL_SHORT"), None);
+
+    let disks = parse_diskutil_info(
+        "   Device Identifier:         disk0
+   Device / Media Name:       APPLE SSD AP0512Q
+   Whole:                     Yes
+   Part of Whole:             disk0
+   Protocol:                  Apple Fabric
+   Solid State:               Yes
+   Disk Size:                 500.3 GB (500277792768 Bytes) (exactly 977105064 512-Byte-Units)
+**********
+
+   Device Identifier:         disk0s2
+   Whole:                     No
+   Part of Whole:             disk0
+**********
+
+   Device Identifier:         disk3
+   Whole:                     Yes
+   Part of Whole:             disk3
+   Virtual:                   Yes
+**********
+",
+    );
+    assert_eq!(disks, vec![PhysicalDiskInfo {
+        name:          "disk0".to_string(),
+        model:         Some("APPLE SSD AP0512Q".to_string()),
+        serial:        None,
+        bus:           DiskBus::Nvme,
+        is_rotational: Some(false),
+        total:         500_277_792_768,
+        partitions:    vec!["disk0s2".to_string()],
+    }]);
+
+    let drives = parse_disk_drives(&[vec![
+        r"\\.\PHYSICALDRIVE0".to_string(),
+        "ST2000DM008".to_string(),
+        " ZFL1ABCD".to_string(),
+        "IDE".to_string(),
+        "2000396321280".to_string(),
+    ]]);
+    assert_eq!(drives[0].name, "PHYSICALDRIVE0");
+    assert_eq!(drives[0].serial.as_deref(), Some("ZFL1ABCD"));
+    assert_eq!(drives[0].bus, DiskBus::Sata);
+}
@@ -0,0 +1,144 @@
+// Plugins add information crossinfo doesn't know about itself, which
+// frontends show like their own categories. A plugin is any executable in
+// the plugins directory inside crossinfo's config directory. It's started
+// the first time the plugins are asked for and keeps running, printing a
+// line of JSON whenever it has something new to show:
+//
+// {"title": "GPU", "items": [{"name": "Temperature", "value": "54 °C"}]}
+//
+// Only the latest line counts. A line that isn't a reading is kept as the
+// plugin's error, as is why it stopped. Plugins are killed along with the
+// Manager that started them.
+
+use std::{
+    io::BufRead,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    sync::{Arc, Mutex, PoisonError},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PluginItem {
+    pub name:  String,
+    pub value: String,
+}
+
+/// One line a plugin printed
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PluginReading {
+    /// What the category is called, e.g. the title of its tab
+    pub title: String,
+    #[serde(default)]
+    pub items: Vec<PluginItem>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct PluginInfo {
+    /// The file name of the executable
+    pub name:    String,
+    /// None until the plugin printed its first reading
+    pub reading: Option<PluginReading>,
+    pub error:   Option<String>,
+}
+
+pub struct Plugins {
+    running: Vec<(Option<Child>, Arc<Mutex<PluginInfo>>)>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn directory() -> Option<PathBuf> {
+    Some(crate::config_directory()?.join("plugins"))
+}
+
+impl Plugins {
+    /// Starts every plugin in the plugins directory, in the order of their
+    /// names
+    pub fn start() -> Self {
+        let mut paths = directory()
+            .and_then(|directory| std::fs::read_dir(directory).ok())
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.is_file())
+            .collect::<Vec<PathBuf>>();
+        paths.sort();
+        Self {
+            running: paths.iter().map(|path| start(path)).collect(),
+        }
+    }
+
+    pub fn information(&self) -> Vec<PluginInfo> {
+        self.running.iter().map(|(_, info)| lock(info).clone()).collect()
+    }
+}
+
+impl Drop for Plugins {
+    fn drop(&mut self) {
+        for child in self.running.iter_mut().filter_map(|(child, _)| child.as_mut()) {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+    }
+}
+
+fn start(path: &Path) -> (Option<Child>, Arc<Mutex<PluginInfo>>) {
+    let info = Arc::new(Mutex::new(PluginInfo {
+        name:    path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+        reading: None,
+        error:   None,
+    }));
+    let mut child = match Command::new(path).stdin(Stdio::null()).stdout(Stdio::piped()).stderr(Stdio::null()).spawn() {
+        Ok(child) => child,
+        Err(error) => {
+            lock(&info).error = Some(format!("Couldn't be started: {error}"));
+            return (None, info);
+        }
+    };
+    if let Some(stdout) = child.stdout.take() {
+        let info = Arc::clone(&info);
+        std::thread::spawn(move || {
+            for line in std::io::BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match parse_reading(&line) {
+                    Ok(reading) => {
+                        let mut info = lock(&info);
+                        info.reading = Some(reading);
+                        info.error = None;
+                    }
+                    Err(error) => lock(&info).error = Some(error),
+                }
+            }
+            lock(&info).error = Some("Stopped".to_string());
+        });
+    }
+    (Some(child), info)
+}
+
+fn parse_reading(line: &str) -> Result<PluginReading, String> {
+    serde_json::from_str(line).map_err(|error| format!("Printed something that isn't a reading: {error}"))
+}
+
+#[test]
+fn parse_plugin_reading() {
+    assert_eq!(
+        parse_reading(r#"{"title": "GPU", "items": [{"name": "Temperature", "value": "54 °C"}]}"#),
+        Ok(PluginReading {
+            title: "GPU".to_string(),
+            items: vec![PluginItem {
+                name:  "Temperature".to_string(),
+                value: "54 °C".to_string(),
+            }],
+        })
+    );
+    assert_eq!(parse_reading(r#"{"title": "Empty"}"#).map(|reading| reading.items.len()), Ok(0));
+    assert!(parse_reading("not json").is_err());
+}
@@ -0,0 +1,425 @@
+// Process controls that sysinfo doesn't cover. The backend forbids unsafe
+// code, so instead of calling ioprio_get/ioprio_set and setpriority
+// directly this goes through util-linux's `ionice` and `renice`, which are
+// installed practically everywhere. On Windows PowerShell sets the
+// priority class, which is what SetPriorityClass does.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IoPriorityClass {
+    /// No class set explicitly, the kernel derives one from the nice value
+    None,
+    RealTime,
+    BestEffort,
+    /// Only gets disk time when nobody else needs it
+    Idle,
+}
+
+impl std::fmt::Display for IoPriorityClass {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::None => "none",
+            Self::RealTime => "realtime",
+            Self::BestEffort => "best-effort",
+            Self::Idle => "idle",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IoPriority {
+    pub class: IoPriorityClass,
+    /// 0 (highest) to 7 (lowest), only meaningful for the realtime and
+    /// best-effort classes
+    pub level: Option<u8>,
+}
+
+impl std::fmt::Display for IoPriority {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self.level {
+            Some(level) => write!(f, "{} (level {level})", self.class),
+            None => write!(f, "{}", self.class),
+        }
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn io_priority(pid: sysinfo::Pid) -> Option<IoPriority> {
+    let output = std::process::Command::new("ionice")
+        .args(["-p", &pid.to_string()])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    parse_ionice(String::from_utf8_lossy(&output.stdout).trim())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn io_priority(_pid: sysinfo::Pid) -> Option<IoPriority> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+pub fn set_io_priority(pid: sysinfo::Pid, priority: IoPriority) -> bool {
+    let class = match priority.class {
+        IoPriorityClass::None => "0",
+        IoPriorityClass::RealTime => "1",
+        IoPriorityClass::BestEffort => "2",
+        IoPriorityClass::Idle => "3",
+    };
+    let mut command = std::process::Command::new("ionice");
+    command.args(["-c", class]);
+    if let Some(level) = priority.level
+        && matches!(priority.class, IoPriorityClass::RealTime | IoPriorityClass::BestEffort)
+    {
+        command.args(["-n", &level.min(7).to_string()]);
+    }
+    command.args(["-p", &pid.to_string()]).output().is_ok_and(|output| output.status.success())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn set_io_priority(_pid: sysinfo::Pid, _priority: IoPriority) -> bool {
+    false
+}
+
+// ionice prints "none: prio 4", "best-effort: prio 7", "realtime: prio 0"
+// or just "idle"
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_ionice(output: &str) -> Option<IoPriority> {
+    let (class, level) = match output.split_once(':') {
+        Some((class, level)) => (class, level.trim().strip_prefix("prio ").and_then(|level| level.parse().ok())),
+        None => (output, None),
+    };
+    Some(IoPriority {
+        class: match class.trim() {
+            "none" => IoPriorityClass::None,
+            "realtime" => IoPriorityClass::RealTime,
+            "best-effort" => IoPriorityClass::BestEffort,
+            "idle" => IoPriorityClass::Idle,
+            _ => return None,
+        },
+        level,
+    })
+}
+
+/// Nice values go from -20, which gets the CPU first, to 19, which gets
+/// what is left
+pub const PRIORITY_RANGE: std::ops::RangeInclusive<i32> = -20..=19;
+
+#[cfg(target_os = "linux")]
+pub fn process_nice(pid: sysinfo::Pid) -> Option<i32> {
+    std::fs::read_to_string(format!("/proc/{pid}/stat")).ok().and_then(|stat| parse_nice(&stat))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn process_nice(_pid: sysinfo::Pid) -> Option<i32> {
+    None
+}
+
+/// The nice values of every process, keyed by PID, where reading them one
+/// by one would mean running a command per process
+#[cfg(target_os = "macos")]
+pub fn priorities() -> Option<HashMap<u32, i32>> {
+    let output = std::process::Command::new("ps")
+        .args(["-A", "-o", "pid=,nice="])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(parse_ps_priorities(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(windows)]
+pub fn priorities() -> Option<HashMap<u32, i32>> {
+    crate::performance_counters::process_priorities()
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+pub const fn priorities() -> Option<HashMap<u32, i32>> {
+    None
+}
+
+#[cfg(unix)]
+pub fn set_priority(pid: sysinfo::Pid, nice: i32) -> bool {
+    // "renice <priority> -p <pid>" sets the value on both util-linux and
+    // BSD, where -n would add to it instead
+    std::process::Command::new("renice")
+        .args([&nice.clamp(*PRIORITY_RANGE.start(), *PRIORITY_RANGE.end()).to_string(), "-p", &pid.to_string()])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+/// Windows only has six priority classes, the nice value is rounded to
+/// the closest one. Realtime is never set, it can starve the system
+#[cfg(windows)]
+pub fn set_priority(pid: sysinfo::Pid, nice: i32) -> bool {
+    let class = match nice {
+        15.. => "Idle",
+        5..=14 => "BelowNormal",
+        -4..=4 => "Normal",
+        -9..=-5 => "AboveNormal",
+        _ => "High",
+    };
+    std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &format!("(Get-Process -Id {pid}).PriorityClass = '{class}'")])
+        .output()
+        .is_ok_and(|output| output.status.success())
+}
+
+#[cfg(not(any(unix, windows)))]
+pub const fn set_priority(_pid: sysinfo::Pid, _nice: i32) -> bool {
+    false
+}
+
+// The nice value is the 19th field of /proc/<pid>/stat, counted like in
+// parse_cpu_time
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_nice(stat: &str) -> Option<i32> {
+    let (_, fields) = stat.rsplit_once(')')?;
+    fields.split_whitespace().nth(16)?.parse().ok()
+}
+
+// "  PID NI" without the header, kernel threads show "-" instead of a
+// value
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_ps_priorities(ps: &str) -> HashMap<u32, i32> {
+    ps.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            Some((fields.next()?.parse().ok()?, fields.next()?.parse().ok()?))
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessExit {
+    /// Gone, or a zombie that only waits for its parent to collect the
+    /// exit code
+    Exited,
+    /// Still running when the timeout ran out
+    TimedOut,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Termination {
+    /// Quit on its own after being asked to
+    Terminated,
+    /// Ignored the request and had to be killed
+    Killed,
+    /// Still running, usually because it belongs to another user
+    Failed,
+}
+
+/// A process by its PID and when it started. PIDs are reused once a
+/// process quits, so the PID alone can end up meaning another process
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ProcessId {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization"))]
+    pub pid:        sysinfo::Pid,
+    pub start_time: std::time::SystemTime,
+}
+
+/// What the program using the backend costs, see `Manager::own_usage`
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnUsage {
+    /// In percent of one core since the last call
+    pub cpu_usage:    f32,
+    /// Resident memory in bytes
+    pub memory_usage: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FileDescriptorUsage {
+    pub open:  u64,
+    /// `None` if there is no limit or it couldn't be read
+    pub limit: Option<u64>,
+}
+
+impl FileDescriptorUsage {
+    /// Whether 90% of the limit is used up, at which point "Too many open
+    /// files" errors aren't far off
+    #[must_use]
+    pub fn is_near_limit(&self) -> bool {
+        self.limit.is_some_and(|limit| self.open.saturating_mul(10) >= limit.saturating_mul(9))
+    }
+}
+
+/// File handles allocated by the whole system compared to the kernel wide
+/// maximum
+#[cfg(target_os = "linux")]
+pub fn system_file_descriptors() -> Option<FileDescriptorUsage> {
+    // "allocated  free  maximum", free has been always 0 since Linux 2.6
+    let file_nr = std::fs::read_to_string("/proc/sys/fs/file-nr").ok()?;
+    let mut fields = file_nr.split_whitespace().map(|field| field.parse::<u64>().ok());
+    let allocated = fields.next()??;
+    let free = fields.next()??;
+    Some(FileDescriptorUsage {
+        open:  allocated.saturating_sub(free),
+        limit: fields.next().flatten(),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn system_file_descriptors() -> Option<FileDescriptorUsage> {
+    None
+}
+
+/// Open file descriptors of a process compared to its soft limit. The fd
+/// directory of other users' processes can only be read by root
+#[cfg(target_os = "linux")]
+pub fn process_file_descriptors(pid: sysinfo::Pid) -> Option<FileDescriptorUsage> {
+    let open = std::fs::read_dir(format!("/proc/{pid}/fd")).ok()?.count() as u64;
+    Some(FileDescriptorUsage {
+        open,
+        limit: std::fs::read_to_string(format!("/proc/{pid}/limits")).ok().and_then(|limits| parse_open_files_limit(&limits)),
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn process_file_descriptors(_pid: sysinfo::Pid) -> Option<FileDescriptorUsage> {
+    None
+}
+
+/// Open file descriptors of the processes with `pids`, keyed by PID. The
+/// ones that can't be read directly are asked about in one go from the
+/// helper, if it's running. On Windows these are the open handles
+pub fn file_descriptors(pids: &[sysinfo::Pid]) -> HashMap<u32, FileDescriptorUsage> {
+    let handles = crate::performance_counters::process_handles();
+    let mut usage = pids
+        .iter()
+        .filter_map(|pid| {
+            let usage = process_file_descriptors(*pid).or_else(|| {
+                Some(FileDescriptorUsage {
+                    open:  *handles.as_ref()?.get(&pid.as_u32())?,
+                    limit: None,
+                })
+            })?;
+            Some((pid.as_u32(), usage))
+        })
+        .collect::<HashMap<u32, FileDescriptorUsage>>();
+    let unreadable = pids.iter().map(|pid| pid.as_u32()).filter(|pid| !usage.contains_key(pid)).collect::<Vec<u32>>();
+    // Only there if the user started `cli helper` as root
+    if !unreadable.is_empty()
+        && let Some(helper_usage) = crate::helper::file_descriptors(&unreadable)
+    {
+        usage.extend(helper_usage);
+    }
+    usage
+}
+
+/// CPU time the process used so far, in user and kernel mode together
+#[cfg(target_os = "linux")]
+pub fn process_cpu_time(pid: sysinfo::Pid) -> Option<std::time::Duration> {
+    std::fs::read_to_string(format!("/proc/{pid}/stat")).ok().and_then(|stat| parse_cpu_time(&stat))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn process_cpu_time(_pid: sysinfo::Pid) -> Option<std::time::Duration> {
+    None
+}
+
+// /proc/<pid>/stat looks like "1234 (name) S 1 ...", with utime and stime
+// as the 14th and 15th field. The name can contain spaces and parentheses,
+// so the fields are counted from the last ')'. Both are in clock ticks,
+// which the kernel always reports to userspace at 100 per second.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn parse_cpu_time(stat: &str) -> Option<std::time::Duration> {
+    const TICKS_PER_SECOND: u64 = 100;
+    let (_, fields) = stat.rsplit_once(')')?;
+    let mut fields = fields.split_whitespace().skip(11);
+    let user_time = fields.next()?.parse::<u64>().ok()?;
+    let system_time = fields.next()?.parse::<u64>().ok()?;
+    Some(std::time::Duration::from_millis((user_time + system_time) * (1000 / TICKS_PER_SECOND)))
+}
+
+/// Adds the CPU, memory and SWAP usage and the CPU time of every process
+/// to all of its ancestors, so a shell running a heavy compiler doesn't
+/// look idle
+pub fn aggregate_children(processes: &mut [crate::ProcessInfo]) {
+    let indices = processes.iter().enumerate().map(|(index, process)| (process.pid, index)).collect::<HashMap<sysinfo::Pid, usize>>();
+    let parents = processes
+        .iter()
+        .map(|process| process.parent.and_then(|parent| indices.get(&parent).copied()))
+        .collect::<Vec<Option<usize>>>();
+    // Summed up on the side, so what a child adds to its parent isn't added
+    // to the grandparent a second time
+    let mut added = vec![(0.0, 0, 0, std::time::Duration::ZERO); processes.len()];
+    for (index, process) in processes.iter().enumerate() {
+        for ancestor in ancestors(&parents, index) {
+            let added = &mut added[ancestor];
+            added.0 += process.cpu_usage;
+            added.1 += process.memory_usage;
+            added.2 += process.swap_usage;
+            added.3 += process.cpu_time.unwrap_or_default();
+        }
+    }
+    for (process, (cpu_usage, memory_usage, swap_usage, cpu_time)) in processes.iter_mut().zip(added) {
+        process.cpu_usage += cpu_usage;
+        process.memory_usage += memory_usage;
+        process.swap_usage += swap_usage;
+        process.cpu_time = process.cpu_time.map(|time| time + cpu_time);
+    }
+}
+
+// The parent, its parent and so on. A reused PID can make the parents go
+// in a circle, which ends the walk
+fn ancestors(parents: &[Option<usize>], index: usize) -> Vec<usize> {
+    let mut ancestors = vec![];
+    let mut current = parents[index];
+    while let Some(ancestor) = current
+        && ancestor != index
+        && !ancestors.contains(&ancestor)
+    {
+        ancestors.push(ancestor);
+        current = parents[ancestor];
+    }
+    ancestors
+}
+
+// The line looks like "Max open files  1024  524288  files", with the soft
+// limit first. Either of them can also be "unlimited"
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_open_files_limit(limits: &str) -> Option<u64> {
+    limits
+        .lines()
+        .find_map(|line| line.strip_prefix("Max open files"))
+        .and_then(|limit| limit.split_whitespace().next())
+        .and_then(|soft_limit| soft_limit.parse().ok())
+}
+
+#[test]
+fn parse_process_cpu_time() {
+    let stat = "4321 (Web Content (2)) S 1200 1200 1200 0 -1 4194560 91234 0 12 0 1234 566 0 0 20 0 31 0 5430 3041230848 61234 18446744073709551615";
+    assert_eq!(parse_cpu_time(stat), Some(std::time::Duration::from_secs(18)));
+    assert_eq!(parse_cpu_time("4321 (truncated) S 1200"), None);
+    assert_eq!(parse_nice(stat), Some(0));
+    assert_eq!(parse_nice("77 (niced) S 1 77 77 0 -1 4194560 10 0 0 0 5 1 0 0 39 19 1 0 12"), Some(19));
+    assert_eq!(parse_ps_priorities("    1   0\n  412  -5\n  500   -\n"), HashMap::from([(1, 0), (412, -5)]));
+}
+
+#[test]
+fn walk_ancestors() {
+    let parents = [None, Some(0), Some(1), Some(4), Some(3)];
+    assert_eq!(ancestors(&parents, 2), vec![1, 0]);
+    assert_eq!(ancestors(&parents, 0), Vec::<usize>::new());
+    assert_eq!(ancestors(&parents, 3), vec![4]);
+}
+
+#[test]
+fn parse_io_priority() {
+    assert_eq!(parse_ionice("none: prio 4\n"), Some(IoPriority {
+        class: IoPriorityClass::None,
+        level: Some(4),
+    }));
+    assert_eq!(parse_ionice("idle\n"), Some(IoPriority {
+        class: IoPriorityClass::Idle,
+        level: None,
+    }));
+    assert_eq!(parse_ionice("best-effort: prio 7"), Some(IoPriority {
+        class: IoPriorityClass::BestEffort,
+        level: Some(7),
+    }));
+    assert_eq!(parse_ionice("ionice: ioprio_get failed: No such process"), None);
+}
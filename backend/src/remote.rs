@@ -0,0 +1,614 @@
+// Watching another machine: `Manager::run_agent` on it sends its readings
+// to every client that knows a token, and a RemoteManager on this side
+// keeps the latest of them. The protocol runs over TCP, or over TLS if the
+// agent has a certificate:
+//
+// -> the token
+// -> the encodings the client can read, preferred first, e.g. "msgpack
+//    json"
+// <- "ok", what the token allows ("read" or "manage") and the encoding
+//    the agent picked, e.g. "ok read msgpack", or "unauthorized" before
+//    the connection is closed
+// <- the readings, once per interval until either side hangs up
+// -> "kill PID START" or "renice PID START NICE" at any time, which the
+//    agent ignores unless it answered "manage". START is when the process
+//    started in seconds since the Unix epoch, so a process that got the
+//    PID in the meantime is left alone
+//
+// The readings are either a line of JSON or MessagePack prefixed with its
+// length as four big-endian bytes, which is a lot less to send for
+// hundreds of processes. JSON is what the agent falls back to, so
+// something like netcat is all it takes to look at them.
+//
+// Whether a request worked shows in the next readings. There are two
+// tokens, one that only lets the client read and one that also lets it
+// manage processes. They're compared in constant time, but without TLS
+// they travel in the clear, so anything beyond a trusted network should
+// either use it or go through an SSH tunnel or a VPN.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{self, BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc, Mutex, PoisonError,
+    },
+    time::{Duration, Instant},
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{BatteryInfo, ComponentInfo, ConnectivityState, CpuInfo, DiskInfo, Manager, MemoryInfo, NetworkInfo, ProcessId, ProcessInfo, SystemInfo};
+
+// Until the whole handshake has to have arrived, no matter how slowly the
+// client trickles it in
+const AUTHENTICATION_TIMEOUT: Duration = Duration::from_secs(10);
+// The token and the encodings are far shorter than this
+const HANDSHAKE_LIMIT: u64 = 4096;
+// Nothing the other side sends may grow bigger than this before it's a
+// whole frame, even if a length prefix claims more. Requests are a few
+// bytes, readings of thousands of processes a few megabytes
+const REQUEST_LIMIT: usize = 4096;
+const READINGS_LIMIT: usize = 64 * 1024 * 1024;
+// A client that stops reading is dropped after this
+const WRITE_TIMEOUT: Duration = Duration::from_secs(5);
+// Both sides read and write on the same thread, since a TLS stream can't
+// be split in two. Reading gives up after this to see if there is
+// something to write
+const POLL_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// What a client of the agent may do, depending on its token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AgentCapability {
+    Read,
+    /// Also kill and renice processes
+    Manage,
+}
+
+impl AgentCapability {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Read => "read",
+            Self::Manage => "manage",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Encoding {
+    Json,
+    MessagePack,
+}
+
+impl Encoding {
+    const fn name(self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::MessagePack => "msgpack",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        [Self::Json, Self::MessagePack].into_iter().find(|encoding| encoding.name() == name)
+    }
+
+    fn encode(self, readings: &Readings) -> io::Result<Arc<[u8]>> {
+        match self {
+            Self::Json => Ok((serde_json::to_string(readings).map_err(io::Error::other)? + "\n").into_bytes().into()),
+            Self::MessagePack => {
+                let message = rmp_serde::to_vec_named(readings).map_err(io::Error::other)?;
+                let mut frame = u32::try_from(message.len()).map_err(io::Error::other)?.to_be_bytes().to_vec();
+                frame.extend(message);
+                Ok(frame.into())
+            }
+        }
+    }
+
+    fn decode(self, frame: &[u8]) -> Option<Readings> {
+        match self {
+            Self::Json => serde_json::from_slice(frame).ok(),
+            Self::MessagePack => rmp_serde::from_slice(frame.get(4..)?).ok(),
+        }
+    }
+
+    /// Takes the first whole frame out of `received`
+    fn split_frame(self, received: &mut Vec<u8>) -> Option<Vec<u8>> {
+        let end = match self {
+            Self::Json => received.iter().position(|byte| *byte == b'\n')? + 1,
+            Self::MessagePack => 4 + usize::try_from(u32::from_be_bytes(received.get(..4)?.try_into().ok()?)).ok()?,
+        };
+        if received.len() < end {
+            return None;
+        }
+        let rest = received.split_off(end);
+        Some(std::mem::replace(received, rest))
+    }
+}
+
+/// PEM files for the agent's side of TLS
+#[derive(Debug, Clone)]
+pub struct TlsIdentity {
+    /// The certificate chain, starting with the agent's own
+    pub certificate: PathBuf,
+    pub private_key: PathBuf,
+}
+
+#[derive(Debug, Clone)]
+pub struct AgentConfig {
+    /// Clients with this token get the readings
+    pub token:        String,
+    /// Clients with this one may also manage processes. Nobody may without
+    /// it
+    pub manage_token: Option<String>,
+    /// Without it, nothing is encrypted
+    pub tls:          Option<TlsIdentity>,
+    pub interval:     Duration,
+}
+
+/// A TCP stream with or without TLS on top
+enum Connection {
+    Plain(TcpStream),
+    Server(Box<rustls::StreamOwned<rustls::ServerConnection, TcpStream>>),
+    Client(Box<rustls::StreamOwned<rustls::ClientConnection, TcpStream>>),
+}
+
+impl Connection {
+    fn tcp(&self) -> &TcpStream {
+        match self {
+            Self::Plain(stream) => stream,
+            Self::Server(stream) => stream.get_ref(),
+            Self::Client(stream) => stream.get_ref(),
+        }
+    }
+}
+
+impl Read for Connection {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(stream) => stream.read(buf),
+            Self::Server(stream) => stream.read(buf),
+            Self::Client(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Connection {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(stream) => stream.write(buf),
+            Self::Server(stream) => stream.write(buf),
+            Self::Client(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(stream) => stream.flush(),
+            Self::Server(stream) => stream.flush(),
+            Self::Client(stream) => stream.flush(),
+        }
+    }
+}
+
+/// A frame from the other side, if a whole one arrived within the read
+/// timeout. What arrived of the next ones stays in `received`, which may
+/// hold at most `limit` bytes of a frame
+fn poll_frame(reader: &mut BufReader<Connection>, received: &mut Vec<u8>, encoding: Encoding, limit: usize) -> io::Result<Option<Vec<u8>>> {
+    if let Some(frame) = encoding.split_frame(received) {
+        return Ok(Some(frame));
+    }
+    match reader.fill_buf() {
+        Ok([]) => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "The other side hung up")),
+        Ok(buffer) => {
+            let length = buffer.len();
+            received.extend_from_slice(buffer);
+            reader.consume(length);
+            let frame = encoding.split_frame(received);
+            if frame.is_none() && received.len() > limit {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, "The other side sent too much at once"));
+            }
+            Ok(frame)
+        }
+        Err(error) if matches!(error.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => Ok(None),
+        Err(error) => Err(error),
+    }
+}
+
+fn send(reader: &mut BufReader<Connection>, message: &[u8]) -> io::Result<()> {
+    let connection = reader.get_mut();
+    connection.write_all(message)?;
+    connection.flush()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Request {
+    Kill(ProcessId),
+    Renice(ProcessId, i32),
+}
+
+fn parse_request(line: &str) -> Option<Request> {
+    let id = |pid: &str, start_time: &str| {
+        Some(ProcessId {
+            pid:        pid.parse().ok()?,
+            start_time: std::time::UNIX_EPOCH + Duration::from_secs(start_time.parse().ok()?),
+        })
+    };
+    match line.split_whitespace().collect::<Vec<&str>>().as_slice() {
+        ["kill", pid, start_time] => Some(Request::Kill(id(pid, start_time)?)),
+        ["renice", pid, start_time, nice] => Some(Request::Renice(id(pid, start_time)?, nice.parse().ok()?)),
+        _ => None,
+    }
+}
+
+/// How `parse_request` expects a process
+fn format_process_id(id: ProcessId) -> String {
+    let start_time = id.start_time.duration_since(std::time::UNIX_EPOCH).map_or(0, |since_epoch| since_epoch.as_secs());
+    format!("{} {start_time}", id.pid)
+}
+
+fn certificates(path: &Path) -> io::Result<Vec<rustls::pki_types::CertificateDer<'static>>> {
+    rustls_pemfile::certs(&mut BufReader::new(std::fs::File::open(path)?)).collect()
+}
+
+fn server_config(identity: &TlsIdentity) -> io::Result<Arc<rustls::ServerConfig>> {
+    let private_key = rustls_pemfile::private_key(&mut BufReader::new(std::fs::File::open(&identity.private_key)?))?
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "The private key file contains no private key"))?;
+    let config = rustls::ServerConfig::builder()
+        .with_no_client_auth()
+        .with_single_cert(certificates(&identity.certificate)?, private_key)
+        .map_err(io::Error::other)?;
+    Ok(Arc::new(config))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Readings {
+    system:     Option<SystemInfo>,
+    cpu:        Option<Vec<CpuInfo>>,
+    memory:     Option<MemoryInfo>,
+    disks:      Option<Vec<DiskInfo>>,
+    processes:  Option<Vec<ProcessInfo>>,
+    components: Option<Vec<ComponentInfo>>,
+    batteries:  Option<Vec<BatteryInfo>>,
+    network:    NetworkInfo,
+}
+
+impl Readings {
+    fn read(manager: &mut Manager) -> Self {
+        Self {
+            system:     manager.system_information(),
+            cpu:        manager.cpu_information(),
+            memory:     manager.memory_information(),
+            disks:      manager.disk_information(),
+            processes:  manager.process_information(),
+            components: manager.component_information(),
+            batteries:  manager.battery_information(),
+            network:    manager.network_information(),
+        }
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+pub fn run_agent(mut manager: Manager, address: &str, config: &AgentConfig) -> io::Result<()> {
+    let listener = TcpListener::bind(address)?;
+    // Fails here instead of on the first client if the files are wrong
+    let tls = config.tls.as_ref().map(server_config).transpose()?;
+    let clients = Arc::new(Mutex::new(Vec::<(Encoding, mpsc::Sender<Arc<[u8]>>)>::new()));
+    let (requests, received) = mpsc::channel();
+    {
+        let clients = Arc::clone(&clients);
+        let config = config.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let clients = Arc::clone(&clients);
+                let config = config.clone();
+                let tls = tls.clone();
+                let requests = requests.clone();
+                // A client that never sends the token mustn't keep others
+                // from connecting
+                std::thread::spawn(move || {
+                    if let Some((connection, capability, encoding)) = authenticate(stream, &config, tls) {
+                        let (readings, unsent) = mpsc::channel();
+                        lock(&clients).push((encoding, readings));
+                        let _ = serve_client(connection, capability, &unsent, &requests);
+                    }
+                });
+            }
+        });
+    }
+    loop {
+        let started = Instant::now();
+        let encodings = lock(&clients).iter().map(|(encoding, _)| *encoding).collect::<BTreeSet<Encoding>>();
+        if !encodings.is_empty() {
+            let readings = Readings::read(&mut manager);
+            let frames = encodings
+                .into_iter()
+                .map(|encoding| Ok((encoding, encoding.encode(&readings)?)))
+                .collect::<io::Result<BTreeMap<Encoding, Arc<[u8]>>>>()?;
+            // Sending fails once the client is gone. Clients that connected
+            // in the meantime get the next readings
+            lock(&clients).retain(|(encoding, client)| frames.get(encoding).is_none_or(|frame| client.send(Arc::clone(frame)).is_ok()));
+        }
+        // Requests are carried out until the next readings are due
+        while let Ok(request) = received.recv_timeout(config.interval.saturating_sub(started.elapsed())) {
+            let _ = match request {
+                Request::Kill(id) => manager.is_running(id) && manager.kill_process(id.pid),
+                Request::Renice(id, nice) => manager.is_running(id) && manager.set_priority(id.pid, nice),
+            };
+        }
+    }
+}
+
+/// Sends the readings to the client and passes its requests on, until
+/// either fails
+fn serve_client(connection: Connection, capability: AgentCapability, readings: &mpsc::Receiver<Arc<[u8]>>, requests: &mpsc::Sender<Request>) -> io::Result<()> {
+    connection.tcp().set_read_timeout(Some(POLL_TIMEOUT))?;
+    let mut reader = BufReader::new(connection);
+    let mut received = Vec::new();
+    loop {
+        for reading in readings.try_iter() {
+            send(&mut reader, &reading)?;
+        }
+        // Requests are always lines
+        if let Some(request) = poll_frame(&mut reader, &mut received, Encoding::Json, REQUEST_LIMIT)?.and_then(|line| parse_request(&String::from_utf8_lossy(&line)))
+            && capability == AgentCapability::Manage
+        {
+            requests.send(request).map_err(io::Error::other)?;
+        }
+    }
+}
+
+/// The token and the encodings the client sent, unless it took longer
+/// than `AUTHENTICATION_TIMEOUT` or sent more than `HANDSHAKE_LIMIT` bytes
+fn read_handshake(connection: &mut Connection) -> Option<(String, String)> {
+    let deadline = Instant::now() + AUTHENTICATION_TIMEOUT;
+    connection.tcp().set_read_timeout(Some(POLL_TIMEOUT)).ok()?;
+    // The client waits for the answer, so nothing after the encodings is
+    // buffered and lost
+    let mut reader = BufReader::new(Read::by_ref(connection).take(HANDSHAKE_LIMIT));
+    let mut handshake = Vec::new();
+    while handshake.iter().filter(|byte| **byte == b'\n').count() < 2 {
+        match reader.read_until(b'\n', &mut handshake) {
+            // Hung up or sent too much
+            Ok(0) => return None,
+            Ok(_) => (),
+            Err(error) if matches!(error.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) && Instant::now() < deadline => (),
+            Err(_) => return None,
+        }
+    }
+    let handshake = String::from_utf8_lossy(&handshake);
+    let (token, encodings) = handshake.split_once('\n')?;
+    Some((token.to_string(), encodings.to_string()))
+}
+
+fn authenticate(stream: TcpStream, config: &AgentConfig, tls: Option<Arc<rustls::ServerConfig>>) -> Option<(Connection, AgentCapability, Encoding)> {
+    stream.set_write_timeout(Some(WRITE_TIMEOUT)).ok()?;
+    let mut connection = match tls {
+        Some(tls) => Connection::Server(Box::new(rustls::StreamOwned::new(rustls::ServerConnection::new(tls).ok()?, stream))),
+        None => Connection::Plain(stream),
+    };
+    let (token, encodings) = read_handshake(&mut connection)?;
+    let token = token.trim_end();
+    let capability = if config.manage_token.as_deref().is_some_and(|manage_token| crate::token::matches(manage_token, token)) {
+        AgentCapability::Manage
+    } else if crate::token::matches(&config.token, token) {
+        AgentCapability::Read
+    } else {
+        let _ = connection.write_all(b"unauthorized\n").and_then(|()| connection.flush());
+        return None;
+    };
+    let encoding = encodings.split_whitespace().find_map(Encoding::from_name).unwrap_or(Encoding::Json);
+    connection
+        .write_all(format!("ok {} {}\n", capability.name(), encoding.name()).as_bytes())
+        .and_then(|()| connection.flush())
+        .ok()?;
+    Some((connection, capability, encoding))
+}
+
+/// The readings of a machine running `Manager::run_agent`, with the same
+/// methods as the Manager. They are `None` until the first readings
+/// arrive and once the connection is lost
+pub struct RemoteManager {
+    latest:     Arc<Mutex<Option<Readings>>>,
+    connected:  Arc<AtomicBool>,
+    capability: AgentCapability,
+    requests:   mpsc::Sender<String>,
+}
+
+impl RemoteManager {
+    /// Connects over TLS if `ca_certificate` is the PEM file of the
+    /// certificate authority that signed the agent's certificate
+    ///
+    /// # Errors
+    ///
+    /// If the agent can't be reached or doesn't accept the token
+    pub fn connect(address: &str, token: &str, ca_certificate: Option<&Path>) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_read_timeout(Some(AUTHENTICATION_TIMEOUT))?;
+        let mut connection = match ca_certificate {
+            Some(ca_certificate) => {
+                let mut roots = rustls::RootCertStore::empty();
+                for certificate in certificates(ca_certificate)? {
+                    roots.add(certificate).map_err(io::Error::other)?;
+                }
+                let config = rustls::ClientConfig::builder().with_root_certificates(roots).with_no_client_auth();
+                // The certificate has to be for the host name or IP address
+                // that was connected to
+                let host = address.rsplit_once(':').map_or(address, |(host, _)| host).trim_matches(['[', ']']);
+                let name = rustls::pki_types::ServerName::try_from(host.to_string()).map_err(io::Error::other)?;
+                let client = rustls::ClientConnection::new(Arc::new(config), name).map_err(io::Error::other)?;
+                Connection::Client(Box::new(rustls::StreamOwned::new(client, stream)))
+            }
+            None => Connection::Plain(stream),
+        };
+        let encodings = [Encoding::MessagePack, Encoding::Json].map(Encoding::name).join(" ");
+        connection.write_all(format!("{token}\n{encodings}\n").as_bytes())?;
+        connection.flush()?;
+        let mut reader = BufReader::new(connection);
+        let mut answer = String::new();
+        reader.read_line(&mut answer)?;
+        let (capability, encoding) = match answer.split_whitespace().collect::<Vec<&str>>().as_slice() {
+            ["ok", "read", encoding] => (AgentCapability::Read, Encoding::from_name(encoding)),
+            ["ok", "manage", encoding] => (AgentCapability::Manage, Encoding::from_name(encoding)),
+            _ => return Err(io::Error::new(io::ErrorKind::PermissionDenied, "The agent didn't accept the token")),
+        };
+        let encoding = encoding.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "The agent picked an unknown encoding"))?;
+        // Reading gives up every now and then to send requests
+        reader.get_ref().tcp().set_read_timeout(Some(POLL_TIMEOUT))?;
+
+        let latest = Arc::new(Mutex::new(None));
+        let connected = Arc::new(AtomicBool::new(true));
+        let (requests, unsent) = mpsc::channel::<String>();
+        {
+            let latest = Arc::clone(&latest);
+            let connected = Arc::clone(&connected);
+            std::thread::spawn(move || {
+                let mut received = Vec::new();
+                loop {
+                    // Readings take a while on a busy machine, so timeouts
+                    // are no reason to give up
+                    let frame = unsent
+                        .try_iter()
+                        .try_for_each(|request| send(&mut reader, request.as_bytes()))
+                        .and_then(|()| poll_frame(&mut reader, &mut received, encoding, READINGS_LIMIT));
+                    match frame {
+                        Ok(Some(frame)) => {
+                            if let Some(readings) = encoding.decode(&frame) {
+                                *lock(&latest) = Some(readings);
+                            }
+                        }
+                        Ok(None) => (),
+                        Err(_) => break,
+                    }
+                }
+                // Old readings would look like a frozen machine
+                *lock(&latest) = None;
+                connected.store(false, Ordering::Relaxed);
+            });
+        }
+        Ok(Self {
+            latest,
+            connected,
+            capability,
+            requests,
+        })
+    }
+
+    /// What the token allows on the agent
+    #[must_use]
+    pub const fn capability(&self) -> AgentCapability {
+        self.capability
+    }
+
+    /// Asks the agent to kill the process, unless another one has its PID
+    /// by then. Only sends the request, whether it worked shows in the next
+    /// readings. `false` if the token doesn't allow it or the connection is
+    /// lost
+    #[must_use]
+    pub fn kill_process(&self, id: ProcessId) -> bool {
+        self.request(format!("kill {}\n", format_process_id(id)))
+    }
+
+    /// Asks the agent to change the nice value of the process, like
+    /// `kill_process`
+    #[must_use]
+    pub fn set_priority(&self, id: ProcessId, nice: i32) -> bool {
+        self.request(format!("renice {} {nice}\n", format_process_id(id)))
+    }
+
+    fn request(&self, request: String) -> bool {
+        self.capability == AgentCapability::Manage && self.is_connected() && self.requests.send(request).is_ok()
+    }
+
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    fn reading<T>(&self, read: impl FnOnce(&Readings) -> Option<T>) -> Option<T> {
+        lock(&self.latest).as_ref().and_then(read)
+    }
+
+    #[must_use]
+    pub fn system_information(&self) -> Option<SystemInfo> {
+        self.reading(|readings| readings.system.clone())
+    }
+
+    #[must_use]
+    pub fn cpu_information(&self) -> Option<Vec<CpuInfo>> {
+        self.reading(|readings| readings.cpu.clone())
+    }
+
+    #[must_use]
+    pub fn memory_information(&self) -> Option<MemoryInfo> {
+        self.reading(|readings| readings.memory.clone())
+    }
+
+    #[must_use]
+    pub fn disk_information(&self) -> Option<Vec<DiskInfo>> {
+        self.reading(|readings| readings.disks.clone())
+    }
+
+    #[must_use]
+    pub fn process_information(&self) -> Option<Vec<ProcessInfo>> {
+        self.reading(|readings| readings.processes.clone())
+    }
+
+    #[must_use]
+    pub fn component_information(&self) -> Option<Vec<ComponentInfo>> {
+        self.reading(|readings| readings.components.clone())
+    }
+
+    #[must_use]
+    pub fn battery_information(&self) -> Option<Vec<BatteryInfo>> {
+        self.reading(|readings| readings.batteries.clone())
+    }
+
+    /// Without connectivity and networks until the first readings arrive
+    #[must_use]
+    pub fn network_information(&self) -> NetworkInfo {
+        self.reading(|readings| Some(readings.network.clone())).unwrap_or(NetworkInfo {
+            connectivity:  ConnectivityState::Unknown,
+            wifis:         None,
+            networks:      None,
+            ip_address_v4: None,
+            ip_address_v6: None,
+            connected_v6:  false,
+            gateway_v6:    None,
+        })
+    }
+}
+
+#[test]
+fn parse_requests() {
+    let id = ProcessId {
+        pid:        sysinfo::Pid::from(1234),
+        start_time: std::time::UNIX_EPOCH + Duration::from_secs(1_700_000_000),
+    };
+    assert_eq!(format_process_id(id), "1234 1700000000");
+    assert_eq!(parse_request("kill 1234 1700000000"), Some(Request::Kill(id)));
+    assert_eq!(parse_request("renice 1234 1700000000 -5"), Some(Request::Renice(id, -5)));
+    assert_eq!(parse_request("kill 1234"), None);
+    assert_eq!(parse_request("renice 1234 1700000000"), None);
+    assert_eq!(parse_request("kill all 0"), None);
+}
+
+#[test]
+fn split_frames() {
+    let mut received = b"{\"a\":1}\n{\"b\":".to_vec();
+    assert_eq!(Encoding::Json.split_frame(&mut received), Some(b"{\"a\":1}\n".to_vec()));
+    assert_eq!(Encoding::Json.split_frame(&mut received), None);
+    assert_eq!(received, b"{\"b\":");
+
+    let mut received = vec![0, 0, 0, 3, 0x93, 1, 2, 3, 0, 0];
+    assert_eq!(Encoding::MessagePack.split_frame(&mut received), Some(vec![0, 0, 0, 3, 0x93, 1, 2]));
+    assert_eq!(received, vec![3, 0, 0]);
+    assert_eq!(Encoding::MessagePack.split_frame(&mut received), None);
+
+    assert_eq!(Encoding::from_name("msgpack"), Some(Encoding::MessagePack));
+    assert_eq!(Encoding::from_name("cbor"), None);
+}
@@ -0,0 +1,261 @@
+// Applications that didn't come from the system package manager, and how
+// they are confined. Flatpak leaves a .flatpak-info file in the root of
+// every sandbox, snaps and Microsoft Store apps run from well known
+// directories and AppImages are mounted to /tmp/.mount_* while they run.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SandboxKind {
+    Flatpak,
+    Snap,
+    /// Not actually sandboxed, just packaged into a single file
+    AppImage,
+    MicrosoftStore,
+}
+
+impl std::fmt::Display for SandboxKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Flatpak => "Flatpak",
+            Self::Snap => "Snap",
+            Self::AppImage => "AppImage",
+            Self::MicrosoftStore => "Microsoft Store",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SandboxInfo {
+    pub kind:        SandboxKind,
+    /// E.g. "org.mozilla.firefox" for Flatpak or "firefox" for Snap
+    pub app_id:      Option<String>,
+    /// What the application is allowed to access, like "network", "x11"
+    /// or "filesystem:home". `None` if unknown
+    pub permissions: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SandboxedApplication {
+    pub name:    String,
+    pub version: Option<String>,
+    pub sandbox: SandboxInfo,
+}
+
+pub fn process_sandbox(pid: sysinfo::Pid, path: Option<&str>) -> Option<SandboxInfo> {
+    // Only readable for the user's own processes, or as root
+    if let Ok(flatpak_info) = std::fs::read_to_string(format!("/proc/{pid}/root/.flatpak-info")) {
+        return Some(parse_flatpak_info(&flatpak_info));
+    }
+    let path = path?;
+    let (kind, app_id) = path
+        .strip_prefix("/snap/")
+        .map(|snap| (SandboxKind::Snap, snap.split('/').next()))
+        .or_else(|| path.contains("/.mount_").then_some((SandboxKind::AppImage, None)))
+        .or_else(|| Some((SandboxKind::MicrosoftStore, path.split_once("\\WindowsApps\\")?.1.split('\\').next())))?;
+    Some(SandboxInfo {
+        kind,
+        app_id: app_id.map(ToString::to_string),
+        permissions: None,
+    })
+}
+
+#[cfg(target_os = "linux")]
+fn command_output(command: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(command).args(args).output().ok().filter(|output| output.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Flatpaks, snaps and AppImages lying around in the usual places. Asks
+/// Flatpak for the permissions of every application, so this takes a
+/// moment
+#[cfg(target_os = "linux")]
+pub fn sandboxed_applications() -> Option<Vec<SandboxedApplication>> {
+    let mut applications = Vec::new();
+
+    if let Some(flatpaks) = command_output("flatpak", &["list", "--app", "--columns=application,version"]) {
+        for line in flatpaks.lines() {
+            let (name, version) = line.split_once('\t').unwrap_or((line, ""));
+            let mut sandbox = command_output("flatpak", &["info", "--show-permissions", name]).map_or(
+                SandboxInfo {
+                    kind:        SandboxKind::Flatpak,
+                    app_id:      None,
+                    permissions: None,
+                },
+                |permissions| parse_flatpak_info(&permissions),
+            );
+            sandbox.app_id = Some(name.to_string());
+            applications.push(SandboxedApplication {
+                name: name.to_string(),
+                version: Some(version.to_string()).filter(|version| !version.is_empty()),
+                sandbox,
+            });
+        }
+    }
+
+    if let Some(snaps) = command_output("snap", &["list"]) {
+        let connections = command_output("snap", &["connections"]).unwrap_or_default();
+        // The first line is the header
+        for line in snaps.lines().skip(1) {
+            let mut columns = line.split_whitespace();
+            let Some(name) = columns.next() else {
+                continue;
+            };
+            applications.push(SandboxedApplication {
+                name:    name.to_string(),
+                version: columns.next().map(ToString::to_string),
+                sandbox: SandboxInfo {
+                    kind:        SandboxKind::Snap,
+                    app_id:      Some(name.to_string()),
+                    permissions: Some(snap_permissions(&connections, name)),
+                },
+            });
+        }
+    }
+
+    let home = std::env::var("HOME").map(std::path::PathBuf::from).ok()?;
+    for directory in ["Applications", "AppImages", ".local/bin"] {
+        let Ok(entries) = std::fs::read_dir(home.join(directory)) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().is_some_and(|extension| extension.eq_ignore_ascii_case("appimage"))
+                && let Some(name) = path.file_stem()
+            {
+                applications.push(SandboxedApplication {
+                    name:    name.to_string_lossy().to_string(),
+                    version: None,
+                    sandbox: SandboxInfo {
+                        kind:        SandboxKind::AppImage,
+                        app_id:      None,
+                        permissions: None,
+                    },
+                });
+            }
+        }
+    }
+
+    Some(applications)
+}
+
+#[cfg(windows)]
+pub fn sandboxed_applications() -> Option<Vec<SandboxedApplication>> {
+    let output = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "Get-AppxPackage | Select-Object Name,Version | ConvertTo-Csv -NoTypeInformation",
+        ])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(
+        crate::performance_counters::parse_csv(&String::from_utf8_lossy(&output.stdout))
+            .into_iter()
+            // The first row is the header
+            .skip(1)
+            .filter_map(|row| match row.as_slice() {
+                [name, version] => Some(SandboxedApplication {
+                    name:    name.clone(),
+                    version: Some(version.clone()),
+                    sandbox: SandboxInfo {
+                        kind:        SandboxKind::MicrosoftStore,
+                        app_id:      Some(name.clone()),
+                        permissions: None,
+                    },
+                }),
+                _ => None,
+            })
+            .collect(),
+    )
+}
+
+#[cfg(not(any(target_os = "linux", windows)))]
+pub const fn sandboxed_applications() -> Option<Vec<SandboxedApplication>> {
+    None
+}
+
+// `snap connections` lists one plug per line, like
+// "network  firefox:network  :network  -". Plugs that aren't connected
+// have "-" as their slot.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn snap_permissions(connections: &str, snap: &str) -> Vec<String> {
+    connections
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let mut columns = line.split_whitespace();
+            let interface = columns.next()?;
+            let (plug_snap, _) = columns.next()?.split_once(':')?;
+            (plug_snap == snap && columns.next()? != "-").then(|| interface.to_string())
+        })
+        .collect()
+}
+
+// Both .flatpak-info and `flatpak info --show-permissions` are ini files
+// with a [Context] section like
+//
+// [Context]
+// shared=network;ipc;
+// sockets=x11;wayland;
+// filesystems=xdg-download;
+fn parse_flatpak_info(flatpak_info: &str) -> SandboxInfo {
+    let mut section = "";
+    let mut app_id = None;
+    let mut permissions = Vec::new();
+    for line in flatpak_info.lines().map(str::trim) {
+        if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+            section = name;
+        } else if let Some((key, value)) = line.split_once('=') {
+            match (section, key) {
+                ("Application", "name") => app_id = Some(value.to_string()),
+                ("Context", "shared" | "sockets" | "devices") => permissions.extend(value.split(';').filter(|value| !value.is_empty()).map(ToString::to_string)),
+                ("Context", "filesystems") => permissions.extend(value.split(';').filter(|value| !value.is_empty()).map(|filesystem| format!("filesystem:{filesystem}"))),
+                _ => {}
+            }
+        }
+    }
+    SandboxInfo {
+        kind: SandboxKind::Flatpak,
+        app_id,
+        permissions: Some(permissions),
+    }
+}
+
+#[test]
+fn parse_flatpak_permissions() {
+    let sandbox = parse_flatpak_info(
+        "[Application]
+name=org.mozilla.firefox
+runtime=runtime/org.freedesktop.Platform/x86_64/23.08
+
+[Context]
+shared=network;ipc;
+sockets=x11;wayland;pulseaudio;
+devices=dri;
+filesystems=xdg-download;
+",
+    );
+    assert_eq!(sandbox.app_id.as_deref(), Some("org.mozilla.firefox"));
+    assert_eq!(sandbox.permissions.expect("Flatpak always knows the permissions"), [
+        "network",
+        "ipc",
+        "x11",
+        "wayland",
+        "pulseaudio",
+        "dri",
+        "filesystem:xdg-download"
+    ]);
+    assert_eq!(
+        snap_permissions(
+            "Interface  Plug              Slot       Notes
+home       firefox:home      :home      -
+camera     firefox:camera    -          -
+network    firefox:network   :network   -
+network    spotify:network   :network   -",
+            "firefox"
+        ),
+        ["home", "network"]
+    );
+}
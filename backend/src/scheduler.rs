@@ -0,0 +1,135 @@
+// How busy the scheduler is. A storm of context switches or interrupts
+// slows everything down without showing up as high usage of any process:
+//
+// - Linux: the counters since boot in /proc/stat
+// - Windows: the performance counters, which are already rates
+// - macOS only reports them through host_statistics, which needs unsafe
+//   code
+
+use std::time::Instant;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SchedulerActivity {
+    pub context_switches_per_second: f64,
+    pub interrupts_per_second:       f64,
+}
+
+/// What a process gave up the CPU for since it started, only available
+/// on Linux
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ContextSwitches {
+    /// Waiting for something, e.g. IO or a lock
+    pub voluntary:   u64,
+    /// Preempted because its time slice ran out. A lot of these mean it
+    /// competes with other processes for the CPU
+    pub involuntary: u64,
+}
+
+/// Context switches and interrupts since boot, and when they were read
+#[derive(Debug, Clone, Copy)]
+pub struct SchedulerCounters {
+    read_at:          Instant,
+    context_switches: u64,
+    interrupts:       u64,
+}
+
+/// `previous` are the counters of the last call, which are replaced. The
+/// first call averages over the time since boot
+pub fn scheduler_activity(previous: &mut Option<SchedulerCounters>, uptime: std::time::Duration) -> Option<SchedulerActivity> {
+    if let Some(current) = scheduler_counters() {
+        let (context_switches, interrupts, seconds) = previous.replace(current).map_or((current.context_switches, current.interrupts, uptime.as_secs_f64()), |earlier| {
+            (
+                current.context_switches.saturating_sub(earlier.context_switches),
+                current.interrupts.saturating_sub(earlier.interrupts),
+                current.read_at.duration_since(earlier.read_at).as_secs_f64(),
+            )
+        });
+        if seconds == 0.0 {
+            return None;
+        }
+        #[allow(clippy::cast_precision_loss)]
+        return Some(SchedulerActivity {
+            context_switches_per_second: context_switches as f64 / seconds,
+            interrupts_per_second:       interrupts as f64 / seconds,
+        });
+    }
+    performance_counter_activity()
+}
+
+#[cfg(target_os = "linux")]
+fn scheduler_counters() -> Option<SchedulerCounters> {
+    let (context_switches, interrupts) = parse_proc_stat(&std::fs::read_to_string("/proc/stat").ok()?)?;
+    Some(SchedulerCounters {
+        read_at: Instant::now(),
+        context_switches,
+        interrupts,
+    })
+}
+
+#[cfg(not(target_os = "linux"))]
+const fn scheduler_counters() -> Option<SchedulerCounters> {
+    None
+}
+
+// ctxt 115315
+// intr 3936282 0 9 0 0 ...
+//
+// The first number after intr is the total, the rest are per interrupt
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_stat(stat: &str) -> Option<(u64, u64)> {
+    let counter = |name: &str| stat.lines().find_map(|line| line.strip_prefix(name)?.split_whitespace().next()?.parse::<u64>().ok());
+    Some((counter("ctxt ")?, counter("intr ")?))
+}
+
+#[cfg(windows)]
+fn performance_counter_activity() -> Option<SchedulerActivity> {
+    let context_switches = crate::performance_counters::context_switches()?;
+    let interrupts = crate::performance_counters::processor_times()?.into_iter().find_map(|row| match row.as_slice() {
+        [name, .., interrupts] if name == "_Total" => interrupts.parse().ok(),
+        _ => None,
+    })?;
+    Some(SchedulerActivity {
+        context_switches_per_second: context_switches,
+        interrupts_per_second:       interrupts,
+    })
+}
+
+#[cfg(not(windows))]
+const fn performance_counter_activity() -> Option<SchedulerActivity> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+pub fn process_context_switches(pid: sysinfo::Pid) -> Option<ContextSwitches> {
+    parse_status(&std::fs::read_to_string(format!("/proc/{pid}/status")).ok()?)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn process_context_switches(_pid: sysinfo::Pid) -> Option<ContextSwitches> {
+    None
+}
+
+// voluntary_ctxt_switches:	150
+// nonvoluntary_ctxt_switches:	545
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_status(status: &str) -> Option<ContextSwitches> {
+    let counter = |name: &str| status.lines().find_map(|line| line.strip_prefix(name)?.trim().parse::<u64>().ok());
+    Some(ContextSwitches {
+        voluntary:   counter("voluntary_ctxt_switches:")?,
+        involuntary: counter("nonvoluntary_ctxt_switches:")?,
+    })
+}
+
+#[test]
+fn parse_scheduler_counters() {
+    assert_eq!(
+        parse_proc_stat("cpu  4705 356 584 3699 23 23 0 0 0 0\nintr 3936282 0 9 0\nctxt 115315\nbtime 1721000000\n"),
+        Some((115_315, 3_936_282))
+    );
+    assert_eq!(
+        parse_status("Name:\tbash\nvoluntary_ctxt_switches:\t150\nnonvoluntary_ctxt_switches:\t545\n"),
+        Some(ContextSwitches { voluntary: 150, involuntary: 545 })
+    );
+}
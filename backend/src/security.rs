@@ -0,0 +1,250 @@
+// Security subsystem. Denials from SELinux or AppArmor are a common
+// reason for processes failing in ways that look nothing like a permission
+// problem, so it's worth being able to see at a glance which of them is
+// active and in which mode.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MandatoryAccessControlSystem {
+    SeLinux,
+    AppArmor,
+    /// macOS System Integrity Protection
+    Sip,
+}
+
+impl std::fmt::Display for MandatoryAccessControlSystem {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::SeLinux => "SELinux",
+            Self::AppArmor => "AppArmor",
+            Self::Sip => "System Integrity Protection",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MandatoryAccessControlMode {
+    Enforcing,
+    /// Violations are logged but not denied. Called permissive by SELinux
+    /// and complain mode by AppArmor
+    Permissive,
+    Disabled,
+}
+
+impl std::fmt::Display for MandatoryAccessControlMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Enforcing => "enforcing",
+            Self::Permissive => "permissive",
+            Self::Disabled => "disabled",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MandatoryAccessControlProfile {
+    pub name: String,
+    pub mode: MandatoryAccessControlMode,
+}
+
+#[derive(Debug, Clone)]
+pub struct MandatoryAccessControlInfo {
+    pub system:   MandatoryAccessControlSystem,
+    pub mode:     MandatoryAccessControlMode,
+    /// Name of the loaded policy, e.g. "targeted" for SELinux
+    pub policy:   Option<String>,
+    /// Loaded AppArmor profiles. Reading them needs root, so this is
+    /// usually `None` when running as a regular user
+    pub profiles: Option<Vec<MandatoryAccessControlProfile>>,
+}
+
+#[cfg(target_os = "linux")]
+pub fn mandatory_access_control() -> Vec<MandatoryAccessControlInfo> {
+    let mut systems = Vec::new();
+    // selinuxfs is only mounted if SELinux is enabled in the kernel
+    if let Ok(enforce) = std::fs::read_to_string("/sys/fs/selinux/enforce") {
+        systems.push(MandatoryAccessControlInfo {
+            system:   MandatoryAccessControlSystem::SeLinux,
+            mode:     if enforce.trim() == "1" {
+                MandatoryAccessControlMode::Enforcing
+            } else {
+                MandatoryAccessControlMode::Permissive
+            },
+            policy:   std::fs::read_to_string("/etc/selinux/config")
+                .ok()
+                .and_then(|config| config.lines().find_map(|line| line.trim().strip_prefix("SELINUXTYPE=").map(ToString::to_string))),
+            profiles: None,
+        });
+    }
+    if let Ok(enabled) = std::fs::read_to_string("/sys/module/apparmor/parameters/enabled") {
+        systems.push(MandatoryAccessControlInfo {
+            system:   MandatoryAccessControlSystem::AppArmor,
+            mode:     if enabled.trim() == "Y" {
+                MandatoryAccessControlMode::Enforcing
+            } else {
+                MandatoryAccessControlMode::Disabled
+            },
+            policy:   None,
+            profiles: std::fs::read_to_string("/sys/kernel/security/apparmor/profiles")
+                .ok()
+                .map(|profiles| parse_apparmor_profiles(&profiles)),
+        });
+    }
+    systems
+}
+
+#[cfg(target_os = "macos")]
+pub fn mandatory_access_control() -> Vec<MandatoryAccessControlInfo> {
+    std::process::Command::new("csrutil")
+        .arg("status")
+        .output()
+        .ok()
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+        .filter(|status| status.contains("System Integrity Protection status:"))
+        .map(|status| MandatoryAccessControlInfo {
+            system:   MandatoryAccessControlSystem::Sip,
+            mode:     if status.contains("status: enabled") {
+                MandatoryAccessControlMode::Enforcing
+            } else {
+                MandatoryAccessControlMode::Disabled
+            },
+            policy:   None,
+            profiles: None,
+        })
+        .into_iter()
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub const fn mandatory_access_control() -> Vec<MandatoryAccessControlInfo> {
+    Vec::new()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MitigationStatus {
+    NotAffected,
+    Mitigated,
+    Vulnerable,
+    /// The kernel doesn't know, e.g. because the microcode doesn't say
+    Unknown,
+}
+
+impl std::fmt::Display for MitigationStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::NotAffected => "not affected",
+            Self::Mitigated => "mitigated",
+            Self::Vulnerable => "vulnerable",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuVulnerability {
+    /// Like "Spectre v2" or "Retbleed"
+    pub name:    String,
+    pub status:  MitigationStatus,
+    /// What the kernel says about it, e.g. which mitigation is used
+    pub details: Option<String>,
+}
+
+/// Speculative execution and other hardware vulnerabilities of the CPU
+///
+/// Only Linux reports them, in /sys/devices/system/cpu/vulnerabilities.
+/// Windows only has them in Get-SpeculationControlSettings, which isn't
+/// installed by default, and macOS doesn't report them at all
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CpuSecurityInfo {
+    /// Ordered by name
+    pub vulnerabilities: Vec<CpuVulnerability>,
+}
+
+#[cfg(target_os = "linux")]
+pub fn cpu_security() -> Option<CpuSecurityInfo> {
+    let mut vulnerabilities = std::fs::read_dir("/sys/devices/system/cpu/vulnerabilities")
+        .ok()?
+        .flatten()
+        .filter_map(|entry| Some(parse_vulnerability(entry.file_name().to_str()?, std::fs::read_to_string(entry.path()).ok()?.trim())))
+        .collect::<Vec<CpuVulnerability>>();
+    vulnerabilities.sort_by(|a, b| a.name.cmp(&b.name));
+    Some(CpuSecurityInfo { vulnerabilities })
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn cpu_security() -> Option<CpuSecurityInfo> {
+    None
+}
+
+// The file is named after the vulnerability and has one line like "Not
+// affected", "Mitigation: PTI", "Vulnerable: Clear CPU buffers attempted,
+// no microcode" or "Unknown: Dependent on hypervisor status"
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_vulnerability(file_name: &str, contents: &str) -> CpuVulnerability {
+    let name = match file_name {
+        "meltdown" => "Meltdown".to_string(),
+        "spectre_v1" => "Spectre v1".to_string(),
+        "spectre_v2" => "Spectre v2".to_string(),
+        "spec_store_bypass" => "Speculative Store Bypass".to_string(),
+        "l1tf" => "L1 Terminal Fault".to_string(),
+        "mds" => "Microarchitectural Data Sampling".to_string(),
+        "tsx_async_abort" => "TSX Asynchronous Abort".to_string(),
+        "itlb_multihit" => "iTLB Multihit".to_string(),
+        "srbds" => "Special Register Buffer Data Sampling".to_string(),
+        "mmio_stale_data" => "MMIO Stale Data".to_string(),
+        "retbleed" => "Retbleed".to_string(),
+        "spec_rstack_overflow" => "Speculative Return Stack Overflow".to_string(),
+        "gather_data_sampling" => "Gather Data Sampling (Downfall)".to_string(),
+        "reg_file_data_sampling" => "Register File Data Sampling".to_string(),
+        // Newer kernels keep adding them
+        other => other.replace('_', " "),
+    };
+    let (status, details) = contents.split_once(':').map_or((contents, None), |(status, details)| (status, Some(details.trim().to_string())));
+    CpuVulnerability {
+        name,
+        status: match status {
+            "Not affected" => MitigationStatus::NotAffected,
+            "Mitigation" => MitigationStatus::Mitigated,
+            "Vulnerable" => MitigationStatus::Vulnerable,
+            _ => MitigationStatus::Unknown,
+        },
+        details: details.filter(|details| !details.is_empty()),
+    }
+}
+
+// One profile per line, e.g. "/usr/bin/man (enforce)" or "firefox
+// (complain)"
+#[cfg(target_os = "linux")]
+fn parse_apparmor_profiles(profiles: &str) -> Vec<MandatoryAccessControlProfile> {
+    profiles
+        .lines()
+        .filter_map(|line| {
+            let (name, mode) = line.rsplit_once(" (")?;
+            Some(MandatoryAccessControlProfile {
+                name: name.to_string(),
+                mode: match mode.trim_end_matches(')') {
+                    "enforce" | "kill" => MandatoryAccessControlMode::Enforcing,
+                    "complain" => MandatoryAccessControlMode::Permissive,
+                    _ => MandatoryAccessControlMode::Disabled,
+                },
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn parse_cpu_vulnerabilities() {
+    assert_eq!(parse_vulnerability("meltdown", "Mitigation: PTI"), CpuVulnerability {
+        name:    "Meltdown".to_string(),
+        status:  MitigationStatus::Mitigated,
+        details: Some("PTI".to_string()),
+    });
+    assert_eq!(parse_vulnerability("retbleed", "Not affected").status, MitigationStatus::NotAffected);
+    assert_eq!(parse_vulnerability("retbleed", "Not affected").details, None);
+    let vulnerability = parse_vulnerability("some_new_one", "Vulnerable: Clear CPU buffers attempted, no microcode");
+    assert_eq!(vulnerability.name, "some new one");
+    assert_eq!(vulnerability.status, MitigationStatus::Vulnerable);
+    assert_eq!(parse_vulnerability("tsx_async_abort", "Unknown: Dependent on hypervisor status").status, MitigationStatus::Unknown);
+}
@@ -0,0 +1,126 @@
+// sysinfo only finds one or two temperature sensors on macOS. The SMC
+// knows about many more, plus fans and power draw, but reading it means
+// calling into IOKit, which the backend can't do without unsafe code.
+// `powermetrics` reads the same sensors and prints them as text. It has to
+// run as root, and it samples for a moment, so the results are cached for
+// a few seconds.
+
+use uom::si::{
+    angular_velocity::revolution_per_minute,
+    f64::{AngularVelocity, Power},
+    power::{milliwatt, watt},
+};
+
+#[derive(Debug, Clone)]
+pub struct FanInfo {
+    pub name:  String,
+    pub speed: AngularVelocity,
+}
+
+#[derive(Debug, Clone)]
+pub struct PowerRailInfo {
+    /// E.g. "CPU Power" or "GPU Power"
+    pub name:  String,
+    pub power: Power,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SensorReadings {
+    /// Name and degrees Celsius
+    pub temperatures: Vec<(String, f32)>,
+    pub fans:         Vec<FanInfo>,
+    pub power_rails:  Vec<PowerRailInfo>,
+}
+
+#[cfg(target_os = "macos")]
+pub fn sensor_readings() -> Option<SensorReadings> {
+    type Cache = (Option<SensorReadings>, Option<std::time::Instant>);
+    static CACHE: std::sync::Mutex<Cache> = std::sync::Mutex::new((None, None));
+
+    let mut cache = CACHE.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+    if let (readings, Some(read_at)) = &*cache
+        && read_at.elapsed() < std::time::Duration::from_secs(5)
+    {
+        return readings.clone();
+    }
+    // Apple Silicon has no smc sampler, powermetrics refuses to run at all
+    // when asked for it there
+    let readings = ["smc,cpu_power,gpu_power", "cpu_power,gpu_power"].into_iter().find_map(|samplers| {
+        let output = std::process::Command::new("powermetrics")
+            .args(["--samplers", samplers, "--sample-count", "1", "--sample-rate", "200"])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())?;
+        Some(parse_powermetrics(&String::from_utf8_lossy(&output.stdout)))
+    });
+    *cache = (readings.clone(), Some(std::time::Instant::now()));
+    drop(cache);
+    readings
+}
+
+#[cfg(not(target_os = "macos"))]
+pub const fn sensor_readings() -> Option<SensorReadings> {
+    None
+}
+
+// Every reading is on its own line, like "CPU die temperature: 47.84 C",
+// "Fan: 1798.53 rpm", "GPU Power: 12 mW" or, on Intel Macs, "Intel energy
+// model derived package power (CPUs+GT+SA): 1.63W"
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+#[allow(clippy::cast_possible_truncation)]
+fn parse_powermetrics(output: &str) -> SensorReadings {
+    let mut readings = SensorReadings::default();
+    for line in output.lines() {
+        let Some((name, value)) = line.rsplit_once(':') else {
+            continue;
+        };
+        let name = name.trim().to_string();
+        let value = value.trim();
+        let number = |unit: &str| value.strip_suffix(unit)?.trim().parse::<f64>().ok();
+        if let Some(temperature) = number("C") {
+            readings.temperatures.push((name, temperature as f32));
+        } else if let Some(speed) = number("rpm") {
+            readings.fans.push(FanInfo {
+                name,
+                speed: AngularVelocity::new::<revolution_per_minute>(speed),
+            });
+        } else if let Some(power) = number("mW") {
+            readings.power_rails.push(PowerRailInfo {
+                name,
+                power: Power::new::<milliwatt>(power),
+            });
+        } else if let Some(power) = number("W") {
+            readings.power_rails.push(PowerRailInfo {
+                name,
+                power: Power::new::<watt>(power),
+            });
+        }
+    }
+    readings
+}
+
+#[test]
+fn parse_smc_sensors() {
+    let readings = parse_powermetrics(
+        "**** SMC sensors ****
+
+CPU Thermal level: 0
+Fan: 1798.53 rpm
+CPU die temperature: 47.84 C
+GPU die temperature: 44.00 C
+CPU Plimit: 0.00
+
+**** Processor usage ****
+
+Intel energy model derived package power (CPUs+GT+SA): 1.63W
+GPU Power: 12 mW
+",
+    );
+    assert_eq!(readings.temperatures.len(), 2);
+    assert_eq!(readings.temperatures[0].0, "CPU die temperature");
+    assert_eq!(readings.fans.len(), 1);
+    assert!((readings.fans[0].speed.get::<revolution_per_minute>() - 1798.53).abs() < 1e-9);
+    assert_eq!(readings.power_rails.len(), 2);
+    assert!((readings.power_rails[0].power.get::<watt>() - 1.63).abs() < 1e-9);
+    assert!((readings.power_rails[1].power.get::<milliwatt>() - 12.0).abs() < 1e-9);
+}
@@ -0,0 +1,344 @@
+// Serialize and Deserialize for the info structs, behind the `serde`
+// feature. Most fields are std types serde already knows. Neither the
+// traits nor the types of the other crates are ours, so those get a
+// newtype wrapper here, and fields holding them are marked with
+// `#[serde(with = "serialization")]`, which also works through Option and
+// Vec. The formats match schema/crossinfo-v1.schema.json: enums are their
+// variant names, PIDs numbers and addresses strings.
+
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+
+pub trait Foreign: Sized {
+    type Wrapper: Serialize + DeserializeOwned;
+
+    fn wrap(&self) -> Self::Wrapper;
+    fn unwrap(wrapper: Self::Wrapper) -> Self;
+}
+
+impl<T: Foreign> Foreign for Option<T> {
+    type Wrapper = Option<T::Wrapper>;
+
+    fn wrap(&self) -> Self::Wrapper {
+        self.as_ref().map(T::wrap)
+    }
+
+    fn unwrap(wrapper: Self::Wrapper) -> Self {
+        wrapper.map(T::unwrap)
+    }
+}
+
+impl<T: Foreign> Foreign for Vec<T> {
+    type Wrapper = Vec<T::Wrapper>;
+
+    fn wrap(&self) -> Self::Wrapper {
+        self.iter().map(T::wrap).collect()
+    }
+
+    fn unwrap(wrapper: Self::Wrapper) -> Self {
+        wrapper.into_iter().map(T::unwrap).collect()
+    }
+}
+
+/// # Errors
+///
+/// If the serializer fails
+pub fn serialize<T: Foreign, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    value.wrap().serialize(serializer)
+}
+
+/// # Errors
+///
+/// If the value isn't in the format `serialize` writes
+pub fn deserialize<'de, T: Foreign, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    T::Wrapper::deserialize(deserializer).map(T::unwrap)
+}
+
+// Enums that are written as the name of their variant. Unknown names are
+// read as the fallback, so newer versions of the other crate can add
+// variants
+macro_rules! by_variant_name {
+    ($wrapper:ident, $foreign:ty, $fallback:ident, [$($variant:ident),*]) => {
+        pub struct $wrapper($foreign);
+
+        impl Serialize for $wrapper {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(&format_args!("{:?}", self.0))
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $wrapper {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                Ok(Self(match String::deserialize(deserializer)?.as_str() {
+                    $(stringify!($variant) => <$foreign>::$variant,)*
+                    _ => <$foreign>::$fallback,
+                }))
+            }
+        }
+
+        impl Foreign for $foreign {
+            type Wrapper = $wrapper;
+
+            fn wrap(&self) -> Self::Wrapper {
+                $wrapper(*self)
+            }
+
+            fn unwrap(wrapper: Self::Wrapper) -> Self {
+                wrapper.0
+            }
+        }
+    };
+}
+
+by_variant_name!(BatteryState, battery::State, Unknown, [Charging, Discharging, Empty, Full]);
+by_variant_name!(BatteryTechnology, battery::Technology, Unknown, [
+    LithiumIon,
+    LeadAcid,
+    LithiumPolymer,
+    NickelMetalHydride,
+    NickelCadmium,
+    NickelZinc,
+    LithiumIronPhosphate,
+    RechargeableAlkalineManganese
+]);
+
+// Types that are written with `Display` and read with `FromStr`
+macro_rules! as_string {
+    ($wrapper:ident, $foreign:ty, $parse:expr) => {
+        pub struct $wrapper($foreign);
+
+        impl Serialize for $wrapper {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                // Uid only implements Display through Deref
+                serializer.serialize_str(&self.0.to_string())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $wrapper {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let text = String::deserialize(deserializer)?;
+                let parse: fn(&str) -> Option<$foreign> = $parse;
+                parse(&text)
+                    .map(Self)
+                    .ok_or_else(|| serde::de::Error::custom(format!("Invalid {}: {text}", stringify!($wrapper))))
+            }
+        }
+
+        impl Foreign for $foreign {
+            type Wrapper = $wrapper;
+
+            fn wrap(&self) -> Self::Wrapper {
+                $wrapper(self.clone())
+            }
+
+            fn unwrap(wrapper: Self::Wrapper) -> Self {
+                wrapper.0
+            }
+        }
+    };
+}
+
+as_string!(Uid, sysinfo::Uid, |text| text.parse().ok());
+as_string!(MacAddress, sysinfo::MacAddr, |text| {
+    let bytes = text.split(':').map(|byte| u8::from_str_radix(byte, 16).ok()).collect::<Option<Vec<u8>>>()?;
+    Some(sysinfo::MacAddr(bytes.try_into().ok()?))
+});
+
+// Quantities that are written as a number in a fixed unit, which the
+// schema names in the field, rather than in uom's base unit
+macro_rules! in_unit {
+    ($wrapper:ident, $foreign:ty, $unit:ty) => {
+        pub struct $wrapper(f64);
+
+        impl Serialize for $wrapper {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_f64(self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $wrapper {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                f64::deserialize(deserializer).map(Self)
+            }
+        }
+
+        impl Foreign for $foreign {
+            type Wrapper = $wrapper;
+
+            fn wrap(&self) -> Self::Wrapper {
+                $wrapper(self.get::<$unit>())
+            }
+
+            fn unwrap(wrapper: Self::Wrapper) -> Self {
+                Self::new::<$unit>(wrapper.0)
+            }
+        }
+    };
+}
+
+in_unit!(WattHours, uom::si::f64::Energy, uom::si::energy::watt_hour);
+in_unit!(Volts, uom::si::f64::ElectricPotential, uom::si::electric_potential::volt);
+in_unit!(Watts, uom::si::f64::Power, uom::si::power::watt);
+
+/// Written as the number, like the schema has it
+pub struct Pid(u32);
+
+impl Serialize for Pid {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Pid {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u32::deserialize(deserializer).map(Self)
+    }
+}
+
+impl Foreign for sysinfo::Pid {
+    type Wrapper = Pid;
+
+    fn wrap(&self) -> Self::Wrapper {
+        Pid(self.as_u32())
+    }
+
+    fn unwrap(wrapper: Self::Wrapper) -> Self {
+        Self::from_u32(wrapper.0)
+    }
+}
+
+/// The variant name, e.g. "Sleep" or "Unknown(5)". `Display` differs
+/// between the platforms
+pub struct ProcessStatus(sysinfo::ProcessStatus);
+
+impl Serialize for ProcessStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(&format_args!("{:?}", self.0))
+    }
+}
+
+impl<'de> Deserialize<'de> for ProcessStatus {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use sysinfo::ProcessStatus as Status;
+
+        let name = String::deserialize(deserializer)?;
+        Ok(Self(match name.as_str() {
+            "Idle" => Status::Idle,
+            "Run" => Status::Run,
+            "Sleep" => Status::Sleep,
+            "Stop" => Status::Stop,
+            "Zombie" => Status::Zombie,
+            "Tracing" => Status::Tracing,
+            "Dead" => Status::Dead,
+            "Wakekill" => Status::Wakekill,
+            "Waking" => Status::Waking,
+            "Parked" => Status::Parked,
+            "LockBlocked" => Status::LockBlocked,
+            "UninterruptibleDiskSleep" => Status::UninterruptibleDiskSleep,
+            name => Status::Unknown(name.strip_prefix("Unknown(").and_then(|code| code.strip_suffix(')')?.parse().ok()).unwrap_or_default()),
+        }))
+    }
+}
+
+impl Foreign for sysinfo::ProcessStatus {
+    type Wrapper = ProcessStatus;
+
+    fn wrap(&self) -> Self::Wrapper {
+        ProcessStatus(*self)
+    }
+
+    fn unwrap(wrapper: Self::Wrapper) -> Self {
+        wrapper.0
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Wifi {
+    mac:          String,
+    ssid:         String,
+    channel:      String,
+    signal_level: String,
+    security:     String,
+}
+
+impl Foreign for crate::Wifi {
+    type Wrapper = Wifi;
+
+    fn wrap(&self) -> Self::Wrapper {
+        let wifi = self.clone();
+        Wifi {
+            mac:          wifi.mac,
+            ssid:         wifi.ssid,
+            channel:      wifi.channel,
+            signal_level: wifi.signal_level,
+            security:     wifi.security,
+        }
+    }
+
+    fn unwrap(wrapper: Self::Wrapper) -> Self {
+        Self {
+            mac:          wrapper.mac,
+            ssid:         wrapper.ssid,
+            channel:      wrapper.channel,
+            signal_level: wrapper.signal_level,
+            security:     wrapper.security,
+        }
+    }
+}
+
+/// `NetworkInfo.connectivity`, which is flattened into a `connectivity`
+/// and the `connected` boolean it replaced, so consumers of schema v1 and
+/// older agents keep working
+pub mod connectivity {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use crate::ConnectivityState;
+
+    #[derive(Serialize)]
+    struct Written {
+        connectivity: ConnectivityState,
+        connected:    bool,
+    }
+
+    #[derive(Deserialize)]
+    struct Read {
+        connectivity: Option<ConnectivityState>,
+        #[serde(default)]
+        connected:    bool,
+    }
+
+    /// # Errors
+    ///
+    /// If the serializer fails
+    pub fn serialize<S: Serializer>(connectivity: &ConnectivityState, serializer: S) -> Result<S::Ok, S::Error> {
+        Written {
+            connectivity: *connectivity,
+            connected:    connectivity.is_connected(),
+        }
+        .serialize(serializer)
+    }
+
+    /// # Errors
+    ///
+    /// If the value isn't in the format `serialize` writes
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ConnectivityState, D::Error> {
+        let read = Read::deserialize(deserializer)?;
+        // Older versions only knew whether the internet answered
+        let connected = if read.connected { ConnectivityState::Connected } else { ConnectivityState::NoInternet };
+        Ok(read.connectivity.unwrap_or(connected))
+    }
+}
+
+#[test]
+fn connectivity_stays_compatible() {
+    #[derive(Serialize, Deserialize)]
+    struct Network {
+        #[serde(flatten, with = "connectivity")]
+        connectivity: crate::ConnectivityState,
+    }
+
+    let written = serde_json::to_value(Network { connectivity: crate::ConnectivityState::CaptivePortal }).expect("it serializes");
+    assert_eq!(written, serde_json::json!({ "connectivity": "CaptivePortal", "connected": false }));
+    let read = |json| serde_json::from_value::<Network>(json).map(|network| network.connectivity).ok();
+    assert_eq!(read(written), Some(crate::ConnectivityState::CaptivePortal));
+    assert_eq!(read(serde_json::json!({ "connected": true })), Some(crate::ConnectivityState::Connected));
+}
@@ -0,0 +1,141 @@
+// A small HTTP server behind the `server` feature, so dashboards and other
+// machines can query a host running crossinfo. Every reading is the JSON
+// the `serde` feature produces:
+//
+// GET    /system
+// GET    /cpu
+// GET    /cpu/states
+// GET    /memory
+// GET    /disks
+// GET    /network
+// GET    /processes
+// GET    /processes/{pid}
+// DELETE /processes/{pid}   kills the process, with the token
+// GET    /components
+// GET    /batteries
+// GET    /plugins           with the `plugins` feature
+//
+// Requests are answered one after another by the Manager that started the
+// server, so a slow one like /network holds up the rest.
+//
+// Reading needs no token, but killing does, sent as "Authorization: Bearer
+// <token>". Nothing is encrypted, so the token should only travel over a
+// trusted network, an SSH tunnel or a VPN.
+
+use serde::Serialize;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use crate::Manager;
+
+enum Reply {
+    Json(String),
+    NoContent,
+    Error(u16, &'static str),
+}
+
+fn json(value: Option<impl Serialize>) -> Reply {
+    match value.map(|value| serde_json::to_string(&value)) {
+        Some(Ok(json)) => Reply::Json(json),
+        Some(Err(_)) => Reply::Error(500, "Serializing failed"),
+        None => Reply::Error(404, "Not available on this host"),
+    }
+}
+
+/// Blocks for as long as the server runs
+pub fn serve(mut manager: Manager, address: &str, kill_token: Option<&str>) -> std::io::Result<()> {
+    let server = Server::http(address).map_err(std::io::Error::other)?;
+    for request in server.incoming_requests() {
+        let bearer = request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv("Authorization"))
+            .and_then(|header| header.value.as_str().strip_prefix("Bearer "));
+        let reply = route(&mut manager, request.method(), request.url(), bearer, kill_token);
+        // A client hanging up shouldn't stop the server
+        let _ = respond(request, reply);
+    }
+    Ok(())
+}
+
+fn route(manager: &mut Manager, method: &Method, url: &str, bearer: Option<&str>, kill_token: Option<&str>) -> Reply {
+    let path = url.split('?').next().unwrap_or_default().trim_end_matches('/');
+    let segments = path.split('/').skip(1).collect::<Vec<&str>>();
+    match (method, segments.as_slice()) {
+        (Method::Get, ["system"]) => json(manager.system_information()),
+        (Method::Get, ["cpu"]) => json(manager.cpu_information()),
+        (Method::Get, ["cpu", "states"]) => json(manager.cpu_state_information()),
+        (Method::Get, ["memory"]) => json(manager.memory_information()),
+        (Method::Get, ["disks"]) => json(manager.disk_information()),
+        (Method::Get, ["network"]) => json(Some(manager.network_information())),
+        (Method::Get, ["processes"]) => json(manager.process_information()),
+        (Method::Get, ["processes", pid]) => {
+            let Ok(pid) = pid.parse::<sysinfo::Pid>() else {
+                return Reply::Error(400, "Invalid PID");
+            };
+            manager.process_information().map_or(Reply::Error(404, "Not available on this host"), |processes| {
+                processes
+                    .into_iter()
+                    .find(|process| process.pid == pid)
+                    .map_or(Reply::Error(404, "No such process"), |process| json(Some(process)))
+            })
+        }
+        (Method::Delete, ["processes", pid]) => {
+            let Some(kill_token) = kill_token else {
+                return Reply::Error(403, "Killing processes is not allowed");
+            };
+            if !bearer.is_some_and(|bearer| crate::token::matches(kill_token, bearer.trim())) {
+                return Reply::Error(401, "Killing processes needs the token");
+            }
+            let Ok(pid) = pid.parse::<sysinfo::Pid>() else {
+                return Reply::Error(400, "Invalid PID");
+            };
+            if manager.get_process(pid).is_none() {
+                Reply::Error(404, "No such process")
+            } else if manager.kill_process(pid) {
+                Reply::NoContent
+            } else {
+                Reply::Error(500, "Killing the process failed")
+            }
+        }
+        (Method::Get, ["components"]) => json(manager.component_information()),
+        (Method::Get, ["batteries"]) => json(manager.battery_information()),
+        #[cfg(feature = "plugins")]
+        (Method::Get, ["plugins"]) => json(Some(manager.plugin_information())),
+        (method, ["system" | "cpu" | "memory" | "disks" | "network" | "processes" | "components" | "batteries" | "plugins", ..]) if *method != Method::Get => Reply::Error(405, "Method not allowed"),
+        _ => Reply::Error(404, "Not found"),
+    }
+}
+
+fn respond(request: Request, reply: Reply) -> std::io::Result<()> {
+    match reply {
+        Reply::Json(json) => {
+            let mut response = Response::from_string(json);
+            if let Ok(content_type) = Header::from_bytes("Content-Type", "application/json") {
+                response.add_header(content_type);
+            }
+            request.respond(response)
+        }
+        Reply::NoContent => request.respond(Response::empty(204)),
+        Reply::Error(status, message) => request.respond(Response::from_string(message).with_status_code(status)),
+    }
+}
+
+#[test]
+fn route_errors() {
+    let mut manager = Manager::new();
+    let mut status = |method: Method, url: &str, bearer: Option<&str>, kill_token: Option<&str>| match route(&mut manager, &method, url, bearer, kill_token) {
+        Reply::Json(_) => 200,
+        Reply::NoContent => 204,
+        Reply::Error(status, _) => status,
+    };
+    assert_eq!(status(Method::Get, "/nothing", None, None), 404);
+    assert_eq!(status(Method::Get, "/processes/abc", None, None), 400);
+    assert_eq!(status(Method::Post, "/system", None, None), 405);
+    assert_eq!(status(Method::Post, "/plugins", None, None), 405);
+    assert_eq!(status(Method::Delete, "/processes/1", Some("secret"), None), 403);
+    assert_eq!(status(Method::Delete, "/processes/1", None, Some("secret")), 401);
+    assert_eq!(status(Method::Delete, "/processes/1", Some("guess"), Some("secret")), 401);
+    assert_eq!(status(Method::Delete, "/processes/abc", Some("secret"), Some("secret")), 400);
+    // Above the highest PID Linux hands out
+    assert_eq!(status(Method::Delete, "/processes/4294967", Some("secret"), Some("secret")), 404);
+}
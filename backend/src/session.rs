@@ -0,0 +1,72 @@
+// Whether anyone is actually sitting in front of the machine. On Linux
+// systemd-logind tracks idle and lock state per session, fed by the
+// desktop environment. macOS keeps the time since the last input event in
+// the IOHIDSystem registry entry.
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[derive(Debug, Clone, Copy)]
+pub struct SessionActivity {
+    pub is_idle:   Option<bool>,
+    /// How long there hasn't been any input. `None` if the session isn't
+    /// idle or it's unknown
+    pub idle_time: Option<Duration>,
+    /// Whether the screen is locked or the screensaver is showing
+    pub is_locked: Option<bool>,
+}
+
+#[cfg(target_os = "linux")]
+pub fn session_activity() -> Option<SessionActivity> {
+    // Sessions started outside of a login (e.g. over sudo) don't have an
+    // id, "self" is only understood by newer versions of loginctl
+    let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "self".to_string());
+    let output = std::process::Command::new("loginctl")
+        .args(["show-session", &session, "--property=IdleHint", "--property=IdleSinceHint", "--property=LockedHint"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(parse_show_session(&String::from_utf8_lossy(&output.stdout), SystemTime::now()))
+}
+
+#[cfg(target_os = "macos")]
+pub fn session_activity() -> Option<SessionActivity> {
+    let output = std::process::Command::new("ioreg")
+        .args(["-c", "IOHIDSystem", "-d", "4"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    // The line looks like `    | |   "HIDIdleTime" = 1234567890`, in
+    // nanoseconds
+    let idle_time = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .find_map(|line| line.split_once("\"HIDIdleTime\" = "))
+        .and_then(|(_, nanos)| nanos.trim().parse::<u64>().ok())
+        .map(Duration::from_nanos)?;
+    Some(SessionActivity {
+        // Same threshold GNOME uses for its idle hint by default
+        is_idle:   Some(idle_time > Duration::from_secs(300)),
+        idle_time: Some(idle_time),
+        is_locked: None,
+    })
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub const fn session_activity() -> Option<SessionActivity> {
+    None
+}
+
+// loginctl prints one "Property=value" pair per line. IdleSinceHint is a
+// realtime timestamp in microseconds and 0 if the session isn't idle.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_show_session(properties: &str, now: SystemTime) -> SessionActivity {
+    let property = |name: &str| properties.lines().find_map(|line| line.strip_prefix(name)?.strip_prefix('='));
+    let is_idle = property("IdleHint").map(|idle| idle == "yes");
+    SessionActivity {
+        is_idle,
+        idle_time: property("IdleSinceHint")
+            .and_then(|since| since.parse::<u64>().ok())
+            .filter(|since| *since != 0 && is_idle == Some(true))
+            .and_then(|since| now.duration_since(UNIX_EPOCH + Duration::from_micros(since)).ok()),
+        is_locked: property("LockedHint").map(|locked| locked == "yes"),
+    }
+}
@@ -0,0 +1,277 @@
+// S.M.A.R.T. data tells a drive that's about to fail apart from one that's
+// merely full. Reading it takes ioctls no safe crate wraps, so this module
+// goes through `smartctl` from smartmontools, which exists for Linux, macOS
+// and Windows alike:
+//
+// - `smartctl --scan` lists the drives along with the device type to use
+// - `smartctl -H -A -i` prints the health assessment and the attributes,
+//   as an attribute table for SATA drives and as a list for NVMe drives
+//
+// Opening a drive needs root (or Administrator) almost everywhere. On
+// Linux the root helper is asked instead if it's running. A drive that
+// still can't be opened is listed, with the reason in `error`, so
+// frontends can tell the user what to do instead of showing nothing.
+
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SmartHealth {
+    Passed,
+    Failed,
+    Unknown,
+}
+
+impl std::fmt::Display for SmartHealth {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Passed => "Passed",
+            Self::Failed => "Failed",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+/// A row of the attribute table of a SATA drive
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SmartAttribute {
+    pub id:        u8,
+    pub name:      String,
+    /// Normalized by the vendor, usually from 100 or 200 down to 1
+    pub value:     u8,
+    /// The attribute counts as failing once `value` drops to this
+    pub threshold: u8,
+    pub raw:       String,
+}
+
+impl SmartAttribute {
+    #[must_use]
+    pub const fn is_failing(&self) -> bool {
+        self.threshold > 0 && self.value <= self.threshold
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DiskSmartInfo {
+    /// e.g. /dev/sda or /dev/nvme0
+    pub device:              String,
+    pub model:               Option<String>,
+    pub health:              SmartHealth,
+    pub reallocated_sectors: Option<u64>,
+    pub power_on_hours:      Option<u64>,
+    /// How much of its rated endurance an SSD has used up, in percent. Can
+    /// go past 100
+    pub wear_level:          Option<u8>,
+    /// Degrees Celsius
+    pub temperature:         Option<f32>,
+    /// Empty for NVMe drives, which report a fixed set of values instead
+    pub attributes:          Vec<SmartAttribute>,
+    /// Why the drive couldn't be read, most often missing privileges
+    pub error:               Option<String>,
+}
+
+const NEEDS_PRIVILEGES: &str = "Reading S.M.A.R.T. data needs elevated privileges";
+
+/// None if smartctl isn't installed
+pub fn smart_information() -> Option<Vec<DiskSmartInfo>> {
+    Some(
+        scan()?
+            .into_iter()
+            .map(|(device, device_type)| match smartctl(&device, &device_type) {
+                Ok(output) => {
+                    let info = parse_smartctl(&device, &output);
+                    if info.error.as_deref() == Some(NEEDS_PRIVILEGES)
+                        && let Some(output) = crate::helper::smartctl(&device, &device_type)
+                    {
+                        parse_smartctl(&device, &output)
+                    } else {
+                        info
+                    }
+                }
+                Err(error) => DiskSmartInfo {
+                    error: Some(format!("smartctl couldn't be run: {error}")),
+                    ..unreadable(&device)
+                },
+            })
+            .collect(),
+    )
+}
+
+/// What smartctl prints for `device`, but only if `smartctl --scan` lists
+/// it with `device_type`. The root helper runs it for whoever connects, so
+/// it shouldn't open anything else
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn scanned_drive(device: &str, device_type: &str) -> Option<String> {
+    let scanned = scan()?.iter().any(|(scanned_device, scanned_type)| scanned_device == device && scanned_type == device_type);
+    scanned.then(|| smartctl(device, device_type).ok()).flatten()
+}
+
+fn scan() -> Option<Vec<(String, String)>> {
+    let output = Command::new("smartctl").arg("--scan").output().ok()?;
+    Some(parse_scan(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn smartctl(device: &str, device_type: &str) -> std::io::Result<String> {
+    // -n standby leaves sleeping hard drives alone instead of spinning them
+    // up every refresh
+    let output = Command::new("smartctl").args(["-H", "-A", "-i", "-n", "standby", "-d", device_type, device]).output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn unreadable(device: &str) -> DiskSmartInfo {
+    DiskSmartInfo {
+        device:              device.to_string(),
+        model:               None,
+        health:              SmartHealth::Unknown,
+        reallocated_sectors: None,
+        power_on_hours:      None,
+        wear_level:          None,
+        temperature:         None,
+        attributes:          Vec::new(),
+        error:               None,
+    }
+}
+
+// /dev/sda -d sat # /dev/sda [SAT], ATA device
+// /dev/nvme0 -d nvme # /dev/nvme0, NVMe device
+fn parse_scan(scan: &str) -> Vec<(String, String)> {
+    scan.lines()
+        .filter_map(|line| {
+            let mut words = line.split('#').next()?.split_whitespace();
+            let device = words.next()?;
+            let device_type = words.skip_while(|word| *word != "-d").nth(1).unwrap_or("auto");
+            Some((device.to_string(), device_type.to_string()))
+        })
+        .collect()
+}
+
+/// Counts like "1,234" or "3%" in the NVMe list, or "35 (Min/Max 20/45)"
+/// and "17543h+05m+12s" in the raw values of the attribute table
+fn parse_count(value: &str) -> Option<u64> {
+    value
+        .trim()
+        .chars()
+        .take_while(|chr| chr.is_ascii_digit() || matches!(chr, ',' | '.' | '\''))
+        .filter(char::is_ascii_digit)
+        .collect::<String>()
+        .parse()
+        .ok()
+}
+
+// Device Model:     Samsung SSD 860 EVO 500GB
+// SMART overall-health self-assessment test result: PASSED
+// ID# ATTRIBUTE_NAME          FLAG     VALUE WORST THRESH TYPE      UPDATED  WHEN_FAILED RAW_VALUE
+//   5 Reallocated_Sector_Ct   0x0033   100   100   010    Pre-fail  Always       -       0
+//
+// or for NVMe drives
+//
+// Model Number:                       Samsung SSD 970 EVO Plus 1TB
+// SMART overall-health self-assessment test result: PASSED
+// Temperature:                        35 Celsius
+// Percentage Used:                    3%
+// Power On Hours:                     1,234
+fn parse_smartctl(device: &str, output: &str) -> DiskSmartInfo {
+    let mut info = unreadable(device);
+    for line in output.lines() {
+        let fields = line.split_whitespace().collect::<Vec<&str>>();
+        if let [id, name, _flag, value, _worst, threshold, _type, _updated, _when_failed, raw @ ..] = fields.as_slice()
+            && let (Ok(id), Ok(value), Ok(threshold)) = (id.parse(), value.parse(), threshold.parse())
+        {
+            info.attributes.push(SmartAttribute {
+                id,
+                name: (*name).to_string(),
+                value,
+                threshold,
+                raw: raw.join(" "),
+            });
+        } else if let Some((key, value)) = line.split_once(':') {
+            let value = value.trim();
+            match key.trim() {
+                "Device Model" | "Model Number" | "Product" => info.model = Some(value.to_string()),
+                "SMART overall-health self-assessment test result" | "SMART Health Status" => {
+                    info.health = if value == "PASSED" || value == "OK" { SmartHealth::Passed } else { SmartHealth::Failed };
+                }
+                "Percentage Used" => info.wear_level = parse_count(value).and_then(|used| u8::try_from(used).ok()),
+                "Power On Hours" => info.power_on_hours = parse_count(value),
+                #[allow(clippy::cast_precision_loss)]
+                "Temperature" => info.temperature = parse_count(value).map(|celsius| celsius as f32),
+                _ => {}
+            }
+        }
+    }
+    let attribute = |ids: &[u8]| info.attributes.iter().find(|attribute| ids.contains(&attribute.id));
+    info.reallocated_sectors = info.reallocated_sectors.or_else(|| parse_count(&attribute(&[5])?.raw));
+    info.power_on_hours = info.power_on_hours.or_else(|| parse_count(&attribute(&[9])?.raw));
+    // Wear_Leveling_Count, SSD_Life_Left and Media_Wearout_Indicator count
+    // down the endurance that's left
+    info.wear_level = info.wear_level.or_else(|| Some(100_u8.saturating_sub(attribute(&[177, 231, 233])?.value)));
+    // Temperature_Celsius, or Airflow_Temperature_Cel on drives without it
+    #[allow(clippy::cast_precision_loss)]
+    let temperature = attribute(&[194]).or_else(|| attribute(&[190])).and_then(|attribute| parse_count(&attribute.raw)).map(|celsius| celsius as f32);
+    info.temperature = info.temperature.or(temperature);
+
+    if info.health == SmartHealth::Unknown && info.attributes.is_empty() {
+        info.error = Some(if output.contains("Permission denied") || output.contains("Operation not permitted") || output.contains("Access is denied") {
+            NEEDS_PRIVILEGES.to_string()
+        } else if output.contains("STANDBY") {
+            "In standby".to_string()
+        } else {
+            output.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or("No S.M.A.R.T. data").trim().to_string()
+        });
+    }
+    info
+}
+
+#[test]
+fn parse_smart() {
+    assert_eq!(parse_scan("/dev/sda -d sat # /dev/sda [SAT], ATA device\n/dev/nvme0 -d nvme # /dev/nvme0, NVMe device\n"), vec![
+        ("/dev/sda".to_string(), "sat".to_string()),
+        ("/dev/nvme0".to_string(), "nvme".to_string())
+    ]);
+
+    let sata = parse_smartctl(
+        "/dev/sda",
+        "=== START OF INFORMATION SECTION ===
+Device Model:     Samsung SSD 860 EVO 500GB
+
+=== START OF READ SMART DATA SECTION ===
+SMART overall-health self-assessment test result: PASSED
+
+ID# ATTRIBUTE_NAME          FLAG     VALUE WORST THRESH TYPE      UPDATED  WHEN_FAILED RAW_VALUE
+  5 Reallocated_Sector_Ct   0x0033   100   100   010    Pre-fail  Always       -       2
+  9 Power_On_Hours          0x0032   096   096   000    Old_age   Always       -       17543h+05m+12s
+177 Wear_Leveling_Count     0x0013   093   093   000    Pre-fail  Always       -       112
+194 Temperature_Celsius     0x0022   065   052   000    Old_age   Always       -       35 (Min/Max 20/48)
+",
+    );
+    assert_eq!(sata.model.as_deref(), Some("Samsung SSD 860 EVO 500GB"));
+    assert_eq!(sata.health, SmartHealth::Passed);
+    assert_eq!(sata.reallocated_sectors, Some(2));
+    assert_eq!(sata.power_on_hours, Some(17_543));
+    assert_eq!(sata.wear_level, Some(7));
+    assert_eq!(sata.temperature, Some(35.0));
+    assert_eq!(sata.attributes.len(), 4);
+    assert_eq!(sata.attributes[3].raw, "35 (Min/Max 20/48)");
+    assert_eq!(sata.error, None);
+
+    let nvme = parse_smartctl(
+        "/dev/nvme0",
+        "Model Number:                       Samsung SSD 970 EVO Plus 1TB
+SMART overall-health self-assessment test result: FAILED!
+Temperature:                        35 Celsius
+Percentage Used:                    3%
+Power On Hours:                     1,234
+",
+    );
+    assert_eq!(nvme.health, SmartHealth::Failed);
+    assert_eq!(nvme.wear_level, Some(3));
+    assert_eq!(nvme.temperature, Some(35.0));
+    assert_eq!(nvme.power_on_hours, Some(1234));
+    assert_eq!(nvme.reallocated_sectors, None);
+
+    let denied = parse_smartctl("/dev/sda", "Smartctl open device: /dev/sda failed: Permission denied\n");
+    assert_eq!(denied.health, SmartHealth::Unknown);
+    assert!(denied.error.is_some());
+}
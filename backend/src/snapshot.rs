@@ -0,0 +1,365 @@
+// A snapshot records the parts of a system that tend to change without
+// anybody noticing: what gets started automatically, which ports are open,
+// how full the disks are and which packages are installed. Comparing a
+// saved baseline to the current state turns that into a change report.
+//
+// Snapshots are saved as plain text with one section per category, e.g.
+//
+// crossinfo snapshot
+// taken_at 1696932000
+// [startup_items]
+// sshd.service
+// [listening_sockets]
+// tcp 0.0.0.0 22
+// [disk_usage]
+// / 51234567890
+// [packages]
+// bash 5.2.15-2
+//
+// A category that couldn't be read is left out completely, so it isn't
+// mistaken for an empty one.
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    net::{IpAddr, SocketAddr},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ListeningSocket {
+    /// "tcp" or "udp"
+    pub protocol: String,
+    pub address:  IpAddr,
+    pub port:     u16,
+}
+
+impl std::fmt::Display for ListeningSocket {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{} {}", self.protocol, std::net::SocketAddr::new(self.address, self.port))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Snapshot {
+    pub taken_at:          SystemTime,
+    /// Enabled services and autostart entries
+    pub startup_items:     Option<BTreeSet<String>>,
+    pub listening_sockets: Option<BTreeSet<ListeningSocket>>,
+    /// Used bytes per mount point
+    pub disk_usage:        BTreeMap<String, u64>,
+    /// Installed packages and their version
+    pub packages:          Option<BTreeMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SnapshotDiff {
+    pub new_startup_items:        Vec<String>,
+    pub removed_startup_items:    Vec<String>,
+    pub new_listening_sockets:    Vec<ListeningSocket>,
+    pub closed_listening_sockets: Vec<ListeningSocket>,
+    /// Mount points whose usage changed, with the difference in bytes
+    pub disk_growth:              Vec<(String, i64)>,
+    /// Name and version
+    pub new_packages:             Vec<(String, String)>,
+    pub removed_packages:         Vec<String>,
+    /// Name, old version and new version
+    pub updated_packages:         Vec<(String, String, String)>,
+}
+
+impl SnapshotDiff {
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.new_startup_items.is_empty()
+            && self.removed_startup_items.is_empty()
+            && self.new_listening_sockets.is_empty()
+            && self.closed_listening_sockets.is_empty()
+            && self.disk_growth.is_empty()
+            && self.new_packages.is_empty()
+            && self.removed_packages.is_empty()
+            && self.updated_packages.is_empty()
+    }
+}
+
+fn added_and_removed<T: Ord + Clone>(old: Option<&BTreeSet<T>>, new: Option<&BTreeSet<T>>) -> (Vec<T>, Vec<T>) {
+    match (old, new) {
+        (Some(old), Some(new)) => (new.difference(old).cloned().collect(), old.difference(new).cloned().collect()),
+        _ => (Vec::new(), Vec::new()),
+    }
+}
+
+impl Snapshot {
+    /// What changed between `self` and the newer snapshot. Categories that
+    /// are missing from either snapshot are skipped
+    #[must_use]
+    pub fn diff(&self, newer: &Self) -> SnapshotDiff {
+        let (new_startup_items, removed_startup_items) = added_and_removed(self.startup_items.as_ref(), newer.startup_items.as_ref());
+        let (new_listening_sockets, closed_listening_sockets) = added_and_removed(self.listening_sockets.as_ref(), newer.listening_sockets.as_ref());
+        let mut diff = SnapshotDiff {
+            new_startup_items,
+            removed_startup_items,
+            new_listening_sockets,
+            closed_listening_sockets,
+            disk_growth: self
+                .disk_usage
+                .iter()
+                .filter_map(|(mount_point, old_used)| {
+                    let new_used = newer.disk_usage.get(mount_point)?;
+                    let growth = i64::try_from(*new_used).ok()? - i64::try_from(*old_used).ok()?;
+                    (growth != 0).then(|| (mount_point.clone(), growth))
+                })
+                .collect(),
+            ..SnapshotDiff::default()
+        };
+        if let (Some(old_packages), Some(new_packages)) = (&self.packages, &newer.packages) {
+            for (name, version) in new_packages {
+                match old_packages.get(name) {
+                    None => diff.new_packages.push((name.clone(), version.clone())),
+                    Some(old_version) if old_version != version => diff.updated_packages.push((name.clone(), old_version.clone(), version.clone())),
+                    Some(_) => (),
+                }
+            }
+            diff.removed_packages = old_packages.keys().filter(|name| !new_packages.contains_key(*name)).cloned().collect();
+        }
+        diff
+    }
+
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![
+            "crossinfo snapshot".to_string(),
+            format!("taken_at {}", self.taken_at.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs()),
+        ];
+        if let Some(startup_items) = &self.startup_items {
+            lines.push("[startup_items]".to_string());
+            lines.extend(startup_items.iter().cloned());
+        }
+        if let Some(listening_sockets) = &self.listening_sockets {
+            lines.push("[listening_sockets]".to_string());
+            lines.extend(listening_sockets.iter().map(|socket| format!("{} {} {}", socket.protocol, socket.address, socket.port)));
+        }
+        lines.push("[disk_usage]".to_string());
+        // Mount points can contain spaces, the number can't
+        lines.extend(self.disk_usage.iter().map(|(mount_point, used)| format!("{mount_point} {used}")));
+        if let Some(packages) = &self.packages {
+            lines.push("[packages]".to_string());
+            lines.extend(packages.iter().map(|(name, version)| format!("{name} {version}")));
+        }
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    /// Reads a snapshot written by `to_text`. Returns `None` if it isn't
+    /// one
+    #[must_use]
+    pub fn from_text(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+        if lines.next()? != "crossinfo snapshot" {
+            return None;
+        }
+        let mut snapshot = Self {
+            taken_at:          UNIX_EPOCH + Duration::from_secs(lines.next()?.strip_prefix("taken_at ")?.parse().ok()?),
+            startup_items:     None,
+            listening_sockets: None,
+            disk_usage:        BTreeMap::new(),
+            packages:          None,
+        };
+        let mut section = "";
+        for line in lines {
+            if let Some(name) = line.strip_prefix('[').and_then(|line| line.strip_suffix(']')) {
+                section = name;
+                match section {
+                    "startup_items" => snapshot.startup_items = Some(BTreeSet::new()),
+                    "listening_sockets" => snapshot.listening_sockets = Some(BTreeSet::new()),
+                    "packages" => snapshot.packages = Some(BTreeMap::new()),
+                    _ => (),
+                }
+                continue;
+            }
+            match section {
+                "startup_items" => {
+                    snapshot.startup_items.as_mut()?.insert(line.to_string());
+                }
+                "listening_sockets" => {
+                    let mut fields = line.split(' ');
+                    snapshot.listening_sockets.as_mut()?.insert(ListeningSocket {
+                        protocol: fields.next()?.to_string(),
+                        address:  fields.next()?.parse().ok()?,
+                        port:     fields.next()?.parse().ok()?,
+                    });
+                }
+                "disk_usage" => {
+                    let (mount_point, used) = line.rsplit_once(' ')?;
+                    snapshot.disk_usage.insert(mount_point.to_string(), used.parse().ok()?);
+                }
+                "packages" => {
+                    let (name, version) = line.split_once(' ')?;
+                    snapshot.packages.as_mut()?.insert(name.to_string(), version.to_string());
+                }
+                _ => return None,
+            }
+        }
+        Some(snapshot)
+    }
+}
+
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    std::process::Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn file_names(directory: impl AsRef<std::path::Path>) -> impl Iterator<Item = String> {
+    std::fs::read_dir(directory)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.file_name().to_string_lossy().to_string())
+}
+
+#[cfg(target_os = "linux")]
+pub fn startup_items() -> Option<BTreeSet<String>> {
+    let mut items = command_output("systemctl", &["list-unit-files", "--state=enabled", "--no-legend", "--no-pager"])?
+        .lines()
+        .filter_map(|line| line.split_whitespace().next().map(ToString::to_string))
+        .collect::<BTreeSet<String>>();
+    let user_autostart = std::env::var("HOME").map(|home| std::path::PathBuf::from(home).join(".config/autostart")).ok();
+    for directory in [Some(std::path::PathBuf::from("/etc/xdg/autostart")), user_autostart].into_iter().flatten() {
+        items.extend(file_names(directory).filter(|name| name.ends_with(".desktop")));
+    }
+    Some(items)
+}
+
+#[cfg(target_os = "macos")]
+pub fn startup_items() -> Option<BTreeSet<String>> {
+    let user_agents = std::env::var("HOME").map(|home| std::path::PathBuf::from(home).join("Library/LaunchAgents")).ok();
+    Some(
+        [
+            Some(std::path::PathBuf::from("/Library/LaunchAgents")),
+            Some(std::path::PathBuf::from("/Library/LaunchDaemons")),
+            user_agents,
+        ]
+        .into_iter()
+        .flatten()
+        .flat_map(file_names)
+        .filter(|name| name.ends_with(".plist"))
+        .collect(),
+    )
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub const fn startup_items() -> Option<BTreeSet<String>> {
+    None
+}
+
+#[cfg(target_os = "linux")]
+pub fn listening_sockets() -> Option<BTreeSet<ListeningSocket>> {
+    // A TCP socket in the LISTEN state is 0A, an unconnected UDP socket 07
+    let mut sockets = parse_proc_net(&std::fs::read_to_string("/proc/net/tcp").ok()?, "tcp", "0A")
+        .into_iter()
+        .collect::<BTreeSet<ListeningSocket>>();
+    // The IPv6 tables are missing if IPv6 is disabled
+    for (file, protocol, state) in [("tcp6", "tcp", "0A"), ("udp", "udp", "07"), ("udp6", "udp", "07")] {
+        if let Ok(table) = std::fs::read_to_string(format!("/proc/net/{file}")) {
+            sockets.extend(parse_proc_net(&table, protocol, state));
+        }
+    }
+    Some(sockets)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn listening_sockets() -> Option<BTreeSet<ListeningSocket>> {
+    None
+}
+
+// Every line after the header looks like
+//
+//   0: 0100007F:0277 00000000:0000 0A ...
+//
+// with the local address, the remote address and the state. Addresses are
+// the raw in-memory representation printed as 32 bit words in hex, hence
+// the native endianness.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_proc_net(table: &str, protocol: &str, state: &str) -> Vec<ListeningSocket> {
+    table
+        .lines()
+        .skip(1)
+        .filter_map(|line| {
+            let fields = line.split_whitespace().collect::<Vec<&str>>();
+            if fields.get(3) != Some(&state) {
+                return None;
+            }
+            let address = parse_proc_net_address(fields.get(1)?)?;
+            Some(ListeningSocket {
+                protocol: protocol.to_string(),
+                address:  address.ip(),
+                port:     address.port(),
+            })
+        })
+        .collect()
+}
+
+/// An address of the socket tables in /proc/net, like 0100007F:0277
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+pub fn parse_proc_net_address(field: &str) -> Option<SocketAddr> {
+    let (address, port) = field.split_once(':')?;
+    let words = (0..address.len() / 8)
+        .map(|index| u32::from_str_radix(address.get(index * 8..(index + 1) * 8)?, 16).ok())
+        .collect::<Option<Vec<u32>>>()?;
+    let bytes = words.iter().flat_map(|word| word.to_ne_bytes()).collect::<Vec<u8>>();
+    let address = match bytes.len() {
+        4 => IpAddr::from(<[u8; 4]>::try_from(bytes).ok()?),
+        16 => IpAddr::from(<[u8; 16]>::try_from(bytes).ok()?),
+        _ => return None,
+    };
+    Some(SocketAddr::new(address, u16::from_str_radix(port, 16).ok()?))
+}
+
+/// Asks whichever package manager is installed. The first one that answers
+/// wins
+pub fn packages() -> Option<BTreeMap<String, String>> {
+    let parse = |output: String| {
+        output
+            .lines()
+            .filter_map(|line| line.split_once(' ').map(|(name, version)| (name.to_string(), version.trim().to_string())))
+            .collect::<BTreeMap<String, String>>()
+    };
+    command_output("dpkg-query", &["--show", "--showformat=${Package} ${Version}\\n"])
+        .or_else(|| command_output("rpm", &["--query", "--all", "--queryformat=%{NAME} %{VERSION}-%{RELEASE}\\n"]))
+        .or_else(|| command_output("pacman", &["--query"]))
+        .or_else(|| command_output("brew", &["list", "--versions"]))
+        .map(parse)
+}
+
+#[test]
+fn diff_saved_snapshot() {
+    let baseline = Snapshot::from_text(
+        "crossinfo snapshot
+taken_at 1696932000
+[startup_items]
+sshd.service
+cups.service
+[listening_sockets]
+tcp 0.0.0.0 22
+[disk_usage]
+/home/user/My Files 1000
+[packages]
+bash 5.2.15-2
+vim 9.0
+",
+    )
+    .expect("The snapshot should be parsed");
+    let mut current = Snapshot::from_text(&baseline.to_text()).expect("A written snapshot should be read back");
+    current.startup_items.as_mut().expect("Startup items were saved").remove("cups.service");
+    current.disk_usage.insert("/home/user/My Files".to_string(), 500);
+    current.packages.as_mut().expect("Packages were saved").insert("bash".to_string(), "5.2.21-1".to_string());
+    current.listening_sockets = None;
+
+    let diff = baseline.diff(&current);
+    assert_eq!(diff.removed_startup_items, ["cups.service"]);
+    assert_eq!(diff.disk_growth, [("/home/user/My Files".to_string(), -500)]);
+    assert_eq!(diff.updated_packages, [("bash".to_string(), "5.2.15-2".to_string(), "5.2.21-1".to_string())]);
+    assert!(diff.new_listening_sockets.is_empty() && diff.closed_listening_sockets.is_empty());
+}
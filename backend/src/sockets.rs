@@ -0,0 +1,124 @@
+// Which remote hosts the traffic goes to. The kernel keeps byte counters
+// for every TCP socket, which `ss` from iproute2 prints without needing
+// root, so no packets have to be captured. UDP sockets have no such
+// counters and aren't included. The counters only ever grow, so the
+// traffic of the last interval is the difference to the previous sample.
+
+use std::{
+    collections::HashMap,
+    net::{IpAddr, SocketAddr},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TopTalker {
+    /// Compare with `Network::ips` to find the interface
+    pub local_address:  IpAddr,
+    pub remote_address: IpAddr,
+    /// Bytes since the previous call
+    pub received:       u64,
+    /// Bytes since the previous call
+    pub transmitted:    u64,
+}
+
+/// Received and transmitted bytes of every TCP connection
+pub type SocketCounters = HashMap<(SocketAddr, SocketAddr), (u64, u64)>;
+
+#[cfg(target_os = "linux")]
+pub fn socket_counters() -> Option<SocketCounters> {
+    let output = std::process::Command::new("ss")
+        .args(["--tcp", "--info", "--numeric", "--no-header"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+    Some(parse_ss(&String::from_utf8_lossy(&output.stdout)))
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn socket_counters() -> Option<SocketCounters> {
+    None
+}
+
+/// What every pair of hosts transferred between the two samples, busiest
+/// first. Connections that are new in `current` count from zero
+pub fn top_talkers(previous: &SocketCounters, current: &SocketCounters) -> Vec<TopTalker> {
+    let mut hosts = HashMap::<(IpAddr, IpAddr), (u64, u64)>::new();
+    for ((local, remote), (received, transmitted)) in current {
+        let (last_received, last_transmitted) = previous.get(&(*local, *remote)).copied().unwrap_or_default();
+        let usage = hosts.entry((local.ip(), remote.ip())).or_default();
+        usage.0 += received.saturating_sub(last_received);
+        usage.1 += transmitted.saturating_sub(last_transmitted);
+    }
+    let mut talkers = hosts
+        .into_iter()
+        .filter(|(_, (received, transmitted))| received + transmitted > 0)
+        .map(|((local_address, remote_address), (received, transmitted))| TopTalker {
+            local_address,
+            remote_address,
+            received,
+            transmitted,
+        })
+        .collect::<Vec<TopTalker>>();
+    talkers.sort_by_key(|talker| std::cmp::Reverse(talker.received + talker.transmitted));
+    talkers
+}
+
+// Every socket takes two lines, the addresses and then the indented TCP
+// info:
+//
+// ESTAB 0      0      192.168.1.20:59452 140.82.121.4:443
+//      cubic rto:204 ... bytes_acked:8147355 bytes_received:1001598 ...
+//
+// IPv6 addresses are in brackets, link-local ones with the interface
+// appended like [fe80::1]%eth0:22.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_ss(output: &str) -> SocketCounters {
+    let parse_address = |address: &str| -> Option<SocketAddr> {
+        let (ip, port) = address.rsplit_once(':')?;
+        let ip = ip.split('%').next()?.trim_start_matches('[').trim_end_matches(']');
+        Some(SocketAddr::new(ip.parse::<IpAddr>().ok()?.to_canonical(), port.parse().ok()?))
+    };
+    let mut counters = SocketCounters::new();
+    let mut addresses = None;
+    for line in output.lines() {
+        if !line.starts_with(char::is_whitespace) {
+            let mut columns = line.split_whitespace().skip(3);
+            addresses = columns.next().and_then(parse_address).zip(columns.next().and_then(parse_address));
+        } else if let Some(addresses) = addresses.take() {
+            let counter = |name: &str| {
+                line.split_whitespace()
+                    .find_map(|field| field.strip_prefix(name)?.strip_prefix(':')?.parse::<u64>().ok())
+                    .unwrap_or_default()
+            };
+            counters.insert(addresses, (counter("bytes_received"), counter("bytes_acked")));
+        }
+    }
+    counters
+}
+
+#[test]
+fn find_top_talkers() {
+    let previous = parse_ss(
+        "ESTAB 0      0      192.168.1.20:59452 140.82.121.4:443
+	 cubic wscale:7,7 rto:204 bytes_sent:9000 bytes_acked:8000 bytes_received:1000 segs_out:524
+",
+    );
+    let current = parse_ss(
+        "ESTAB 0      0      192.168.1.20:59452 140.82.121.4:443
+	 cubic wscale:7,7 rto:204 bytes_sent:9000 bytes_acked:9000 bytes_received:5000 segs_out:530
+ESTAB 0      0      [::ffff:192.168.1.20]:41000 [::ffff:140.82.121.4]:443
+	 cubic wscale:7,7 rto:204 bytes_acked:100 bytes_received:200 segs_out:3
+ESTAB 0      0      [fe80::1]%eth0:22 [fe80::2]%eth0:51000
+	 cubic wscale:7,7 rto:204 bytes_acked:50 segs_out:3
+",
+    );
+    let talkers = top_talkers(&previous, &current);
+    assert_eq!(talkers.len(), 2);
+    assert_eq!(talkers[0], TopTalker {
+        local_address:  [192, 168, 1, 20].into(),
+        remote_address: [140, 82, 121, 4].into(),
+        received:       4200,
+        transmitted:    1100,
+    });
+    assert_eq!(talkers[1].remote_address, "fe80::2".parse::<IpAddr>().expect("The address is valid"));
+    assert_eq!(talkers[1].transmitted, 50);
+}
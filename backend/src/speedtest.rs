@@ -0,0 +1,258 @@
+// A speedtest against the servers of speedtest.net, like speedtest-cli
+// does it: the closest servers come from the static server list, the one
+// answering fastest is used. The download fetches test images of growing
+// size and the upload posts blocks of data, each for at most
+// TEST_DURATION. A request that stalls ends its phase once that is up,
+// with what arrived until then. Needs the `network-extra` feature for
+// reqwest.
+
+use std::time::Duration;
+#[cfg(feature = "network-extra")]
+use std::time::Instant;
+
+use crate::Error;
+
+#[cfg(feature = "network-extra")]
+const SERVER_LIST: &str = "https://www.speedtest.net/speedtest-servers-static.php";
+// The list is sorted by distance, trying more than a few only takes longer
+#[cfg(feature = "network-extra")]
+const CANDIDATES: u32 = 5;
+#[cfg(feature = "network-extra")]
+const LATENCY_SAMPLES: u32 = 5;
+#[cfg(feature = "network-extra")]
+const TEST_DURATION: Duration = Duration::from_secs(10);
+// For connecting and for the requests before the test
+#[cfg(feature = "network-extra")]
+const TIMEOUT: Duration = Duration::from_secs(5);
+// The test images are random{size}x{size}.jpg, the largest ones are about
+// 30 MB
+#[cfg(feature = "network-extra")]
+const DOWNLOAD_SIZES: [u32; 6] = [750, 1500, 2000, 2500, 3500, 4000];
+#[cfg(feature = "network-extra")]
+const UPLOAD_BLOCK_SIZE: usize = 2 * 1024 * 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SpeedtestServer {
+    /// The city the server is in
+    pub name:    String,
+    pub country: String,
+    /// The company running the server
+    pub sponsor: String,
+    /// Host and port
+    pub host:    String,
+    // The upload URL, which the others are next to
+    url:         String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Speedtest {
+    pub server:   SpeedtestServer,
+    /// Average round trip time of a small request
+    pub latency:  Duration,
+    /// Bits per second
+    pub download: f64,
+    /// Bits per second
+    pub upload:   f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SpeedtestPhase {
+    /// Finding the server that answers fastest and measuring its latency
+    Latency,
+    Download,
+    Upload,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeedtestProgress {
+    pub phase:           SpeedtestPhase,
+    /// Between 0 and 1, of the current phase
+    pub progress:        f32,
+    /// The throughput so far, `None` while measuring the latency
+    pub bits_per_second: Option<f64>,
+}
+
+#[cfg(feature = "network-extra")]
+#[allow(clippy::cast_precision_loss)]
+fn bits_per_second(bytes: usize, start: Instant) -> f64 {
+    bytes as f64 * 8.0 / start.elapsed().as_secs_f64().max(f64::EPSILON)
+}
+
+#[cfg(feature = "network-extra")]
+fn elapsed_fraction(start: Instant) -> f32 {
+    (start.elapsed().as_secs_f32() / TEST_DURATION.as_secs_f32()).min(1.0)
+}
+
+/// Of the phase that started at `start`
+#[cfg(feature = "network-extra")]
+fn remaining(start: Instant) -> Duration {
+    TEST_DURATION.saturating_sub(start.elapsed())
+}
+
+#[cfg(feature = "network-extra")]
+async fn latency(client: &reqwest::Client, server: &SpeedtestServer, sample: u32) -> Result<Duration, Error> {
+    // The query keeps caches from answering
+    let start = Instant::now();
+    client
+        .get(format!("{}/latency.txt?x={sample}", server.base_url()))
+        .timeout(TIMEOUT)
+        .send()
+        .await?
+        .error_for_status()?
+        .bytes()
+        .await?;
+    Ok(start.elapsed())
+}
+
+/// `progress` is called whenever data arrives or was sent
+///
+/// # Errors
+///
+/// `Error::NotFound` if no server could be reached, `Error::Network` if a
+/// request failed during the test
+#[cfg(feature = "network-extra")]
+pub async fn speedtest(progress: impl Fn(SpeedtestProgress)) -> Result<Speedtest, Error> {
+    let client = reqwest::Client::builder().user_agent("crossinfo").connect_timeout(TIMEOUT).build()?;
+
+    let servers = parse_servers(&client.get(SERVER_LIST).timeout(TIMEOUT).send().await?.error_for_status()?.text().await?);
+    let mut fastest = None;
+    for (candidate, server) in (1..=CANDIDATES).zip(servers) {
+        if let Ok(latency) = latency(&client, &server, 0).await
+            && fastest.as_ref().is_none_or(|(_, fastest)| latency < *fastest)
+        {
+            fastest = Some((server, latency));
+        }
+        #[allow(clippy::cast_precision_loss)]
+        progress(SpeedtestProgress {
+            phase:           SpeedtestPhase::Latency,
+            progress:        candidate as f32 / (CANDIDATES + LATENCY_SAMPLES) as f32,
+            bits_per_second: None,
+        });
+    }
+    let (server, _) = fastest.ok_or(Error::NotFound("reachable speedtest server"))?;
+
+    let mut total_latency = Duration::ZERO;
+    for sample in 1..=LATENCY_SAMPLES {
+        total_latency += latency(&client, &server, sample).await?;
+        #[allow(clippy::cast_precision_loss)]
+        progress(SpeedtestProgress {
+            phase:           SpeedtestPhase::Latency,
+            progress:        (CANDIDATES + sample) as f32 / (CANDIDATES + LATENCY_SAMPLES) as f32,
+            bits_per_second: None,
+        });
+    }
+
+    let start = Instant::now();
+    let mut downloaded = 0;
+    'download: for size in DOWNLOAD_SIZES {
+        let request = client.get(format!("{}/random{size}x{size}.jpg", server.base_url())).send();
+        let Ok(response) = tokio::time::timeout(remaining(start), request).await else {
+            break;
+        };
+        let mut response = response?.error_for_status()?;
+        loop {
+            let Ok(chunk) = tokio::time::timeout(remaining(start), response.chunk()).await else {
+                break 'download;
+            };
+            let Some(chunk) = chunk? else {
+                break;
+            };
+            downloaded += chunk.len();
+            progress(SpeedtestProgress {
+                phase:           SpeedtestPhase::Download,
+                progress:        elapsed_fraction(start),
+                bits_per_second: Some(bits_per_second(downloaded, start)),
+            });
+            if start.elapsed() > TEST_DURATION {
+                break 'download;
+            }
+        }
+    }
+    let download = bits_per_second(downloaded, start);
+
+    // speedtest.net's upload.php expects a form field
+    let block = format!("content1={}", "0".repeat(UPLOAD_BLOCK_SIZE)).into_bytes();
+    let start = Instant::now();
+    let mut uploaded = 0;
+    while start.elapsed() < TEST_DURATION {
+        let Ok(response) = tokio::time::timeout(remaining(start), client.post(&server.url).body(block.clone()).send()).await else {
+            break;
+        };
+        response?.error_for_status()?;
+        uploaded += block.len();
+        progress(SpeedtestProgress {
+            phase:           SpeedtestPhase::Upload,
+            progress:        elapsed_fraction(start),
+            bits_per_second: Some(bits_per_second(uploaded, start)),
+        });
+    }
+    let upload = bits_per_second(uploaded, start);
+
+    Ok(Speedtest {
+        server,
+        latency: total_latency / LATENCY_SAMPLES,
+        download,
+        upload,
+    })
+}
+
+#[cfg(not(feature = "network-extra"))]
+#[allow(clippy::unused_async)]
+pub async fn speedtest(_progress: impl Fn(SpeedtestProgress)) -> Result<Speedtest, Error> {
+    Err(Error::Unsupported)
+}
+
+impl SpeedtestServer {
+    #[cfg_attr(not(feature = "network-extra"), allow(dead_code))]
+    fn base_url(&self) -> &str {
+        self.url.rsplit_once('/').map_or(&self.url, |(base, _)| base)
+    }
+}
+
+// One element per server, closest first:
+//
+// <server url="http://speedtest.example.com:8080/speedtest/upload.php"
+//  lat="47.3667" lon="8.5500" name="Zurich" country="Switzerland" cc="CH"
+//  sponsor="Example AG" id="1234" host="speedtest.example.com:8080"/>
+#[cfg_attr(not(feature = "network-extra"), allow(dead_code))]
+fn parse_servers(xml: &str) -> Vec<SpeedtestServer> {
+    let attribute = |element: &str, name: &str| {
+        let (_, value) = element.split_once(&format!(" {name}=\""))?;
+        let (value, _) = value.split_once('"')?;
+        Some(value.replace("&quot;", "\"").replace("&apos;", "'").replace("&lt;", "<").replace("&gt;", ">").replace("&amp;", "&"))
+    };
+    xml.split("<server ")
+        .skip(1)
+        .filter_map(|element| {
+            let element = format!(" {}", element.split_once('>')?.0);
+            Some(SpeedtestServer {
+                name:    attribute(&element, "name")?,
+                country: attribute(&element, "country")?,
+                sponsor: attribute(&element, "sponsor")?,
+                host:    attribute(&element, "host")?,
+                url:     attribute(&element, "url")?,
+            })
+        })
+        .collect()
+}
+
+#[test]
+fn parse_speedtest_servers() {
+    let servers = parse_servers(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<settings>
+<servers><server url="http://speedtest.example.com:8080/speedtest/upload.php" lat="47.3667" lon="8.5500" name="Zurich" country="Switzerland" cc="CH" sponsor="Example &amp; Sons AG" id="1234" host="speedtest.example.com:8080"/>
+<server url="http://other.example.net:8080/upload.php" lat="46.9480" lon="7.4474" name="Bern" country="Switzerland" cc="CH" sponsor="Other" id="5678"/>
+</servers>
+</settings>
+"#,
+    );
+    // The second one has no host
+    assert_eq!(servers.len(), 1);
+    assert_eq!(servers[0].name, "Zurich");
+    assert_eq!(servers[0].sponsor, "Example & Sons AG");
+    assert_eq!(servers[0].host, "speedtest.example.com:8080");
+    assert_eq!(servers[0].base_url(), "http://speedtest.example.com:8080/speedtest");
+}
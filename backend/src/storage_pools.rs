@@ -0,0 +1,418 @@
+// Storage pools are assembled from several block devices and show up in
+// `Disks` as a single mount point, which hides a degraded array
+// completely. This module looks at the pool layer directly: mdraid through
+// /proc/mdstat, btrfs through /sys/fs/btrfs and ZFS through the `zpool`
+// command line tool, as the kernel module doesn't expose member health
+// anywhere else. LVM volume groups come from `pvs` and `lvs`, which only
+// work as root because they read the physical volumes themselves.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoragePoolKind {
+    MdRaid,
+    Zfs,
+    Btrfs,
+    Lvm,
+}
+
+impl std::fmt::Display for StoragePoolKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::MdRaid => "mdraid",
+            Self::Zfs => "ZFS",
+            Self::Btrfs => "btrfs",
+            Self::Lvm => "LVM",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StoragePoolHealth {
+    Online,
+    Degraded,
+    Faulted,
+    Unknown,
+}
+
+impl std::fmt::Display for StoragePoolHealth {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::Online => "Online",
+            Self::Degraded => "Degraded",
+            Self::Faulted => "Faulted",
+            Self::Unknown => "unknown",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StoragePoolMember {
+    pub name:      String,
+    pub is_faulty: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct StoragePoolInfo {
+    pub name:     String,
+    pub kind:     StoragePoolKind,
+    /// RAID level or vdev layout, e.g. "raid1" or "raidz2"
+    pub level:    Option<String>,
+    pub health:   StoragePoolHealth,
+    pub members:  Vec<StoragePoolMember>,
+    /// Human readable scrub/check state as reported by the pool, e.g.
+    /// "idle" or "scrub in progress"
+    pub scrub:    Option<String>,
+    /// How far the rebuild, resync, resilver or scrub `scrub` describes
+    /// has got, between 0 and 1. None while nothing is running
+    pub progress: Option<f32>,
+}
+
+impl StoragePoolInfo {
+    #[must_use]
+    pub fn degraded_members(&self) -> Vec<&StoragePoolMember> {
+        self.members.iter().filter(|member| member.is_faulty).collect()
+    }
+}
+
+#[cfg(target_os = "linux")]
+pub fn storage_pools() -> Vec<StoragePoolInfo> {
+    let mut pools = std::fs::read_to_string("/proc/mdstat").map_or_else(|_| Vec::new(), |mdstat| parse_mdstat(&mdstat));
+    for pool in &mut pools {
+        pool.scrub = std::fs::read_to_string(format!("/sys/block/{}/md/sync_action", pool.name)).ok().map(|action| action.trim().to_string());
+    }
+    pools.extend(btrfs_pools());
+    pools.extend(lvm_pools());
+    pools.extend(zfs_pools());
+    pools
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn storage_pools() -> Vec<StoragePoolInfo> {
+    zfs_pools()
+}
+
+// The format of /proc/mdstat is roughly
+//
+// md0 : active raid1 sdb1[1] sda1[0](F)
+//       1953382464 blocks super 1.2 [2/1] [U_]
+//
+// where "(F)" marks a failed member and the "[U_]" block shows which slots
+// are up. An array with missing slots has no faulty member listed, so both
+// are taken into account for the health. While an array is rebuilt a line
+// like
+//
+//       [==>..................]  recovery = 12.6% (246720/1953382464) finish=127.6min speed=223488K/sec
+//
+// follows.
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_mdstat(mdstat: &str) -> Vec<StoragePoolInfo> {
+    let mut pools: Vec<StoragePoolInfo> = Vec::new();
+    for line in mdstat.lines() {
+        if let Some((name, description)) = line.split_once(" : ")
+            && name.starts_with("md")
+        {
+            let mut words = description.split_whitespace();
+            let is_active = words.next() == Some("active");
+            let mut level = None;
+            let mut members = Vec::new();
+            for word in words {
+                if let Some((device, flags)) = word.split_once('[') {
+                    members.push(StoragePoolMember {
+                        name:      device.to_string(),
+                        is_faulty: flags.contains("(F)"),
+                    });
+                } else if word != "(auto-read-only)" && word != "(read-only)" {
+                    level = Some(word.to_string());
+                }
+            }
+            pools.push(StoragePoolInfo {
+                name: name.trim().to_string(),
+                kind: StoragePoolKind::MdRaid,
+                level,
+                health: if is_active { StoragePoolHealth::Online } else { StoragePoolHealth::Faulted },
+                members,
+                scrub: None,
+                progress: None,
+            });
+        } else if let Some(pool) = pools.last_mut()
+            && let Some((_, percent)) = line.split_once(" = ")
+            && let Some(percent) = percent.split('%').next()
+            && let Ok(percent) = percent.trim().parse::<f32>()
+        {
+            pool.progress = Some(percent / 100.0);
+        } else if let Some(pool) = pools.last_mut()
+            && let Some(slots) = line.split_whitespace().last()
+            && slots.starts_with('[')
+            && slots.chars().all(|chr| matches!(chr, '[' | ']' | 'U' | '_'))
+            && pool.health == StoragePoolHealth::Online
+            && (slots.contains('_') || pool.members.iter().any(|member| member.is_faulty))
+        {
+            pool.health = StoragePoolHealth::Degraded;
+        }
+    }
+    pools
+}
+
+#[cfg(target_os = "linux")]
+fn btrfs_pools() -> Vec<StoragePoolInfo> {
+    let Ok(filesystems) = std::fs::read_dir("/sys/fs/btrfs") else {
+        return Vec::new();
+    };
+    filesystems
+        .flatten()
+        .filter_map(|filesystem| {
+            let path = filesystem.path();
+            let devices = std::fs::read_dir(path.join("devices"))
+                .ok()?
+                .flatten()
+                .map(|device| device.file_name().to_string_lossy().to_string())
+                .collect::<Vec<String>>();
+            // Single device filesystems aren't pools in any meaningful sense
+            if devices.len() < 2 {
+                return None;
+            }
+            // Only newer kernels have devinfo/<id>/missing, older ones can't
+            // tell us anything about missing members
+            let missing = std::fs::read_dir(path.join("devinfo")).map_or(0, |devinfo| {
+                devinfo
+                    .flatten()
+                    .filter(|device| std::fs::read_to_string(device.path().join("missing")).is_ok_and(|missing| missing.trim() == "1"))
+                    .count()
+            });
+            let label = std::fs::read_to_string(path.join("label")).map(|label| label.trim().to_string()).unwrap_or_default();
+            Some(StoragePoolInfo {
+                name:     if label.is_empty() { filesystem.file_name().to_string_lossy().to_string() } else { label },
+                kind:     StoragePoolKind::Btrfs,
+                level:    None,
+                health:   if missing == 0 { StoragePoolHealth::Online } else { StoragePoolHealth::Degraded },
+                members:  devices.into_iter().map(|name| StoragePoolMember { name, is_faulty: false }).collect(),
+                scrub:    None,
+                progress: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(target_os = "linux")]
+fn lvm_pools() -> Vec<StoragePoolInfo> {
+    let report = |command: &str, fields: &str| {
+        std::process::Command::new(command)
+            .args(["--noheadings", "--separator", ";", "-o", fields])
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .map(|output| String::from_utf8_lossy(&output.stdout).to_string())
+    };
+    let Some(physical_volumes) = report("pvs", "vg_name,pv_name,pv_attr,vg_attr") else {
+        return Vec::new();
+    };
+    parse_lvm(&physical_volumes, &report("lvs", "vg_name,segtype,copy_percent").unwrap_or_default())
+}
+
+// `pvs` prints a line per physical volume with its volume group, its
+// attributes and those of the volume group:
+//
+//   vg0;/dev/sda2;a--;wz--n-
+//   vg0;[unknown];a-m;wz-pn-
+//
+// The third attribute of a physical volume is m if it's missing, the
+// fourth of a volume group p if it's partial because of that. Physical
+// volumes outside of any volume group have no name there. `lvs` prints the
+// segment type of every logical volume and how far RAID and mirror
+// volumes are synced:
+//
+//   vg0;raid1;45.20
+//   vg0;linear;
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_lvm(physical_volumes: &str, logical_volumes: &str) -> Vec<StoragePoolInfo> {
+    let mut pools: Vec<StoragePoolInfo> = Vec::new();
+    for line in physical_volumes.lines() {
+        let [group, volume, volume_attributes, group_attributes] = line.trim().split(';').collect::<Vec<&str>>()[..] else {
+            continue;
+        };
+        if group.is_empty() {
+            continue;
+        }
+        let member = StoragePoolMember {
+            name:      volume.to_string(),
+            is_faulty: volume_attributes.chars().nth(2) == Some('m'),
+        };
+        let health = if group_attributes.chars().nth(3) == Some('p') { StoragePoolHealth::Degraded } else { StoragePoolHealth::Online };
+        if let Some(pool) = pools.iter_mut().find(|pool| pool.name == group) {
+            pool.members.push(member);
+        } else {
+            pools.push(StoragePoolInfo {
+                name: group.to_string(),
+                kind: StoragePoolKind::Lvm,
+                level: None,
+                health,
+                members: vec![member],
+                scrub: None,
+                progress: None,
+            });
+        }
+    }
+    for line in logical_volumes.lines() {
+        let [group, segment_type, synced] = line.trim().split(';').collect::<Vec<&str>>()[..] else {
+            continue;
+        };
+        let Some(pool) = pools.iter_mut().find(|pool| pool.name == group) else {
+            continue;
+        };
+        if segment_type.starts_with("raid") || segment_type == "mirror" {
+            pool.level.get_or_insert_with(|| segment_type.to_string());
+        }
+        // The volume that's furthest behind
+        if let Ok(synced) = synced.trim().parse::<f32>()
+            && synced < 100.0
+            && pool.progress.is_none_or(|progress| synced / 100.0 < progress)
+        {
+            pool.progress = Some(synced / 100.0);
+            pool.scrub = Some("resync in progress".to_string());
+        }
+    }
+    pools
+}
+
+fn zfs_pools() -> Vec<StoragePoolInfo> {
+    std::process::Command::new("zpool")
+        .arg("status")
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map_or_else(Vec::new, |output| parse_zpool_status(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn parse_zfs_health(state: &str) -> StoragePoolHealth {
+    match state {
+        "ONLINE" => StoragePoolHealth::Online,
+        "DEGRADED" => StoragePoolHealth::Degraded,
+        "FAULTED" | "UNAVAIL" | "REMOVED" | "OFFLINE" => StoragePoolHealth::Faulted,
+        _ => StoragePoolHealth::Unknown,
+    }
+}
+
+// `zpool status` prints a block per pool with "pool:", "state:" and
+// "scan:" headers followed by an indented config table. The first table
+// row is the pool itself, vdev rows (mirror-0, raidz2-0, ...) follow, then
+// the disks. A running resilver or scrub continues the scan header with
+// lines like
+//
+//     200G resilvered, 31.25% done, 01:23:45 to go
+fn parse_zpool_status(status: &str) -> Vec<StoragePoolInfo> {
+    let mut pools: Vec<StoragePoolInfo> = Vec::new();
+    let mut in_config = false;
+    for line in status.lines() {
+        let trimmed = line.trim();
+        if let Some(name) = trimmed.strip_prefix("pool:") {
+            in_config = false;
+            pools.push(StoragePoolInfo {
+                name:     name.trim().to_string(),
+                kind:     StoragePoolKind::Zfs,
+                level:    None,
+                health:   StoragePoolHealth::Unknown,
+                members:  Vec::new(),
+                scrub:    None,
+                progress: None,
+            });
+        } else if let Some(pool) = pools.last_mut() {
+            if let Some(state) = trimmed.strip_prefix("state:") {
+                pool.health = parse_zfs_health(state.trim());
+            } else if let Some(scan) = trimmed.strip_prefix("scan:") {
+                pool.scrub = Some(scan.trim().to_string());
+            } else if let Some(done) = trimmed.split(", ").find_map(|part| part.strip_suffix("% done"))
+                && let Ok(done) = done.trim().parse::<f32>()
+            {
+                pool.progress = Some(done / 100.0);
+            } else if trimmed.starts_with("NAME") {
+                in_config = true;
+            } else if trimmed.starts_with("errors:") || trimmed.is_empty() {
+                in_config = false;
+            } else if in_config && let Some(name) = trimmed.split_whitespace().next() {
+                let state = trimmed.split_whitespace().nth(1).unwrap_or_default();
+                if name == pool.name || name == "logs" || name == "cache" || name == "spares" {
+                    continue;
+                }
+                if let Some((layout, _)) = name.rsplit_once('-')
+                    && matches!(layout, "mirror" | "raidz1" | "raidz2" | "raidz3" | "draid")
+                {
+                    pool.level.get_or_insert_with(|| layout.to_string());
+                    continue;
+                }
+                pool.members.push(StoragePoolMember {
+                    name:      name.to_string(),
+                    is_faulty: parse_zfs_health(state) != StoragePoolHealth::Online,
+                });
+            }
+        }
+    }
+    pools
+}
+
+#[test]
+fn parse_degraded_mdstat() {
+    let pools = parse_mdstat(
+        "Personalities : [raid1] [raid6] [raid5] [raid4]
+md1 : active raid5 sdd1[3](F) sdc1[1] sdb1[0]
+      3906764800 blocks super 1.2 level 5, 512k chunk, algorithm 2 [3/2] [UU_]
+
+md0 : active raid1 sdf1[1] sde1[0]
+      1953382464 blocks super 1.2 [2/2] [UU]
+      [==>..................]  recovery = 12.6% (246720/1953382464) finish=127.6min speed=223488K/sec
+      bitmap: 0/15 pages [0KB], 65536KB chunk
+
+unused devices: <none>
+",
+    );
+    assert_eq!(pools.len(), 2);
+    assert_eq!(pools[0].level.as_deref(), Some("raid5"));
+    assert_eq!(pools[0].health, StoragePoolHealth::Degraded);
+    assert_eq!(pools[0].degraded_members().len(), 1);
+    assert_eq!(pools[1].health, StoragePoolHealth::Online);
+    assert_eq!(pools[1].members.len(), 2);
+    assert_eq!(pools[0].progress, None);
+    assert!(pools[1].progress.is_some_and(|progress| (progress - 0.126).abs() < 0.001));
+}
+
+#[test]
+fn parse_lvm_and_zfs_rebuilds() {
+    let pools = parse_lvm(
+        "  vg0;/dev/sda2;a--;wz-pn-
+  vg0;[unknown];a-m;wz-pn-
+  ;/dev/sdc1;---;
+  data;/dev/nvme0n1p3;a--;wz--n-
+",
+        "  vg0;raid1;45.20
+  vg0;linear;
+  data;linear;
+",
+    );
+    assert_eq!(pools.len(), 2);
+    assert_eq!(pools[0].health, StoragePoolHealth::Degraded);
+    assert_eq!(pools[0].degraded_members().len(), 1);
+    assert_eq!(pools[0].level.as_deref(), Some("raid1"));
+    assert!(pools[0].progress.is_some_and(|progress| (progress - 0.452).abs() < 0.001));
+    assert_eq!(pools[1].health, StoragePoolHealth::Online);
+    assert_eq!(pools[1].level, None);
+    assert_eq!(pools[1].progress, None);
+
+    let pools = parse_zpool_status(
+        "  pool: tank
+ state: DEGRADED
+  scan: resilver in progress since Sun Oct 15 10:00:00 2023
+\t1.23T scanned at 500M/s, 800G issued at 300M/s, 2.50T total
+\t200G resilvered, 31.25% done, 01:23:45 to go
+config:
+
+\tNAME        STATE     READ WRITE CKSUM
+\ttank        DEGRADED     0     0     0
+\t  mirror-0  DEGRADED     0     0     0
+\t    sda     ONLINE       0     0     0
+\t    sdb     UNAVAIL      0     0     0
+
+errors: No known data errors
+",
+    );
+    assert_eq!(pools[0].level.as_deref(), Some("mirror"));
+    assert!(pools[0].progress.is_some_and(|progress| (progress - 0.3125).abs() < 0.001));
+    assert_eq!(pools[0].degraded_members().len(), 1);
+}
@@ -0,0 +1,90 @@
+// Instead of polling every function in a loop, a frontend can subscribe
+// to what it shows. A thread with its own Manager refreshes it and sends
+// the readings as they come in, until the receiver is dropped.
+
+use std::{
+    sync::mpsc::{Receiver, Sender},
+    time::{Duration, Instant},
+};
+
+use crate::{BatteryInfo, ComponentInfo, CpuInfo, DiskInfo, Manager, MemoryInfo, NetworkInfo, ProcessInfo, SystemInfo};
+
+/// Which readings to send
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct RefreshSpec {
+    pub cpu:        bool,
+    pub memory:     bool,
+    pub processes:  bool,
+    pub disks:      bool,
+    /// Slow, see `Manager::network_information`
+    pub network:    bool,
+    pub components: bool,
+    pub batteries:  bool,
+}
+
+impl RefreshSpec {
+    #[must_use]
+    pub const fn all() -> Self {
+        Self {
+            cpu:        true,
+            memory:     true,
+            processes:  true,
+            disks:      true,
+            network:    true,
+            components: true,
+            batteries:  true,
+        }
+    }
+}
+
+/// Readings the platform doesn't support are never sent. Also what an
+/// `InfoCategory` reads
+#[derive(Debug, Clone)]
+pub enum Update {
+    /// Only read through the categories, subscribing to it isn't possible
+    System(SystemInfo),
+    Cpu(Vec<CpuInfo>),
+    Memory(MemoryInfo),
+    ProcessList(Vec<ProcessInfo>),
+    Disks(Vec<DiskInfo>),
+    Network(NetworkInfo),
+    Components(Vec<ComponentInfo>),
+    Batteries(Vec<BatteryInfo>),
+}
+
+pub fn subscribe(spec: RefreshSpec, interval: Duration) -> Receiver<Update> {
+    let (sender, receiver) = std::sync::mpsc::channel();
+    // The thread would never find out that nobody listens
+    if spec == RefreshSpec::default() {
+        return receiver;
+    }
+    std::thread::spawn(move || {
+        let mut manager = Manager::new();
+        loop {
+            let started = Instant::now();
+            if send_updates(&mut manager, spec, &sender).is_none() {
+                break;
+            }
+            std::thread::sleep(interval.saturating_sub(started.elapsed()));
+        }
+    });
+    receiver
+}
+
+/// `None` once nobody is listening anymore
+fn send_updates(manager: &mut Manager, spec: RefreshSpec, sender: &Sender<Update>) -> Option<()> {
+    let updates = [
+        spec.cpu.then(|| manager.cpu_information().map(Update::Cpu)),
+        spec.memory.then(|| manager.memory_information().map(Update::Memory)),
+        spec.processes.then(|| manager.process_information().map(Update::ProcessList)),
+        spec.disks.then(|| manager.disk_information().map(Update::Disks)),
+        spec.network.then(|| Some(Update::Network(manager.network_information()))),
+        spec.components.then(|| manager.component_information().map(Update::Components)),
+        spec.batteries.then(|| manager.battery_information().map(Update::Batteries)),
+    ];
+    for update in updates.into_iter().flatten().flatten() {
+        sender.send(update).ok()?;
+    }
+    Some(())
+}
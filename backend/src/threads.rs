@@ -0,0 +1,104 @@
+// The threads of a process, like htop shows them:
+//
+// - Linux: /proc/<pid>/task, which has a stat file per thread just like
+//   the one of the process
+// - macOS and Windows only list threads through task_threads and
+//   Toolhelp32, which need unsafe code, so nothing is listed
+//
+// CPU usage needs two readings, so it is only there from the second call
+// for the same process on.
+
+use std::{collections::HashMap, time::Instant};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ThreadKind {
+    User,
+    /// Threads of kernel workers like kworker or ksoftirqd
+    Kernel,
+}
+
+impl std::fmt::Display for ThreadKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", match self {
+            Self::User => "user",
+            Self::Kernel => "kernel",
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ThreadInfo {
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization"))]
+    pub tid:       sysinfo::Pid,
+    /// What the thread named itself, e.g. with pthread_setname_np. Threads
+    /// that didn't have the name of the process
+    pub name:      String,
+    /// In percent of one core since the last call, like
+    /// `ProcessInfo::cpu_usage`. `None` on the first call
+    pub cpu_usage: Option<f32>,
+    pub kind:      ThreadKind,
+}
+
+/// The CPU time of every thread of a process, and when it was read
+#[derive(Debug, Clone)]
+pub struct ThreadTimes {
+    pid:     sysinfo::Pid,
+    read_at: Instant,
+    times:   HashMap<sysinfo::Pid, std::time::Duration>,
+}
+
+/// `previous` are the CPU times of the last call, which are replaced
+#[cfg(target_os = "linux")]
+pub fn process_threads(pid: sysinfo::Pid, previous: &mut Option<ThreadTimes>) -> Option<Vec<ThreadInfo>> {
+    let read_at = Instant::now();
+    let mut times = HashMap::new();
+    let mut threads = std::fs::read_dir(format!("/proc/{pid}/task"))
+        .ok()?
+        .flatten()
+        .filter_map(|entry| {
+            let tid = entry.file_name().to_str()?.parse().ok()?;
+            let (name, kind, cpu_time) = parse_thread_stat(&std::fs::read_to_string(entry.path().join("stat")).ok()?)?;
+            times.insert(tid, cpu_time);
+            let cpu_usage = previous.as_ref().filter(|previous| previous.pid == pid).and_then(|previous| {
+                let seconds = read_at.duration_since(previous.read_at).as_secs_f32();
+                (seconds > 0.0).then_some(cpu_time.saturating_sub(*previous.times.get(&tid)?).as_secs_f32() / seconds * 100.0)
+            });
+            Some(ThreadInfo { tid, name, cpu_usage, kind })
+        })
+        .collect::<Vec<ThreadInfo>>();
+    threads.sort_by_key(|thread| thread.tid);
+    *previous = Some(ThreadTimes { pid, read_at, times });
+    Some(threads)
+}
+
+#[cfg(not(target_os = "linux"))]
+pub const fn process_threads(_pid: sysinfo::Pid, _previous: &mut Option<ThreadTimes>) -> Option<Vec<ThreadInfo>> {
+    None
+}
+
+// The same format as /proc/<pid>/stat: "4322 (IPC I/O Child) S 1200 ...",
+// with the flags as the 9th field. PF_KTHREAD marks kernel threads
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_thread_stat(stat: &str) -> Option<(String, ThreadKind, std::time::Duration)> {
+    const PF_KTHREAD: u64 = 0x0020_0000;
+    let name = stat.get(stat.find('(')? + 1..stat.rfind(')')?)?.to_string();
+    let (_, fields) = stat.rsplit_once(')')?;
+    let flags = fields.split_whitespace().nth(6)?.parse::<u64>().ok()?;
+    let kind = if flags & PF_KTHREAD == 0 { ThreadKind::User } else { ThreadKind::Kernel };
+    Some((name, kind, crate::processes::parse_cpu_time(stat)?))
+}
+
+#[test]
+fn parse_threads() {
+    let (name, kind, cpu_time) =
+        parse_thread_stat("4322 (IPC I/O (Child)) S 1200 1200 1200 0 -1 4194368 91 0 0 0 250 50 0 0 20 0 31 0 5430 3041230848 61234 18446744073709551615").expect("The stat is valid");
+    assert_eq!(name, "IPC I/O (Child)");
+    assert_eq!(kind, ThreadKind::User);
+    assert_eq!(cpu_time, std::time::Duration::from_secs(3));
+    assert_eq!(
+        parse_thread_stat("15 (ksoftirqd/0) S 2 0 0 0 -1 69238848 0 0 0 0 0 12 0 0 20 0 1 0 3").map(|(_, kind, _)| kind),
+        Some(ThreadKind::Kernel)
+    );
+}
@@ -0,0 +1,17 @@
+// The server and the agent both let in whoever knows a shared token.
+// Comparing it with == stops at the first wrong byte, so how long a
+// rejection takes would tell an attacker how much of a guess was right.
+
+/// Whether `given` is `expected`, taking as long for every `given` of the
+/// same length
+pub fn matches(expected: &str, given: &str) -> bool {
+    expected.len() == given.len() && expected.bytes().zip(given.bytes()).fold(0, |difference, (expected, given)| difference | (expected ^ given)) == 0
+}
+
+#[test]
+fn compare_tokens() {
+    assert!(matches("correct horse", "correct horse"));
+    assert!(!matches("correct horse", "correct horsf"));
+    assert!(!matches("correct horse", "correct"));
+    assert!(!matches("correct horse", ""));
+}
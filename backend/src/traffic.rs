@@ -0,0 +1,177 @@
+// Data volume per interface and day, for people on metered connections.
+// The kernel only counts since boot, so the history keeps the last
+// counters it saw along with the boot time and adds up the differences.
+// Everything is stored as plain text in crossinfo's data directory:
+//
+// crossinfo traffic
+// boot 1696932000
+// counter 1000 2000 eth0
+// day 2023-10-10 1000 2000 eth0
+//
+// Interface names come last as they can contain spaces on Windows.
+
+use std::{collections::BTreeMap, path::PathBuf};
+
+use chrono::{Datelike, NaiveDate};
+
+use crate::Network;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrafficUsage {
+    /// Bytes
+    pub received:    u64,
+    /// Bytes
+    pub transmitted: u64,
+}
+
+impl std::ops::AddAssign for TrafficUsage {
+    fn add_assign(&mut self, other: Self) {
+        self.received += other.received;
+        self.transmitted += other.transmitted;
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrafficHistory {
+    boot_time: u64,
+    /// The totals since boot the last time `record` was called
+    counters:  BTreeMap<String, TrafficUsage>,
+    days:      BTreeMap<(NaiveDate, String), TrafficUsage>,
+}
+
+fn path() -> Option<PathBuf> {
+    Some(crate::data_directory()?.join("traffic.txt"))
+}
+
+impl TrafficHistory {
+    /// An empty history if nothing was saved yet
+    #[must_use]
+    pub fn load() -> Self {
+        path().and_then(|path| std::fs::read_to_string(path).ok()).and_then(|text| Self::from_text(&text)).unwrap_or_default()
+    }
+
+    /// # Errors
+    ///
+    /// If there is no data directory or it can't be written to
+    pub fn save(&self) -> std::io::Result<()> {
+        let path = path().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "There is no data directory"))?;
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)?;
+        }
+        std::fs::write(path, self.to_text())
+    }
+
+    /// Adds whatever the interfaces transferred since the last call to
+    /// today's totals
+    pub fn record(&mut self, networks: &[Network]) {
+        self.record_at(networks, sysinfo::System::boot_time(), chrono::Local::now().date_naive());
+    }
+
+    fn record_at(&mut self, networks: &[Network], boot_time: u64, today: NaiveDate) {
+        // After a reboot the counters start at zero again
+        if boot_time != self.boot_time {
+            self.boot_time = boot_time;
+            self.counters.clear();
+        }
+        for network in networks {
+            let (Some(received), Some(transmitted)) = (network.received_total, network.transmitted_total) else {
+                continue;
+            };
+            let current = TrafficUsage { received, transmitted };
+            let last = self.counters.insert(network.name.clone(), current).unwrap_or_default();
+            // Interfaces that disappear and come back also start over
+            let difference = if current.received < last.received || current.transmitted < last.transmitted {
+                current
+            } else {
+                TrafficUsage {
+                    received:    current.received - last.received,
+                    transmitted: current.transmitted - last.transmitted,
+                }
+            };
+            *self.days.entry((today, network.name.clone())).or_default() += difference;
+        }
+    }
+
+    /// Oldest first
+    #[must_use]
+    pub fn daily(&self, interface: &str) -> Vec<(NaiveDate, TrafficUsage)> {
+        self.days.iter().filter(|((_, name), _)| name == interface).map(|((day, _), usage)| (*day, *usage)).collect()
+    }
+
+    /// Year and month, oldest first
+    #[must_use]
+    pub fn monthly(&self, interface: &str) -> Vec<((i32, u32), TrafficUsage)> {
+        let mut months = BTreeMap::<(i32, u32), TrafficUsage>::new();
+        for (day, usage) in self.daily(interface) {
+            *months.entry((day.year(), day.month())).or_default() += usage;
+        }
+        months.into_iter().collect()
+    }
+
+    #[must_use]
+    pub fn to_text(&self) -> String {
+        let mut lines = vec!["crossinfo traffic".to_string(), format!("boot {}", self.boot_time)];
+        lines.extend(self.counters.iter().map(|(name, usage)| format!("counter {} {} {name}", usage.received, usage.transmitted)));
+        lines.extend(self.days.iter().map(|((day, name), usage)| format!("day {day} {} {} {name}", usage.received, usage.transmitted)));
+        lines.push(String::new());
+        lines.join("\n")
+    }
+
+    /// Reads a history written by `to_text`. Returns `None` if it isn't
+    /// one
+    #[must_use]
+    pub fn from_text(text: &str) -> Option<Self> {
+        let mut lines = text.lines();
+        if lines.next()? != "crossinfo traffic" {
+            return None;
+        }
+        let mut history = Self {
+            boot_time: lines.next()?.strip_prefix("boot ")?.parse().ok()?,
+            ..Self::default()
+        };
+        let usage = |received: &str, transmitted: &str| {
+            Some(TrafficUsage {
+                received:    received.parse().ok()?,
+                transmitted: transmitted.parse().ok()?,
+            })
+        };
+        for line in lines {
+            if let Some(line) = line.strip_prefix("counter ") {
+                let mut fields = line.splitn(3, ' ');
+                if let (Some(received), Some(transmitted), Some(name)) = (fields.next(), fields.next(), fields.next()) {
+                    history.counters.insert(name.to_string(), usage(received, transmitted)?);
+                }
+            } else if let Some(line) = line.strip_prefix("day ") {
+                let mut fields = line.splitn(4, ' ');
+                if let (Some(day), Some(received), Some(transmitted), Some(name)) = (fields.next(), fields.next(), fields.next(), fields.next()) {
+                    history.days.insert((day.parse().ok()?, name.to_string()), usage(received, transmitted)?);
+                }
+            }
+        }
+        Some(history)
+    }
+}
+
+#[test]
+fn accumulate_traffic_across_reboots() {
+    let network = |received, transmitted| Network {
+        name: "Ethernet 2".to_string(),
+        received_total: Some(received),
+        transmitted_total: Some(transmitted),
+        ..Default::default()
+    };
+    let day = |day| NaiveDate::from_ymd_opt(2023, 10, day).expect("The date is valid");
+    let mut history = TrafficHistory::default();
+    history.record_at(&[network(100, 10)], 1000, day(30));
+    history.record_at(&[network(300, 20)], 1000, day(30));
+    history.record_at(&[network(400, 25)], 1000, day(31));
+    // Rebooted, the counters start over
+    history.record_at(&[network(50, 5)], 2000, day(31));
+
+    let history = TrafficHistory::from_text(&history.to_text()).expect("A written history should be read back");
+    assert_eq!(history.daily("Ethernet 2"), [
+        (day(30), TrafficUsage { received: 300, transmitted: 20 }),
+        (day(31), TrafficUsage { received: 150, transmitted: 10 })
+    ]);
+    assert_eq!(history.monthly("Ethernet 2"), [((2023, 10), TrafficUsage { received: 450, transmitted: 30 })]);
+}
@@ -0,0 +1,281 @@
+// Units frontends let the user choose between. The backend always reports
+// temperatures in degrees Celsius and sizes in bytes, these helpers
+// convert them for display. Numbers are formatted the Rust way, with a
+// decimal point and without grouping, which `NumberFormat` turns into
+// what the user's locale expects.
+
+use std::time::Duration;
+
+use uom::si::{
+    electric_potential::volt,
+    energy::{kilowatt_hour, watt_hour},
+    f64::{ElectricPotential, Energy},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TemperatureUnit {
+    #[default]
+    Celsius,
+    Fahrenheit,
+    Kelvin,
+}
+
+impl TemperatureUnit {
+    #[must_use]
+    pub fn from_celsius(self, celsius: f32) -> f32 {
+        match self {
+            Self::Celsius => celsius,
+            Self::Fahrenheit => celsius.mul_add(1.8, 32.0),
+            Self::Kelvin => celsius + 273.15,
+        }
+    }
+
+    #[must_use]
+    pub const fn symbol(self) -> &'static str {
+        match self {
+            Self::Celsius => "°C",
+            Self::Fahrenheit => "°F",
+            Self::Kelvin => "K",
+        }
+    }
+
+    /// Converts a temperature in degrees Celsius and appends the symbol
+    #[must_use]
+    pub fn format(self, celsius: f32, precision: usize) -> String {
+        format!("{:.precision$}{}", self.from_celsius(celsius), self.symbol())
+    }
+}
+
+impl std::str::FromStr for TemperatureUnit {
+    type Err = String;
+
+    fn from_str(unit: &str) -> Result<Self, Self::Err> {
+        match unit.to_lowercase().as_str() {
+            "celsius" | "c" => Ok(Self::Celsius),
+            "fahrenheit" | "f" => Ok(Self::Fahrenheit),
+            "kelvin" | "k" => Ok(Self::Kelvin),
+            _ => Err(format!("Unknown temperature unit {unit}, use celsius, fahrenheit or kelvin")),
+        }
+    }
+}
+
+/// Decimal units (kB, MB, GB) are what disk manufacturers and macOS use,
+/// binary ones (KiB, MiB, GiB) what Windows, `free` and most other tools
+/// on Linux use
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ByteUnit {
+    #[default]
+    Decimal,
+    Binary,
+}
+
+impl ByteUnit {
+    #[must_use]
+    pub fn format(self, bytes: u64) -> String {
+        humansize::format_size(bytes, match self {
+            Self::Decimal => humansize::DECIMAL,
+            Self::Binary => humansize::BINARY,
+        })
+    }
+}
+
+impl std::str::FromStr for ByteUnit {
+    type Err = String;
+
+    fn from_str(unit: &str) -> Result<Self, Self::Err> {
+        match unit.to_lowercase().as_str() {
+            "decimal" | "si" => Ok(Self::Decimal),
+            "binary" | "iec" => Ok(Self::Binary),
+            _ => Err(format!("Unknown byte unit {unit}, use decimal or binary")),
+        }
+    }
+}
+
+/// Decimal separator and digit grouping
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NumberFormat {
+    pub decimal_separator:  char,
+    /// Between every three digits before the decimal separator
+    pub grouping_separator: Option<char>,
+}
+
+/// How Rust formats numbers, which is also the C locale's way
+impl Default for NumberFormat {
+    fn default() -> Self {
+        Self {
+            decimal_separator:  '.',
+            grouping_separator: None,
+        }
+    }
+}
+
+impl NumberFormat {
+    /// According to `LC_ALL`, `LC_NUMERIC` or `LANG`
+    #[must_use]
+    pub fn system() -> Self {
+        Self::for_locale(&locale("LC_NUMERIC"))
+    }
+
+    /// For a locale name like "de_CH.UTF-8". Unknown ones get the default
+    #[must_use]
+    pub fn for_locale(locale: &str) -> Self {
+        let name = locale.split(['.', '@']).next().unwrap_or_default().replace('-', "_");
+        let language = name.split('_').next().unwrap_or_default();
+        let (decimal_separator, grouping_separator) = match (language, name.as_str()) {
+            (_, "de_CH" | "it_CH" | "rm_CH" | "de_LI") => ('.', '’'),
+            (_, "fr_CH" | "pt_PT") => (',', '\u{a0}'),
+            (_, "es_MX" | "es_US") | ("en" | "ja" | "zh" | "ko" | "he" | "th" | "hi" | "ga", _) => ('.', ','),
+            ("de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" | "el" | "ro" | "hr" | "sl" | "sr" | "ca", _) => (',', '.'),
+            ("fr" | "ru" | "pl" | "cs" | "sk" | "uk" | "fi" | "sv" | "nb" | "nn" | "no" | "hu" | "bg" | "lt" | "lv" | "et", _) => (',', '\u{a0}'),
+            _ => return Self::default(),
+        };
+        Self {
+            decimal_separator,
+            grouping_separator: Some(grouping_separator),
+        }
+    }
+
+    #[must_use]
+    pub fn format(self, value: f64, precision: usize) -> String {
+        self.localize(&format!("{value:.precision$}"))
+    }
+
+    /// Rewrites the numbers in text that was formatted the Rust way, like
+    /// "1.50 MB" or " 41.3°C". Dotted numbers like IP addresses and
+    /// versions and digits that are part of a word stay as they are
+    #[must_use]
+    pub fn localize(self, text: &str) -> String {
+        let characters = text.chars().collect::<Vec<char>>();
+        let mut localized = String::with_capacity(text.len());
+        let mut index = 0;
+        while index < characters.len() {
+            let previous = index.checked_sub(1).map(|previous| characters[previous]);
+            if !characters[index].is_ascii_digit() || previous.is_some_and(|previous| previous.is_alphanumeric() || previous == '.' || previous == ':') {
+                localized.push(characters[index]);
+                index += 1;
+                continue;
+            }
+            let digits = |start: usize| characters[start..].iter().take_while(|character| character.is_ascii_digit()).count();
+            let integer_end = index + digits(index);
+            let has_fraction = characters.get(integer_end) == Some(&'.') && characters.get(integer_end + 1).is_some_and(char::is_ascii_digit);
+            let end = if has_fraction { integer_end + 1 + digits(integer_end + 1) } else { integer_end };
+            let word_continues = characters.get(end).is_some_and(|next| *next == '.' || *next == ':' || next.is_ascii_digit());
+            if word_continues {
+                localized.extend(&characters[index..end]);
+            } else {
+                let integer = &characters[index..integer_end];
+                for (position, digit) in integer.iter().enumerate() {
+                    if position > 0 && (integer.len() - position) % 3 == 0 {
+                        localized.extend(self.grouping_separator);
+                    }
+                    localized.push(*digit);
+                }
+                if has_fraction {
+                    localized.push(self.decimal_separator);
+                    localized.extend(&characters[integer_end + 1..end]);
+                }
+            }
+            index = end;
+        }
+        localized
+    }
+}
+
+/// "system" for the locale of the environment, "c" for the default or a
+/// locale name like "de_CH"
+impl std::str::FromStr for NumberFormat {
+    type Err = String;
+
+    fn from_str(locale: &str) -> Result<Self, Self::Err> {
+        match locale.trim() {
+            "" => Err("Empty locale, use system, c or a name like de_CH".to_string()),
+            locale if locale.eq_ignore_ascii_case("system") => Ok(Self::system()),
+            locale if locale.eq_ignore_ascii_case("c") || locale.eq_ignore_ascii_case("posix") => Ok(Self::default()),
+            locale => Ok(Self::for_locale(locale)),
+        }
+    }
+}
+
+/// The first of `LC_ALL`, the variable of the category and `LANG` that is
+/// set
+fn locale(category: &str) -> String {
+    ["LC_ALL", category, "LANG"]
+        .into_iter()
+        .find_map(|variable| std::env::var(variable).ok().filter(|locale| !locale.is_empty()))
+        .unwrap_or_default()
+}
+
+/// Like "3 days, 4 h 12 min" or "42 s", with only the two largest units.
+/// h, min and s are the same in most languages, days are translated for
+/// a few of them according to `LC_ALL`, `LC_TIME` or `LANG`
+#[must_use]
+pub fn format_duration(duration: Duration) -> String {
+    let language = locale("LC_TIME");
+    format_duration_in(duration, language.split(['_', '.', '-']).next().unwrap_or_default())
+}
+
+fn format_duration_in(duration: Duration, language: &str) -> String {
+    let seconds = duration.as_secs();
+    let (days, hours, minutes, seconds) = (seconds / 86400, (seconds / 3600) % 24, (seconds / 60) % 60, seconds % 60);
+    if days > 0 {
+        let (day, days_plural) = match language {
+            "de" => ("Tag", "Tage"),
+            "fr" => ("jour", "jours"),
+            "es" => ("día", "días"),
+            "it" => ("giorno", "giorni"),
+            "nl" => ("dag", "dagen"),
+            _ => ("day", "days"),
+        };
+        format!("{days} {}, {hours} h {minutes} min", if days == 1 { day } else { days_plural })
+    } else if hours > 0 {
+        format!("{hours} h {minutes} min")
+    } else if minutes > 0 {
+        format!("{minutes} min {seconds} s")
+    } else {
+        format!("{seconds} s")
+    }
+}
+
+/// In watt-hours like batteries are rated, kilowatt-hours from 1000 Wh up
+#[must_use]
+pub fn format_energy(energy: Energy) -> String {
+    let watt_hours = energy.get::<watt_hour>();
+    if watt_hours.abs() >= 1000.0 {
+        format!("{:.2} kWh", energy.get::<kilowatt_hour>())
+    } else {
+        format!("{watt_hours:.2} Wh")
+    }
+}
+
+#[must_use]
+pub fn format_voltage(voltage: ElectricPotential) -> String {
+    format!("{:.2} V", voltage.get::<volt>())
+}
+
+#[test]
+fn convert_units() {
+    assert_eq!("Fahrenheit".parse(), Ok(TemperatureUnit::Fahrenheit));
+    assert!("rankine".parse::<TemperatureUnit>().is_err());
+    assert_eq!(TemperatureUnit::Celsius.format(41.256, 1), "41.3°C");
+    assert_eq!(TemperatureUnit::Fahrenheit.format(100.0, 0), "212°F");
+    assert_eq!(TemperatureUnit::Kelvin.format(-273.15, 2), "0.00K");
+    assert_eq!(ByteUnit::Decimal.format(1_500_000), "1.50 MB");
+    assert_eq!(ByteUnit::Binary.format(1_572_864), "1.50 MiB");
+    assert_eq!(format_duration_in(Duration::from_secs(271_330), "en"), "3 days, 3 h 22 min");
+    assert_eq!(format_duration_in(Duration::from_secs(90_061), "de"), "1 Tag, 1 h 1 min");
+    assert_eq!(format_duration_in(Duration::from_secs(3_725), "fr"), "1 h 2 min");
+    assert_eq!(format_duration_in(Duration::from_secs(42), ""), "42 s");
+    assert_eq!(format_energy(Energy::new::<watt_hour>(56.04)), "56.04 Wh");
+    assert_eq!(format_energy(Energy::new::<watt_hour>(13_500.0)), "13.50 kWh");
+    assert_eq!(format_voltage(ElectricPotential::new::<volt>(12.6)), "12.60 V");
+
+    let swiss = NumberFormat::for_locale("de_CH.UTF-8");
+    assert_eq!(swiss.format(1_234_567.891, 2), "1’234’567.89");
+    let german = "de_DE".parse::<NumberFormat>().unwrap_or_default();
+    assert_eq!(german.localize("1.50 MB"), "1,50 MB");
+    assert_eq!(german.localize("  5.1%  100.0%"), "  5,1%  100,0%");
+    assert_eq!(german.localize("12345 rpm"), "12.345 rpm");
+    assert_eq!(german.localize("192.168.1.1, eth0 and v2.5"), "192.168.1.1, eth0 and v2.5");
+    assert_eq!(NumberFormat::for_locale("xx").localize("1.5"), "1.5");
+    assert_eq!("c".parse(), Ok(NumberFormat::default()));
+}
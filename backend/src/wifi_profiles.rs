@@ -0,0 +1,185 @@
+// The Wi-Fi networks the system remembers, in range or not:
+//
+// - Linux: the wireless connections of NetworkManager through `nmcli`
+// - Windows: the WLAN profiles through `netsh`. Its output is translated,
+//   so only the layout is relied on where possible
+// - macOS: the preferred networks of the Wi-Fi interface through
+//   `networksetup`, which only lists their SSIDs. The rest is in a plist
+//   only root can read
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WifiProfile {
+    pub ssid:      String,
+    pub auto_join: Option<bool>,
+    /// In the words of the OS, e.g. "wpa-psk" or "WPA2-Personal". "open"
+    /// for networks without any
+    pub security:  Option<String>,
+}
+
+#[cfg(any(target_os = "linux", target_os = "macos", windows))]
+fn run(program: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(program).args(args).output().ok().filter(|output| output.status.success())?;
+    Some(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(target_os = "linux")]
+pub fn wifi_profiles() -> Option<Vec<WifiProfile>> {
+    let connections = run("nmcli", &["-t", "-f", "UUID,TYPE", "connection", "show"])?;
+    Some(
+        connections
+            .lines()
+            .filter_map(|line| line.strip_suffix(":802-11-wireless"))
+            .filter_map(|uuid| {
+                parse_nmcli_profile(&run("nmcli", &[
+                    "-g",
+                    "802-11-wireless.ssid,802-11-wireless-security.key-mgmt,connection.autoconnect",
+                    "connection",
+                    "show",
+                    uuid,
+                ])?)
+            })
+            .collect(),
+    )
+}
+
+// One field per line, the key management is empty for open networks:
+//
+// HomeNet
+// wpa-psk
+// yes
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+fn parse_nmcli_profile(output: &str) -> Option<WifiProfile> {
+    let mut lines = output.lines();
+    let ssid = lines.next()?.trim();
+    let security = lines.next()?.trim();
+    Some(WifiProfile {
+        ssid:      ssid.to_string(),
+        auto_join: match lines.next()?.trim() {
+            "yes" => Some(true),
+            "no" => Some(false),
+            _ => None,
+        },
+        security:  Some(if security.is_empty() { "open" } else { security }.to_string()),
+    })
+}
+
+#[cfg(windows)]
+pub fn wifi_profiles() -> Option<Vec<WifiProfile>> {
+    let profiles = run("netsh", &["wlan", "show", "profiles"])?;
+    Some(
+        parse_netsh_values(&profiles)
+            .into_iter()
+            .filter_map(|(_, name)| parse_netsh_profile(&run("netsh", &["wlan", "show", "profile", &format!("name={name}")])?))
+            .collect(),
+    )
+}
+
+// Every value is on an indented "key : value" line, the keys depend on
+// the language of Windows:
+//
+//     All User Profile     : HomeNet
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_netsh_values(output: &str) -> Vec<(&str, &str)> {
+    output
+        .lines()
+        .filter(|line| line.starts_with(' '))
+        .filter_map(|line| {
+            let (key, value) = line.split_once(" : ")?;
+            Some((key.trim(), value.trim()))
+        })
+        .collect()
+}
+
+#[cfg_attr(not(windows), allow(dead_code))]
+fn parse_netsh_profile(output: &str) -> Option<WifiProfile> {
+    let values = parse_netsh_values(output);
+    let value = |key: &str| values.iter().find_map(|(found, value)| (*found == key).then_some(*value));
+    Some(WifiProfile {
+        ssid:      value("SSID name").or_else(|| value("Name"))?.trim_matches('"').to_string(),
+        auto_join: value("Connection mode").map(|mode| mode == "Connect automatically"),
+        security:  value("Authentication").map(|authentication| if authentication == "Open" { "open" } else { authentication }.to_string()),
+    })
+}
+
+#[cfg(target_os = "macos")]
+pub fn wifi_profiles() -> Option<Vec<WifiProfile>> {
+    let ports = run("networksetup", &["-listallhardwareports"])?;
+    let device = parse_wifi_device(&ports)?;
+    Some(parse_preferred_networks(&run("networksetup", &["-listpreferredwirelessnetworks", &device])?))
+}
+
+// Hardware Port: Wi-Fi
+// Device: en0
+// Ethernet Address: 3c:22:fb:00:00:00
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_wifi_device(output: &str) -> Option<String> {
+    let mut lines = output.lines().skip_while(|line| !matches!(line.trim(), "Hardware Port: Wi-Fi" | "Hardware Port: AirPort"));
+    lines.nth(1)?.strip_prefix("Device: ").map(|device| device.trim().to_string())
+}
+
+// Preferred networks on en0:
+// 	HomeNet
+// 	Cafe
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+fn parse_preferred_networks(output: &str) -> Vec<WifiProfile> {
+    output
+        .lines()
+        .filter_map(|line| line.strip_prefix('\t'))
+        .map(|ssid| WifiProfile {
+            ssid:      ssid.to_string(),
+            auto_join: None,
+            security:  None,
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub const fn wifi_profiles() -> Option<Vec<WifiProfile>> {
+    None
+}
+
+#[test]
+fn parse_wifi_profiles() {
+    assert_eq!(
+        parse_nmcli_profile("Cafe\n\nno\n"),
+        Some(WifiProfile {
+            ssid:      "Cafe".to_string(),
+            auto_join: Some(false),
+            security:  Some("open".to_string()),
+        })
+    );
+
+    let netsh = "
+Profile information
+-------------------
+    Version                : 1
+    Type                   : Wireless LAN
+    Name                   : HomeNet
+    Control options        :
+        Connection mode    : Connect automatically
+        Network broadcast  : Connect only if this network is broadcasting
+
+Connectivity settings
+---------------------
+    Number of SSIDs        : 1
+    SSID name              : \"HomeNet\"
+
+Security settings
+-----------------
+    Authentication         : WPA2-Personal
+    Cipher                 : CCMP
+";
+    assert_eq!(
+        parse_netsh_profile(netsh),
+        Some(WifiProfile {
+            ssid:      "HomeNet".to_string(),
+            auto_join: Some(true),
+            security:  Some("WPA2-Personal".to_string()),
+        })
+    );
+
+    let ports = "\nHardware Port: Ethernet\nDevice: en1\n\nHardware Port: Wi-Fi\nDevice: en0\nEthernet Address: 3c:22:fb:00:00:00\n";
+    assert_eq!(parse_wifi_device(ports).as_deref(), Some("en0"));
+    assert_eq!(parse_preferred_networks("Preferred networks on en0:\n\tHomeNet\n\tCafe\n").len(), 2);
+}
@@ -0,0 +1,74 @@
+// `cli baseline save [file]` records the current state of the system,
+// `cli baseline compare [file]` prints everything that changed since then.
+// Without a file the baseline is kept in crossinfo's data directory.
+
+use std::{io, path::PathBuf};
+
+fn path_or_default(path: Option<&str>) -> io::Result<PathBuf> {
+    path.map(PathBuf::from)
+        .or_else(|| Some(backend::data_directory()?.join("baseline.txt")))
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "There is no data directory, pass a file instead"))
+}
+
+pub fn save(path: Option<&str>) -> io::Result<()> {
+    let path = path_or_default(path)?;
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+    std::fs::write(&path, backend::Manager::new().snapshot().to_text())?;
+    println!("Saved the baseline to {}", path.display());
+    Ok(())
+}
+
+pub fn compare(path: Option<&str>) -> io::Result<()> {
+    let path = path_or_default(path)?;
+    let baseline = backend::Snapshot::from_text(&std::fs::read_to_string(&path)?).ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("{} is not a baseline", path.display())))?;
+    let diff = baseline.diff(&backend::Manager::new().snapshot());
+
+    println!("Changes since {}", super::format_time(baseline.taken_at));
+    if diff.is_empty() {
+        println!("\nNothing changed.");
+        return Ok(());
+    }
+    let section = |title: &str, lines: Vec<String>| {
+        if !lines.is_empty() {
+            println!("\n{title}:");
+            for line in lines {
+                println!("  {line}");
+            }
+        }
+    };
+    section(
+        "Startup items",
+        diff.new_startup_items
+            .iter()
+            .map(|item| format!("+ {item}"))
+            .chain(diff.removed_startup_items.iter().map(|item| format!("- {item}")))
+            .collect(),
+    );
+    section(
+        "Listening sockets",
+        diff.new_listening_sockets
+            .iter()
+            .map(|socket| format!("+ {socket}"))
+            .chain(diff.closed_listening_sockets.iter().map(|socket| format!("- {socket}")))
+            .collect(),
+    );
+    section(
+        "Disk usage",
+        diff.disk_growth
+            .iter()
+            .map(|(mount_point, growth)| format!("{mount_point}: {}{}", if *growth < 0 { '-' } else { '+' }, super::format_bytes(growth.unsigned_abs())))
+            .collect(),
+    );
+    section(
+        "Packages",
+        diff.new_packages
+            .iter()
+            .map(|(name, version)| format!("+ {name} {version}"))
+            .chain(diff.removed_packages.iter().map(|name| format!("- {name}")))
+            .chain(diff.updated_packages.iter().map(|(name, old_version, new_version)| format!("~ {name} {old_version} -> {new_version}")))
+            .collect(),
+    );
+    Ok(())
+}
@@ -0,0 +1,198 @@
+// User preferences, read once at startup from a small config file with one
+// `key = value` per line:
+//
+// # Lines starting with # are ignored
+// temperature_unit = fahrenheit
+// byte_unit = binary
+// # Decimal separator and digit grouping: system, c for 1234.5 or a
+// # locale like de_CH
+// locale = de_CH
+// # Seconds a process gets to quit before it is killed
+// kill_grace_period = 5
+// # Show which hosts the traffic goes to, costs a bit of CPU
+// top_talkers = true
+// # Battery alerts, in percent and percentage points per minute
+// battery_low = 20
+// battery_critical = 5
+// battery_drop = 5
+// battery_health = 80
+// notifications = false
+// # How often each kind of information is refreshed, in seconds
+// cpu_interval = 1
+// process_interval = 2
+// network_interval = 1
+// disk_interval = 30
+// wifi_interval = 45
+// # While the terminal is in the background
+// background_interval = 5
+// # While a custom tab is open, how often its command runs again
+// custom_tab_interval = 5
+// # How to tell whether the internet is reachable: https, dns or tcp,
+// # optionally followed by the URL, host name or host:port to reach
+// connectivity_check = tcp 1.1.1.1:443
+// # Keep the CPU and memory charts of the last hour across restarts
+// persist_history = true
+// # Keep the lowest and highest temperature of every component across
+// # restarts instead of only for this run
+// persist_temperatures = true
+// # How many points a chart keeps before older ones are averaged together
+// chart_points = 1000
+// # Extra tabs showing what a command prints, as title | command. Can be
+// # given more than once
+// custom_tab = GPU | nvidia-smi
+//
+// The file lives at $CROSSINFO_CONFIG if that is set, otherwise at config
+// inside crossinfo's config directory. A missing file
+// just means defaults, broken lines are reported and skipped.
+
+use std::{path::PathBuf, sync::OnceLock, time::Duration};
+
+#[derive(Debug)]
+#[allow(clippy::struct_excessive_bools)]
+pub struct Config {
+    pub temperature_unit:     backend::TemperatureUnit,
+    pub byte_unit:            backend::ByteUnit,
+    pub number_format:        backend::NumberFormat,
+    pub kill_grace_period:    Duration,
+    pub top_talkers:          bool,
+    pub battery_alerts:       backend::BatteryAlertRules,
+    /// Also show alerts as desktop notifications
+    pub notifications:        bool,
+    pub intervals:            RefreshIntervals,
+    pub connectivity_check:   backend::ConnectivityCheck,
+    pub persist_history:      bool,
+    pub persist_temperatures: bool,
+    pub chart_points:         usize,
+    pub custom_tabs:          Vec<crate::custom_tabs::CustomTab>,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RefreshIntervals {
+    /// Also how often a point is added to the CPU and memory charts
+    pub cpu:         Duration,
+    pub processes:   Duration,
+    /// Interfaces, traffic and connectivity
+    pub network:     Duration,
+    pub disks:       Duration,
+    /// Scanning takes long and disturbs the connection on some drivers
+    pub wifi:        Duration,
+    /// How often the screen is drawn and the network read while the
+    /// terminal doesn't have focus
+    pub background:  Duration,
+    pub custom_tabs: Duration,
+}
+
+impl Default for RefreshIntervals {
+    fn default() -> Self {
+        Self {
+            cpu:         Duration::from_secs(1),
+            processes:   Duration::from_secs(1),
+            network:     Duration::from_secs(1),
+            disks:       Duration::from_secs(30),
+            wifi:        Duration::from_secs(45),
+            background:  Duration::from_secs(5),
+            custom_tabs: Duration::from_secs(5),
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            temperature_unit:     backend::TemperatureUnit::default(),
+            byte_unit:            backend::ByteUnit::default(),
+            number_format:        backend::NumberFormat::system(),
+            kill_grace_period:    Duration::from_secs(5),
+            top_talkers:          false,
+            battery_alerts:       backend::BatteryAlertRules::default(),
+            notifications:        true,
+            intervals:            RefreshIntervals::default(),
+            connectivity_check:   backend::ConnectivityCheck::default(),
+            persist_history:      false,
+            persist_temperatures: false,
+            chart_points:         1000,
+            custom_tabs:          vec![],
+        }
+    }
+}
+
+fn path() -> Option<PathBuf> {
+    std::env::var_os("CROSSINFO_CONFIG").map(PathBuf::from).or_else(|| Some(backend::config_directory()?.join("config")))
+}
+
+fn percent(value: &str) -> Result<f32, String> {
+    value
+        .parse()
+        .ok()
+        .filter(|percent| (0.0..=100.0).contains(percent))
+        .ok_or_else(|| format!("{value} isn't a percentage"))
+}
+
+fn seconds(value: &str) -> Result<Duration, String> {
+    value
+        .parse()
+        .ok()
+        .and_then(|seconds| Duration::try_from_secs_f64(seconds).ok())
+        .filter(|interval| !interval.is_zero())
+        .ok_or_else(|| format!("{value} isn't a positive number of seconds"))
+}
+
+fn load() -> Config {
+    let mut config = Config::default();
+    let Some(contents) = path().and_then(|path| std::fs::read_to_string(path).ok()) else {
+        return config;
+    };
+    for (number, line) in contents.lines().enumerate().map(|(index, line)| (index + 1, line.trim())) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            eprintln!("Config line {number} isn't a key = value pair");
+            continue;
+        };
+        let value = value.trim().trim_matches('"');
+        let result = match key.trim() {
+            "temperature_unit" => value.parse().map(|unit| config.temperature_unit = unit),
+            "byte_unit" => value.parse().map(|unit| config.byte_unit = unit),
+            "locale" => value.parse().map(|format| config.number_format = format),
+            "kill_grace_period" => value
+                .parse()
+                .map(|seconds| config.kill_grace_period = Duration::from_secs(seconds))
+                .map_err(|error| format!("Invalid kill_grace_period: {error}")),
+            "top_talkers" => value.parse().map(|enabled| config.top_talkers = enabled).map_err(|error| format!("Invalid top_talkers: {error}")),
+            "battery_low" => percent(value).map(|percent| config.battery_alerts.low_charge = percent),
+            "battery_critical" => percent(value).map(|percent| config.battery_alerts.critical_charge = percent),
+            "battery_drop" => percent(value).map(|percent| config.battery_alerts.sudden_drop = percent),
+            "battery_health" => percent(value).map(|percent| config.battery_alerts.low_health = percent),
+            "notifications" => value.parse().map(|enabled| config.notifications = enabled).map_err(|error| format!("Invalid notifications: {error}")),
+            "cpu_interval" => seconds(value).map(|interval| config.intervals.cpu = interval),
+            "process_interval" => seconds(value).map(|interval| config.intervals.processes = interval),
+            "network_interval" => seconds(value).map(|interval| config.intervals.network = interval),
+            "disk_interval" => seconds(value).map(|interval| config.intervals.disks = interval),
+            "wifi_interval" => seconds(value).map(|interval| config.intervals.wifi = interval),
+            "background_interval" => seconds(value).map(|interval| config.intervals.background = interval),
+            "custom_tab_interval" => seconds(value).map(|interval| config.intervals.custom_tabs = interval),
+            "connectivity_check" => value.parse().map(|check| config.connectivity_check = check),
+            "persist_history" => value
+                .parse()
+                .map(|enabled| config.persist_history = enabled)
+                .map_err(|error| format!("Invalid persist_history: {error}")),
+            "persist_temperatures" => value
+                .parse()
+                .map(|enabled| config.persist_temperatures = enabled)
+                .map_err(|error| format!("Invalid persist_temperatures: {error}")),
+            "custom_tab" => value.parse().map(|tab| config.custom_tabs.push(tab)),
+            "chart_points" => value.parse().map(|points| config.chart_points = points).map_err(|error| format!("Invalid chart_points: {error}")),
+            key => Err(format!("Unknown key {key}")),
+        };
+        if let Err(error) = result {
+            eprintln!("Config line {number}: {error}");
+        }
+    }
+    config
+}
+
+pub fn get() -> &'static Config {
+    static CONFIG: OnceLock<Config> = OnceLock::new();
+    CONFIG.get_or_init(load)
+}
@@ -0,0 +1,67 @@
+// A panic inside the TUI would otherwise leave the terminal in raw mode on
+// the alternate screen, with the panic message drawn somewhere in between
+// the widgets. The hook puts the terminal back first, prints the message
+// where it can be read and offers to save everything needed for a bug
+// report. The process exits afterwards, even if the panic happened on a
+// background thread.
+
+use std::io::{self, IsTerminal, Write};
+
+use crossterm::{
+    event::{DisableFocusChange, DisableMouseCapture},
+    execute,
+    terminal::{disable_raw_mode, LeaveAlternateScreen},
+};
+
+/// Safe to call even if the terminal was never switched over
+pub fn restore_terminal() {
+    let _ = disable_raw_mode();
+    let _ = execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture, DisableFocusChange, crossterm::cursor::Show);
+}
+
+pub fn install_panic_hook() {
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        restore_terminal();
+        default_hook(info);
+        offer_report(&info.to_string());
+        std::process::exit(101);
+    }));
+}
+
+fn offer_report(panic: &str) {
+    if !io::stdin().is_terminal() {
+        return;
+    }
+    let path = format!("crossinfo-crash-{}.txt", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+    eprint!("\ncrossinfo crashed. Write a diagnostic report to {path}? [y/N] ");
+    let _ = io::stderr().flush();
+    let mut answer = String::new();
+    if io::stdin().read_line(&mut answer).is_err() || !answer.trim().eq_ignore_ascii_case("y") {
+        return;
+    }
+    let report = format!(
+        "crossinfo crash report
+Version: {}
+Time: {}
+Operating System: {}
+Kernel Version: {}
+Arguments: {}
+
+{panic}
+
+Backtrace:
+{}
+",
+        env!("CARGO_PKG_VERSION"),
+        super::format_time(std::time::SystemTime::now()),
+        super::to_string_or_unknown(sysinfo::System::long_os_version()),
+        super::to_string_or_unknown(sysinfo::System::kernel_version()),
+        std::env::args().skip(1).collect::<Vec<String>>().join(" "),
+        std::backtrace::Backtrace::force_capture()
+    );
+    match std::fs::write(&path, report) {
+        Ok(()) => eprintln!("Saved to {path}, please attach it to a bug report"),
+        Err(error) => eprintln!("Couldn't write {path}: {error}"),
+    }
+}
@@ -0,0 +1,90 @@
+// Tabs defined in the config, each showing what a shell command prints,
+// for information crossinfo doesn't know about itself:
+//
+// custom_tab = GPU | nvidia-smi
+//
+// The command runs on a thread of its own so a slow one doesn't hold up
+// drawing, and only again once the custom tab interval has passed while
+// its tab is open.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write as _,
+    process::Command,
+    str::FromStr,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone)]
+pub struct CustomTab {
+    pub title:   String,
+    pub command: String,
+}
+
+impl FromStr for CustomTab {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (title, command) = s.split_once('|').ok_or_else(|| format!("{s} isn't a title | command pair"))?;
+        let (title, command) = (title.trim(), command.trim());
+        if title.is_empty() || command.is_empty() {
+            return Err(format!("{s} is missing the title or the command"));
+        }
+        Ok(Self {
+            title:   title.to_string(),
+            command: command.to_string(),
+        })
+    }
+}
+
+struct Output {
+    text:       String,
+    started_at: Instant,
+    finished:   bool,
+}
+
+// By the index of the tab in the config
+static OUTPUTS: Mutex<BTreeMap<usize, Output>> = Mutex::new(BTreeMap::new());
+
+/// What the command of the tab printed the last time it ran. Runs it again
+/// if that was longer than `interval` ago
+pub fn output(index: usize, interval: Duration) -> String {
+    let Some(tab) = super::config::get().custom_tabs.get(index) else {
+        return String::new();
+    };
+    let mut outputs = super::lock(&OUTPUTS);
+    let previous = outputs.get(&index);
+    if previous.is_none_or(|output| output.finished && output.started_at.elapsed() > interval) {
+        let text = previous.map_or_else(|| format!("Running {}…", tab.command), |output| output.text.clone());
+        outputs.insert(index, Output {
+            text,
+            started_at: Instant::now(),
+            finished: false,
+        });
+        let command = tab.command.clone();
+        std::thread::spawn(move || {
+            let text = run(&command);
+            if let Some(output) = super::lock(&OUTPUTS).get_mut(&index) {
+                output.text = text;
+                output.finished = true;
+            }
+        });
+    }
+    outputs.get(&index).map(|output| output.text.clone()).unwrap_or_default()
+}
+
+fn run(command: &str) -> String {
+    let (shell, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+    match Command::new(shell).args([flag, command]).output() {
+        Ok(output) => {
+            let mut text = String::from_utf8_lossy(&output.stdout).into_owned();
+            text.push_str(&String::from_utf8_lossy(&output.stderr));
+            if !output.status.success() {
+                let _ = write!(text, "\n{command} failed with {}", output.status);
+            }
+            text
+        }
+        Err(error) => format!("{command} couldn't be run: {error}"),
+    }
+}
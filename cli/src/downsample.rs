@@ -0,0 +1,28 @@
+// The charts get a point every `cpu_interval`, so after a day they hold
+// tens of thousands of them, all of which ratatui walks on every frame
+// although the chart is only a few hundred cells wide. Once a dataset grows
+// past `chart_points`, the older half is thinned out by averaging
+// neighbouring points. The recent end stays at full resolution and older
+// points keep getting coarser, so the length stays bounded.
+
+use crate::DataPoints;
+
+/// Thins out `points` until there are at most `max_points` of them. Points
+/// are expected to be in chronological order
+pub fn downsample(points: &mut DataPoints, max_points: usize) {
+    // Fewer couldn't even keep the newest point at full resolution
+    let max_points = max_points.max(2);
+    while points.len() > max_points {
+        // Pairs of at least two, otherwise nothing would be merged
+        let older = (points.len() / 2).max(2);
+        let averaged = points[..older]
+            .chunks(2)
+            .map(|pair| {
+                #[allow(clippy::cast_precision_loss)]
+                let count = pair.len() as f64;
+                (pair.iter().map(|(x, _)| x).sum::<f64>() / count, pair.iter().map(|(_, y)| y).sum::<f64>() / count)
+            })
+            .collect::<DataPoints>();
+        points.splice(..older, averaged);
+    }
+}
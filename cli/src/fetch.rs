@@ -0,0 +1,69 @@
+// `cli fetch` prints the logo next to a handful of key facts, like
+// neofetch does. Colors are left out if NO_COLOR is set.
+
+use crossterm::style::Stylize;
+use uom::si::frequency::megahertz;
+
+pub fn print() {
+    let formatter = super::format_bytes;
+    let mut manager = backend::Manager::new();
+
+    let user = std::env::var("USER").or_else(|_| std::env::var("USERNAME")).unwrap_or_default();
+    let host = sysinfo::System::host_name().unwrap_or_default();
+    let title = format!("{user}@{host}");
+
+    let mut facts = vec![title.clone().bold().cyan().to_string(), "-".repeat(title.len())];
+    let mut fact = |name: &str, value: String| facts.push(format!("{}: {value}", name.bold().cyan()));
+    if let Some(system) = manager.system_information() {
+        fact("OS", format!("{} {}", super::to_string_or_unknown(system.os), system.os_version.unwrap_or_default()));
+        fact("Kernel", super::to_string_or_unknown(system.kernel_version));
+        fact("Uptime", backend::format_duration(system.uptime));
+    }
+    if let Some(cpus) = manager.cpu_information()
+        && let Some(cpu) = cpus.first()
+    {
+        fact(
+            "CPU",
+            format!("{} ({}) @ {} MHz", cpu.manufacturer, cpus.len(), super::localize(&cpu.frequency.get::<megahertz>().to_string())),
+        );
+    }
+    if let Some(memory) = manager.memory_information() {
+        fact("Memory", format!("{} / {}", formatter(memory.used_memory), formatter(memory.total_memory)));
+    }
+    if let Some(disks) = manager.disk_information()
+        && let Some(root) = disks.iter().find(|disk| disk.mount_point == "/" || disk.mount_point == "C:\\")
+    {
+        fact("Disk", format!("{} / {}", formatter(root.used), formatter(root.total)));
+    }
+    if let Some(batteries) = manager.battery_information()
+        && let Some(battery) = batteries.first()
+    {
+        fact("Battery", super::localize(&format!("{:.0}% ({})", battery.charge * 100.0, battery.state)));
+    }
+    facts.push(String::new());
+    facts.push(
+        [
+            crossterm::style::Color::Black,
+            crossterm::style::Color::DarkRed,
+            crossterm::style::Color::DarkGreen,
+            crossterm::style::Color::DarkYellow,
+            crossterm::style::Color::DarkBlue,
+            crossterm::style::Color::DarkMagenta,
+            crossterm::style::Color::DarkCyan,
+            crossterm::style::Color::Grey,
+        ]
+        .into_iter()
+        .map(|color| "   ".on(color).to_string())
+        .collect(),
+    );
+
+    let logo = super::Logo::get(20).lines().collect::<Vec<&str>>();
+    let logo_width = logo.iter().map(|line| line.chars().count()).max().unwrap_or_default();
+    for index in 0..logo.len().max(facts.len()) {
+        println!(
+            "{}  {}",
+            format!("{:logo_width$}", logo.get(index).copied().unwrap_or_default()).cyan(),
+            facts.get(index).map_or("", String::as_str)
+        );
+    }
+}
@@ -0,0 +1,99 @@
+// With `persist_history = true` the CPU and memory charts are saved to
+// crossinfo's data directory on quit and restored on the next start, so
+// restarting doesn't throw away what led up to a problem. The charts count
+// seconds from the start of each run, so points are stored by their age
+// when they were saved:
+//
+// crossinfo history 1696932000
+// ram	12.5,0.41 11.5,0.42
+// swap	12.5,0 11.5,0
+// cpu	AuthenticAMD	cpu0	12.5,30.2 11.5,28
+//
+// Memory is stored as the used fraction of the total. Restored points end
+// up left of zero, anything older than an hour is dropped.
+
+use std::{collections::HashMap, path::PathBuf, time::SystemTime};
+
+use crate::DataPoints;
+
+const MAX_AGE: f64 = 3600.0;
+
+#[derive(Debug, Default)]
+pub struct ChartHistory {
+    /// Keyed by manufacturer and model, which is how `CpuInfo`s are told
+    /// apart
+    pub cpu:  HashMap<(String, String), DataPoints>,
+    pub ram:  DataPoints,
+    pub swap: DataPoints,
+}
+
+fn path() -> Option<PathBuf> {
+    Some(backend::data_directory()?.join("history.txt"))
+}
+
+fn now() -> f64 {
+    SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap_or_default().as_secs_f64()
+}
+
+fn format_points(points: &DataPoints, elapsed: f64) -> String {
+    points
+        .iter()
+        .filter(|(x, _)| elapsed - x <= MAX_AGE)
+        .map(|(x, y)| format!("{},{y}", elapsed - x))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+fn parse_points(text: &str, since_saved: f64) -> DataPoints {
+    text.split_whitespace()
+        .filter_map(|point| {
+            let (age, value) = point.split_once(',')?;
+            let age = age.parse::<f64>().ok()? + since_saved;
+            (age <= MAX_AGE).then_some((-age, value.parse().ok()?))
+        })
+        .collect()
+}
+
+/// `elapsed` is where the x axis of the charts currently ends
+pub fn save(history: &ChartHistory, elapsed: f64) -> std::io::Result<()> {
+    let path = path().ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "There is no data directory"))?;
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)?;
+    }
+    let lines = [
+        format!("crossinfo history {}", now()),
+        format!("ram\t{}", format_points(&history.ram, elapsed)),
+        format!("swap\t{}", format_points(&history.swap, elapsed)),
+    ]
+    .into_iter()
+    .chain(
+        history
+            .cpu
+            .iter()
+            .map(|((manufacturer, model), points)| format!("cpu\t{manufacturer}\t{model}\t{}", format_points(points, elapsed))),
+    );
+    std::fs::write(path, lines.map(|line| line + "\n").collect::<String>())
+}
+
+/// The points are placed before the start of this run, `None` if nothing
+/// was saved
+pub fn load() -> Option<ChartHistory> {
+    let text = std::fs::read_to_string(path()?).ok()?;
+    let mut lines = text.lines();
+    let since_saved = now() - lines.next()?.strip_prefix("crossinfo history ")?.parse::<f64>().ok()?;
+    if since_saved > MAX_AGE {
+        return None;
+    }
+    let mut history = ChartHistory::default();
+    for line in lines {
+        match line.split('\t').collect::<Vec<&str>>().as_slice() {
+            ["ram", points] => history.ram = parse_points(points, since_saved),
+            ["swap", points] => history.swap = parse_points(points, since_saved),
+            ["cpu", manufacturer, model, points] => {
+                history.cpu.insert(((*manufacturer).to_string(), (*model).to_string()), parse_points(points, since_saved));
+            }
+            _ => {}
+        }
+    }
+    Some(history)
+}
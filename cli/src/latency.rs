@@ -0,0 +1,70 @@
+// `cli latency [SECONDS]` traces block IO and run queue latency for a few
+// seconds and prints the histograms, plus the processes that waited the
+// longest for their disks. Only built with the `ebpf` feature.
+
+use std::time::Duration;
+
+const DEFAULT_DURATION: Duration = Duration::from_secs(5);
+const BAR_WIDTH: u64 = 40;
+
+fn format_latency(latency: Duration) -> String {
+    if latency >= Duration::from_millis(1) {
+        format!("{}ms", latency.as_millis())
+    } else {
+        format!("{}µs", latency.as_micros())
+    }
+}
+
+fn print_histogram(histogram: &backend::LatencyHistogram) {
+    let largest = histogram.buckets.iter().map(|bucket| bucket.count).max().unwrap_or_default().max(1);
+    for bucket in &histogram.buckets {
+        println!(
+            "  {:>16} {:>8} {}",
+            format!("{} - {}", format_latency(bucket.low), format_latency(bucket.high)),
+            bucket.count,
+            "█".repeat(usize::try_from(bucket.count * BAR_WIDTH / largest).unwrap_or_default())
+        );
+    }
+}
+
+pub fn print(seconds: Option<&str>) -> std::io::Result<()> {
+    let duration = match seconds {
+        Some(seconds) => Duration::from_secs(
+            seconds
+                .parse()
+                .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{seconds} isn't a number of seconds")))?,
+        ),
+        None => DEFAULT_DURATION,
+    };
+    eprintln!("Tracing for {} seconds...", duration.as_secs());
+    let Some(mut latency) = backend::Manager::new().latency_information(duration) else {
+        return Err(std::io::Error::other("Tracing failed, bpftrace has to be installed and crossinfo has to run as root"));
+    };
+
+    if let Some(run_queue) = &latency.run_queue {
+        println!(
+            "Run queue latency (p50 {}, p99 {})",
+            super::to_string_or_unknown(run_queue.percentile(0.5).map(format_latency)),
+            super::to_string_or_unknown(run_queue.percentile(0.99).map(format_latency))
+        );
+        print_histogram(run_queue);
+        println!();
+    }
+
+    latency.block_io.sort_by_key(|histogram| std::cmp::Reverse(histogram.percentile(0.99)));
+    println!("Block IO latency by process");
+    if latency.block_io.is_empty() {
+        println!("  No block IO while tracing");
+    }
+    for histogram in latency.block_io.iter().take(5) {
+        println!(
+            "{} ({} requests, p50 {}, p99 {})",
+            super::to_string_or_unknown(histogram.name.as_ref()),
+            histogram.count(),
+            super::to_string_or_unknown(histogram.percentile(0.5).map(format_latency)),
+            super::to_string_or_unknown(histogram.percentile(0.99).map(format_latency))
+        );
+        print_histogram(histogram);
+    }
+    Ok(())
+}
@@ -9,18 +9,39 @@
 #![allow(clippy::unwrap_used)]
 #![allow(clippy::too_many_lines)]
 
+mod baseline;
+mod config;
+mod crash;
+mod custom_tabs;
+mod downsample;
+mod fetch;
+mod history;
+#[cfg(feature = "ebpf")]
+mod latency;
+mod overhead;
+mod remote;
+mod report;
+mod schema;
+mod selection;
+mod speedtest;
+mod summary;
+mod widths;
+
 use std::{
     collections::HashMap,
+    fmt::Write as _,
     io,
-    sync::Mutex,
+    sync::{
+        atomic::{AtomicBool, Ordering as AtomicOrdering},
+        Mutex,
+    },
     time::{Duration, Instant},
 };
 
-use backend::{EnumCount, IntoEnumIterator};
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture, Event, KeyCode, ModifierKeyCode, MouseEventKind},
+    event::{EnableFocusChange, EnableMouseCapture, Event, KeyCode, ModifierKeyCode, MouseButton, MouseEventKind},
     execute,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
+    terminal::{enable_raw_mode, EnterAlternateScreen},
 };
 use itertools::Itertools;
 use ratatui::{
@@ -29,7 +50,9 @@ use ratatui::{
     style::{Color, Modifier, Style},
     symbols::Marker,
     text::{Line, Span},
-    widgets::{block::Title, Axis, Block, Borders, Chart, Clear, Dataset, GraphType, List, ListItem, ListState, Paragraph, Tabs, Wrap},
+    widgets::{
+        block::{Position, Title},
+        Axis, Block, Borders, Chart, Clear, Dataset, Gauge, GraphType, List, ListItem, ListState, Paragraph, Tabs, Wrap},
     Frame, Terminal,
 };
 
@@ -48,12 +71,43 @@ impl Ordering {
         T: std::cmp::PartialOrd,
     {
         move |a, b| match self {
-            Self::Ascending => a.partial_cmp(&b).unwrap(),
-            Self::Descending => b.partial_cmp(&a).unwrap(),
+            // NaN sorts next to everything, there's no point in crashing over it
+            Self::Ascending => a.partial_cmp(&b).unwrap_or(std::cmp::Ordering::Equal),
+            Self::Descending => b.partial_cmp(&a).unwrap_or(std::cmp::Ordering::Equal),
+        }
+    }
+
+    const fn reversed(self) -> Self {
+        match self {
+            Self::Ascending => Self::Descending,
+            Self::Descending => Self::Ascending,
         }
     }
 }
 
+/// Sorts by the column whose header was clicked, the other way around if
+/// the list already is sorted by it
+fn sort_by_clicked<T>(sorting: &T, ordering: Ordering, column: fn(Ordering) -> T) -> T {
+    if std::mem::discriminant(sorting) == std::mem::discriminant(&column(ordering)) {
+        column(ordering.reversed())
+    } else {
+        column(Ordering::Descending)
+    }
+}
+
+/// Which column a click `x` characters into a list's title landed on. The
+/// columns are given as the gap in front of them and their width
+fn clicked_column(x: u16, columns: &[(usize, usize)]) -> Option<usize> {
+    let x = usize::from(x);
+    let mut start = 0;
+    columns.iter().position(|&(gap, width)| {
+        start += gap;
+        let clicked = (start..start + width).contains(&x);
+        start += width;
+        clicked
+    })
+}
+
 // Function copied straight from https://github.com/ratatui-org/ratatui/blob/main/examples/popup.rs
 fn centered_rect(percent_x: u16, percent_y: u16, r: Rect) -> Rect {
     let popup_layout = Layout::default()
@@ -87,21 +141,58 @@ enum SortByProcess {
     MemoryUsage(Ordering),
     SwapUsage(Ordering),
     Runtime(Ordering),
+    CpuTime(Ordering),
+}
+
+impl SortByProcess {
+    const fn ordering(self) -> Ordering {
+        match self {
+            Self::CpuUsage(ordering) | Self::MemoryUsage(ordering) | Self::SwapUsage(ordering) | Self::Runtime(ordering) | Self::CpuTime(ordering) => ordering,
+        }
+    }
 }
 
 #[derive(Copy, Clone, Debug)]
 enum SortByComponent {
     Temperature(Ordering),
     Critical(Ordering),
+    Maximum(Ordering),
+}
+
+impl SortByComponent {
+    const fn ordering(self) -> Ordering {
+        match self {
+            Self::Temperature(ordering) | Self::Critical(ordering) | Self::Maximum(ordering) => ordering,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, Default)]
+struct ProcessView {
+    extra_columns:      bool,
+    /// Every process shows the usage of its whole subtree instead of its
+    /// own
+    aggregate_children: bool,
+    /// Of the whole system, for the summary below the list
+    cpu_usage:          Option<f64>,
+    load_average:       Option<backend::LoadAverage>,
+}
+
+#[derive(Copy, Clone, Debug)]
+enum AudioAction {
+    VolumeUp,
+    VolumeDown,
+    MakeDefault,
 }
 
 #[derive(Clone, Debug)]
 enum ProcessPopup {
-    KillProcess { process_name: String, pid: sysinfo::Pid },
+    KillProcess { process_name: String, id: backend::ProcessId },
     MoreInformation { contents: String },
     NoSelected,
 }
 
+#[allow(clippy::struct_excessive_bools)]
 struct AppState {
     manager:               backend::Manager,
     current_line:          u16,
@@ -113,16 +204,103 @@ struct AppState {
     component_ordering:    SortByComponent,
     shift_pressed:         bool,
     kill_current_process:  bool,
+    /// Kill without asking and without giving the process a chance to
+    /// quit
+    force_kill:            bool,
     more_information:      bool,
-    process_to_kill:       Option<(String, sysinfo::Pid)>,
+    process_view:          ProcessView,
+    toggle_background_io:  bool,
+    /// How much to change the nice value of the selected process by
+    renice:                Option<i32>,
+    audio_action:          Option<AudioAction>,
+    start_benchmark:       bool,
+    process_to_kill:       Option<(String, backend::ProcessId)>,
     confirm_kill:          Option<bool>,
+    /// Set for one frame so the popup can say what's happening before the
+    /// grace period blocks
+    process_to_terminate:  Option<(String, backend::ProcessId)>,
+    /// What happened to the last process that was killed
+    kill_report:           Option<String>,
+    battery_alerts:        backend::BatteryAlerts,
+    /// Lowest and highest temperature of every component
+    temperature_extremes:  backend::TemperatureExtremes,
+    /// Shown next to the tabs for a while
+    latest_alert:          Option<(Instant, backend::Alert)>,
+    /// Write the open detail popup to a file this frame
+    dump_popup:            bool,
+    /// Only Wi-Fis and interfaces containing this are listed
+    network_filter:        String,
+    /// Keys go to the filter instead of being shortcuts
+    editing_filter:        bool,
+    hide_hidden_wifis:     bool,
     cpu_dataset:           HashMap<backend::CpuInfo, DataPoints>,
     ram_dataset:           DataPoints,
     swap_dataset:          DataPoints,
+    /// The one minute load average as a share of the cores, so it fits in
+    /// the CPU charts
+    load_dataset:          DataPoints,
+    load_average:          Option<backend::LoadAverage>,
+    /// Chart history of the previous run, waiting for the first CPU
+    /// reading to find out which core is which
+    restored_cpu_history:  HashMap<(String, String), DataPoints>,
+    /// Whether the terminal has focus, as far as it reports it
+    focused:               bool,
+    latest_input:          Instant,
+    /// What crossinfo costs itself, over whatever tab is open
+    show_overhead:         bool,
+    /// The categories, then one per plugin and one per custom tab
+    tabs:                  Vec<TabKind>,
+    /// Where the left mouse button was pressed this frame, as column and
+    /// row
+    mouse_click:           Option<(u16, u16)>,
+}
+
+impl AppState {
+    /// The id of the category whose tab is open, None for plugins and
+    /// custom tabs
+    fn current_category(&self) -> Option<&'static str> {
+        match self.tabs.get(self.current_tab) {
+            Some(TabKind::Category(category)) => Some(category.id),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum TabKind {
+    Category(&'static backend::InfoCategory),
+    /// By their index in the plugin information
+    Plugin(usize),
+    /// By their index in the config
+    Custom(usize),
 }
 
+// The categories with a tab, the others can't be drawn yet
+const DRAWN_CATEGORIES: [&str; 11] = ["overview", "system", "cpu", "memory", "disks", "battery", "network", "processes", "components", "audio", "benchmark"];
+
 static NETWORK_INFO: Mutex<Option<backend::NetworkInfo>> = Mutex::new(None);
+// When NETWORK_INFO was last replaced, so the list widths know to change
+static NETWORK_INFO_READ_AT: Mutex<Option<Instant>> = Mutex::new(None);
+static TRAFFIC_HISTORY: Mutex<Option<backend::TrafficHistory>> = Mutex::new(None);
+static TOP_TALKERS: Mutex<Option<Vec<backend::TopTalker>>> = Mutex::new(None);
+static WIFI_PROFILES: Mutex<Option<Vec<backend::WifiProfile>>> = Mutex::new(None);
+// Set while the terminal is unfocused, so the network thread slows down too
+static BACKGROUNDED: AtomicBool = AtomicBool::new(false);
+// Printed on exit, as the clipboard is out of reach over SSH
+static DUMPED_POPUPS: Mutex<Vec<std::path::PathBuf>> = Mutex::new(vec![]);
+// Saved now and then so a crash doesn't lose everything since the start
+const TRAFFIC_SAVE_INTERVAL: Duration = Duration::from_secs(30);
 const INTERVAL: Duration = Duration::from_secs(1);
+// How long to wait for a killed process to disappear before reporting that
+// it's still there
+const KILL_TIMEOUT: Duration = Duration::from_secs(2);
+const REMOTE_KILL: &str = "Killing processes on another machine needs the manage token";
+// Nice values go from -20 to 19, single steps would take forever
+const RENICE_STEP: i32 = 5;
+const ALERT_DURATION: Duration = Duration::from_secs(30);
+// Without input for this long, frames are only drawn when the charts get a
+// new point
+const IDLE_AFTER: Duration = Duration::from_secs(60);
 
 struct Logo;
 
@@ -144,22 +322,80 @@ impl Logo {
     }
 }
 
+fn chart_history(app_state: &AppState) -> history::ChartHistory {
+    let unscale = |points: &DataPoints, important_digits: Option<f64>| {
+        points
+            .iter()
+            .map(|&(x, y)| (x, important_digits.filter(|digits| *digits > 0.0).map_or(0.0, |digits| y / digits)))
+            .collect()
+    };
+    history::ChartHistory {
+        cpu:  app_state
+            .cpu_dataset
+            .iter()
+            .map(|(cpu_core, dataset)| ((cpu_core.manufacturer.clone(), cpu_core.model.clone()), dataset.clone()))
+            .collect(),
+        ram:  unscale(&app_state.ram_dataset, app_state.ram_important_digits),
+        swap: unscale(&app_state.swap_dataset, app_state.swap_important_digits),
+    }
+}
+
 const WIDTH_NUMERATOR: usize = 1400; // This is basically a magic number I found using trial and error. If there
                                      // is a mathematical way to get this same number or an even better one,
                                      // tell me about it.
 
-fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>) {
+fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>) -> io::Result<()> {
     let (sender, receiver) = std::sync::mpsc::channel();
     let thread = std::thread::spawn(move || {
         let mut parallel_manager = backend::Manager::new();
+        if !remote::is_remote() {
+            *lock(&TRAFFIC_HISTORY) = Some(backend::TrafficHistory::load());
+        }
+        let mut latest_save = Instant::now();
+        let mut latest_top_talkers: Option<Instant> = None;
+        let mut latest_wifi_profiles: Option<Instant> = None;
+        let intervals = config::get().intervals;
+        parallel_manager.set_wifi_interval(intervals.wifi);
+        parallel_manager.set_connectivity_check(config::get().connectivity_check.clone());
+        let mut latest_refresh: Option<Instant> = None;
         loop {
-            if receiver.try_recv().is_ok() {
+            // Waiting on the channel instead of sleeping so quitting doesn't
+            // take up to a whole interval
+            let interval = if BACKGROUNDED.load(AtomicOrdering::Relaxed) { intervals.network.max(intervals.background) } else { intervals.network };
+            let remaining = latest_refresh.map_or(Duration::ZERO, |latest_refresh| interval.saturating_sub(latest_refresh.elapsed()));
+            if !matches!(receiver.recv_timeout(remaining), Err(std::sync::mpsc::RecvTimeoutError::Timeout)) {
+                if let Some(traffic_history) = &*lock(&TRAFFIC_HISTORY) {
+                    let _ = traffic_history.save();
+                }
                 break;
             }
-            let network_info_temp = Some(parallel_manager.network_information()); // This temporary must be used otherwise
-                                                                                  // network_tab blocks on NETWORK_INFO.lock
-            let mut network_info = NETWORK_INFO.lock().unwrap();
-            *network_info = network_info_temp;
+            latest_refresh = Some(Instant::now());
+            let network_info_temp = Some(remote::network_information(&mut parallel_manager)); // This temporary must be used otherwise
+                                                                                              // network_tab blocks on NETWORK_INFO.lock
+            if let Some(traffic_history) = &mut *lock(&TRAFFIC_HISTORY)
+                && let Some(networks) = network_info_temp.as_ref().and_then(|network_info| network_info.networks.as_ref())
+            {
+                traffic_history.record(networks);
+                if latest_save.elapsed() > TRAFFIC_SAVE_INTERVAL {
+                    let _ = traffic_history.save();
+                    latest_save = Instant::now();
+                }
+            }
+            // Only opt-in as this runs `ss` every time
+            if config::get().top_talkers && !BACKGROUNDED.load(AtomicOrdering::Relaxed) && (latest_top_talkers.is_none() || latest_top_talkers.unwrap().elapsed() > INTERVAL) {
+                let top_talkers = remote::local(|| parallel_manager.top_talkers());
+                *lock(&TOP_TALKERS) = top_talkers;
+                latest_top_talkers = Some(Instant::now());
+            }
+            // Saved networks rarely change, so they're read as often as the
+            // Wi-Fi networks are scanned
+            if latest_wifi_profiles.is_none_or(|latest_wifi_profiles| latest_wifi_profiles.elapsed() > intervals.wifi) {
+                let wifi_profiles = remote::local(|| parallel_manager.wifi_profiles());
+                *lock(&WIFI_PROFILES) = wifi_profiles;
+                latest_wifi_profiles = Some(Instant::now());
+            }
+            *lock(&NETWORK_INFO) = network_info_temp;
+            *lock(&NETWORK_INFO_READ_AT) = latest_refresh;
         }
     });
 
@@ -174,33 +410,52 @@ fn run_app<B: ratatui::backend::Backend>(terminal: &mut Terminal<B>) {
         component_ordering:    SortByComponent::Temperature(Ordering::Descending),
         shift_pressed:         false,
         kill_current_process:  false,
+        force_kill:            false,
         more_information:      false,
+        process_view:          ProcessView::default(),
+        toggle_background_io:  false,
+        renice:                None,
+        audio_action:          None,
+        start_benchmark:       false,
         process_to_kill:       None,
         confirm_kill:          None,
+        process_to_terminate:  None,
+        kill_report:           None,
+        battery_alerts:        backend::BatteryAlerts::new(config::get().battery_alerts),
+        temperature_extremes:  if config::get().persist_temperatures && !remote::is_remote() {
+            backend::TemperatureExtremes::load()
+        } else {
+            backend::TemperatureExtremes::default()
+        },
+        latest_alert:          None,
+        dump_popup:            false,
+        network_filter:        String::new(),
+        editing_filter:        false,
+        hide_hidden_wifis:     false,
         cpu_dataset:           HashMap::new(),
         ram_dataset:           vec![],
         swap_dataset:          vec![],
+        load_dataset:          vec![],
+        load_average:          None,
+        restored_cpu_history:  HashMap::new(),
+        focused:               true,
+        latest_input:          Instant::now(),
+        show_overhead:         false,
+        tabs:                  vec![],
+        mouse_click:           None,
     };
 
     let mut latest_update = Instant::now();
+    let mut latest_alert_check: Option<Instant> = None;
     let mut elapsed: Duration;
 
     // Note: This assumes that the amount of RAM and SWAP stays constant. I
-    // would guess the chance of this breaking is quite low (I hope)
-    #[allow(clippy::cast_precision_loss)]
-    #[allow(clippy::while_float)]
-    if let Some(memory_info) = app_state.manager.memory_information() {
-        app_state.ram_important_digits = Some(memory_info.total_memory as f64);
-        while app_state.ram_important_digits.unwrap() > 1000.0 {
-            app_state.ram_important_digits = Some(app_state.ram_important_digits.unwrap() / 1000.0);
-        }
-        app_state.ram_important_digits = Some(app_state.ram_important_digits.unwrap().floor());
-
-        app_state.swap_important_digits = Some(memory_info.total_swap as f64);
-        while app_state.swap_important_digits.unwrap() > 1000.0 {
-            app_state.swap_important_digits = Some(app_state.swap_important_digits.unwrap() / 1000.0);
-        }
-        app_state.swap_important_digits = Some(app_state.swap_important_digits.unwrap().floor());
+    // would guess the chance of this breaking is quite low (I hope). The
+    // readings of another machine only arrive later, so there the loop
+    // sets them
+    if let Some(memory_info) = remote::memory_information(&mut app_state.manager) {
+        app_state.ram_important_digits = Some(important_digits(memory_info.total_memory));
+        app_state.swap_important_digits = Some(important_digits(memory_info.total_swap));
     }
 
     let welcome_parts = [
@@ -219,14 +474,14 @@ The tabs can be navigated using the left and right arrow keys. They are shown at
 
 The paragraphs can be scrolled using either the up and down arrow or the scroll wheel.
 
-The lists can be scrolled in the same way paragraphs can be, but they (sometimes) offer an extra element of interactivity: sorting. If you want to sort a list by a certain property, look out for the list header, where different properties are listed. If the list can be sorted after a certain property, there is a pair of square brackets containing a letter next to it. If you press this letter in its small form (without shift), the list is sorted after that property in ascending order. If you press the letter in its capital form (with shift), the list is sorted in descending order.
+The lists can be scrolled in the same way paragraphs can be, but they (sometimes) offer an extra element of interactivity: sorting. If you want to sort a list by a certain property, look out for the list header, where different properties are listed. If the list can be sorted after a certain property, there is a pair of square brackets containing a letter next to it. If you press this letter in its small form (without shift), the list is sorted after that property in ascending order. If you press the letter in its capital form (with shift), the list is sorted in descending order. Clicking on a property in the header sorts the list by it too, and clicking it again reverses the order.
 
-To exit the program, press 'q' or Esc.
+To exit the program, press 'q' or Esc. To see how much crossinfo itself uses, press F12.
 ",
     ];
 
     loop {
-        let _ = terminal.draw(|f| {
+        terminal.draw(|f| {
             let height = f.size().height as usize;
             let width = f.size().width as usize;
             let welcome_text = welcome_parts[0].to_string()
@@ -247,14 +502,16 @@ To exit the program, press 'q' or Esc.
                     .wrap(Wrap { trim: false }),
                 f.size(),
             );
-        });
-        if crossterm::event::poll(Duration::from_millis(0)).unwrap() {
+        })?;
+        if crossterm::event::poll(Duration::from_millis(0))? {
             if let Ok(Event::Key(event)) = crossterm::event::read() {
                 match event.code {
                     KeyCode::Char('q') | KeyCode::Esc => {
-                        sender.send(()).unwrap();
-                        thread.join().unwrap();
-                        return;
+                        // The thread only fails if it panicked, and then the panic hook has
+                        // already ended the program
+                        let _ = sender.send(());
+                        let _ = thread.join();
+                        return Ok(());
                     }
                     KeyCode::Enter => {
                         break;
@@ -266,106 +523,172 @@ To exit the program, press 'q' or Esc.
     }
     app_state.starting_time = Instant::now(); // I don't want there to be a big gap in the data if the tutorial screen is
                                               // read
+    if config::get().persist_history
+        && !remote::is_remote()
+        && let Some(history) = history::load()
+    {
+        let scale = |points: DataPoints, important_digits: Option<f64>| points.into_iter().map(|(x, y)| (x, y * important_digits.unwrap_or_default())).collect();
+        app_state.ram_dataset = scale(history.ram, app_state.ram_important_digits);
+        app_state.swap_dataset = scale(history.swap, app_state.swap_important_digits);
+        app_state.restored_cpu_history = history.cpu;
+    }
 
-    let mut accumulator = 0;
     loop {
-        // Code to test FPS
-        // TODO delete this
-        let seconds_passed = app_state.starting_time.elapsed().as_secs();
-        let mut fps = FPS.lock().unwrap();
-        if let Some(current_fps) = fps.get_mut(seconds_passed as usize)
-            && *current_fps > 0
-        {
-            *current_fps += 1;
-        } else {
-            accumulator += 1;
-            if accumulator == 5 {
-                app_state.current_tab += 1;
-                accumulator = 0;
-            }
-            if app_state.current_tab == 8 {
-                std::fs::write("log.txt", format!("{fps:#?}")).expect("wtf");
-                panic!();
-            }
-            fps[seconds_passed as usize] = 1;
-        }
-
-        let _ = terminal.draw(|f| ui(f, &mut app_state));
+        terminal.draw(|f| ui(f, &mut app_state))?;
         app_state.confirm_kill = None;
+        app_state.audio_action = None;
+        app_state.start_benchmark = false;
         app_state.shift_pressed = false;
+        app_state.dump_popup = false;
+        app_state.mouse_click = None;
 
         elapsed = app_state.starting_time.elapsed();
 
-        if let Some(cpu_info) = app_state.manager.cpu_information()
-            && let Some(memory_info) = app_state.manager.memory_information()
+        if let Some(cpu_info) = remote::cpu_information(&mut app_state.manager)
+            && let Some(memory_info) = remote::memory_information(&mut app_state.manager)
         {
             if app_state.cpu_dataset.is_empty() {
                 latest_update = Instant::now();
                 for cpu_core in cpu_info {
-                    app_state.cpu_dataset.insert(cpu_core.clone(), vec![(elapsed.as_secs_f64(), f64::from(cpu_core.usage))]);
+                    let mut dataset = app_state.restored_cpu_history.remove(&(cpu_core.manufacturer.clone(), cpu_core.model.clone())).unwrap_or_default();
+                    dataset.push((elapsed.as_secs_f64(), f64::from(cpu_core.usage)));
+                    app_state.cpu_dataset.insert(cpu_core, dataset);
                 }
-            } else if latest_update.elapsed() > INTERVAL {
+            } else if latest_update.elapsed() > config::get().intervals.cpu {
                 latest_update = Instant::now();
+                if let Some(load) = remote::load_average(&app_state.manager)
+                    && !cpu_info.is_empty()
+                {
+                    #[allow(clippy::cast_precision_loss)]
+                    app_state.load_dataset.push((elapsed.as_secs_f64(), load.one / cpu_info.len() as f64 * 100.0));
+                    app_state.load_average = Some(load);
+                }
                 for cpu_core in cpu_info {
-                    app_state
-                        .cpu_dataset
-                        .get_mut(&cpu_core)
-                        .expect("The core should exist")
-                        .push((elapsed.as_secs_f64(), f64::from(cpu_core.usage)));
+                    let usage = f64::from(cpu_core.usage);
+                    app_state.cpu_dataset.entry(cpu_core).or_default().push((elapsed.as_secs_f64(), usage));
                 }
 
+                let ram_important_digits = *app_state.ram_important_digits.get_or_insert_with(|| important_digits(memory_info.total_memory));
+                let swap_important_digits = *app_state.swap_important_digits.get_or_insert_with(|| important_digits(memory_info.total_swap));
                 app_state.ram_dataset.push((elapsed.as_secs_f64(), match memory_info.total_memory {
                     // This is highly unlikely to ever trigger as computers tend to have memory
                     0 => 0.0,
                     #[allow(clippy::cast_precision_loss)]
-                    _ => (memory_info.used_memory as f64 / memory_info.total_memory as f64) * app_state.ram_important_digits.unwrap(),
+                    _ => (memory_info.used_memory as f64 / memory_info.total_memory as f64) * ram_important_digits,
                 }));
 
                 app_state.swap_dataset.push((elapsed.as_secs_f64(), match memory_info.total_swap {
                     0 => 0.0,
                     #[allow(clippy::cast_precision_loss)]
-                    _ => (memory_info.used_swap as f64 / memory_info.total_swap as f64) * app_state.swap_important_digits.unwrap(),
+                    _ => (memory_info.used_swap as f64 / memory_info.total_swap as f64) * swap_important_digits,
                 }));
+
+                let chart_points = config::get().chart_points;
+                for dataset in app_state
+                    .cpu_dataset
+                    .values_mut()
+                    .chain([&mut app_state.ram_dataset, &mut app_state.swap_dataset, &mut app_state.load_dataset])
+                {
+                    downsample::downsample(dataset, chart_points);
+                }
+            }
+        }
+
+        if (latest_alert_check.is_none() || latest_alert_check.unwrap().elapsed() > INTERVAL)
+            && let Some(batteries) = remote::battery_information(&app_state.manager)
+        {
+            latest_alert_check = Some(Instant::now());
+            for alert in app_state.battery_alerts.check(&batteries) {
+                if config::get().notifications {
+                    let _ = alert.notify();
+                }
+                app_state.latest_alert = Some((Instant::now(), alert));
             }
         }
 
-        if crossterm::event::poll(Duration::from_millis(0)).unwrap() {
-            match crossterm::event::read() {
+        // Nobody is looking at a backgrounded terminal, and while idle nothing
+        // changes between the points of the charts, so instead of drawing
+        // again right away this waits for input for a while
+        let backgrounded = !app_state.focused;
+        BACKGROUNDED.store(backgrounded, AtomicOrdering::Relaxed);
+        let timeout = if backgrounded {
+            config::get().intervals.background
+        } else if app_state.latest_input.elapsed() > IDLE_AFTER {
+            config::get().intervals.cpu
+        } else {
+            Duration::ZERO
+        };
+        if crossterm::event::poll(timeout)? {
+            let event = crossterm::event::read();
+            if matches!(event, Ok(Event::Key(_) | Event::Mouse(_))) {
+                app_state.latest_input = Instant::now();
+                app_state.focused = true;
+            }
+            match event {
+                Ok(Event::Key(event)) if app_state.editing_filter => match event.code {
+                    KeyCode::Char(chr) => app_state.network_filter.push(chr),
+                    KeyCode::Backspace => {
+                        app_state.network_filter.pop();
+                    }
+                    KeyCode::Enter => app_state.editing_filter = false,
+                    KeyCode::Esc => {
+                        app_state.network_filter.clear();
+                        app_state.editing_filter = false;
+                    }
+                    _ => (),
+                },
                 Ok(Event::Key(event)) => match event.code {
                     KeyCode::Char('q') | KeyCode::Esc => {
-                        sender.send(()).unwrap();
-                        thread.join().unwrap();
-                        return;
+                        // The thread only fails if it panicked, and then the panic hook has
+                        // already ended the program
+                        let _ = sender.send(());
+                        let _ = thread.join();
+                        if config::get().persist_history && !remote::is_remote() {
+                            let _ = history::save(&chart_history(&app_state), app_state.starting_time.elapsed().as_secs_f64());
+                        }
+                        if config::get().persist_temperatures && !remote::is_remote() {
+                            let _ = app_state.temperature_extremes.save();
+                        }
+                        return Ok(());
                     }
                     KeyCode::Char(chr) => match chr {
-                        'c' => match app_state.current_tab {
-                            6 => app_state.process_ordering = SortByProcess::CpuUsage(Ordering::Ascending),
-                            7 => app_state.component_ordering = SortByComponent::Critical(Ordering::Ascending),
+                        'c' => match app_state.current_category() {
+                            Some("processes") => app_state.process_ordering = SortByProcess::CpuUsage(Ordering::Ascending),
+                            Some("components") => app_state.component_ordering = SortByComponent::Critical(Ordering::Ascending),
                             _ => (),
                         },
-                        'C' => match app_state.current_tab {
-                            6 => app_state.process_ordering = SortByProcess::CpuUsage(Ordering::Descending),
-                            7 => app_state.component_ordering = SortByComponent::Critical(Ordering::Descending),
+                        'C' => match app_state.current_category() {
+                            Some("processes") => app_state.process_ordering = SortByProcess::CpuUsage(Ordering::Descending),
+                            Some("components") => app_state.component_ordering = SortByComponent::Critical(Ordering::Descending),
                             _ => (),
                         },
-                        'm' => {
-                            app_state.process_ordering = SortByProcess::MemoryUsage(Ordering::Ascending);
-                        }
-                        'M' => {
-                            app_state.process_ordering = SortByProcess::MemoryUsage(Ordering::Descending);
-                        }
+                        'm' => match app_state.current_category() {
+                            Some("components") => app_state.component_ordering = SortByComponent::Maximum(Ordering::Ascending),
+                            _ => app_state.process_ordering = SortByProcess::MemoryUsage(Ordering::Ascending),
+                        },
+                        'M' => match app_state.current_category() {
+                            Some("components") => app_state.component_ordering = SortByComponent::Maximum(Ordering::Descending),
+                            _ => app_state.process_ordering = SortByProcess::MemoryUsage(Ordering::Descending),
+                        },
                         's' => {
                             app_state.process_ordering = SortByProcess::SwapUsage(Ordering::Ascending);
                         }
                         'S' => {
                             app_state.process_ordering = SortByProcess::SwapUsage(Ordering::Descending);
                         }
-                        'r' => {
-                            app_state.process_ordering = SortByProcess::Runtime(Ordering::Ascending);
-                        }
+                        'r' => match app_state.current_category() {
+                            Some("components") => app_state.temperature_extremes.clear(),
+                            _ => app_state.process_ordering = SortByProcess::Runtime(Ordering::Ascending),
+                        },
                         'R' => {
                             app_state.process_ordering = SortByProcess::Runtime(Ordering::Descending);
                         }
+                        'u' => {
+                            app_state.process_ordering = SortByProcess::CpuTime(Ordering::Ascending);
+                        }
+                        'U' => {
+                            app_state.process_ordering = SortByProcess::CpuTime(Ordering::Descending);
+                        }
                         't' => {
                             app_state.component_ordering = SortByComponent::Temperature(Ordering::Ascending);
                         }
@@ -375,13 +698,53 @@ To exit the program, press 'q' or Esc.
                         'k' => {
                             app_state.kill_current_process = true;
                         }
+                        'K' => {
+                            app_state.kill_current_process = true;
+                            app_state.force_kill = true;
+                        }
                         'i' => {
                             app_state.more_information = true;
                         }
+                        'o' => {
+                            app_state.process_view.extra_columns = !app_state.process_view.extra_columns;
+                        }
+                        'a' => {
+                            app_state.process_view.aggregate_children = !app_state.process_view.aggregate_children;
+                        }
+                        'b' => match app_state.current_category() {
+                            Some("disks") => app_state.start_benchmark = true,
+                            _ => app_state.toggle_background_io = true,
+                        },
+                        '<' => {
+                            app_state.renice = Some(-RENICE_STEP);
+                        }
+                        '>' => {
+                            app_state.renice = Some(RENICE_STEP);
+                        }
+                        '+' => {
+                            app_state.audio_action = Some(AudioAction::VolumeUp);
+                        }
+                        '-' => {
+                            app_state.audio_action = Some(AudioAction::VolumeDown);
+                        }
+                        'd' => {
+                            app_state.audio_action = Some(AudioAction::MakeDefault);
+                        }
+                        'w' => {
+                            app_state.dump_popup = true;
+                        }
+                        '/' if app_state.current_category() == Some("network") => {
+                            app_state.editing_filter = true;
+                            app_state.current_line = 0;
+                        }
+                        'h' if app_state.current_category() == Some("network") => {
+                            app_state.hide_hidden_wifis = !app_state.hide_hidden_wifis;
+                        }
                         'x' => {
                             app_state.more_information = false;
                             app_state.kill_current_process = false;
                             app_state.process_to_kill = None;
+                            app_state.kill_report = None;
                         }
                         'y' => {
                             app_state.confirm_kill = Some(true);
@@ -398,6 +761,8 @@ To exit the program, press 'q' or Esc.
                         // This just straight up doesn't work
                         app_state.shift_pressed = true;
                     }
+                    KeyCode::Enter => app_state.start_benchmark = true,
+                    KeyCode::F(12) => app_state.show_overhead = !app_state.show_overhead,
                     KeyCode::Up => app_state.current_line = app_state.current_line.saturating_sub(1),
                     KeyCode::Down => app_state.current_line = app_state.current_line.saturating_add(1),
                     KeyCode::Left => {
@@ -405,17 +770,23 @@ To exit the program, press 'q' or Esc.
                         app_state.current_line = 0;
                     }
                     KeyCode::Right => {
-                        if app_state.current_tab < backend::Tab::COUNT - 1 {
+                        if app_state.current_tab + 1 < app_state.tabs.len() {
                             app_state.current_tab += 1;
                         }
                         app_state.current_line = 0;
                     }
                     _ => (),
                 },
+                Ok(Event::FocusLost) => app_state.focused = false,
+                Ok(Event::FocusGained) => {
+                    app_state.focused = true;
+                    app_state.latest_input = Instant::now();
+                }
                 Ok(Event::Mouse(event)) => match event.kind {
                     // TODO: Limit scrolling
                     MouseEventKind::ScrollDown => app_state.current_line = app_state.current_line.saturating_add(1),
                     MouseEventKind::ScrollUp => app_state.current_line = app_state.current_line.saturating_sub(1),
+                    MouseEventKind::Down(MouseButton::Left) => app_state.mouse_click = Some((event.column, event.row)),
                     _ => (),
                 },
                 _ => (),
@@ -424,8 +795,31 @@ To exit the program, press 'q' or Esc.
     }
 }
 
-fn format_duration(duration: &Duration) -> String {
-    format!("{:0>2}:{:0>2}:{:0>2}", duration.as_secs() / 3600, (duration.as_secs() / 60) % 60, duration.as_secs() % 60)
+fn kill_report(manager: &mut backend::Manager, process_name: &str, id: backend::ProcessId) -> String {
+    if remote::is_remote() {
+        return if remote::kill_process(id) {
+            format!(r#"Asked the other machine to kill the process "{process_name}""#)
+        } else {
+            REMOTE_KILL.to_string()
+        };
+    }
+    // Its PID might belong to another process by now
+    if !manager.is_running(id) {
+        return format!(r#"The process "{process_name}" had already quit"#);
+    }
+    if manager.kill_process(id.pid) {
+        match manager.wait_process(id.pid, KILL_TIMEOUT) {
+            Some(backend::ProcessExit::Exited) => format!(r#"The process "{process_name}" was killed"#),
+            Some(backend::ProcessExit::TimedOut) => format!(r#"The process "{process_name}" is still running {} seconds after being killed"#, KILL_TIMEOUT.as_secs()),
+            None => format!(r#"The process "{process_name}" was sent the kill signal"#),
+        }
+    } else {
+        format!(r#"The process "{process_name}" couldn't be killed, it might belong to another user"#)
+    }
+}
+
+fn format_time(time: std::time::SystemTime) -> String {
+    chrono::DateTime::<chrono::Local>::from(time).format("%Y-%m-%d %H:%M:%S").to_string()
 }
 
 // TODO: Convert as much as possible to this function
@@ -437,10 +831,64 @@ fn format_or_unknown<T>(opt: Option<T>, formatter: &impl Fn(T) -> String) -> Str
     opt.map_or("unknown".to_string(), formatter)
 }
 
-static FPS: Mutex<[u16; 40]> = Mutex::new([0; 40]);
+// Like uptime prints it
+fn format_load_average(load: backend::LoadAverage) -> String {
+    localize(&format!("{:.2}, {:.2}, {:.2}", load.one, load.five, load.fifteen))
+}
+
+/// Applies the configured locale to numbers formatted with format!
+fn localize(text: &str) -> String {
+    config::get().number_format.localize(text)
+}
+
+fn format_bytes(bytes: u64) -> String {
+    localize(&config::get().byte_unit.format(bytes))
+}
+
+/// The leading digits of `total` in steps of 1000, e.g. 16 for 16 GB. The
+/// charts are scaled to them so the labels line up with round numbers
+#[allow(clippy::cast_precision_loss)]
+#[allow(clippy::while_float)]
+fn important_digits(total: u64) -> f64 {
+    let mut digits = total as f64;
+    while digits > 1000.0 {
+        digits /= 1000.0;
+    }
+    digits.floor()
+}
+
+fn format_temperature(celsius: f32, precision: usize) -> String {
+    localize(&config::get().temperature_unit.format(celsius, precision))
+}
+
+// A panic while one of the caches is locked shouldn't take every later
+// frame down with it
+fn lock<T>(mutex: &Mutex<T>) -> std::sync::MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+}
 
 fn ui(f: &mut Frame, app_state: &mut AppState) {
-    let titles = backend::Tab::iter().map(|tab| Line::from(tab.to_string())).collect::<Vec<Line>>();
+    // They run on this machine, like the custom tabs
+    let plugins = remote::local(|| Some(app_state.manager.plugin_information())).unwrap_or_default();
+    // Whether the other machine has a battery isn't known here
+    app_state.tabs = backend::CATEGORIES
+        .iter()
+        .filter(|category| DRAWN_CATEGORIES.contains(&category.id) && (remote::is_remote() || category.is_available(&app_state.manager)))
+        .map(TabKind::Category)
+        .chain((0..plugins.len()).map(TabKind::Plugin))
+        .chain((0..config::get().custom_tabs.len()).map(TabKind::Custom))
+        .collect();
+    app_state.current_tab = app_state.current_tab.min(app_state.tabs.len().saturating_sub(1));
+    let titles = app_state
+        .tabs
+        .iter()
+        .map(|tab| match tab {
+            TabKind::Category(category) => category.name.to_string(),
+            TabKind::Plugin(index) => plugins[*index].reading.as_ref().map_or_else(|| plugins[*index].name.clone(), |reading| reading.title.clone()),
+            TabKind::Custom(index) => config::get().custom_tabs[*index].title.clone(),
+        })
+        .map(Line::from)
+        .collect::<Vec<Line>>();
 
     let size = f.size();
 
@@ -451,7 +899,12 @@ fn ui(f: &mut Frame, app_state: &mut AppState) {
 
     let cpu_vertical_chunks = Layout::default()
         .direction(Direction::Vertical)
-        .constraints([Constraint::Percentage(20), Constraint::Percentage(80)])
+        .constraints([Constraint::Percentage(20), Constraint::Percentage(25), Constraint::Percentage(55)])
+        .split(chunks[1]);
+
+    let memory_chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Percentage(75), Constraint::Percentage(25)])
         .split(chunks[1]);
 
     let network_chunks = Layout::default()
@@ -463,8 +916,26 @@ fn ui(f: &mut Frame, app_state: &mut AppState) {
 
     f.render_widget(block, size);
 
+    let mut tabs_block = Block::default().borders(Borders::ALL);
+    if let Some((raised_at, alert)) = &app_state.latest_alert
+        && raised_at.elapsed() < ALERT_DURATION
+    {
+        tabs_block = tabs_block.title(
+            Title::from(Span::styled(
+                format!(" {} ", alert.message),
+                Style::default().fg(Color::Black).bg(match alert.severity {
+                    backend::AlertSeverity::Warning => Color::Yellow,
+                    backend::AlertSeverity::Critical => Color::Red,
+                }),
+            ))
+            .alignment(Alignment::Right),
+        );
+    }
+    if let Some(status) = remote::status() {
+        tabs_block = tabs_block.title(status);
+    }
     let tabs = Tabs::new(titles)
-        .block(Block::default().borders(Borders::ALL))
+        .block(tabs_block)
         .select(app_state.current_tab)
         .highlight_style(Style::default().add_modifier(Modifier::BOLD).bg(Color::White).fg(Color::Black));
 
@@ -475,14 +946,65 @@ fn ui(f: &mut Frame, app_state: &mut AppState) {
     let mut list_state = ListState::default();
     list_state.select(Some(app_state.current_line as usize));
 
-    match app_state.current_tab {
-        0 => f.render_widget(system_tab(&mut app_state.manager, app_state.current_line), chunks[1]),
+    // How far into the title of a list filling the tab a click on it was,
+    // which starts after the corner of the border
+    let header_click = app_state
+        .mouse_click
+        .filter(|&(column, row)| row == chunks[1].y && column > chunks[1].x)
+        .map(|(column, _)| column - chunks[1].x - 1);
+
+    match app_state.current_category() {
+        Some("overview") => {
+            let overview_tab_widgets = overview_tab(&mut app_state.manager);
+            let overview_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3), Constraint::Min(0), Constraint::Length(6)])
+                .split(chunks[1]);
+            let gauge_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(overview_chunks[0]);
+            let process_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(overview_chunks[1]);
+            f.render_widget(overview_tab_widgets.0, gauge_chunks[0]);
+            f.render_widget(overview_tab_widgets.1, gauge_chunks[1]);
+            f.render_widget(overview_tab_widgets.2, process_chunks[0]);
+            f.render_widget(overview_tab_widgets.3, process_chunks[1]);
+            f.render_widget(overview_tab_widgets.4, overview_chunks[2]);
+        }
+        Some("system") => {
+            f.render_widget(system_tab(&mut app_state.manager, app_state.current_line), chunks[1]);
+            if app_state.more_information {
+                let boot_times = boot_times_popup(&app_state.manager);
+                if app_state.dump_popup {
+                    dump_popup("Boot times", &boot_times);
+                }
+                f.render_widget(Clear, popup_rect);
+                f.render_widget(
+                    Paragraph::new(boot_times)
+                        .block(
+                            Block::default()
+                                .title(Title::from("[w]rite to a file").alignment(Alignment::Left))
+                                .title(Title::from("[x]").alignment(Alignment::Right))
+                                .title(Title::from("Boot times").alignment(Alignment::Center))
+                                .borders(Borders::ALL),
+                        )
+                        .style(Style::default().fg(Color::White).bg(Color::Black))
+                        .alignment(Alignment::Left)
+                        .wrap(Wrap { trim: false }),
+                    popup_rect,
+                );
+            }
+        }
         #[allow(clippy::cast_possible_truncation)]
-        1 => {
+        Some("cpu") => {
             let cpu_tab_widgets = cpu_tab(
                 &mut app_state.manager,
                 app_state.starting_time,
                 &app_state.cpu_dataset.iter().map(|(cpu_core, dataset)| (cpu_core, dataset.as_slice())).collect(),
+                (app_state.load_average, &app_state.load_dataset),
             );
 
             let cpu_list_chunks = Layout::default()
@@ -493,36 +1015,79 @@ fn ui(f: &mut Frame, app_state: &mut AppState) {
             let cpu_chart_chunks = Layout::default()
                 .direction(Direction::Horizontal)
                 .constraints(vec![Constraint::Percentage(100 / cpu_tab_widgets.len() as u16); cpu_tab_widgets.len()])
-                .split(cpu_vertical_chunks[1]);
+                .split(cpu_vertical_chunks[2]);
 
             for (index, (list, chart)) in cpu_tab_widgets.iter().enumerate() {
                 f.render_stateful_widget(list.clone(), cpu_list_chunks[index], &mut list_state);
                 f.render_widget(chart.clone(), cpu_chart_chunks[index]);
             }
+            f.render_widget(cpu_state_tab(&mut app_state.manager), cpu_vertical_chunks[1]);
+            if app_state.more_information {
+                let text = cpu_features_popup(&app_state.manager);
+                if app_state.dump_popup {
+                    dump_popup("CPU features", &text);
+                }
+                f.render_widget(Clear, popup_rect);
+                f.render_widget(
+                    Paragraph::new(text)
+                        .block(
+                            Block::default()
+                                .title(Title::from("[w]rite to a file").alignment(Alignment::Left))
+                                .title(Title::from("[x]").alignment(Alignment::Right))
+                                .title(Title::from("CPU features").alignment(Alignment::Center))
+                                .borders(Borders::ALL),
+                        )
+                        .style(Style::default().fg(Color::White).bg(Color::Black))
+                        .alignment(Alignment::Left)
+                        .wrap(Wrap { trim: false }),
+                    popup_rect,
+                );
+            }
         }
-        2 => f.render_widget(
-            memory_tab(
+        Some("memory") => {
+            let memory_tab_widgets = memory_tab(
                 &mut app_state.manager,
                 app_state.starting_time,
                 app_state.ram_dataset.as_slice(),
                 app_state.swap_dataset.as_slice(),
                 app_state.ram_important_digits,
                 app_state.swap_important_digits,
-            ),
-            chunks[1],
-        ),
-        3 => f.render_widget(disk_tab(&mut app_state.manager, app_state.current_line), chunks[1]),
-        4 => f.render_widget(battery_tab(&app_state.manager, app_state.current_line), chunks[1]),
-        5 => {
-            let network_tab_widgets = network_tab(app_state.more_information, app_state.current_line);
+            );
+            let memory_list_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(30), Constraint::Percentage(40), Constraint::Percentage(30)])
+                .split(memory_chunks[1]);
+            f.render_widget(memory_tab_widgets.0, memory_chunks[0]);
+            f.render_widget(memory_tab_widgets.3, memory_list_chunks[0]);
+            f.render_stateful_widget(memory_tab_widgets.1, memory_list_chunks[1], &mut list_state);
+            f.render_widget(memory_tab_widgets.2, memory_list_chunks[2]);
+        }
+        Some("disks") => f.render_widget(disk_tab(&mut app_state.manager, app_state.current_line, app_state.start_benchmark), chunks[1]),
+        Some("battery") => f.render_widget(battery_tab(&app_state.manager, app_state.current_line), chunks[1]),
+        Some("network") => {
+            let network_tab_widgets = network_tab(
+                app_state.more_information,
+                app_state.current_line,
+                &app_state.network_filter,
+                app_state.editing_filter,
+                app_state.hide_hidden_wifis,
+            );
             f.render_widget(network_tab_widgets.0, network_chunks[0]);
             f.render_stateful_widget(network_tab_widgets.1, network_chunks[1], &mut list_state);
             f.render_stateful_widget(network_tab_widgets.2, network_chunks[2], &mut list_state);
             if let Some(text) = network_tab_widgets.3 {
+                if app_state.dump_popup {
+                    dump_popup("Network", &text);
+                }
                 f.render_widget(Clear, popup_rect);
                 f.render_widget(
                     Paragraph::new(text)
-                        .block(Block::default().title(Title::from("[x]").alignment(Alignment::Right)).borders(Borders::ALL))
+                        .block(
+                            Block::default()
+                                .title(Title::from("[w]rite to a file").alignment(Alignment::Left))
+                                .title(Title::from("[x]").alignment(Alignment::Right))
+                                .borders(Borders::ALL),
+                        )
                         .style(Style::default().fg(Color::White).bg(Color::Black))
                         .alignment(Alignment::Left)
                         .wrap(Wrap { trim: false }),
@@ -530,20 +1095,64 @@ fn ui(f: &mut Frame, app_state: &mut AppState) {
                 );
             }
         }
-        6 => {
-            let process_tab_widgets = process_tab(
+        Some("processes") => {
+            if let Some((process_name, id)) = app_state.process_to_terminate.take() {
+                let termination = if app_state.manager.is_running(id) { app_state.manager.terminate_gracefully(id.pid, config::get().kill_grace_period) } else { None };
+                app_state.kill_report = Some(match termination {
+                    Some(backend::Termination::Terminated) => format!(r#"The process "{process_name}" quit"#),
+                    Some(backend::Termination::Killed) => format!(r#"The process "{process_name}" didn't quit in time and was killed"#),
+                    Some(backend::Termination::Failed) => format!(r#"The process "{process_name}" couldn't be stopped, it might belong to another user"#),
+                    None => format!(r#"The process "{process_name}" had already quit"#),
+                });
+            }
+            // 'y' can also be pressed without a kill popup being open
+            if app_state.confirm_kill.is_some_and(|x| x)
+                && let Some((process_name, id)) = app_state.process_to_kill.take()
+            {
+                // The agent can only kill right away
+                if remote::is_remote() {
+                    app_state.kill_report = Some(kill_report(&mut app_state.manager, &process_name, id));
+                } else {
+                    app_state.kill_report = Some(format!(
+                        r#"Asking the process "{process_name}" to quit, it will be killed if it's still running after {} seconds"#,
+                        config::get().kill_grace_period.as_secs()
+                    ));
+                    app_state.process_to_terminate = Some((process_name, id));
+                }
+            }
+            let (process_list, process_popup) = process_tab(
                 &mut app_state.manager,
-                app_state.process_ordering,
+                &mut app_state.process_ordering,
+                header_click,
                 app_state.shift_pressed,
                 app_state.kill_current_process,
                 app_state.more_information,
-                app_state.current_line,
+                ProcessView {
+                    // The average of the latest reading of every core
+                    #[allow(clippy::cast_precision_loss)]
+                    cpu_usage: (!app_state.cpu_dataset.is_empty())
+                        .then(|| app_state.cpu_dataset.values().filter_map(|dataset| dataset.last()).map(|point| point.1).sum::<f64>() / app_state.cpu_dataset.len() as f64),
+                    load_average: app_state.load_average,
+                    ..app_state.process_view
+                },
+                app_state.toggle_background_io,
+                app_state.renice.take(),
+                &mut app_state.current_line,
             );
-            f.render_stateful_widget(process_tab_widgets.0, chunks[1], &mut list_state);
-            let popup_information: Option<(&str, String)> = match process_tab_widgets.1 {
-                Some(ProcessPopup::KillProcess { process_name, pid }) => {
+            app_state.toggle_background_io = false;
+            // The selection may have followed its process to another line
+            list_state.select(Some(app_state.current_line as usize));
+            f.render_stateful_widget(process_list, chunks[1], &mut list_state);
+            let popup_information: Option<(&str, String)> = match process_popup {
+                Some(ProcessPopup::KillProcess { process_name, id }) if app_state.force_kill => {
+                    app_state.force_kill = false;
+                    app_state.kill_current_process = false;
+                    app_state.kill_report = Some(kill_report(&mut app_state.manager, &process_name, id));
+                    app_state.kill_report.clone().map(|report| ("Kill process", report))
+                }
+                Some(ProcessPopup::KillProcess { process_name, id }) => {
                     if app_state.process_to_kill.is_none() {
-                        app_state.process_to_kill = Some((process_name, pid));
+                        app_state.process_to_kill = Some((process_name, id));
                     }
                     Some((
                         "Kill process?",
@@ -555,20 +1164,22 @@ fn ui(f: &mut Frame, app_state: &mut AppState) {
                         ),
                     ))
                 }
-                Some(ProcessPopup::MoreInformation { contents }) => Some(("More information", contents)),
+                Some(ProcessPopup::MoreInformation { contents }) => {
+                    if app_state.dump_popup {
+                        dump_popup("Process", &contents);
+                    }
+                    Some(("More information", contents))
+                }
                 Some(ProcessPopup::NoSelected) => Some(("No process selected!", "You don't have a process selected!".to_string())),
-                None => None,
+                None => app_state.kill_report.clone().map(|report| ("Kill process", report)),
             };
-            if app_state.confirm_kill.is_some_and(|x| x) {
-                app_state.manager.kill_process(app_state.process_to_kill.as_ref().expect("Pid should be set at this point. Report").1);
-                app_state.process_to_kill = None;
-            }
             if let Some((title, body)) = popup_information {
                 f.render_widget(Clear, popup_rect);
                 f.render_widget(
                     Paragraph::new(body)
                         .block(
                             Block::default()
+                                .title(Title::from(if title == "More information" { "[w]rite to a file" } else { "" }).alignment(Alignment::Left))
                                 .title(Title::from("[x]").alignment(Alignment::Right))
                                 .title(Title::from(title).alignment(Alignment::Center))
                                 .borders(Borders::ALL),
@@ -580,24 +1191,407 @@ fn ui(f: &mut Frame, app_state: &mut AppState) {
                 );
             }
         }
-        7 => f.render_stateful_widget(component_tab(&mut app_state.manager, app_state.component_ordering, app_state.shift_pressed), chunks[1], &mut list_state),
-        // 8 => f.render_widget(display_tab(&mut app_state.manager, app_state.current_line), chunks[1]),
-        // 9 => f.render_widget(bluetooth_tab(&mut app_state.manager, app_state.current_line), chunks[1]),
-        _ => unreachable!(),
+        Some("components") => {
+            let component_list = component_tab(
+                &mut app_state.manager,
+                &mut app_state.temperature_extremes,
+                &mut app_state.component_ordering,
+                header_click,
+                app_state.shift_pressed,
+                &mut app_state.current_line,
+            );
+            list_state.select(Some(app_state.current_line as usize));
+            f.render_stateful_widget(component_list, chunks[1], &mut list_state);
+        }
+        Some("audio") => {
+            let audio_tab_widgets = audio_tab(&app_state.manager, app_state.audio_action, app_state.current_line);
+            let audio_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .split(chunks[1]);
+            f.render_stateful_widget(audio_tab_widgets.0, audio_chunks[0], &mut list_state);
+            f.render_widget(audio_tab_widgets.1, audio_chunks[1]);
+        }
+        Some("benchmark") => {
+            let benchmark_tab_widgets = benchmark_tab(app_state.start_benchmark);
+            let benchmark_chunks = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints([Constraint::Length(3), Constraint::Min(0)])
+                .split(chunks[1]);
+            f.render_widget(benchmark_tab_widgets.0, benchmark_chunks[0]);
+            f.render_widget(benchmark_tab_widgets.1, benchmark_chunks[1]);
+        }
+        // Some("displays") => f.render_widget(display_tab(&mut app_state.manager, app_state.current_line), chunks[1]),
+        // Some("bluetooth") => f.render_widget(bluetooth_tab(&mut app_state.manager, app_state.current_line), chunks[1]),
+        Some(_) => unreachable!(),
+        None => match app_state.tabs.get(app_state.current_tab) {
+            Some(TabKind::Plugin(index)) => f.render_widget(plugin_tab(&plugins[*index], app_state.current_line), chunks[1]),
+            Some(TabKind::Custom(index)) => f.render_widget(custom_tab(*index, app_state.current_line), chunks[1]),
+            _ => (),
+        },
+    };
+
+    if app_state.show_overhead {
+        let (overhead, line_count) = overhead_overlay(&mut app_state.manager);
+        // In the top right corner, where it covers the least. Two more
+        // lines for the borders
+        let height = u16::try_from(line_count + 2).unwrap_or(u16::MAX).min(chunks[1].height);
+        let width = 48.min(chunks[1].width);
+        let overlay_rect = Rect::new(chunks[1].right() - width, chunks[1].y, width, height);
+        f.render_widget(Clear, overlay_rect);
+        f.render_widget(overhead, overlay_rect);
+    }
+}
+
+fn overhead_overlay<'a>(manager: &mut backend::Manager) -> (Paragraph<'a>, usize) {
+    // Reading the usage every frame would make the CPU usage jump around
+    static OWN_USAGE: Mutex<Option<(Option<backend::OwnUsage>, Instant)>> = Mutex::new(None);
+    let mut own_usage = lock(&OWN_USAGE);
+    if !own_usage.is_some_and(|(_, updated)| updated.elapsed() < INTERVAL) {
+        *own_usage = Some((manager.own_usage(), Instant::now()));
+    }
+    let usage = own_usage.and_then(|(usage, _)| usage);
+    drop(own_usage);
+
+    let mut lines = vec![
+        Line::from(vec![
+            Span::raw("CPU Usage: "),
+            Span::raw(format_or_unknown(usage, &|usage: backend::OwnUsage| format!("{}%", localize(&format!("{:.2}", usage.cpu_usage))))),
+        ]),
+        Line::from(vec![Span::raw("Memory Usage: "), Span::raw(format_or_unknown(usage, &|usage: backend::OwnUsage| format_bytes(usage.memory_usage)))]),
+        Line::from(Span::raw("Refresh cost (latest / average):")),
+    ];
+    lines.extend(overhead::costs().into_iter().map(|(subsystem, cost)| {
+        Line::from(Span::raw(format!(
+            "  {subsystem}: {} / {} ms",
+            localize(&format!("{:.2}", cost.latest.as_secs_f64() * 1000.0)),
+            localize(&format!("{:.2}", cost.average().as_secs_f64() * 1000.0))
+        )))
+    }));
+    let line_count = lines.len();
+    (
+        Paragraph::new(lines)
+            .block(Block::default().title("crossinfo itself").title(Title::from("[F12]").alignment(Alignment::Right)).borders(Borders::ALL))
+            .style(Style::default().fg(Color::White).bg(Color::Black)),
+        line_count,
+    )
+}
+
+// Everything here also has its own tab, this is just the landing page to
+// figure out which one to look at
+#[allow(clippy::cast_precision_loss)]
+fn overview_tab<'a>(manager: &mut backend::Manager) -> (Gauge<'a>, Gauge<'a>, List<'a>, List<'a>, Paragraph<'a>) {
+    // Refreshing every process on every frame is too slow
+    static LATEST_PROCESSES: Mutex<(Option<Vec<backend::ProcessInfo>>, Option<Instant>)> = Mutex::new((None, None));
+    const TOP_PROCESSES: usize = 5;
+
+    let formatter = format_bytes;
+    let usage_gauge = |title: &'a str, percentage: Option<f64>| {
+        Gauge::default()
+            .block(Block::default().title(title).borders(Borders::ALL))
+            .gauge_style(Style::default().fg(Color::Green).bg(Color::Black))
+            .label(percentage.map_or_else(|| "unknown".to_string(), |percentage| localize(&format!("{percentage:.1}%"))))
+            .ratio((percentage.unwrap_or_default() / 100.0).clamp(0.0, 1.0))
     };
+
+    let cpu_usage = manager
+        .cpu_information()
+        .filter(|cpus| !cpus.is_empty())
+        .map(|cpus| cpus.iter().map(|cpu| f64::from(cpu.usage)).sum::<f64>() / cpus.len() as f64);
+    let memory_usage = manager
+        .memory_information()
+        .filter(|memory| memory.total_memory != 0)
+        .map(|memory| memory.used_memory as f64 / memory.total_memory as f64 * 100.0);
+
+    let mut latest_processes = lock(&LATEST_PROCESSES);
+    if latest_processes.1.is_none() || latest_processes.1.unwrap().elapsed() > INTERVAL {
+        *latest_processes = (remote::process_information(manager), Some(Instant::now()));
+    }
+    let mut processes = latest_processes.0.clone().unwrap_or_default();
+    drop(latest_processes);
+
+    processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+    let top_cpu = processes
+        .iter()
+        .take(TOP_PROCESSES)
+        .map(|process| ListItem::new(format!("{:>6.1}%  {}", process.cpu_usage, process.name)))
+        .collect::<Vec<ListItem>>();
+    processes.sort_by_key(|process| std::cmp::Reverse(process.memory_usage));
+    let top_memory = processes
+        .iter()
+        .take(TOP_PROCESSES)
+        .map(|process| ListItem::new(format!("{:>10}  {}", formatter(process.memory_usage), process.name)))
+        .collect::<Vec<ListItem>>();
+
+    // There are no per disk IO statistics, so the fullest disk is the most
+    // interesting one
+    let fullest_disk = remote::disk_information(manager).and_then(|disks| {
+        disks
+            .into_iter()
+            .filter(|disk| disk.total != 0)
+            .max_by(|a, b| (a.used as f64 / a.total as f64).total_cmp(&(b.used as f64 / b.total as f64)))
+    });
+    let network_info = (*lock(&NETWORK_INFO)).clone();
+    let busiest_interface = network_info.and_then(|network_info| {
+        network_info
+            .networks?
+            .into_iter()
+            .max_by_key(|interface| interface.received_recently.unwrap_or_default() + interface.transmitted_recently.unwrap_or_default())
+    });
+    let hottest_component = manager
+        .component_information()
+        .and_then(|components| components.into_iter().max_by(|a, b| a.temperature.total_cmp(&b.temperature)));
+    let battery = remote::battery_information(manager).and_then(|batteries| batteries.into_iter().next());
+
+    let text = vec![
+        Line::from(vec![
+            Span::raw("Fullest Disk: "),
+            Span::raw(format_or_unknown(fullest_disk, &|disk| {
+                format!("{} ({} of {} used)", disk.mount_point, formatter(disk.used), formatter(disk.total))
+            })),
+        ]),
+        Line::from(vec![
+            Span::raw("Busiest Network Interface: "),
+            Span::raw(format_or_unknown(busiest_interface, &|interface| {
+                format!(
+                    "{} ({} received, {} sent)",
+                    interface.name,
+                    formatter(interface.received_recently.unwrap_or_default()),
+                    formatter(interface.transmitted_recently.unwrap_or_default())
+                )
+            })),
+        ]),
+        Line::from(vec![
+            Span::raw("Hottest Component: "),
+            Span::raw(format_or_unknown(hottest_component, &|component| {
+                format!("{} ({})", component.name, format_temperature(component.temperature, 1))
+            })),
+        ]),
+        Line::from(vec![
+            Span::raw("Battery: "),
+            Span::raw(battery.map_or_else(|| "none".to_string(), |battery| localize(&format!("{:.0}% ({})", battery.charge * 100.0, battery.state)))),
+        ]),
+    ];
+
+    (
+        usage_gauge("CPU", cpu_usage),
+        usage_gauge("Memory", memory_usage),
+        List::new(top_cpu)
+            .block(Block::default().title("Top CPU").borders(Borders::ALL))
+            .style(Style::default().fg(Color::White).bg(Color::Black)),
+        List::new(top_memory)
+            .block(Block::default().title("Top Memory").borders(Borders::ALL))
+            .style(Style::default().fg(Color::White).bg(Color::Black)),
+        Paragraph::new(text)
+            .block(Block::default().borders(Borders::ALL))
+            .style(Style::default().fg(Color::White).bg(Color::Black))
+            .alignment(Alignment::Left),
+    )
+}
+
+// Consecutive CPUs as ranges, like the kernel lists them: 0-7,16-23
+fn format_cpu_list(cpus: &[usize]) -> String {
+    let mut ranges: Vec<(usize, usize)> = vec![];
+    for &cpu in cpus {
+        match ranges.last_mut() {
+            Some((_, end)) if *end + 1 == cpu => *end = cpu,
+            _ => ranges.push((cpu, cpu)),
+        }
+    }
+    ranges
+        .into_iter()
+        .map(|(start, end)| if start == end { start.to_string() } else { format!("{start}-{end}") })
+        .collect::<Vec<String>>()
+        .join(",")
 }
 
 fn system_tab(manager: &mut backend::Manager, scroll: u16) -> Paragraph {
-    if let Some(system_info) = manager.system_information() {
+    // The boot history doesn't change while the program is running
+    static BOOT_HISTORY: std::sync::OnceLock<Option<Vec<backend::BootRecord>>> = std::sync::OnceLock::new();
+    static MANDATORY_ACCESS_CONTROL: std::sync::OnceLock<Option<Vec<backend::MandatoryAccessControlInfo>>> = std::sync::OnceLock::new();
+    // Only changes with a new kernel or microcode, which needs a reboot
+    static CPU_SECURITY: std::sync::OnceLock<Option<backend::CpuSecurityInfo>> = std::sync::OnceLock::new();
+    static SESSION_ACTIVITY: Mutex<(Option<backend::SessionActivity>, Option<Instant>)> = Mutex::new((None, None));
+    static NUMA: Mutex<(Option<backend::NumaInfo>, Option<Instant>)> = Mutex::new((None, None));
+    // New crashes are rare, there's no need to ask coredumpctl every second
+    static CRASH_REPORTS: Mutex<(Option<Vec<backend::CrashReport>>, Option<Instant>)> = Mutex::new((None, None));
+
+    let mut latest_session_activity = lock(&SESSION_ACTIVITY);
+    if latest_session_activity.1.is_none() || latest_session_activity.1.unwrap().elapsed() > INTERVAL {
+        *latest_session_activity = (remote::local(|| manager.session_activity()), Some(Instant::now()));
+    }
+    let session_activity = latest_session_activity.0;
+    drop(latest_session_activity);
+    let mut numa = lock(&NUMA);
+    if numa.1.is_none_or(|refreshed_at| refreshed_at.elapsed() > INTERVAL) {
+        *numa = (remote::local(|| manager.numa_information()), Some(Instant::now()));
+    }
+    let numa_lines = numa.0.as_ref().map_or_else(
+        || vec![Line::from(Span::raw("   unknown"))],
+        |numa| {
+            let hugepages = |hugepages: &[backend::HugePages]| {
+                hugepages
+                    .iter()
+                    .map(|pages| format!("{} pages: {} of {} free", format_bytes(pages.page_size), pages.free, pages.total))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            };
+            numa.nodes
+                .iter()
+                .map(|node| {
+                    format!(
+                        "   Node {}: {} of {} used, CPUs {}{}",
+                        node.id,
+                        format_bytes(node.used_memory),
+                        format_bytes(node.total_memory),
+                        format_cpu_list(&node.cpus),
+                        if node.hugepages.is_empty() { String::new() } else { format!(", {}", hugepages(&node.hugepages)) }
+                    )
+                })
+                .chain([
+                    format!("   Huge pages: {}", if numa.hugepages.is_empty() { "none".to_string() } else { hugepages(&numa.hugepages) }),
+                    format!("   Transparent huge pages: {}", to_string_or_unknown(numa.transparent_hugepages.as_ref())),
+                ])
+                .map(|line| Line::from(Span::raw(line)))
+                .collect()
+        },
+    );
+    drop(numa);
+    let mut crash_reports = lock(&CRASH_REPORTS);
+    if crash_reports.1.is_none() || crash_reports.1.unwrap().elapsed() > INTERVAL * 30 {
+        *crash_reports = (remote::local(|| manager.crash_reports(5)), Some(Instant::now()));
+    }
+    let crash_lines = crash_reports.0.as_ref().map_or_else(
+        || vec![Line::from(Span::raw("   unknown"))],
+        |reports| {
+            if reports.is_empty() {
+                vec![Line::from(Span::raw("   None"))]
+            } else {
+                reports
+                    .iter()
+                    .map(|report| {
+                        Line::from(Span::raw(format!(
+                            "   {}  {}{}{}",
+                            format_time(report.time),
+                            report.process_name,
+                            report.pid.map_or_else(String::new, |pid| format!(" (PID {pid})")),
+                            report.signal.as_ref().map_or_else(String::new, |signal| format!(", {signal}"))
+                        )))
+                    })
+                    .collect()
+            }
+        },
+    );
+    drop(crash_reports);
+
+    if let Some(system_info) = remote::system_information(manager) {
+        let boot_history = BOOT_HISTORY.get_or_init(|| remote::local(|| manager.boot_history(5)));
+        let mandatory_access_control = MANDATORY_ACCESS_CONTROL.get_or_init(|| remote::local(|| manager.mandatory_access_control_information()));
+        let cpu_security = CPU_SECURITY.get_or_init(|| remote::local(|| manager.cpu_security_information()));
         let text = [
             vec![
                 Line::from(vec![Span::raw("Operating System: "), Span::raw(to_string_or_unknown(system_info.os))]),
                 Line::from(vec![Span::raw("Operating System Version: "), Span::raw(to_string_or_unknown(system_info.os_version))]),
                 Line::from(vec![Span::raw("Kernel Version: "), Span::raw(to_string_or_unknown(system_info.kernel_version))]),
-                Line::from(vec![Span::raw("Uptime: "), Span::raw(format_duration(&system_info.uptime))]),
+                Line::from(vec![Span::raw("Uptime: "), Span::raw(backend::format_duration(system_info.uptime))]),
+                Line::from(vec![Span::raw("Load Average: "), Span::raw(format_or_unknown(system_info.load_average, &format_load_average))]),
+                Line::from(vec![
+                    Span::raw("Idle: "),
+                    Span::raw(match session_activity.and_then(|activity| activity.is_idle) {
+                        Some(true) => format_or_unknown(session_activity.and_then(|activity| activity.idle_time), &|idle_time| {
+                            format!("for {}", backend::format_duration(idle_time))
+                        }),
+                        Some(false) => "no".to_string(),
+                        None => "unknown".to_string(),
+                    }),
+                ]),
+                Line::from(vec![
+                    Span::raw("Screen Locked: "),
+                    Span::raw(format_or_unknown(session_activity.and_then(|activity| activity.is_locked), &|is_locked| {
+                        if is_locked { "yes" } else { "no" }.to_string()
+                    })),
+                ]),
+                Line::from(vec![
+                    Span::raw("Open Files: "),
+                    remote::local(|| manager.file_descriptor_information()).map_or_else(
+                        || Span::raw("unknown"),
+                        |usage| {
+                            let text = format!("{} / {}", usage.open, to_string_or_unknown(usage.limit));
+                            if usage.is_near_limit() {
+                                Span::styled(text, Style::default().fg(Color::Red))
+                            } else {
+                                Span::raw(text)
+                            }
+                        },
+                    ),
+                ]),
                 Line::from(Span::raw("Users: ")),
             ],
             system_info.users.iter().map(|user| Line::from(Span::raw(format!("   {user}\n")))).collect(),
+            vec![Line::from(Span::raw("Mandatory Access Control: "))],
+            mandatory_access_control.as_ref().map_or_else(
+                || vec![Line::from(Span::raw("   None"))],
+                |systems| {
+                    systems
+                        .iter()
+                        .map(|mac| {
+                            Line::from(Span::raw(format!(
+                                "   {} ({}{}{})",
+                                mac.system,
+                                mac.mode,
+                                mac.policy.as_ref().map_or_else(String::new, |policy| format!(", policy {policy}")),
+                                mac.profiles.as_ref().map_or_else(String::new, |profiles| format!(", {} profiles loaded", profiles.len()))
+                            )))
+                        })
+                        .collect()
+                },
+            ),
+            vec![Line::from(Span::raw("CPU Vulnerabilities: "))],
+            cpu_security.as_ref().map_or_else(
+                || vec![Line::from(Span::raw("   unknown"))],
+                |security| {
+                    security
+                        .vulnerabilities
+                        .iter()
+                        .map(|vulnerability| {
+                            let text = format!(
+                                "   {}: {}{}",
+                                vulnerability.name,
+                                vulnerability.status,
+                                vulnerability.details.as_ref().map_or_else(String::new, |details| format!(" ({details})"))
+                            );
+                            if vulnerability.status == backend::MitigationStatus::Vulnerable {
+                                Line::from(Span::styled(text, Style::default().fg(Color::Red)))
+                            } else {
+                                Line::from(Span::raw(text))
+                            }
+                        })
+                        .collect()
+                },
+            ),
+            vec![Line::from(Span::raw("NUMA Nodes: "))],
+            numa_lines,
+            vec![Line::from(Span::raw("Recent crashes: "))],
+            crash_lines,
+            vec![Line::from(Span::raw("Last boots: "))],
+            boot_history.as_ref().map_or_else(
+                || vec![Line::from(Span::raw("   unknown"))],
+                |boots| {
+                    boots
+                        .iter()
+                        .map(|boot| {
+                            Line::from(vec![Span::raw(format!("   {} - ", format_time(boot.boot_time))), match (boot.shutdown_time, boot.clean_shutdown) {
+                                // Windows doesn't log when a boot crashed
+                                (None, Some(false)) => Span::styled("crashed or lost power", Style::default().fg(Color::Red)),
+                                (None, _) => Span::raw("still running"),
+                                (Some(shutdown_time), Some(true)) => Span::raw(format!("{} (clean shutdown)", format_time(shutdown_time))),
+                                (Some(shutdown_time), Some(false)) => Span::styled(format!("{} (crashed or lost power)", format_time(shutdown_time)), Style::default().fg(Color::Red)),
+                                (Some(shutdown_time), None) => Span::raw(format_time(shutdown_time)),
+                            }])
+                        })
+                        .collect()
+                },
+            ),
         ]
         .into_iter()
         .flatten()
@@ -607,12 +1601,57 @@ fn system_tab(manager: &mut backend::Manager, scroll: u16) -> Paragraph {
     } else {
         Paragraph::new("No information available!")
     }
-    .block(Block::default().title("System").borders(Borders::ALL))
+    .block(Block::default().title("System").title(Title::from("Boot t[i]mes").alignment(Alignment::Right)).borders(Borders::ALL))
     .style(Style::default().fg(Color::White).bg(Color::Black))
     .alignment(Alignment::Left)
     .wrap(Wrap { trim: false })
 }
 
+/// Writes the contents of a popup to the temp directory, so it can be
+/// copied with scp when there's no clipboard
+fn dump_popup(title: &str, contents: &str) {
+    let time = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap_or_default().as_millis();
+    let path = std::env::temp_dir().join(format!("crossinfo-{}-{time}.txt", title.to_lowercase().replace(' ', "-")));
+    if std::fs::write(&path, format!("{title}\n\n{contents}\n")).is_ok() {
+        lock(&DUMPED_POPUPS).push(path);
+    }
+}
+
+fn boot_times_popup(manager: &backend::Manager) -> String {
+    // systemd-analyze takes a moment and the result can't change until the
+    // next boot
+    static BOOT_TIMES: std::sync::OnceLock<Option<backend::BootTimes>> = std::sync::OnceLock::new();
+
+    BOOT_TIMES.get_or_init(|| remote::local(|| manager.boot_times(10))).as_ref().map_or_else(
+        || "No boot time information available!".to_string(),
+        |boot_times| {
+            let phase = |duration: Option<Duration>| format_or_unknown(duration, &|duration: Duration| format!("{:.2}s", duration.as_secs_f64()));
+            format!(
+                r"Firmware: {}
+Loader: {}
+Kernel: {}
+Initrd: {}
+Userspace: {}
+Total: {:.2}s
+
+Slowest units:
+{}",
+                phase(boot_times.firmware),
+                phase(boot_times.loader),
+                phase(boot_times.kernel),
+                phase(boot_times.initrd),
+                phase(boot_times.userspace),
+                boot_times.total.as_secs_f64(),
+                boot_times
+                    .slowest_units
+                    .iter()
+                    .map(|(unit, duration)| format!("   {:>8.2}s  {unit}", duration.as_secs_f64()))
+                    .join("\n")
+            )
+        },
+    )
+}
+
 const COLORS: [Color; 15] = [
     Color::Red,
     Color::Green,
@@ -633,13 +1672,28 @@ const COLORS: [Color; 15] = [
 
 // TODO: Make the charts a lil better in manycpu
 // setups
-fn cpu_tab<'a>(manager: &'a mut backend::Manager, starting_time: Instant, cpu_dataset: &HashMap<&'a backend::CpuInfo, &'a [DataPoint]>) -> Vec<(List<'a>, Chart<'a>)> {
-    static LATEST_INFO: Mutex<(Option<Vec<backend::CpuInfo>>, Option<Instant>)> = Mutex::new((None, None));
-
-    let mut latest_info = LATEST_INFO.lock().unwrap();
+/// Starts before zero if the charts of the previous run were restored
+fn elapsed_axis<'a>(start: f64, elapsed: Duration) -> Axis<'a> {
+    let end = elapsed.as_secs_f64();
+    Axis::default()
+        .title(Span::raw("Seconds Elapsed"))
+        .style(Style::default().fg(Color::White).bg(Color::Black))
+        .bounds([start, end])
+        .labels([start, f64::midpoint(start, end), end].iter().map(|seconds| Span::from(format!("{seconds:.0}"))).collect())
+}
 
-    if latest_info.1.is_none() || latest_info.1.unwrap().elapsed() > INTERVAL {
-        *latest_info = (manager.cpu_information(), Some(Instant::now()));
+fn cpu_tab<'a>(
+    manager: &'a mut backend::Manager,
+    starting_time: Instant,
+    cpu_dataset: &HashMap<&'a backend::CpuInfo, &'a [DataPoint]>,
+    (load_average, load_dataset): (Option<backend::LoadAverage>, &'a [DataPoint]),
+) -> Vec<(List<'a>, Chart<'a>)> {
+    static LATEST_INFO: Mutex<(Option<Vec<backend::CpuInfo>>, Option<Instant>)> = Mutex::new((None, None));
+
+    let mut latest_info = lock(&LATEST_INFO);
+
+    if latest_info.1.is_none() || latest_info.1.unwrap().elapsed() > config::get().intervals.cpu {
+        *latest_info = (remote::cpu_information(manager), Some(Instant::now()));
     }
 
     let elapsed = starting_time.elapsed();
@@ -687,11 +1741,11 @@ fn cpu_tab<'a>(manager: &'a mut backend::Manager, starting_time: Instant, cpu_da
                             }
                             List::new(cpu.iter().map(|cpu_core| {
                                 ListItem::new(format!(
-                                    "{:manufacturer_width$}  {:model_width$}  {:frequency_width$.2}  {:usage_width$.2}%",
+                                    "{:manufacturer_width$}  {:model_width$}  {}  {}%",
                                     "",
                                     cpu_core.model.clone(),
-                                    cpu_core.frequency.get::<uom::si::frequency::gigahertz>(),
-                                    cpu_core.usage
+                                    localize(&format!("{:frequency_width$.2}", cpu_core.frequency.get::<uom::si::frequency::gigahertz>())),
+                                    localize(&format!("{:usage_width$.2}", cpu_core.usage))
                                 ))
                             }))
                             .block(
@@ -700,6 +1754,14 @@ fn cpu_tab<'a>(manager: &'a mut backend::Manager, starting_time: Instant, cpu_da
                                         "{:manufacturer_width$}  {model_label:model_width$}  {frequency_label:frequency_width$}  {usage_label:usage_width$}",
                                         cpu[0].manufacturer.clone()
                                     ))
+                                    .title(
+                                        Title::from(format!(
+                                            "Governor: {}  Turbo: {}",
+                                            to_string_or_unknown(cpu[0].governor.as_ref()),
+                                            format_or_unknown(cpu[0].turbo, &|turbo: bool| if turbo { "on" } else { "off" }.to_string())
+                                        ))
+                                        .alignment(Alignment::Right),
+                                    )
                                     .borders(Borders::ALL),
                             )
                         },
@@ -719,6 +1781,14 @@ fn cpu_tab<'a>(manager: &'a mut backend::Manager, starting_time: Instant, cpu_da
                                         }))
                                         .data(cpu_dataset[cpu_core])
                                 })
+                                .chain(load_average.map(|_| {
+                                    Dataset::default()
+                                        .name("Load")
+                                        .marker(Marker::Braille)
+                                        .graph_type(GraphType::Line)
+                                        .style(Style::default().fg(Color::White))
+                                        .data(load_dataset)
+                                }))
                                 .collect(),
                         ),
                     )
@@ -727,6 +1797,11 @@ fn cpu_tab<'a>(manager: &'a mut backend::Manager, starting_time: Instant, cpu_da
         },
     );
     drop(latest_info);
+    let start = cpu_dataset.values().filter_map(|dataset| dataset.first()).fold(0.0, |start, point| point.0.min(start));
+    // A load above the number of cores goes past 100%, so the axis grows in
+    // steps of 50% to fit it
+    let top = (load_dataset.iter().fold(100.0, |top: f64, point| top.max(point.1)) / 50.0).ceil() * 50.0;
+    let load_title = format!("Load average: {}", format_or_unknown(load_average, &format_load_average));
     for (list, chart) in &mut res {
         *list = list
             .clone()
@@ -734,31 +1809,120 @@ fn cpu_tab<'a>(manager: &'a mut backend::Manager, starting_time: Instant, cpu_da
             .highlight_style(Style::default().fg(Color::Black).bg(Color::White));
         *chart = chart
             .clone()
+            .block(Block::default().title(Title::from(load_title.clone()).alignment(Alignment::Right)))
             .style(Style::default().bg(Color::Black).fg(Color::White))
-            .x_axis(
-                Axis::default()
-                    .title(Span::raw("Seconds Elapsed"))
-                    .style(Style::default().fg(Color::White).bg(Color::Black))
-                    .bounds([0.0, elapsed.as_secs_f64()])
-                    .labels(
-                        ["0".to_string(), (elapsed / 2).as_secs().to_string(), elapsed.as_secs().to_string()]
-                            .iter()
-                            .cloned()
-                            .map(Span::from)
-                            .collect(),
-                    ),
-            )
+            .x_axis(elapsed_axis(start, elapsed))
             .y_axis(
                 Axis::default()
                     .title(Span::raw("CPU usage"))
                     .style(Style::default().fg(Color::White).bg(Color::Black))
-                    .bounds([0.0, 100.0])
-                    .labels(["0%", "50%", "100%"].iter().copied().map(Span::raw).collect()),
+                    .bounds([0.0, top])
+                    .labels(["0%".to_string(), format!("{:.0}%", top / 2.0), format!("{top:.0}%")].into_iter().map(Span::raw).collect()),
             );
     }
     res
 }
 
+const CPU_STATE_COLORS: [Color; 5] = [Color::Green, Color::Red, Color::Yellow, Color::Magenta, Color::DarkGray];
+
+type CpuStateInfos = (Option<backend::CpuStates>, Option<backend::SchedulerActivity>);
+
+// Like a spec sheet, which the usage doesn't tell
+fn cpu_features_popup(manager: &backend::Manager) -> String {
+    let Some(features) = remote::local(|| Some(manager.cpu_features())) else {
+        return "CPU features of another machine are unknown".to_string();
+    };
+    let caches = features
+        .caches
+        .iter()
+        .map(|cache| format!("L{} {}: {}", cache.level, cache.kind, format_bytes(cache.size)))
+        .collect::<Vec<String>>();
+    format!(
+        "Instruction Sets: {}\nVirtualization: {}\n\nCaches:\n{}",
+        if features.instruction_sets.is_empty() { "unknown".to_string() } else { features.instruction_sets.join(", ") },
+        features.virtualization.unwrap_or_else(|| "none".to_string()),
+        if caches.is_empty() { "unknown".to_string() } else { caches.join("\n") }
+    )
+}
+
+fn cpu_state_tab(manager: &mut backend::Manager) -> Paragraph<'static> {
+    static LATEST_INFO: Mutex<(CpuStateInfos, Option<Instant>)> = Mutex::new(((None, None), None));
+
+    let mut latest_info = lock(&LATEST_INFO);
+
+    if latest_info.1.is_none_or(|refreshed_at| refreshed_at.elapsed() > config::get().intervals.cpu) {
+        *latest_info = (
+            (remote::local(|| manager.cpu_state_information()), remote::local(|| manager.scheduler_activity())),
+            Some(Instant::now()),
+        );
+    }
+
+    let ((states, activity), _) = &*latest_info;
+    let lines = states.as_ref().map_or_else(
+        || vec![Line::from(Span::raw("   unknown"))],
+        |states| {
+            std::iter::once(("All".to_string(), states.total))
+                .chain(states.cores.iter().enumerate().map(|(index, breakdown)| (index.to_string(), *breakdown)))
+                .map(|(name, breakdown)| cpu_state_line(&name, breakdown))
+                .collect()
+        },
+    );
+    let scheduler_activity = activity.map_or_else(
+        || "Context switches: unknown".to_string(),
+        |activity| format!("{:.0} context switches/s, {:.0} interrupts/s", activity.context_switches_per_second, activity.interrupts_per_second),
+    );
+    drop(latest_info);
+
+    let legend = ["user", "system", "iowait", "steal", "idle"]
+        .into_iter()
+        .zip(CPU_STATE_COLORS)
+        .map(|(state, color)| Span::styled(format!(" {state} "), Style::default().fg(color)))
+        .collect::<Vec<Span>>();
+    Paragraph::new(lines)
+        .block(
+            Block::default()
+                .title(Line::from(legend))
+                .title(Title::from("[i] CPU features").alignment(Alignment::Center))
+                .title(Title::from(scheduler_activity).alignment(Alignment::Right))
+                .borders(Borders::ALL),
+        )
+        .style(Style::default().fg(Color::White).bg(Color::Black))
+}
+
+// A bar of the states stacked on each other, then their percentages. The
+// bar ends are rounded cumulatively so it always has the same width
+fn cpu_state_line(name: &str, breakdown: backend::CpuStateBreakdown) -> Line<'static> {
+    const BAR_WIDTH: f32 = 40.0;
+
+    let percentages = [
+        breakdown.user,
+        breakdown.system,
+        breakdown.iowait.unwrap_or_default(),
+        breakdown.steal.unwrap_or_default(),
+        breakdown.idle,
+    ];
+    let mut spans = vec![Span::raw(format!("{name:>4} "))];
+    let mut filled = 0;
+    let mut sum = 0.0;
+    for (percentage, color) in percentages.into_iter().zip(CPU_STATE_COLORS) {
+        sum += percentage;
+        #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let end = (sum / 100.0 * BAR_WIDTH).round().clamp(0.0, BAR_WIDTH) as usize;
+        spans.push(Span::styled("█".repeat(end.saturating_sub(filled)), Style::default().fg(color)));
+        filled = filled.max(end);
+    }
+    let optional = |percentage: Option<f32>| percentage.map_or_else(|| "     -".to_string(), |percentage| format!("{percentage:5.1}%"));
+    spans.push(Span::raw(localize(&format!(
+        "  {:5.1}%  {:5.1}%  {}  {}  {:5.1}%",
+        breakdown.user,
+        breakdown.system,
+        optional(breakdown.iowait),
+        optional(breakdown.steal),
+        breakdown.idle
+    ))));
+    Line::from(spans)
+}
+
 fn memory_tab<'a>(
     manager: &mut backend::Manager,
     starting_time: Instant,
@@ -766,14 +1930,94 @@ fn memory_tab<'a>(
     swap_dataset: &'a [DataPoint],
     ram_important_digits: Option<f64>,
     swap_important_digits: Option<f64>,
-) -> Chart<'a> {
-    let formatter = humansize::make_format(humansize::DECIMAL);
+) -> (Chart<'a>, List<'a>, List<'a>, Paragraph<'a>) {
+    let formatter = format_bytes;
 
     let elapsed = starting_time.elapsed();
 
-    if let Some(memory_info) = manager.memory_information() {
-        let ram_important_digits = ram_important_digits.unwrap();
-        let swap_important_digits = swap_important_digits.unwrap();
+    let oom_list = manager
+        .oom_kill_information()
+        .map_or_else(
+            || List::new(vec![ListItem::new("No OOM kill information available!")]),
+            |events| match events.len() {
+                0 => List::new(vec![ListItem::new("No process has been killed for running out of memory")]),
+                // Newest first, the list can't be scrolled
+                _ => List::new(events.iter().rev().map(|event| {
+                    ListItem::new(format!(
+                        "{} ago  {} (PID {}, {})",
+                        backend::format_duration(event.time.elapsed().unwrap_or_default()),
+                        event.process_name,
+                        event.pid,
+                        format_or_unknown(event.anon_rss, &formatter)
+                    ))
+                })),
+            },
+        )
+        .block(Block::default().title("Recent OOM kills").borders(Borders::ALL))
+        .style(Style::default().fg(Color::White).bg(Color::Black));
+
+    if let Some(memory_info) = remote::memory_information(manager) {
+        let swap_title = format!(
+            "SWAP devices (zswap: {})",
+            format_or_unknown(memory_info.zswap_enabled, &|enabled| if enabled { "enabled".to_string() } else { "disabled".to_string() })
+        );
+        let swap_list = memory_info.swap_devices.as_ref().map_or_else(
+            || List::new(vec![ListItem::new("No SWAP device information available!")]).block(Block::default().title(swap_title.clone()).borders(Borders::ALL)),
+            |swap_devices| {
+                let name_label = "Name";
+                let kind_label = "Type";
+                let size_label = "Size";
+                let used_label = "Used";
+                let priority_label = "Priority";
+                let name_width = swap_devices.iter().map(|device| device.name.len()).max().unwrap_or_default().max(name_label.len());
+                let kind_width = swap_devices.iter().map(|device| device.kind.to_string().len()).max().unwrap_or_default().max(kind_label.len());
+                let size_width = swap_devices.iter().map(|device| formatter(device.size).len()).max().unwrap_or_default().max(size_label.len());
+                let used_width = swap_devices.iter().map(|device| formatter(device.used).len()).max().unwrap_or_default().max(used_label.len());
+                List::new(swap_devices.iter().map(|device| {
+                    ListItem::new(format!(
+                        "{:name_width$}  {:kind_width$}  {:size_width$}  {:used_width$}  {}",
+                        device.name,
+                        device.kind.to_string(),
+                        formatter(device.size),
+                        formatter(device.used),
+                        device.priority
+                    ))
+                }))
+                .block(
+                    Block::default()
+                        .title(format!(
+                            "{swap_title} {name_label:name_width$}  {kind_label:kind_width$}  {size_label:size_width$}  {used_label:used_width$}  {priority_label}"
+                        ))
+                        .borders(Borders::ALL),
+                )
+            },
+        );
+
+        // Used memory that is mostly cache isn't worth worrying about, this
+        // tells the two apart
+        let breakdown = Paragraph::new(vec![
+            Line::from(format!("Available: {}", formatter(memory_info.available_memory))),
+            Line::from(format!("Free: {}", formatter(memory_info.free_memory))),
+            Line::from(format!("Cached: {}", format_or_unknown(memory_info.cached_memory, &formatter))),
+            Line::from(format!("Buffers: {}", format_or_unknown(memory_info.buffer_memory, &formatter))),
+            Line::from(format!(
+                "Committed: {} of {}",
+                format_or_unknown(memory_info.committed_memory, &formatter),
+                format_or_unknown(memory_info.commit_limit, &formatter)
+            )),
+            Line::from(format!("Compressed: {}", format_or_unknown(memory_info.compressed_memory, &formatter))),
+            Line::from(format!(
+                "Pages swapped in/out: {}/{}",
+                format_or_unknown(memory_info.swap_ins, &|pages: u64| localize(&pages.to_string())),
+                format_or_unknown(memory_info.swap_outs, &|pages: u64| localize(&pages.to_string()))
+            )),
+        ])
+        .block(Block::default().title("Breakdown").borders(Borders::ALL))
+        .style(Style::default().fg(Color::White).bg(Color::Black));
+
+        // Only missing before the loop saw the first readings
+        let ram_important_digits = ram_important_digits.unwrap_or_else(|| important_digits(memory_info.total_memory));
+        let swap_important_digits = swap_important_digits.unwrap_or_else(|| important_digits(memory_info.total_swap));
 
         let max_y_axis_bound = ram_important_digits.max(swap_important_digits);
         let max_y_axis_label = memory_info.total_memory.max(memory_info.total_swap);
@@ -792,28 +2036,18 @@ fn memory_tab<'a>(
                 .data(swap_dataset),
         ];
 
-        return Chart::new(datasets)
-            .block(Block::default().title(format!(
-                "Memory: {}/{}, SWAP: {}/{}",
-                formatter(memory_info.used_memory),
-                formatter(memory_info.total_memory),
-                formatter(memory_info.used_swap),
-                formatter(memory_info.total_swap)
-            )))
+        let start = [ram_dataset.first(), swap_dataset.first()].into_iter().flatten().fold(0.0, |start, point| point.0.min(start));
+        // Where SWAP grows as it's needed its total now isn't a limit
+        let swap = match (memory_info.dynamic_swap, memory_info.swap_limit) {
+            (true, Some(limit)) => format!("{} (grows up to {})", formatter(memory_info.used_swap), formatter(limit)),
+            (true, None) => format!("{} (grows as needed)", formatter(memory_info.used_swap)),
+            (false, _) if memory_info.total_swap == 0 => "none".to_string(),
+            (false, _) => format!("{}/{}", formatter(memory_info.used_swap), formatter(memory_info.total_swap)),
+        };
+        let chart = Chart::new(datasets)
+            .block(Block::default().title(format!("Memory: {}/{}, SWAP: {swap}", formatter(memory_info.used_memory), formatter(memory_info.total_memory))))
             .style(Style::default().bg(Color::Black).fg(Color::White))
-            .x_axis(
-                Axis::default()
-                    .title(Span::raw("Seconds Elapsed"))
-                    .style(Style::default().fg(Color::White).bg(Color::Black))
-                    .bounds([0.0, elapsed.as_secs_f64()])
-                    .labels(
-                        ["0".to_string(), (elapsed / 2).as_secs().to_string(), elapsed.as_secs().to_string()]
-                            .iter()
-                            .cloned()
-                            .map(Span::from)
-                            .collect(),
-                    ),
-            )
+            .x_axis(elapsed_axis(start, elapsed))
             .y_axis(
                 Axis::default()
                     .title(Span::raw("Used Memory/SWAP"))
@@ -821,8 +2055,52 @@ fn memory_tab<'a>(
                     .bounds([0.0, max_y_axis_bound])
                     .labels([formatter(0), formatter(max_y_axis_label / 2), formatter(max_y_axis_label)].iter().cloned().map(Span::from).collect()),
             );
+        return (
+            chart,
+            swap_list
+                .style(Style::default().fg(Color::White).bg(Color::Black))
+                .highlight_style(Style::default().fg(Color::Black).bg(Color::White)),
+            oom_list,
+            breakdown,
+        );
     }
-    return Chart::new(vec![Dataset::default()]).block(Block::default().title("No memory/SWAP information was able to be obtained!"));
+    return (
+        Chart::new(vec![Dataset::default()]).block(Block::default().title("No memory/SWAP information was able to be obtained!")),
+        List::new(Vec::<ListItem>::new()),
+        oom_list,
+        Paragraph::new(""),
+    );
+}
+
+fn plugin_tab(plugin: &backend::PluginInfo, scroll: u16) -> Paragraph<'static> {
+    let mut lines = plugin.reading.as_ref().map_or_else(
+        || vec![Line::from("Nothing to show yet")],
+        |reading| reading.items.iter().map(|item| Line::from(format!("{}: {}", item.name, item.value))).collect(),
+    );
+    if let Some(error) = &plugin.error {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red))));
+    }
+    Paragraph::new(lines)
+        .scroll((scroll, 0))
+        .block(Block::default().title(format!("Plugin {}", plugin.name)).borders(Borders::ALL))
+        .style(Style::default().fg(Color::White).bg(Color::Black))
+}
+
+fn custom_tab(index: usize, scroll: u16) -> Paragraph<'static> {
+    let Some(tab) = config::get().custom_tabs.get(index) else {
+        return Paragraph::new("");
+    };
+    // The command would run here instead of on the other machine
+    let text = if remote::is_remote() {
+        "Custom tabs run their command on this machine, so they're empty while connected to another one".to_string()
+    } else {
+        custom_tabs::output(index, config::get().intervals.custom_tabs)
+    };
+    Paragraph::new(text)
+        .scroll((scroll, 0))
+        .block(Block::default().title(format!("{} ({})", tab.title, tab.command)).borders(Borders::ALL))
+        .style(Style::default().fg(Color::White).bg(Color::Black))
 }
 
 // MAYBE: This could be a list. I don't know if I like that better. You'd
@@ -830,41 +2108,302 @@ fn memory_tab<'a>(
 // paragraph. If you have an idea (maybe something like a list with
 // multiple lines per item) then feel free to experiment. That is what FOSS
 // software is for
-fn disk_tab(manager: &mut backend::Manager, scroll: u16) -> Paragraph {
-    let formatter = humansize::make_format(humansize::DECIMAL);
-    manager
-        .disk_information()
+#[derive(Default)]
+struct DiskBenchmarkState {
+    /// `Some` while the benchmark is running
+    progress:  Option<f32>,
+    directory: Option<std::path::PathBuf>,
+    result:    Option<Result<backend::DiskBenchmark, String>>,
+}
+
+/// Runs the disk benchmark on the disk of the current directory, so it can
+/// be pointed at any disk by starting crossinfo there
+fn disk_benchmark_lines(start: bool) -> Vec<Line<'static>> {
+    static BENCHMARK: Mutex<DiskBenchmarkState> = Mutex::new(DiskBenchmarkState {
+        progress:  None,
+        directory: None,
+        result:    None,
+    });
+    if remote::is_remote() {
+        return vec![Line::from(Span::styled("The disk benchmark only runs on this computer", Style::default().fg(Color::Gray))), Line::from(Span::raw("\n"))];
+    }
+    let mut benchmark = lock(&BENCHMARK);
+
+    if start
+        && benchmark.progress.is_none()
+        && let Ok(directory) = std::env::current_dir()
+    {
+        *benchmark = DiskBenchmarkState {
+            progress:  Some(0.0),
+            directory: Some(directory.clone()),
+            result:    None,
+        };
+        std::thread::spawn(move || {
+            let result = backend::Manager::disk_benchmark(&directory, backend::BenchmarkConfig::default(), |progress| lock(&BENCHMARK).progress = Some(progress));
+            let mut benchmark = lock(&BENCHMARK);
+            benchmark.progress = None;
+            benchmark.result = Some(result.map_err(|error| error.to_string()));
+        });
+    }
+
+    let directory = benchmark.directory.as_ref().map_or_else(String::new, |directory| directory.display().to_string());
+    let mut lines = vec![Line::from(Span::styled(format!("Benchmark {directory}"), Style::default().add_modifier(Modifier::BOLD)))];
+    match (benchmark.progress, &benchmark.result) {
+        (Some(progress), _) => lines.push(Line::from(Span::raw(format!("Running, {:.0}% done...", progress * 100.0)))),
+        (None, Some(Ok(result))) => {
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let speed = |bytes_per_second: f64| format!("{}/s", format_bytes(bytes_per_second as u64));
+            let iops = |iops: f64| localize(&format!("{iops:.0} IOPS"));
+            lines.extend([
+                Line::from(vec![Span::raw("Sequential Read: "), Span::raw(speed(result.sequential_read))]),
+                Line::from(vec![Span::raw("Sequential Write: "), Span::raw(speed(result.sequential_write))]),
+                Line::from(vec![Span::raw("Random Read: "), Span::raw(iops(result.random_read_iops))]),
+                Line::from(vec![Span::raw("Random Write: "), Span::raw(iops(result.random_write_iops))]),
+            ]);
+        }
+        (None, Some(Err(error))) => lines.push(Line::from(Span::styled(error.clone(), Style::default().fg(Color::Red)))),
+        (None, None) => {
+            lines = vec![Line::from(Span::raw(format!(
+                "Press b to benchmark the disk of the current directory. It writes a {} file there and reads it back.",
+                format_bytes(backend::BenchmarkConfig::default().file_size)
+            )))];
+        }
+    }
+    drop(benchmark);
+    lines.push(Line::from(Span::raw("\n")));
+    lines
+}
+
+fn disk_tab(manager: &mut backend::Manager, scroll: u16, start_benchmark: bool) -> Paragraph {
+    type DiskInfos = (
+        Option<Vec<backend::DiskInfo>>,
+        Option<Vec<backend::PhysicalDiskInfo>>,
+        Option<Vec<backend::StoragePoolInfo>>,
+        Option<Vec<backend::DiskSmartInfo>>,
+    );
+    static LATEST_INFO: Mutex<(DiskInfos, Option<Instant>)> = Mutex::new(((None, None, None, None), None));
+    let formatter = format_bytes;
+    let mut latest_info = lock(&LATEST_INFO);
+
+    if latest_info.1.is_none() || latest_info.1.unwrap().elapsed() > config::get().intervals.disks {
+        *latest_info = (
+            (
+                remote::disk_information(manager),
+                remote::local(|| manager.physical_disk_information()),
+                remote::local(|| manager.storage_pool_information()),
+                remote::local(|| manager.disk_smart_information()),
+            ),
+            Some(Instant::now()),
+        );
+    }
+
+    let (disk_info, physical_disk_info, pool_info, smart_info) = latest_info.0.clone();
+    drop(latest_info);
+    disk_info
         .map_or_else(
             || Paragraph::new("No information available!"),
             |disk_info| {
-                let text = disk_info
+                let mut text = disk_benchmark_lines(start_benchmark);
+                // The drives first, with the volumes on them
+                text.extend(physical_disk_info
+                    .unwrap_or_default()
                     .iter()
-                    .flat_map(|disk| {
+                    .flat_map(|drive| {
+                        let partitions = drive
+                            .partitions
+                            .iter()
+                            .map(|partition| {
+                                disk_info
+                                    .iter()
+                                    .find(|disk| disk.name.trim_start_matches("/dev/") == partition.as_str())
+                                    .map_or_else(|| partition.clone(), |disk| format!("{partition} ({})", disk.mount_point))
+                            })
+                            .collect::<Vec<String>>();
                         vec![
-                            Line::from(Span::styled(disk.name.clone(), Style::default().add_modifier(Modifier::BOLD))),
-                            Line::from(vec![Span::raw("Used Space: "), Span::raw(formatter(disk.used))]),
-                            Line::from(vec![Span::raw("Total Space: "), Span::raw(formatter(disk.total))]),
-                            Line::from(vec![Span::raw("Mount Point: "), Span::raw(disk.mount_point.clone())]),
-                            Line::from(vec![Span::raw("Filesystem: "), Span::raw(disk.file_system.clone().unwrap_or_else(|| "unknown".to_string()))]),
+                            Line::from(Span::styled(
+                                format!("{} ({})", to_string_or_unknown(drive.model.clone()), drive.name),
+                                Style::default().add_modifier(Modifier::BOLD),
+                            )),
+                            Line::from(vec![Span::raw("Size: "), Span::raw(formatter(drive.total))]),
+                            Line::from(vec![Span::raw("Bus: "), Span::raw(drive.bus.to_string())]),
+                            Line::from(vec![
+                                Span::raw("Type: "),
+                                Span::raw(match drive.is_rotational {
+                                    Some(true) => "Hard Drive",
+                                    Some(false) => "SSD",
+                                    None => "unknown",
+                                }),
+                            ]),
+                            Line::from(vec![Span::raw("Serial Number: "), Span::raw(to_string_or_unknown(drive.serial.clone()))]),
+                            Line::from(vec![Span::raw("Partitions: "), Span::raw(if partitions.is_empty() { "None".to_string() } else { partitions.join(", ") })]),
                             Line::from(Span::raw("\n")),
                         ]
-                    })
-                    .collect::<Vec<Line>>();
+                    }));
+                text.extend(disk_info.iter().flat_map(|disk| {
+                    vec![
+                        Line::from(Span::styled(disk.name.clone(), Style::default().add_modifier(Modifier::BOLD))),
+                        Line::from(vec![Span::raw("Used Space: "), Span::raw(formatter(disk.used))]),
+                        Line::from(vec![Span::raw("Total Space: "), Span::raw(formatter(disk.total))]),
+                        Line::from(vec![Span::raw("Mount Point: "), Span::raw(disk.mount_point.clone())]),
+                        Line::from(vec![Span::raw("Filesystem: "), Span::raw(disk.file_system.clone().unwrap_or_else(|| "unknown".to_string()))]),
+                        Line::from(vec![
+                            Span::raw("Mount Options: "),
+                            Span::raw(to_string_or_unknown(disk.mount_options.as_ref().map(|options| options.join(",")))),
+                        ]),
+                        Line::from(vec![Span::raw("Inodes: "), match (disk.inodes_used, disk.inodes_total) {
+                            #[allow(clippy::cast_precision_loss)]
+                            (Some(used), Some(total)) => {
+                                let percentage = used as f64 / total as f64 * 100.0;
+                                // Running out of inodes makes the disk just as full as running out of
+                                // space
+                                Span::styled(
+                                    localize(&format!("{used}/{total} ({percentage:.1}%)")),
+                                    if percentage > 90.0 {
+                                        Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                                    } else {
+                                        Style::default()
+                                    },
+                                )
+                            }
+                            _ => Span::raw("unknown"),
+                        }]),
+                    ]
+                    .into_iter()
+                    // Only Linux and Windows report these, there's no need to show "unknown" everywhere else
+                    .chain(disk.queue_length.map(|queue_length| {
+                        Line::from(vec![
+                            Span::raw("Queue Length: "),
+                            Span::raw(localize(&format!("{queue_length:.0}"))),
+                            Span::raw(disk.average_queue_depth.map_or_else(String::new, |depth| localize(&format!(" (average {depth:.2})")))),
+                        ])
+                    }))
+                    .chain(disk.queue_length.map(|_| {
+                        Line::from(vec![Span::raw("Latency: "), match disk.average_latency {
+                            // Slower than a spinning disk seeking, something is queueing up
+                            Some(latency) if latency > Duration::from_millis(50) => {
+                                Span::styled(localize(&format!("{:.1} ms", latency.as_secs_f64() * 1000.0)), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+                            }
+                            Some(latency) => Span::raw(localize(&format!("{:.1} ms", latency.as_secs_f64() * 1000.0))),
+                            None => Span::raw("idle"),
+                        }])
+                    }))
+                    .chain(disk.temperature.map(|temperature| {
+                        Line::from(vec![Span::raw("Temperature: "), disk_temperature_span(temperature, disk.critical_temperature)])
+                    }))
+                    .chain(std::iter::once(Line::from(Span::raw("\n"))))
+                    .collect::<Vec<Line>>()
+                }));
+                if let Some(pools) = pool_info {
+                    text.extend(pools.iter().flat_map(|pool| {
+                        let health_style = match pool.health {
+                            backend::StoragePoolHealth::Online => Style::default(),
+                            backend::StoragePoolHealth::Degraded => Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+                            backend::StoragePoolHealth::Faulted => Style::default().fg(Color::Red).add_modifier(Modifier::BOLD),
+                            backend::StoragePoolHealth::Unknown => Style::default().fg(Color::Gray),
+                        };
+                        vec![
+                            Line::from(Span::styled(format!("{} ({})", pool.name, pool.kind), Style::default().add_modifier(Modifier::BOLD))),
+                            Line::from(vec![Span::raw("Health: "), Span::styled(pool.health.to_string(), health_style)]),
+                            Line::from(vec![Span::raw("Level: "), Span::raw(to_string_or_unknown(pool.level.clone()))]),
+                            Line::from(vec![Span::raw("Members: "), Span::raw(pool.members.iter().map(|member| member.name.clone()).join(", "))]),
+                            Line::from(vec![
+                                Span::raw("Degraded Members: "),
+                                Span::styled(
+                                    match pool.degraded_members().as_slice() {
+                                        [] => "None".to_string(),
+                                        degraded => degraded.iter().map(|member| member.name.clone()).join(", "),
+                                    },
+                                    health_style,
+                                ),
+                            ]),
+                            Line::from(vec![
+                                Span::raw("Scrub: "),
+                                Span::raw(to_string_or_unknown(pool.scrub.clone())),
+                                Span::raw(pool.progress.map_or_else(String::new, |progress| localize(&format!(" ({:.1}%)", progress * 100.0)))),
+                            ]),
+                            Line::from(Span::raw("\n")),
+                        ]
+                    }));
+                }
+                if let Some(drives) = smart_info {
+                    text.extend(drives.iter().flat_map(|drive| {
+                        let title = Line::from(Span::styled(
+                            format!("{} ({})", drive.device, to_string_or_unknown(drive.model.clone())),
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ));
+                        if let Some(error) = &drive.error {
+                            return vec![title, Line::from(Span::styled(error.clone(), Style::default().fg(Color::Gray))), Line::from(Span::raw("\n"))];
+                        }
+                        let failing = drive.attributes.iter().filter(|attribute| attribute.is_failing()).map(|attribute| attribute.name.clone()).collect::<Vec<String>>();
+                        vec![
+                            title,
+                            Line::from(vec![Span::raw("S.M.A.R.T. Health: "), match drive.health {
+                                backend::SmartHealth::Failed => Span::styled(drive.health.to_string(), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
+                                _ => Span::raw(drive.health.to_string()),
+                            }]),
+                            Line::from(vec![Span::raw("Reallocated Sectors: "), match drive.reallocated_sectors {
+                                // Every reallocated sector is one that went bad
+                                Some(sectors) if sectors > 0 => Span::styled(localize(&sectors.to_string()), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+                                sectors => Span::raw(to_string_or_unknown(sectors)),
+                            }]),
+                            Line::from(vec![Span::raw("Power-On Hours: "), Span::raw(format_or_unknown(drive.power_on_hours, &|hours: u64| localize(&hours.to_string())))]),
+                            Line::from(vec![Span::raw("Wear Level: "), Span::raw(format_or_unknown(drive.wear_level, &|wear: u8| format!("{wear}%")))]),
+                            Line::from(vec![
+                                Span::raw("Temperature: "),
+                                drive.temperature.map_or_else(|| Span::raw("unknown"), |temperature| disk_temperature_span(temperature, None)),
+                            ]),
+                            Line::from(vec![
+                                Span::raw("Failing Attributes: "),
+                                if failing.is_empty() {
+                                    Span::raw("None")
+                                } else {
+                                    Span::styled(failing.join(", "), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+                                },
+                            ]),
+                            Line::from(Span::raw("\n")),
+                        ]
+                    }));
+                }
                 Paragraph::new(text).scroll((scroll, 0))
             },
         )
-        .block(Block::default().title("Disks").borders(Borders::ALL))
+        .block(Block::default().title("Disks").title(Title::from("[b]enchmark").alignment(Alignment::Right)).borders(Borders::ALL))
         .style(Style::default().fg(Color::White).bg(Color::Black))
         .alignment(Alignment::Left)
         .wrap(Wrap { trim: false })
 }
 
+/// Red once the drive is too hot, at its own critical temperature where it
+/// reports one
+fn disk_temperature_span(temperature: f32, critical: Option<f32>) -> Span<'static> {
+    // Hard drives are mostly rated up to 60 °C, SSDs a little higher
+    const DEFAULT_CRITICAL: f32 = 60.0;
+    let text = format_temperature(temperature, 0);
+    if temperature >= critical.unwrap_or(DEFAULT_CRITICAL) {
+        Span::styled(format!("{text} (overheating)"), Style::default().fg(Color::Red).add_modifier(Modifier::BOLD))
+    } else {
+        Span::raw(text)
+    }
+}
+
 fn battery_tab(manager: &backend::Manager, scroll: u16) -> Paragraph {
-    manager
-        .battery_information()
+    remote::battery_information(manager)
         .map_or_else(
             || Paragraph::new("No battery information was able to be obtained!"),
             |battery_info| {
+                // Laptops with a second battery drain them one after the
+                // other, so the total is what tells how long it lasts
+                let combined = backend::combine_batteries(&battery_info).filter(|combined| combined.batteries > 1).map(|combined| {
+                    vec![
+                        Line::from(Span::styled("Combined", Style::default().add_modifier(Modifier::BOLD))),
+                        Line::from(vec![Span::raw("Charge: "), Span::raw((combined.charge * 100.0).floor().to_string()), Span::raw("%")]),
+                        Line::from(vec![Span::raw("Status: "), Span::raw(combined.state.to_string())]),
+                        Line::from(vec![Span::raw("Capacity: "), Span::raw(localize(&backend::format_energy(combined.capacity)))]),
+                        // Until full while charging, until empty otherwise
+                        Line::from(vec![Span::raw("Time remaining: "), Span::raw(format_or_unknown(combined.time_remaining, &backend::format_duration))]),
+                        Line::from(Span::raw("\n".repeat(3))),
+                    ]
+                });
                 let batteries = battery_info
                     .iter()
                     .flat_map(|battery| {
@@ -876,10 +2415,10 @@ fn battery_tab(manager: &backend::Manager, scroll: u16) -> Paragraph {
                             Line::from(vec![Span::raw("Manufacturer: "), Span::raw(battery.manufacturer.clone().unwrap_or_else(|| "unknown".to_string()))]),
                             Line::from(vec![Span::raw("Charge: "), Span::raw((battery.charge * 100.0).floor().to_string()), Span::raw("%")]),
                             Line::from(vec![Span::raw("Status: "), Span::raw(battery.state.to_string())]),
-                            Line::from(vec![Span::raw("Capacity: "), Span::raw(format!("{:.2}", battery.capacity_wh)), Span::raw("kWh")]),
-                            Line::from(vec![Span::raw("Intended Capacity: "), Span::raw(format!("{:.2}", battery.capacity_new_wh)), Span::raw("kWh")]),
-                            Line::from(vec![Span::raw("Health: "), Span::raw(format!("{:.2}", battery.health)), Span::raw("%")]),
-                            Line::from(vec![Span::raw("Voltage: "), Span::raw(format!("{:.2}", battery.voltage)), Span::raw("V")]),
+                            Line::from(vec![Span::raw("Capacity: "), Span::raw(localize(&backend::format_energy(battery.capacity)))]),
+                            Line::from(vec![Span::raw("Intended Capacity: "), Span::raw(localize(&backend::format_energy(battery.capacity_new)))]),
+                            Line::from(vec![Span::raw("Health: "), Span::raw(localize(&format!("{:.2}", battery.health))), Span::raw("%")]),
+                            Line::from(vec![Span::raw("Voltage: "), Span::raw(localize(&backend::format_voltage(battery.voltage)))]),
                             Line::from(vec![Span::raw("Technology: "), Span::raw(format!("{:.2}", battery.technology))]),
                             Line::from(vec![
                                 Span::raw("Cycle Count: "),
@@ -887,9 +2426,8 @@ fn battery_tab(manager: &backend::Manager, scroll: u16) -> Paragraph {
                             ]),
                             Line::from(Span::raw("\n".repeat(3))),
                         ]
-                    })
-                    .collect::<Vec<Line>>();
-                Paragraph::new(batteries).scroll((scroll, 0))
+                    });
+                Paragraph::new(combined.into_iter().flatten().chain(batteries).collect::<Vec<Line>>()).scroll((scroll, 0))
             },
         )
         .block(Block::default().title("Batteries").borders(Borders::ALL))
@@ -898,20 +2436,38 @@ fn battery_tab(manager: &backend::Manager, scroll: u16) -> Paragraph {
         .wrap(Wrap { trim: false })
 }
 
-// TODO: Make all "find max width" type statements
-// into one per iterator
+/// Like "Wi-Fi (en0)" where the system has a better name than the
+/// interface's own
+fn network_name(network: &backend::Network) -> String {
+    network.display_name.as_ref().map_or_else(|| network.name.clone(), |display_name| format!("{display_name} ({})", network.name))
+}
+
+/// Whether any of the fields contains the filter, ignoring case
+fn matches_filter(filter: &str, fields: &[&str]) -> bool {
+    let filter = filter.to_lowercase();
+    fields.iter().any(|field| field.to_lowercase().contains(&filter))
+}
 
-fn network_tab<'a>(more_info: bool, selected: u16) -> (Paragraph<'a>, List<'a>, List<'a>, Option<String>) {
-    let formatter = humansize::make_format(humansize::DECIMAL);
+fn network_tab<'a>(more_info: bool, selected: u16, filter: &str, editing_filter: bool, hide_hidden_wifis: bool) -> (Paragraph<'a>, List<'a>, List<'a>, Option<String>) {
+    // Of the name, MAC address, channel, security and signal columns
+    static WIFI_WIDTHS: Mutex<widths::WidthCache<(Option<Instant>, String, bool), 5>> = Mutex::new(widths::WidthCache::new());
+    // Of the name, index, MAC address and flags columns
+    static NETWORK_WIDTHS: Mutex<widths::WidthCache<(Option<Instant>, String), 4>> = Mutex::new(widths::WidthCache::new());
+    let formatter = format_bytes;
+    let read_at = *lock(&NETWORK_INFO_READ_AT);
 
     let popup_input_label = "Display more [i]nformation   ";
     let popup_input_width = popup_input_label.len();
 
     let mut selected_network: Option<backend::Network> = None;
+    let wifi_profiles = (*lock(&WIFI_PROFILES)).clone();
 
-    let mut res = if let Some(network_info) = (*NETWORK_INFO.lock().unwrap()).clone() {
+    // Cloned first, so the refresh thread isn't locked out while the tab is
+    // built
+    let network_info = (*lock(&NETWORK_INFO)).clone();
+    let mut res = if let Some(network_info) = network_info {
         let text = vec![
-            Line::from(vec![Span::raw("Connected to the internet: "), Span::raw(network_info.connected.to_string())]),
+            Line::from(vec![Span::raw("Internet: "), Span::raw(network_info.connectivity.to_string())]),
             Line::from(vec![
                 Span::raw("IP Address (IPv4): "),
                 Span::raw(network_info.ip_address_v4.map_or_else(|| "unknown".to_string(), |addr| addr.to_string())),
@@ -920,40 +2476,52 @@ fn network_tab<'a>(more_info: bool, selected: u16) -> (Paragraph<'a>, List<'a>,
                 Span::raw("IP Address (IPv6): "),
                 Span::raw(network_info.ip_address_v6.map_or_else(|| "unknown".to_string(), |addr| addr.to_string())),
             ]),
+            Line::from(vec![Span::raw("Connected over IPv6: "), Span::raw(network_info.connected_v6.to_string())]),
+            Line::from(vec![Span::raw("IPv6 Gateway: "), Span::raw(to_string_or_unknown(network_info.gateway_v6))]),
+            Line::from(vec![
+                Span::raw("Saved WiFi networks: "),
+                Span::raw(format_or_unknown(wifi_profiles.as_ref(), &|profiles: &Vec<backend::WifiProfile>| {
+                    profiles
+                        .iter()
+                        .filter(|profile| matches_filter(filter, &[&profile.ssid]))
+                        .map(|profile| match profile.auto_join {
+                            Some(true) => format!("{} (auto-join)", profile.ssid),
+                            _ => profile.ssid.clone(),
+                        })
+                        .join(", ")
+                })),
+            ]),
         ];
 
         let (wifis, wifi_title) = network_info.wifis.map_or_else(
             || (vec![ListItem::new("No WiFi information available!")], "WiFi networks".to_string()),
-            |wifis| {
+            |mut wifis| {
+                // Hidden networks show up with an empty SSID or one made of null bytes
+                wifis.retain(|wifi| {
+                    !(hide_hidden_wifis && wifi.ssid.trim_matches(|chr: char| chr == '\0' || chr.is_whitespace()).is_empty()) && matches_filter(filter, &[&wifi.ssid, &wifi.security, &wifi.mac])
+                });
                 let wifi_name_label = "Name";
                 let wifi_mac_label = "MAC Address";
                 let wifi_channel_label = "Channel";
                 let wifi_security_label = "Security";
                 let wifi_signal_label = "Signal Level";
 
-                let mut wifi_name_width = wifi_name_label.len();
-                let mut wifi_mac_width = wifi_mac_label.len();
-                let mut wifi_channel_width = wifi_channel_label.len();
-                let mut wifi_security_width = wifi_security_label.len();
-                let mut wifi_signal_width = wifi_signal_label.len();
-
-                for wifi in &wifis {
-                    if wifi_name_width < wifi.ssid.len() {
-                        wifi_name_width = wifi.ssid.len();
-                    }
-                    if wifi_mac_width < wifi.mac.len() {
-                        wifi_mac_width = wifi.mac.len();
-                    }
-                    if wifi_channel_width < wifi.channel.len() {
-                        wifi_channel_width = wifi.channel.len();
-                    }
-                    if wifi_security_width < wifi.security.len() {
-                        wifi_security_width = wifi.security.len();
-                    }
-                    if wifi_signal_width < wifi.signal_level.len() {
-                        wifi_signal_width = wifi.signal_level.len();
-                    }
-                }
+                let [wifi_name_width, wifi_mac_width, wifi_channel_width, wifi_security_width, wifi_signal_width] =
+                    lock(&WIFI_WIDTHS).get((read_at, filter.to_string(), hide_hidden_wifis), || {
+                        let widest = |cell: &dyn Fn(&backend::Wifi) -> usize| wifis.iter().map(cell).max().unwrap_or_default();
+                        [
+                            widest(&|wifi| wifi.ssid.len()),
+                            widest(&|wifi| wifi.mac.len()),
+                            widest(&|wifi| wifi.channel.len()),
+                            widest(&|wifi| wifi.security.len()),
+                            widest(&|wifi| wifi.signal_level.len()),
+                        ]
+                    });
+                let wifi_name_width = wifi_name_width.max(wifi_name_label.len());
+                let wifi_mac_width = wifi_mac_width.max(wifi_mac_label.len());
+                let wifi_channel_width = wifi_channel_width.max(wifi_channel_label.len());
+                let wifi_security_width = wifi_security_width.max(wifi_security_label.len());
+                let wifi_signal_width = wifi_signal_width.max(wifi_signal_label.len());
 
                 (
                     wifis
@@ -979,37 +2547,26 @@ fn network_tab<'a>(more_info: bool, selected: u16) -> (Paragraph<'a>, List<'a>,
 
         let (networks, network_title) = network_info.networks.map_or_else(
             || (vec![ListItem::new("No network/interface information available!")], "Networks/Interfaces".to_string()),
-            |networks| {
+            |mut networks| {
+                networks.retain(|network| matches_filter(filter, &[&network.name, network.display_name.as_deref().unwrap_or_default(), network.description.as_deref().unwrap_or_default(), &to_string_or_unknown(network.mac_address)]));
                 let network_name_label = "Name";
                 let network_index_label = "Index";
                 let network_mac_label = "MAC Address";
                 let network_flags_label = "Flags";
 
-                let mut network_name_width = network_name_label.len();
-                let mut network_index_width = network_index_label.len();
-                let mut network_mac_width = network_mac_label.len();
-                let mut network_flags_width = network_flags_label.len();
-
-                for network in &networks {
-                    if network_name_width < network.name.len() {
-                        network_name_width = network.name.len();
-                    }
-
-                    let index_width_candidate = to_string_or_unknown(network.index).len();
-                    if network_index_width < index_width_candidate {
-                        network_index_width = index_width_candidate;
-                    }
-
-                    let mac_width_candidate = to_string_or_unknown(network.mac_address).len();
-                    if network_mac_width < mac_width_candidate {
-                        network_mac_width = mac_width_candidate;
-                    }
-
-                    let flags_width_candidate = format_or_unknown(network.flags, &|flags: backend::NetworkFlags| format!("{:b}", flags.raw)).len();
-                    if network_flags_width < flags_width_candidate {
-                        network_flags_width = flags_width_candidate;
-                    }
-                }
+                let [network_name_width, network_index_width, network_mac_width, network_flags_width] = lock(&NETWORK_WIDTHS).get((read_at, filter.to_string()), || {
+                    let widest = |cell: &dyn Fn(&backend::Network) -> usize| networks.iter().map(cell).max().unwrap_or_default();
+                    [
+                        widest(&|network| network_name(network).len()),
+                        widest(&|network| to_string_or_unknown(network.index).len()),
+                        widest(&|network| to_string_or_unknown(network.mac_address).len()),
+                        widest(&|network| format_or_unknown(network.flags, &|flags: backend::NetworkFlags| format!("{:b}", flags.raw)).len()),
+                    ]
+                });
+                let network_name_width = network_name_width.max(network_name_label.len());
+                let network_index_width = network_index_width.max(network_index_label.len());
+                let network_mac_width = network_mac_width.max(network_mac_label.len());
+                let network_flags_width = network_flags_width.max(network_flags_label.len());
                 (
                     networks
                         .iter()
@@ -1020,8 +2577,7 @@ fn network_tab<'a>(more_info: bool, selected: u16) -> (Paragraph<'a>, List<'a>,
                             }
                             ListItem::new(format!(
                                 "{:network_name_width$}  {:network_index_width$}  {:network_mac_width$}  {:network_flags_width$}",
-                                network.name, /* TODO: Convert this to a more human readable format
-                                               * on MacOS (and maybe others) */
+                                network_name(network),
                                 to_string_or_unknown(network.index),
                                 to_string_or_unknown(network.mac_address),
                                 format_or_unknown(network.flags, &|flags: backend::NetworkFlags| format!("{:b}", flags.raw)),
@@ -1052,7 +2608,19 @@ fn network_tab<'a>(more_info: bool, selected: u16) -> (Paragraph<'a>, List<'a>,
     };
     res.0 = res
         .0
-        .block(Block::default().title("Networks").borders(Borders::ALL))
+        .block(
+            Block::default()
+                .title("Networks")
+                .title(
+                    Title::from(format!(
+                        "[/]filter: {filter}{}  [h]idden Wi-Fis: {}",
+                        if editing_filter { "_" } else { "" },
+                        if hide_hidden_wifis { "hidden" } else { "shown" }
+                    ))
+                    .alignment(Alignment::Right),
+                )
+                .borders(Borders::ALL),
+        )
         .style(Style::default().fg(Color::White).bg(Color::Black))
         .alignment(Alignment::Left)
         .wrap(Wrap { trim: false });
@@ -1067,6 +2635,40 @@ fn network_tab<'a>(more_info: bool, selected: u16) -> (Paragraph<'a>, List<'a>,
         .highlight_symbol(popup_input_label);
     if more_info {
         if let Some(n) = selected_network {
+            let traffic_usage = lock(&TRAFFIC_HISTORY).as_ref().map_or_else(
+                || "unknown".to_string(),
+                |traffic_history| {
+                    let format_usage = |usage: backend::TrafficUsage| format!("{} / {}", formatter(usage.received), formatter(usage.transmitted));
+                    let daily = traffic_history.daily(&n.name);
+                    let monthly = traffic_history.monthly(&n.name);
+                    daily
+                        .iter()
+                        .rev()
+                        .take(7)
+                        .map(|(day, usage)| format!("{day}: {}", format_usage(*usage)))
+                        .chain(monthly.iter().rev().take(12).map(|((year, month), usage)| format!("{year}-{month:0>2}: {}", format_usage(*usage))))
+                        .join("\n")
+                },
+            );
+            let ipv6_text = format_or_unknown(n.ipv6_addresses.as_ref(), &|addresses: &Vec<backend::Ipv6Address>| {
+                addresses
+                    .iter()
+                    .map(|address| {
+                        let scope = match address.scope {
+                            backend::Ipv6Scope::Global => "global",
+                            backend::Ipv6Scope::UniqueLocal => "unique local",
+                            backend::Ipv6Scope::LinkLocal => "link-local",
+                            backend::Ipv6Scope::Loopback => "loopback",
+                        };
+                        format!(
+                            "{}{} ({scope}{})",
+                            address.address,
+                            address.prefix_length.map_or_else(String::new, |length| format!("/{length}")),
+                            if address.temporary == Some(true) { ", temporary" } else { "" }
+                        )
+                    })
+                    .join("\n")
+            });
             let flags_text = n.flags.map_or_else(
                 || "Flags: unknown".to_string(),
                 |flags| {
@@ -1084,6 +2686,29 @@ Flags (Raw): {:b}
                 },
             );
 
+            let top_talkers = if config::get().top_talkers {
+                let talkers = lock(&TOP_TALKERS).as_ref().map_or_else(
+                    || "unknown".to_string(),
+                    |talkers| {
+                        let ips = n.ips.as_deref().unwrap_or_default();
+                        let talkers = talkers
+                            .iter()
+                            .filter(|talker| ips.contains(&talker.local_address))
+                            .take(5)
+                            .map(|talker| format!("{}: {} / {}", talker.remote_address, formatter(talker.received), formatter(talker.transmitted)))
+                            .join("\n");
+                        if talkers.is_empty() {
+                            "No TCP traffic".to_string()
+                        } else {
+                            talkers
+                        }
+                    },
+                );
+                format!("\n\nTop talkers (received / transmitted):\n{talkers}")
+            } else {
+                String::new()
+            };
+
             res.3 = Some(format!(
                 r"Name: {}
 Description: {}
@@ -1091,21 +2716,31 @@ MAC-Address: {}
 Index: {}
 IP-addresses: 
 {}
+IPv6 addresses:
+{}
+IPv6 privacy extensions: {}
 {}
 Received: {}
 Transmitted: {}
 Packets received: {}
-Packets transmitted: {}",
-                n.name,
+Packets transmitted: {}
+
+Data usage (received / transmitted):
+{}{}",
+                network_name(&n),
                 to_string_or_unknown(n.description),
                 to_string_or_unknown(n.mac_address),
                 to_string_or_unknown(n.index),
                 to_string_or_unknown(n.ips.map(|ips| ips.iter().map(ToString::to_string).join("\n"))),
+                ipv6_text,
+                to_string_or_unknown(n.ipv6_privacy_extensions),
                 flags_text,
                 format_or_unknown(n.received_total, &formatter),
                 format_or_unknown(n.transmitted_total, &formatter),
                 to_string_or_unknown(n.packets_received_total),
                 to_string_or_unknown(n.packets_transmitted_total),
+                traffic_usage,
+                top_talkers,
             ));
         } else {
             res.3 = Some("Select a network to display information about it!".to_string());
@@ -1116,16 +2751,63 @@ Packets transmitted: {}",
 
 // TODO: make a popup with more information
 // TODO: implement process killing
-fn process_tab(manager: &mut backend::Manager, ordering: SortByProcess, shift_pressed: bool, kill_current_process: bool, more_information: bool, current_line: u16) -> (List, Option<ProcessPopup>) {
-    static LATEST_INFO: Mutex<(Option<Vec<backend::ProcessInfo>>, Option<Instant>)> = Mutex::new((None, None));
-    let formatter = humansize::make_format(humansize::DECIMAL);
-    let mut latest_info = LATEST_INFO.lock().unwrap();
-
-    if latest_info.1.is_none() || latest_info.1.unwrap().elapsed() > INTERVAL {
-        *latest_info = (manager.process_information(), Some(Instant::now()));
+#[allow(clippy::fn_params_excessive_bools)]
+fn process_tab<'a>(
+    manager: &'a mut backend::Manager,
+    ordering: &mut SortByProcess,
+    header_click: Option<u16>,
+    shift_pressed: bool,
+    kill_current_process: bool,
+    more_information: bool,
+    view: ProcessView,
+    toggle_background_io: bool,
+    renice: Option<i32>,
+    current_line: &mut u16,
+) -> (List<'a>, Option<ProcessPopup>) {
+    // Along with whether the children were added up and whether the extra
+    // columns were shown, which reads them again when either is toggled
+    static LATEST_INFO: Mutex<(Option<Vec<backend::ProcessInfo>>, Option<Instant>, (bool, bool))> = Mutex::new((None, None, (false, false)));
+    // Reading the IO priority means running ionice, which is too expensive to
+    // do every frame
+    static IO_PRIORITY: Mutex<Option<(backend::ProcessId, Option<backend::IoPriority>, Instant)>> = Mutex::new(None);
+    // The same for the open files, of which there can be thousands
+    type OpenFiles = (backend::ProcessId, Option<Vec<backend::OpenFile>>, Instant);
+    static OPEN_FILES: Mutex<Option<OpenFiles>> = Mutex::new(None);
+    // And for how many of them there are compared to the limit, which may
+    // have to ask the helper
+    type FileDescriptors = (backend::ProcessId, Option<backend::FileDescriptorUsage>, Instant);
+    static FILE_DESCRIPTORS: Mutex<Option<FileDescriptors>> = Mutex::new(None);
+    // And for the threads, whose CPU usage needs the previous reading
+    type Threads = (backend::ProcessId, Option<Vec<backend::ThreadInfo>>, Instant);
+    static THREADS: Mutex<Option<Threads>> = Mutex::new(None);
+    // More would make the popup too long to scroll through
+    const MAX_OPEN_FILES: usize = 50;
+    const MAX_THREADS: usize = 50;
+    // Of the name, memory, swap, runtime, CPU time, user, status and nice
+    // columns, computed whenever the processes are read
+    static WIDTHS: Mutex<widths::WidthCache<Option<Instant>, 8>> = Mutex::new(widths::WidthCache::new());
+    static SELECTION: Mutex<selection::Selection<backend::ProcessId>> = Mutex::new(selection::Selection::new());
+    let formatter = format_bytes;
+    let mut latest_info = lock(&LATEST_INFO);
+
+    if latest_info.1.is_none() || latest_info.1.unwrap().elapsed() > config::get().intervals.processes || latest_info.2 != (view.aggregate_children, view.extra_columns) {
+        let process_info = remote::process_information(manager).map(|mut process_info| {
+            if view.aggregate_children {
+                backend::aggregate_children(&mut process_info);
+            }
+            // Only needed to warn about processes running out of file
+            // descriptors, which takes a directory read per process
+            if view.extra_columns && !remote::is_remote() {
+                manager.add_file_descriptors(&mut process_info);
+            }
+            process_info
+        });
+        *latest_info = (process_info, Some(Instant::now()), (view.aggregate_children, view.extra_columns));
     }
+    let refreshed_at = latest_info.1;
 
     let mut selected_process: Option<&backend::ProcessInfo>;
+    let mut reniced = false;
 
     let mut res = if let Some(ref mut process_info) = &mut latest_info.0
         && !process_info.is_empty()
@@ -1136,28 +2818,63 @@ fn process_tab(manager: &mut backend::Manager, ordering: SortByProcess, shift_pr
         let memory_label = format!("Memory usage [{}]", if shift_pressed { 'M' } else { 'm' });
         let swap_label = format!("SWAP usage [{}]", if shift_pressed { 'S' } else { 's' });
         let runtime_label = format!("Runtime [{}]", if shift_pressed { 'R' } else { 'r' });
+        let cpu_time_label = format!("CPU time [{}]", if shift_pressed { 'U' } else { 'u' });
 
-        let selected_width = selected_label.len();
+        let user_label = "User";
+        let status_label = "Status";
+        let nice_label = "Nice";
+        let command_label = "Command";
 
-        let name_width = std::cmp::max(process_info.iter().map(|process| process.name.len()).max().unwrap(), name_label.len());
+        let [name_width, memory_width, swap_width, runtime_width, cpu_time_width, user_width, status_width, nice_width] = lock(&WIDTHS).get(refreshed_at, || {
+            let widest = |cell: &dyn Fn(&backend::ProcessInfo) -> usize| process_info.iter().map(cell).max().unwrap_or_default();
+            [
+                widest(&|process| process.name.len()),
+                widest(&|process| formatter(process.memory_usage).len()),
+                widest(&|process| formatter(process.swap_usage).len()),
+                widest(&|process| backend::format_duration(process.run_time).len()),
+                widest(&|process| format_or_unknown(process.cpu_time, &backend::format_duration).len()),
+                widest(&|process| to_string_or_unknown(process.user.as_ref()).len()),
+                widest(&|process| process.status.to_string().len()),
+                widest(&|process| to_string_or_unknown(process.nice).len()),
+            ]
+        });
 
+        let selected_width = selected_label.len();
+        let name_width = name_width.max(name_label.len());
         let cpu_width = cpu_label.len();
+        let memory_width = memory_width.max(memory_label.len());
+        let swap_width = swap_width.max(swap_label.len());
+        let runtime_width = runtime_width.max(runtime_label.len());
+        let cpu_time_width = cpu_time_width.max(cpu_time_label.len());
+        let user_width = user_width.max(user_label.len());
+        let status_width = status_width.max(status_label.len());
+        let nice_width = nice_width.max(nice_label.len());
+
+        if let Some(x) = header_click
+            && let Some(column) = clicked_column(x, &[
+                (selected_width + name_width + 2, cpu_width),
+                (3, memory_width),
+                (2, swap_width),
+                (2, runtime_width),
+                (2, cpu_time_width),
+            ])
+        {
+            let columns: [fn(Ordering) -> SortByProcess; 5] = [SortByProcess::CpuUsage, SortByProcess::MemoryUsage, SortByProcess::SwapUsage, SortByProcess::Runtime, SortByProcess::CpuTime];
+            *ordering = sort_by_clicked(ordering, ordering.ordering(), columns[column]);
+        }
 
-        let memory_width = std::cmp::max(process_info.iter().map(|process| formatter(process.memory_usage).len()).max().unwrap(), memory_label.len());
-
-        let swap_width = std::cmp::max(process_info.iter().map(|process| formatter(process.swap_usage).len()).max().unwrap(), swap_label.len());
-
-        let runtime_width = std::cmp::max(process_info.iter().map(|process| format_duration(&process.run_time).len()).max().unwrap(), runtime_label.len());
-
-        let sort_fn = |a: &backend::ProcessInfo, b: &backend::ProcessInfo| match ordering {
+        let sort_fn = |a: &backend::ProcessInfo, b: &backend::ProcessInfo| match *ordering {
             SortByProcess::CpuUsage(ord) => ord.sort_by()(a.cpu_usage, b.cpu_usage),
             SortByProcess::MemoryUsage(ord) => ord.sort_by()(a.memory_usage, b.memory_usage),
             SortByProcess::SwapUsage(ord) => ord.sort_by()(a.swap_usage, b.swap_usage),
             SortByProcess::Runtime(ord) => ord.sort_by()(a.run_time, b.run_time),
+            SortByProcess::CpuTime(ord) => ord.sort_by()(a.cpu_time.unwrap_or_default(), b.cpu_time.unwrap_or_default()),
         };
 
         process_info.sort_by(sort_fn);
 
+        lock(&SELECTION).follow(current_line, process_info.iter().map(backend::ProcessInfo::id));
+        let current_line = *current_line;
         selected_process = process_info.get(current_line as usize);
 
         let items = process_info
@@ -1167,51 +2884,261 @@ fn process_tab(manager: &mut backend::Manager, ordering: SortByProcess, shift_pr
                 if index == current_line as usize {
                     selected_process = Some(process);
                 }
-                ListItem::new(format!(
-                    "{:name_width$}  {:cpu_width$.2}%  {:memory_width$}  {:swap_width$}  {:runtime_width$}",
+                let row = format!(
+                    "{:name_width$}  {:cpu_width$.2}%  {:memory_width$}  {:swap_width$}  {:runtime_width$}  {:cpu_time_width$}",
                     process.name,
                     process.cpu_usage,
                     formatter(process.memory_usage),
                     formatter(process.swap_usage),
-                    format_duration(&process.run_time)
-                ))
+                    backend::format_duration(process.run_time),
+                    format_or_unknown(process.cpu_time, &backend::format_duration)
+                );
+                let item = ListItem::new(
+                    if view.extra_columns {
+                        format!(
+                            "{row}  {:user_width$}  {:status_width$}  {:>nice_width$}  {}",
+                            to_string_or_unknown(process.user.as_ref()),
+                            process.status.to_string(),
+                            to_string_or_unknown(process.nice),
+                            process.cmd.join(" ")
+                        )
+                    } else {
+                        row
+                    },
+                );
+                // Warn before the process starts failing with "Too many open files"
+                if process.file_descriptors.is_some_and(|usage| usage.is_near_limit()) {
+                    item.style(Style::default().fg(Color::Red))
+                } else {
+                    match process.status {
+                        sysinfo::ProcessStatus::Zombie => item.style(Style::default().fg(Color::DarkGray)),
+                        sysinfo::ProcessStatus::Stop | sysinfo::ProcessStatus::Tracing => item.style(Style::default().fg(Color::Yellow)),
+                        _ => item,
+                    }
+                }
             })
             .collect::<Vec<ListItem>>();
+
+        let mut io_priority = lock(&IO_PRIORITY);
+        if let Some(sp) = selected_process
+            && (more_information || toggle_background_io)
+            && !io_priority.is_some_and(|(id, _, updated)| id == sp.id() && updated.elapsed() < INTERVAL)
+        {
+            *io_priority = Some((sp.id(), remote::local(|| manager.io_priority(sp.pid)), Instant::now()));
+        }
+        if toggle_background_io && let Some((id, Some(current_priority), _)) = *io_priority {
+            manager.set_io_priority(
+                id.pid,
+                if current_priority.class == backend::IoPriorityClass::Idle {
+                    // The kernel default for best-effort
+                    backend::IoPriority {
+                        class: backend::IoPriorityClass::BestEffort,
+                        level: Some(4),
+                    }
+                } else {
+                    backend::IoPriority {
+                        class: backend::IoPriorityClass::Idle,
+                        level: None,
+                    }
+                },
+            );
+            *io_priority = None;
+        }
+        let selected_io_priority = io_priority.and_then(|(_, priority, _)| priority);
+        drop(io_priority);
+
+        // The new value shows up once the processes are read again
+        if let Some(step) = renice
+            && let Some(sp) = selected_process
+        {
+            let nice = sp.nice.unwrap_or_default() + step;
+            reniced = if remote::is_remote() {
+                remote::set_priority(sp.id(), nice)
+            } else {
+                manager.set_priority(sp.pid, nice)
+            };
+        }
+
+        let mut open_files = lock(&OPEN_FILES);
+        if let Some(sp) = selected_process
+            && more_information
+            && !open_files.as_ref().is_some_and(|(id, _, updated)| *id == sp.id() && updated.elapsed() < INTERVAL)
+        {
+            *open_files = Some((sp.id(), remote::local(|| manager.process_open_files(sp.pid)), Instant::now()));
+        }
+        let selected_open_files = format_or_unknown(open_files.as_ref().and_then(|(_, files, _)| files.as_ref()), &|files: &Vec<backend::OpenFile>| {
+            let mut lines = files
+                .iter()
+                .take(MAX_OPEN_FILES)
+                .map(|file| {
+                    let kind = match file.kind {
+                        backend::OpenFileKind::File => "file",
+                        backend::OpenFileKind::Directory => "directory",
+                        backend::OpenFileKind::Socket => "socket",
+                        backend::OpenFileKind::Pipe => "pipe",
+                        backend::OpenFileKind::Device => "device",
+                        backend::OpenFileKind::Other => "other",
+                    };
+                    format!("{:>5} {kind:<9} {}", file.descriptor, file.name)
+                })
+                .collect::<Vec<String>>();
+            if files.len() > MAX_OPEN_FILES {
+                lines.push(format!("and {} more", files.len() - MAX_OPEN_FILES));
+            }
+            lines.join("\n")
+        });
+        drop(open_files);
+
+        let mut file_descriptors = lock(&FILE_DESCRIPTORS);
+        if let Some(sp) = selected_process
+            && more_information
+            && !file_descriptors.is_some_and(|(id, _, updated)| id == sp.id() && updated.elapsed() < INTERVAL)
+        {
+            let usage = sp.file_descriptors.or_else(|| remote::local(|| manager.process_file_descriptors(sp.pid)));
+            *file_descriptors = Some((sp.id(), usage, Instant::now()));
+        }
+        let selected_file_descriptors = file_descriptors.and_then(|(_, usage, _)| usage);
+        drop(file_descriptors);
+
+        let mut threads = lock(&THREADS);
+        if let Some(sp) = selected_process
+            && more_information
+            && !threads.as_ref().is_some_and(|(id, _, updated)| *id == sp.id() && updated.elapsed() < INTERVAL)
+        {
+            *threads = Some((sp.id(), remote::local(|| manager.process_threads(sp.pid)), Instant::now()));
+        }
+        let selected_threads = format_or_unknown(threads.as_ref().and_then(|(_, threads, _)| threads.as_ref()), &|threads: &Vec<backend::ThreadInfo>| {
+            let mut lines = threads
+                .iter()
+                .take(MAX_THREADS)
+                .map(|thread| {
+                    format!(
+                        "{:>7} {:<6} {:>7} {}",
+                        thread.tid,
+                        thread.kind,
+                        format_or_unknown(thread.cpu_usage, &|usage: f32| format!("{}%", localize(&format!("{usage:.2}")))),
+                        thread.name
+                    )
+                })
+                .collect::<Vec<String>>();
+            if threads.len() > MAX_THREADS {
+                lines.push(format!("and {} more", threads.len() - MAX_THREADS));
+            }
+            lines.join("\n")
+        });
+        drop(threads);
+
+        // Like top and htop show above their lists
+        let count = |statuses: &[sysinfo::ProcessStatus]| process_info.iter().filter(|process| statuses.contains(&process.status)).count();
+        let mut summary = format!(
+            "{} processes: {} running, {} sleeping, {} stopped, {} zombie",
+            process_info.len(),
+            count(&[sysinfo::ProcessStatus::Run]),
+            count(&[sysinfo::ProcessStatus::Sleep, sysinfo::ProcessStatus::Idle, sysinfo::ProcessStatus::UninterruptibleDiskSleep]),
+            count(&[sysinfo::ProcessStatus::Stop, sysinfo::ProcessStatus::Tracing]),
+            count(&[sysinfo::ProcessStatus::Zombie]),
+        );
+        if let Some(usage) = view.cpu_usage {
+            let _ = write!(summary, "  CPU {}%", localize(&format!("{usage:.1}")));
+        }
+        if let Some(load) = view.load_average {
+            let _ = write!(summary, "  Load {}", format_load_average(load));
+        }
+
+        let title = format!(
+            "{:selected_width$}{:name_width$}  {:cpu_width$}   {:memory_width$}  {:swap_width$}  {:runtime_width$}  {:cpu_time_width$}",
+            "", name_label, cpu_label, memory_label, swap_label, runtime_label, cpu_time_label
+        );
         (
             List::new(items)
                 .block(
                     Block::default()
-                        .title(format!(
-                            "{:selected_width$}{:name_width$}  {:cpu_width$}   {:memory_width$}  {:swap_width$}  {:runtime_width$}",
-                            "", name_label, cpu_label, memory_label, swap_label, runtime_label
-                        ))
+                        .title(
+                            if view.extra_columns {
+                                format!("{title}  {user_label:user_width$}  {status_label:status_width$}  {nice_label:nice_width$}  {command_label}")
+                            } else {
+                                title
+                            },
+                        )
+                        .title(Title::from(summary).position(Position::Bottom).alignment(Alignment::Left))
+                        .title(
+                            Title::from(format!(
+                                "[K]ill without asking  [o]ptional columns  [a] {}  [b]ackground IO  [<>] priority",
+                                if view.aggregate_children { "own usage" } else { "usage with children" }
+                            ))
+                            .alignment(Alignment::Right),
+                        )
                         .borders(Borders::ALL),
                 )
                 .highlight_symbol(selected_label),
             if kill_current_process {
                 Some(selected_process.map_or(ProcessPopup::NoSelected, |selected_process| ProcessPopup::KillProcess {
                     process_name: selected_process.name.clone(),
-                    pid:          selected_process.pid,
+                    id:           selected_process.id(),
                 }))
             } else if more_information {
                 Some(selected_process.map_or(ProcessPopup::NoSelected, |sp| ProcessPopup::MoreInformation {
                     contents: format!(
                         r"Name: {}
 Path: {}
+Command: {}
+Working Directory: {}
+User: {} (UID {})
+Group: {}
+Status: {}
+Nice: {}
+IO Priority: {}
+Open Files: {}
+Context Switches: {}
+Sandbox: {}
 Memory Usage: {}
 SWAP Usage: {}
 CPU Usage: {}%
-Runtime: {}
+CPU Time: {}
+Started at: {}
+Running for: {}
 PID: {}
-Parent: {}",
+Parent: {}
+
+Open Files:
+{}
+
+Threads:
+{}",
                         sp.name,
                         to_string_or_unknown(sp.path.clone()),
-                        humansize::format_size(sp.memory_usage, humansize::DECIMAL),
-                        humansize::format_size(sp.swap_usage, humansize::DECIMAL),
+                        sp.cmd.join(" "),
+                        to_string_or_unknown(sp.cwd.as_ref().map(|cwd| cwd.display())),
+                        to_string_or_unknown(sp.user.as_ref()),
+                        to_string_or_unknown(sp.uid.as_ref().map(|uid| uid.to_string())),
+                        to_string_or_unknown(sp.group.as_ref()),
+                        sp.status,
+                        to_string_or_unknown(sp.nice),
+                        to_string_or_unknown(selected_io_priority),
+                        to_string_or_unknown(selected_file_descriptors.map(|usage| format!("{} / {}", usage.open, to_string_or_unknown(usage.limit)))),
+                        to_string_or_unknown(sp.context_switches.map(|switches| format!("{} voluntary, {} involuntary", switches.voluntary, switches.involuntary))),
+                        sp.sandbox.as_ref().map_or_else(
+                            || "none".to_string(),
+                            |sandbox| format!(
+                                "{} {}(permissions: {})",
+                                sandbox.kind,
+                                sandbox.app_id.as_ref().map_or_else(String::new, |app_id| format!("{app_id} ")),
+                                to_string_or_unknown(sandbox.permissions.as_ref().map(|permissions| permissions.join(", ")))
+                            )
+                        ),
+                        format_bytes(sp.memory_usage),
+                        format_bytes(sp.swap_usage),
                         sp.cpu_usage,
-                        format_duration(&sp.run_time),
+                        format_or_unknown(sp.cpu_time, &backend::format_duration),
+                        format_time(sp.start_time),
+                        backend::format_duration(sp.run_time),
                         sp.pid,
-                        sp.parent.map_or_else(|| "No parent".to_string(), |parent| to_string_or_unknown(manager.get_process(parent).map(sysinfo::Process::name)))
+                        sp.parent.map_or_else(
+                            || "No parent".to_string(),
+                            |parent| to_string_or_unknown(remote::local(|| manager.get_process(parent).map(sysinfo::Process::name)))
+                        ),
+                        selected_open_files,
+                        selected_threads,
                     ),
                 }))
             } else {
@@ -1225,6 +3152,9 @@ Parent: {}",
         )
     };
 
+    if reniced {
+        latest_info.1 = None;
+    }
     drop(latest_info);
 
     res.0 = res
@@ -1234,34 +3164,64 @@ Parent: {}",
     res
 }
 
-fn component_tab(manager: &mut backend::Manager, ordering: SortByComponent, shift_pressed: bool) -> List {
-    if let Some(mut component_info) = manager.component_information()
+fn component_tab<'a>(
+    manager: &mut backend::Manager,
+    extremes: &mut backend::TemperatureExtremes,
+    ordering: &mut SortByComponent,
+    header_click: Option<u16>,
+    shift_pressed: bool,
+    current_line: &mut u16,
+) -> List<'a> {
+    // Components are told apart by their name, like for the extremes
+    static SELECTION: Mutex<selection::Selection<String>> = Mutex::new(selection::Selection::new());
+
+    if let Some(mut component_info) = remote::component_information(manager)
         && !component_info.is_empty()
     {
+        extremes.record(&component_info);
         let selected_label = ">";
         let name_label = "Name";
         let temperature_label = format!("Temperature [{}]", if shift_pressed { 'T' } else { 't' });
+        let minimum_label = "Lowest";
+        let maximum_label = format!("Highest [{}]", if shift_pressed { 'M' } else { 'm' });
         let critical_label = format!("Critical Temperature [{}]", if shift_pressed { 'C' } else { 'c' });
 
         let selected_width = selected_label.len();
         let name_width = std::cmp::max(component_info.iter().map(|component| component.name.len()).max().unwrap(), name_label.len());
         let temperature_width = temperature_label.len(); // This is a bit of a gamble as it assumes that the label will always be
                                                          // longer than a temperature reading
+        let minimum_width = std::cmp::max(minimum_label.len(), format_temperature(-100.0, 2).len());
+        let maximum_width = maximum_label.len();
         let critical_width = critical_label.len();
 
-        let sort_fn = |a: &backend::ComponentInfo, b: &backend::ComponentInfo| match ordering {
+        let maximum = |component: &backend::ComponentInfo| extremes.get(&component.name).map_or(component.temperature, |range| range.max);
+        // The lowest temperatures can't be sorted by, so they're part of the
+        // gap in front of the highest ones
+        if let Some(x) = header_click
+            && let Some(column) = clicked_column(x, &[(selected_width + name_width + 2, temperature_width), (2 + minimum_width + 2, maximum_width), (4, critical_width)])
+        {
+            let columns: [fn(Ordering) -> SortByComponent; 3] = [SortByComponent::Temperature, SortByComponent::Maximum, SortByComponent::Critical];
+            *ordering = sort_by_clicked(ordering, ordering.ordering(), columns[column]);
+        }
+
+        let sort_fn = |a: &backend::ComponentInfo, b: &backend::ComponentInfo| match *ordering {
             SortByComponent::Temperature(ord) => ord.sort_by()(a.temperature, b.temperature),
             SortByComponent::Critical(ord) => ord.sort_by()(a.critical_temperature.unwrap_or(0.0), b.critical_temperature.unwrap_or(0.0)),
+            SortByComponent::Maximum(ord) => ord.sort_by()(maximum(a), maximum(b)),
         };
         component_info.sort_by(sort_fn);
+        lock(&SELECTION).follow(current_line, component_info.iter().map(|component| component.name.clone()));
         let items = component_info
             .iter()
             .map(|component| {
+                let range = extremes.get(&component.name);
                 ListItem::new(format!(
-                    "{:name_width$}  {:temperature_width$.2}°C  {:critical_width$}",
+                    "{:name_width$}  {:>temperature_width$}  {:>minimum_width$}  {:>maximum_width$}  {:critical_width$}",
                     component.name,
-                    component.temperature,
-                    component.critical_temperature.map_or_else(|| "None".to_string(), |critical_temp| format!("{critical_temp:.2}°C"))
+                    format_temperature(component.temperature, 2),
+                    format_or_unknown(range, &|range: backend::TemperatureRange| format_temperature(range.min, 2)),
+                    format_or_unknown(range, &|range: backend::TemperatureRange| format_temperature(range.max, 2)),
+                    component.critical_temperature.map_or_else(|| "None".to_string(), |critical_temp| format_temperature(critical_temp, 2))
                 ))
             })
             .collect::<Vec<ListItem>>();
@@ -1269,9 +3229,10 @@ fn component_tab(manager: &mut backend::Manager, ordering: SortByComponent, shif
             .block(
                 Block::default()
                     .title(format!(
-                        "{:selected_width$}{:name_width$}  {:temperature_width$}    {:critical_width$}",
-                        "", name_label, temperature_label, critical_label
+                        "{:selected_width$}{:name_width$}  {:temperature_width$}  {:minimum_width$}  {:maximum_width$}    {:critical_width$}",
+                        "", name_label, temperature_label, minimum_label, maximum_label, critical_label
                     ))
+                    .title(Title::from("[r]eset lowest/highest").alignment(Alignment::Right))
                     .borders(Borders::ALL),
             )
             .highlight_symbol(selected_label)
@@ -1282,16 +3243,225 @@ fn component_tab(manager: &mut backend::Manager, ordering: SortByComponent, shif
     .highlight_style(Style::default().fg(Color::Black).bg(Color::White))
 }
 
+fn audio_tab<'a>(manager: &backend::Manager, action: Option<AudioAction>, selected: u16) -> (List<'a>, Paragraph<'a>) {
+    // Every refresh runs pactl several times
+    static LATEST_INFO: Mutex<(Option<backend::AudioInfo>, Option<Instant>)> = Mutex::new((None, None));
+    let mut latest_info = lock(&LATEST_INFO);
+
+    if let Some(action) = action
+        && let Some(audio_info) = &latest_info.0
+        && let Some(device) = audio_info.devices.get(selected as usize)
+    {
+        match action {
+            AudioAction::VolumeUp => manager.set_volume(device, device.volume.unwrap_or_default() + 0.05),
+            AudioAction::VolumeDown => manager.set_volume(device, device.volume.unwrap_or_default() - 0.05),
+            AudioAction::MakeDefault => manager.set_default_audio_device(&device.id),
+        };
+        // Show the change right away instead of after the next refresh
+        latest_info.1 = None;
+    }
+
+    if latest_info.1.is_none() || latest_info.1.unwrap().elapsed() > INTERVAL {
+        *latest_info = (remote::local(|| manager.audio_information()), Some(Instant::now()));
+    }
+
+    let volume = |volume: Option<f32>, is_muted: Option<bool>| {
+        format!(
+            "{}{}",
+            format_or_unknown(volume, &|volume: f32| localize(&format!("{:.0}%", volume * 100.0))),
+            if is_muted == Some(true) { ", muted" } else { "" }
+        )
+    };
+
+    let (devices, streams) = latest_info.0.as_ref().map_or_else(
+        || (vec![ListItem::new("No audio information available!")], vec![Line::from(Span::raw("No audio information available!"))]),
+        |audio_info| {
+            let devices = audio_info
+                .devices
+                .iter()
+                .map(|device| {
+                    ListItem::new(format!(
+                        "{} {}: {} ({})",
+                        if device.is_default { '*' } else { ' ' },
+                        device.direction,
+                        device.name,
+                        volume(device.volume, device.is_muted)
+                    ))
+                })
+                .collect();
+            let streams = [backend::AudioDirection::Output, backend::AudioDirection::Input]
+                .into_iter()
+                .flat_map(|direction| {
+                    [
+                        vec![Line::from(Span::styled(
+                            if direction == backend::AudioDirection::Output { "Playing" } else { "Recording" },
+                            Style::default().add_modifier(Modifier::BOLD),
+                        ))],
+                        audio_info
+                            .streams
+                            .iter()
+                            .filter(|stream| stream.direction == direction)
+                            .map(|stream| {
+                                let text = format!(
+                                    "   {}{} ({})",
+                                    stream.application,
+                                    stream.pid.map_or_else(String::new, |pid| format!(" (PID {pid})")),
+                                    if stream.is_active { volume(stream.volume, stream.is_muted) } else { "paused".to_string() }
+                                );
+                                if stream.is_active {
+                                    Line::from(Span::styled(text, Style::default().fg(Color::Green)))
+                                } else {
+                                    Line::from(Span::raw(text))
+                                }
+                            })
+                            .collect(),
+                        vec![Line::from(Span::raw(""))],
+                    ]
+                    .into_iter()
+                    .flatten()
+                })
+                .collect();
+            (devices, streams)
+        },
+    );
+    drop(latest_info);
+
+    (
+        List::new(devices)
+            .block(
+                Block::default()
+                    .title("Audio devices")
+                    .title(Title::from("[+]/[-] volume  [d]efault").alignment(Alignment::Right))
+                    .borders(Borders::ALL),
+            )
+            .style(Style::default().fg(Color::White).bg(Color::Black))
+            .highlight_style(Style::default().fg(Color::Black).bg(Color::White)),
+        Paragraph::new(streams)
+            .block(Block::default().title("Streams").borders(Borders::ALL))
+            .style(Style::default().fg(Color::White).bg(Color::Black))
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: false }),
+    )
+}
+
+#[derive(Default)]
+struct BenchmarkState {
+    /// `Some` while the benchmark is running
+    progress: Option<f32>,
+    cpu:      Option<backend::CpuBenchmark>,
+    memory:   Option<backend::MemoryBenchmark>,
+}
+
+fn benchmark_tab<'a>(start: bool) -> (Gauge<'a>, Paragraph<'a>) {
+    // The benchmark runs on its own thread so the UI stays responsive
+    static BENCHMARK: Mutex<BenchmarkState> = Mutex::new(BenchmarkState {
+        progress: None,
+        cpu:      None,
+        memory:   None,
+    });
+    let mut benchmark = lock(&BENCHMARK);
+
+    if start && benchmark.progress.is_none() {
+        *benchmark = BenchmarkState {
+            progress: Some(0.0),
+            ..Default::default()
+        };
+        std::thread::spawn(|| {
+            // The CPU benchmark takes a lot longer than the memory one
+            let cpu = backend::Manager::benchmark_cpu(|progress| lock(&BENCHMARK).progress = Some(progress * 0.8));
+            let memory = backend::Manager::benchmark_memory(|progress| lock(&BENCHMARK).progress = Some(progress.mul_add(0.2, 0.8)));
+            *lock(&BENCHMARK) = BenchmarkState {
+                progress: None,
+                cpu:      Some(cpu),
+                memory:   Some(memory),
+            };
+        });
+    }
+
+    let gauge = Gauge::default()
+        .block(Block::default().title("Progress").borders(Borders::ALL))
+        .gauge_style(Style::default().fg(Color::Green).bg(Color::Black))
+        .ratio(f64::from(benchmark.progress.unwrap_or(0.0)).clamp(0.0, 1.0));
+
+    let text = match (benchmark.progress, benchmark.cpu, benchmark.memory) {
+        (Some(_), _, _) => vec![Line::from(Span::raw("Running, this takes a few seconds..."))],
+        (None, Some(cpu), Some(memory)) => vec![
+            Line::from(vec![Span::raw("Single Thread Score: "), Span::raw(format!("{:.0}", cpu.single_thread_score))]),
+            Line::from(vec![Span::raw("Multi Thread Score: "), Span::raw(format!("{:.0} ({} threads)", cpu.multi_thread_score, cpu.threads))]),
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            Line::from(vec![Span::raw("Memory Bandwidth: "), Span::raw(format!("{}/s", format_bytes(memory.bandwidth as u64)))]),
+        ],
+        _ => vec![Line::from(Span::raw("Press Enter to run the benchmark. Every core will be busy while it runs."))],
+    };
+    drop(benchmark);
+
+    (
+        gauge,
+        Paragraph::new(text)
+            .block(Block::default().title("Benchmark").title(Title::from("[Enter] run").alignment(Alignment::Right)).borders(Borders::ALL))
+            .style(Style::default().fg(Color::White).bg(Color::Black))
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: false }),
+    )
+}
+
 fn main() -> Result<(), io::Error> {
+    let args = std::env::args().skip(1).collect::<Vec<String>>();
+    // Loaded now so problems with the config file are printed before the
+    // TUI takes over the terminal
+    config::get();
+    match args.iter().map(String::as_str).collect::<Vec<&str>>().as_slice() {
+        [] => (),
+        ["baseline", "save", path @ ..] if path.len() <= 1 => return baseline::save(path.first().copied()),
+        ["baseline", "compare", path @ ..] if path.len() <= 1 => return baseline::compare(path.first().copied()),
+        ["report"] => return report::print(report::Format::Html),
+        ["fetch"] => {
+            fetch::print();
+            return Ok(());
+        }
+        ["summary"] => {
+            summary::print();
+            return Ok(());
+        }
+        ["schema"] => {
+            schema::print();
+            return Ok(());
+        }
+        ["speedtest"] => return speedtest::print(),
+        ["helper"] => return backend::run_helper(),
+        ["agent", address] => return remote::run_agent(address),
+        ["--connect", address] => remote::connect(address)?,
+        ["report", "--format", format] => return report::print(format.parse()?),
+        #[cfg(feature = "ebpf")]
+        ["latency", seconds @ ..] if seconds.len() <= 1 => return latency::print(seconds.first().copied()),
+        #[cfg(feature = "server")]
+        ["serve", address] => return backend::Manager::new().serve(address, None),
+        #[cfg(feature = "server")]
+        ["serve", address, "--allow-kill"] => return backend::Manager::new().serve(address, Some(&remote::token()?)),
+        _ => {
+            // Only what this build was compiled with
+            let optional = [
+                cfg!(feature = "ebpf").then_some(" [latency [SECONDS]]"),
+                cfg!(feature = "server").then_some(" [serve ADDRESS [--allow-kill]]"),
+            ]
+            .into_iter()
+            .flatten()
+            .collect::<String>();
+            eprintln!("Usage: cli [baseline save|compare [FILE]] [report [--format html|markdown]] [summary] [fetch] [schema] [speedtest] [helper] [agent ADDRESS] [--connect ADDRESS]{optional}");
+            std::process::exit(2);
+        }
+    }
+
+    crash::install_panic_hook();
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
-    let backend = CrosstermBackend::new(stdout);
-    let mut terminal = Terminal::new(backend)?;
-    run_app(&mut terminal);
-
-    disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen, DisableMouseCapture)?;
-    terminal.show_cursor()?;
-    Ok(())
+    let result = execute!(stdout, EnterAlternateScreen, EnableMouseCapture, EnableFocusChange)
+        .and_then(|()| Terminal::new(CrosstermBackend::new(stdout)))
+        .and_then(|mut terminal| run_app(&mut terminal));
+    // Errors are only printed once the terminal is usable again
+    crash::restore_terminal();
+    for path in lock(&DUMPED_POPUPS).iter() {
+        println!("Wrote a popup to {}", path.display());
+    }
+    result
 }
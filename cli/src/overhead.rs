@@ -0,0 +1,44 @@
+// What crossinfo costs itself, shown with F12. Every reading that goes
+// through the remote module is timed here, from whichever thread reads
+// it, so a slow subsystem shows up before users start blaming the
+// monitor for the load it reports.
+
+use std::{
+    collections::BTreeMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RefreshCost {
+    pub latest: Duration,
+    pub total:  Duration,
+    pub calls:  u32,
+}
+
+impl RefreshCost {
+    pub fn average(&self) -> Duration {
+        self.total.checked_div(self.calls).unwrap_or_default()
+    }
+}
+
+static COSTS: Mutex<BTreeMap<&'static str, RefreshCost>> = Mutex::new(BTreeMap::new());
+
+/// Runs `read` and adds how long it took to `subsystem`
+pub fn measure<T>(subsystem: &'static str, read: impl FnOnce() -> T) -> T {
+    let started = Instant::now();
+    let result = read();
+    let elapsed = started.elapsed();
+    let mut costs = super::lock(&COSTS);
+    let cost = costs.entry(subsystem).or_default();
+    cost.latest = elapsed;
+    cost.total += elapsed;
+    cost.calls = cost.calls.saturating_add(1);
+    drop(costs);
+    result
+}
+
+/// Every subsystem read so far, by name
+pub fn costs() -> BTreeMap<&'static str, RefreshCost> {
+    super::lock(&COSTS).clone()
+}
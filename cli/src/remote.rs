@@ -0,0 +1,138 @@
+// `cli --connect ADDRESS` shows the machine running `cli agent ADDRESS`
+// instead of this one. Both read the token from the CROSSINFO_TOKEN
+// environment variable, so it doesn't show up in the process list. `cli
+// serve ADDRESS --allow-kill` wants it for killing processes as well.
+//
+// The agent also reads:
+//
+// - CROSSINFO_MANAGE_TOKEN, a second token that lets clients manage
+//   processes, which is also what `--connect` sends instead if it's set
+// - CROSSINFO_TLS_CERT and CROSSINFO_TLS_KEY, the PEM files of its
+//   certificate chain and private key, to encrypt everything with TLS
+//
+// and `--connect` CROSSINFO_TLS_CA, the PEM file of the certificate
+// authority that signed the agent's certificate.
+//
+// The agent only sends the main readings. Everything else is unknown
+// while connected, since this machine's would be mistaken for the other
+// one's, and nothing that was recorded is saved. Processes can be killed
+// and reniced with the manage token, but not stopped gracefully.
+
+use std::{io, sync::OnceLock, time::Duration};
+
+static REMOTE: OnceLock<(String, backend::RemoteManager)> = OnceLock::new();
+
+const AGENT_INTERVAL: Duration = Duration::from_secs(1);
+
+pub fn token() -> io::Result<String> {
+    variable("CROSSINFO_TOKEN").ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Set CROSSINFO_TOKEN to the token to accept"))
+}
+
+fn variable(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|value| !value.is_empty())
+}
+
+pub fn run_agent(address: &str) -> io::Result<()> {
+    let tls = match (variable("CROSSINFO_TLS_CERT"), variable("CROSSINFO_TLS_KEY")) {
+        (Some(certificate), Some(private_key)) => Some(backend::TlsIdentity {
+            certificate: certificate.into(),
+            private_key: private_key.into(),
+        }),
+        (None, None) => None,
+        _ => return Err(io::Error::new(io::ErrorKind::InvalidInput, "Set both CROSSINFO_TLS_CERT and CROSSINFO_TLS_KEY for TLS")),
+    };
+    let config = backend::AgentConfig {
+        token: token()?,
+        manage_token: variable("CROSSINFO_MANAGE_TOKEN"),
+        tls,
+        interval: AGENT_INTERVAL,
+    };
+    let management = if config.manage_token.is_some() { " (or the manage token)" } else { "" };
+    let encryption = if config.tls.is_some() { " over TLS" } else { "" };
+    println!("Sending readings to everyone connecting to {address} with the token{management}{encryption}");
+    backend::Manager::new().run_agent(address, &config)
+}
+
+pub fn connect(address: &str) -> io::Result<()> {
+    let token = variable("CROSSINFO_MANAGE_TOKEN").map_or_else(token, Ok)?;
+    let ca_certificate = variable("CROSSINFO_TLS_CA").map(std::path::PathBuf::from);
+    let remote = backend::RemoteManager::connect(address, &token, ca_certificate.as_deref())?;
+    let _ = REMOTE.set((address.to_string(), remote));
+    Ok(())
+}
+
+pub fn is_remote() -> bool {
+    REMOTE.get().is_some()
+}
+
+/// Shown next to the tabs while connected
+pub fn status() -> Option<String> {
+    REMOTE.get().map(|(address, remote)| {
+        if remote.is_connected() {
+            format!("Connected to {address}")
+        } else {
+            format!("Lost the connection to {address}")
+        }
+    })
+}
+
+/// Asks the agent to kill the process, which needs the manage token. The
+/// outcome shows in the next readings
+pub fn kill_process(id: backend::ProcessId) -> bool {
+    REMOTE.get().is_some_and(|(_, remote)| remote.kill_process(id))
+}
+
+/// Like `kill_process`, for changing the nice value
+pub fn set_priority(id: backend::ProcessId, nice: i32) -> bool {
+    REMOTE.get().is_some_and(|(_, remote)| remote.set_priority(id, nice))
+}
+
+/// For readings the agent doesn't send
+pub fn local<T>(read: impl FnOnce() -> Option<T>) -> Option<T> {
+    if is_remote() {
+        None
+    } else {
+        read()
+    }
+}
+
+pub fn system_information(manager: &mut backend::Manager) -> Option<backend::SystemInfo> {
+    crate::overhead::measure("System", || REMOTE.get().map_or_else(|| manager.system_information(), |(_, remote)| remote.system_information()))
+}
+
+// The agent only sends it along with the rest of the system information
+pub fn load_average(manager: &backend::Manager) -> Option<backend::LoadAverage> {
+    crate::overhead::measure("Load", || {
+        REMOTE
+            .get()
+            .map_or_else(|| manager.load_average(), |(_, remote)| remote.system_information().and_then(|system| system.load_average))
+    })
+}
+
+pub fn cpu_information(manager: &mut backend::Manager) -> Option<Vec<backend::CpuInfo>> {
+    crate::overhead::measure("CPU", || REMOTE.get().map_or_else(|| manager.cpu_information(), |(_, remote)| remote.cpu_information()))
+}
+
+pub fn memory_information(manager: &mut backend::Manager) -> Option<backend::MemoryInfo> {
+    crate::overhead::measure("Memory", || REMOTE.get().map_or_else(|| manager.memory_information(), |(_, remote)| remote.memory_information()))
+}
+
+pub fn disk_information(manager: &mut backend::Manager) -> Option<Vec<backend::DiskInfo>> {
+    crate::overhead::measure("Disks", || REMOTE.get().map_or_else(|| manager.disk_information(), |(_, remote)| remote.disk_information()))
+}
+
+pub fn process_information(manager: &mut backend::Manager) -> Option<Vec<backend::ProcessInfo>> {
+    crate::overhead::measure("Processes", || REMOTE.get().map_or_else(|| manager.process_information(), |(_, remote)| remote.process_information()))
+}
+
+pub fn component_information(manager: &mut backend::Manager) -> Option<Vec<backend::ComponentInfo>> {
+    crate::overhead::measure("Components", || REMOTE.get().map_or_else(|| manager.component_information(), |(_, remote)| remote.component_information()))
+}
+
+pub fn battery_information(manager: &backend::Manager) -> Option<Vec<backend::BatteryInfo>> {
+    crate::overhead::measure("Batteries", || REMOTE.get().map_or_else(|| manager.battery_information(), |(_, remote)| remote.battery_information()))
+}
+
+pub fn network_information(manager: &mut backend::Manager) -> backend::NetworkInfo {
+    crate::overhead::measure("Network", || REMOTE.get().map_or_else(|| manager.network_information(), |(_, remote)| remote.network_information()))
+}
@@ -0,0 +1,359 @@
+// `cli report [--format html|markdown]` prints a report of the whole
+// machine to stdout, meant for attaching to support tickets or documenting
+// a machine. The TUI's chart history is gone once it exits, so CPU and
+// memory usage are sampled for a few seconds while the report is
+// generated.
+
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+
+use itertools::Itertools;
+use uom::si::frequency::megahertz;
+
+#[derive(Copy, Clone, Debug)]
+pub enum Format {
+    Html,
+    Markdown,
+}
+
+impl std::str::FromStr for Format {
+    type Err = io::Error;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "html" => Ok(Self::Html),
+            "markdown" | "md" => Ok(Self::Markdown),
+            _ => Err(io::Error::new(io::ErrorKind::InvalidInput, format!("Unknown report format {format}, use html or markdown"))),
+        }
+    }
+}
+
+const SAMPLES: u32 = 10;
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(500);
+
+struct Section {
+    title: String,
+    rows:  Vec<(String, String)>,
+    /// Usage in percent over the sampling period
+    chart: Option<Vec<f64>>,
+}
+
+impl Section {
+    fn new(title: impl Into<String>, rows: Vec<(&str, String)>) -> Self {
+        Self {
+            title: title.into(),
+            rows:  rows.into_iter().map(|(key, value)| (key.to_string(), value)).collect(),
+            chart: None,
+        }
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn sections(manager: &mut backend::Manager) -> Vec<Section> {
+    let formatter = super::format_bytes;
+
+    let mut cpu_samples = Vec::new();
+    let mut memory_samples = Vec::new();
+    for _ in 0..SAMPLES {
+        let started = Instant::now();
+        if let Some(cpus) = manager.cpu_information()
+            && !cpus.is_empty()
+        {
+            cpu_samples.push(cpus.iter().map(|cpu| f64::from(cpu.usage)).sum::<f64>() / cpus.len() as f64);
+        }
+        if let Some(memory) = manager.memory_information()
+            && memory.total_memory != 0
+        {
+            memory_samples.push(memory.used_memory as f64 / memory.total_memory as f64 * 100.0);
+        }
+        std::thread::sleep(SAMPLE_INTERVAL.saturating_sub(started.elapsed()));
+    }
+
+    let mut sections = Vec::new();
+
+    if let Some(system) = manager.system_information() {
+        sections.push(Section::new("System", vec![
+            ("Operating System", super::to_string_or_unknown(system.os)),
+            ("Operating System Version", super::to_string_or_unknown(system.os_version)),
+            ("Kernel Version", super::to_string_or_unknown(system.kernel_version)),
+            ("Uptime", backend::format_duration(system.uptime)),
+            ("Load Average", super::format_or_unknown(system.load_average, &super::format_load_average)),
+            ("Users", system.users.join(", ")),
+        ]));
+    }
+
+    if let Some(cpus) = manager.cpu_information()
+        && let Some(cpu) = cpus.first()
+    {
+        let mut section = Section::new("CPU", vec![
+            ("Model", cpu.manufacturer.clone()),
+            ("Logical Cores", cpus.len().to_string()),
+            ("Frequency", format!("{} MHz", super::localize(&cpu.frequency.get::<megahertz>().to_string()))),
+            (
+                "Frequency Range",
+                match (cpu.minimum_frequency, cpu.maximum_frequency) {
+                    (Some(minimum), Some(maximum)) => format!(
+                        "{} - {} MHz",
+                        super::localize(&minimum.get::<megahertz>().to_string()),
+                        super::localize(&maximum.get::<megahertz>().to_string())
+                    ),
+                    _ => "unknown".to_string(),
+                },
+            ),
+            ("Base Frequency", super::format_or_unknown(cpu.base_frequency, &|base: uom::si::f64::Frequency| format!("{} MHz", super::localize(&base.get::<megahertz>().to_string())))),
+            ("Governor", super::to_string_or_unknown(cpu.governor.as_ref())),
+            ("Turbo", super::format_or_unknown(cpu.turbo, &|turbo: bool| if turbo { "enabled" } else { "disabled" }.to_string())),
+            ("Average Usage", super::localize(&format!("{:.1}%", cpu_samples.iter().sum::<f64>() / cpu_samples.len().max(1) as f64))),
+        ]);
+        section.chart = Some(cpu_samples);
+        sections.push(section);
+    }
+
+    if let Some(memory) = manager.memory_information() {
+        let mut section = Section::new("Memory", vec![
+            ("Memory", format!("{} of {} used", formatter(memory.used_memory), formatter(memory.total_memory))),
+            ("Available Memory", formatter(memory.available_memory)),
+            ("Cached Memory", super::format_or_unknown(memory.cached_memory, &formatter)),
+            ("SWAP", format!("{} of {} used", formatter(memory.used_swap), formatter(memory.total_swap))),
+        ]);
+        section.chart = Some(memory_samples);
+        sections.push(section);
+    }
+
+    if let Some(disks) = manager.disk_information() {
+        sections.push(Section {
+            title: "Disks".to_string(),
+            rows:  disks
+                .iter()
+                .map(|disk| {
+                    (
+                        disk.mount_point.clone(),
+                        format!(
+                            "{} ({}), {} of {} used",
+                            disk.name,
+                            super::to_string_or_unknown(disk.file_system.as_ref()),
+                            formatter(disk.used),
+                            formatter(disk.total)
+                        ),
+                    )
+                })
+                .collect(),
+            chart: None,
+        });
+    }
+
+    if let Some(pools) = manager.storage_pool_information() {
+        sections.push(Section {
+            title: "Storage Pools".to_string(),
+            rows:  pools
+                .iter()
+                .map(|pool| {
+                    (
+                        pool.name.clone(),
+                        format!(
+                            "{} {}, {}, members: {}",
+                            pool.kind,
+                            super::to_string_or_unknown(pool.level.as_ref()),
+                            pool.health,
+                            pool.members.iter().map(|member| member.name.as_str()).join(", ")
+                        ),
+                    )
+                })
+                .collect(),
+            chart: None,
+        });
+    }
+
+    if let Some(batteries) = manager.battery_information() {
+        for battery in batteries {
+            sections.push(Section::new(format!("Battery {}", super::to_string_or_unknown(battery.model)), vec![
+                ("Manufacturer", super::to_string_or_unknown(battery.manufacturer)),
+                ("Charge", super::localize(&format!("{:.0}%", battery.charge * 100.0))),
+                ("Status", battery.state.to_string()),
+                ("Health", super::localize(&format!("{:.2}%", battery.health))),
+                ("Capacity", super::localize(&backend::format_energy(battery.capacity))),
+                ("Intended Capacity", super::localize(&backend::format_energy(battery.capacity_new))),
+                ("Cycle Count", super::to_string_or_unknown(battery.cycle_count)),
+            ]));
+        }
+    }
+
+    let network = manager.network_information();
+    let mut network_rows = vec![
+        ("Internet Connection".to_string(), network.connectivity.to_string()),
+        ("Local IPv4 Address".to_string(), super::to_string_or_unknown(network.ip_address_v4)),
+        ("Local IPv6 Address".to_string(), super::to_string_or_unknown(network.ip_address_v6)),
+        ("IPv6 Connection".to_string(), if network.connected_v6 { "yes" } else { "no" }.to_string()),
+        ("IPv6 Gateway".to_string(), super::to_string_or_unknown(network.gateway_v6)),
+    ];
+    network_rows.extend(network.networks.unwrap_or_default().into_iter().map(|interface| {
+        (
+            interface.name,
+            format!("{} (MAC {})", interface.ips.unwrap_or_default().iter().join(", "), super::to_string_or_unknown(interface.mac_address)),
+        )
+    }));
+    sections.push(Section {
+        title: "Network".to_string(),
+        rows:  network_rows,
+        chart: None,
+    });
+
+    if let Some(components) = manager.component_information() {
+        sections.push(Section {
+            title: "Components".to_string(),
+            rows:  components
+                .into_iter()
+                .map(|component| {
+                    (
+                        component.name,
+                        format!(
+                            "{}{}",
+                            super::format_temperature(component.temperature, 1),
+                            component
+                                .critical_temperature
+                                .map_or_else(String::new, |critical| format!(" (critical at {})", super::format_temperature(critical, 1)))
+                        ),
+                    )
+                })
+                .collect(),
+            chart: None,
+        });
+    }
+
+    if let Some(mut processes) = manager.process_information() {
+        processes.sort_by(|a, b| b.cpu_usage.total_cmp(&a.cpu_usage));
+        sections.push(Section {
+            title: "Top Processes".to_string(),
+            rows:  processes
+                .into_iter()
+                .take(10)
+                .map(|process| {
+                    (
+                        format!("{} (PID {})", process.name, process.pid),
+                        format!("{}% CPU, {}", super::localize(&format!("{:.1}", process.cpu_usage)), formatter(process.memory_usage)),
+                    )
+                })
+                .collect(),
+            chart: None,
+        });
+    }
+
+    if let Some(applications) = manager.sandboxed_applications() {
+        sections.push(Section {
+            title: "Sandboxed Applications".to_string(),
+            rows:  applications
+                .into_iter()
+                .map(|application| {
+                    (
+                        application.name,
+                        format!(
+                            "{} {}, permissions: {}",
+                            application.sandbox.kind,
+                            application.version.unwrap_or_default(),
+                            super::to_string_or_unknown(application.sandbox.permissions.map(|permissions| permissions.join(", ")))
+                        ),
+                    )
+                })
+                .collect(),
+            chart: None,
+        });
+    }
+
+    // E.g. there are no components to show inside of most virtual machines
+    sections.retain(|section| !section.rows.is_empty());
+    sections
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+// Percentages are drawn as a polyline, 0% at the bottom
+#[allow(clippy::cast_precision_loss)]
+fn svg_chart(samples: &[f64]) -> String {
+    let step = 600.0 / (samples.len().max(2) - 1) as f64;
+    let points = samples
+        .iter()
+        .enumerate()
+        .map(|(index, sample)| format!("{:.1},{:.1}", index as f64 * step, sample.clamp(0.0, 100.0).mul_add(-1.5, 150.0)))
+        .join(" ");
+    format!(r##"<svg viewBox="0 0 600 150" width="600" height="150"><rect width="600" height="150" fill="#f4f4f4"/><polyline points="{points}" fill="none" stroke="#2a7ae2" stroke-width="2"/></svg>"##)
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn sparkline(samples: &[f64]) -> String {
+    const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    samples.iter().map(|sample| BARS[((sample.clamp(0.0, 100.0) / 100.0) * 7.0).round() as usize]).collect()
+}
+
+fn render_html(sections: &[Section]) -> String {
+    let body = sections
+        .iter()
+        .map(|section| {
+            format!(
+                "<h2>{}</h2>\n{}<table>\n{}\n</table>",
+                escape_html(&section.title),
+                section.chart.as_ref().map_or_else(String::new, |chart| format!("{}\n", svg_chart(chart))),
+                section
+                    .rows
+                    .iter()
+                    .map(|(key, value)| format!("<tr><th>{}</th><td>{}</td></tr>", escape_html(key), escape_html(value)))
+                    .join("\n")
+            )
+        })
+        .join("\n");
+    format!(
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Crossinfo report</title>
+<style>
+body {{ font-family: sans-serif; max-width: 60em; margin: 2em auto; color: #222; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ text-align: left; padding: 0.3em 0.6em; border-bottom: 1px solid #ddd; vertical-align: top; }}
+th {{ width: 30%; }}
+</style>
+</head>
+<body>
+<h1>Crossinfo report</h1>
+<p>Generated {}</p>
+{body}
+</body>
+</html>
+"#,
+        super::format_time(std::time::SystemTime::now())
+    )
+}
+
+fn render_markdown(sections: &[Section]) -> String {
+    let escape = |text: &str| text.replace('|', "\\|");
+    let body = sections
+        .iter()
+        .map(|section| {
+            format!(
+                "## {}\n\n{}| | |\n|---|---|\n{}\n",
+                section.title,
+                section
+                    .chart
+                    .as_ref()
+                    .map_or_else(String::new, |chart| format!("Usage over {} seconds: `{}`\n\n", (SAMPLE_INTERVAL * SAMPLES).as_secs(), sparkline(chart))),
+                section.rows.iter().map(|(key, value)| format!("| {} | {} |", escape(key), escape(value))).join("\n")
+            )
+        })
+        .join("\n");
+    format!("# Crossinfo report\n\nGenerated {}\n\n{body}", super::format_time(std::time::SystemTime::now()))
+}
+
+pub fn print(format: Format) -> io::Result<()> {
+    use std::io::Write;
+
+    let sections = sections(&mut backend::Manager::new());
+    let report = match format {
+        Format::Html => render_html(&sections),
+        Format::Markdown => render_markdown(&sections),
+    };
+    io::stdout().write_all(report.as_bytes())
+}
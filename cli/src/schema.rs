@@ -0,0 +1,14 @@
+// `cli schema` prints the JSON Schema describing crossinfo's machine
+// output, so scripts can validate against it instead of guessing.
+//
+// Compatibility policy: the schema is versioned as a whole. Within one
+// version fields are only ever added, never removed, renamed or changed in
+// type, and consumers are expected to ignore fields they don't know about.
+// Any other change needs a new schema file with the version bumped, the
+// old one stays around. Field names are the ones of the backend structs.
+
+const SCHEMA: &str = include_str!("../../schema/crossinfo-v1.schema.json");
+
+pub fn print() {
+    print!("{SCHEMA}");
+}
@@ -0,0 +1,28 @@
+// The process and component lists re-sort on every refresh, so a
+// selection that is only a line number ends up on whatever moved there.
+// Remembering what is selected lets it follow the item instead, as long
+// as the user didn't move it themselves since.
+
+/// What is selected and on which line it was last seen
+pub struct Selection<T> {
+    selected: Option<(T, u16)>,
+}
+
+impl<T: PartialEq> Selection<T> {
+    pub const fn new() -> Self {
+        Self { selected: None }
+    }
+
+    /// Moves `current_line` to where the selected item is listed now.
+    /// `listed` are the items in the order they are shown
+    pub fn follow(&mut self, current_line: &mut u16, listed: impl IntoIterator<Item = T>) {
+        let listed = listed.into_iter().collect::<Vec<T>>();
+        if let Some((selected, line)) = &self.selected
+            && line == current_line
+            && let Some(index) = listed.iter().position(|item| item == selected)
+        {
+            *current_line = u16::try_from(index).unwrap_or(u16::MAX);
+        }
+        self.selected = listed.into_iter().nth(*current_line as usize).map(|item| (item, *current_line));
+    }
+}
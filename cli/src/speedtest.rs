@@ -0,0 +1,35 @@
+// `cli speedtest` measures the connection against the closest
+// speedtest.net server, with the progress on stderr so the result can be
+// piped somewhere.
+
+use std::io::Write;
+
+fn megabits(bits_per_second: f64) -> String {
+    format!("{:.1} Mbit/s", bits_per_second / 1_000_000.0)
+}
+
+pub fn print() -> std::io::Result<()> {
+    let speedtest = backend::Manager::new()
+        .try_speedtest(|progress| {
+            let phase = match progress.phase {
+                backend::SpeedtestPhase::Latency => "Finding a server",
+                backend::SpeedtestPhase::Download => "Download",
+                backend::SpeedtestPhase::Upload => "Upload",
+            };
+            let speed = progress.bits_per_second.map(megabits).unwrap_or_default();
+            eprint!("\r\x1b[2K{phase}: {:>3.0}% {speed}", progress.progress * 100.0);
+            let _ = std::io::stderr().flush();
+        })
+        .map_err(std::io::Error::other);
+    eprintln!();
+    let speedtest = speedtest?;
+
+    println!(
+        "Server: {} ({}, {}) hosted by {}",
+        speedtest.server.host, speedtest.server.name, speedtest.server.country, speedtest.server.sponsor
+    );
+    println!("Latency: {:.1} ms", speedtest.latency.as_secs_f64() * 1000.0);
+    println!("Download: {}", megabits(speedtest.download));
+    println!("Upload: {}", megabits(speedtest.upload));
+    Ok(())
+}
@@ -0,0 +1,179 @@
+// `cli summary` prints a dense overview in the style of inxi, short enough
+// to paste into a forum post or bug report. One labelled section per
+// subsystem, continuation lines are indented to the same column.
+
+use itertools::Itertools;
+use uom::si::{angular_velocity::revolution_per_minute, frequency::megahertz, power::watt};
+
+const LABEL_WIDTH: usize = 10;
+
+fn print_section(label: &str, lines: &[String]) {
+    for (index, line) in lines.iter().enumerate() {
+        let label = if index == 0 { format!("{label}:") } else { String::new() };
+        println!("{label:LABEL_WIDTH$} {line}");
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn percentage(used: u64, total: u64) -> String {
+    if total == 0 {
+        String::new()
+    } else {
+        super::localize(&format!(" ({:.1}%)", used as f64 / total as f64 * 100.0))
+    }
+}
+
+pub fn print() {
+    let formatter = super::format_bytes;
+    let mut manager = backend::Manager::new();
+
+    if let Some(system) = manager.system_information() {
+        print_section("System", &[format!(
+            "{} {}  Kernel: {}  Uptime: {}",
+            super::to_string_or_unknown(system.os),
+            system.os_version.unwrap_or_default(),
+            super::to_string_or_unknown(system.kernel_version),
+            backend::format_duration(system.uptime)
+        )]);
+    }
+
+    if let Some(cpus) = manager.cpu_information()
+        && let Some(cpu) = cpus.first()
+    {
+        print_section("CPU", &[format!(
+            "{}  Logical Cores: {}  Frequency: {} MHz",
+            cpu.manufacturer,
+            cpus.len(),
+            super::localize(&cpu.frequency.get::<megahertz>().to_string())
+        )]);
+    }
+
+    if let Some(gpus) = manager.gpu_information()
+        && !gpus.is_empty()
+    {
+        print_section(
+            "GPU",
+            &gpus
+                .iter()
+                .map(|gpu| {
+                    [
+                        Some(format!("{} {}", gpu.vendor, gpu.model.as_deref().unwrap_or_default())),
+                        gpu.vram_total.map(|vram_total| {
+                            gpu.vram_used.map_or_else(
+                                || format!("VRAM: {}", formatter(vram_total)),
+                                |vram_used| format!("VRAM: {} / {}{}", formatter(vram_used), formatter(vram_total), percentage(vram_used, vram_total)),
+                            )
+                        }),
+                        gpu.utilization.map(|utilization| format!("Usage: {utilization:.0}%")),
+                        [
+                            ("3D", gpu.engines.graphics),
+                            ("Compute", gpu.engines.compute),
+                            ("Encode", gpu.engines.encode),
+                            ("Decode", gpu.engines.decode),
+                            ("VRAM bandwidth", gpu.memory_utilization),
+                        ]
+                        .into_iter()
+                        .filter_map(|(name, utilization)| Some(format!("{name}: {:.0}%", utilization?)))
+                        .reduce(|engines, engine| format!("{engines}, {engine}"))
+                        .map(|engines| format!("({engines})")),
+                        gpu.temperature.map(|temperature| super::format_temperature(temperature, 0)),
+                        gpu.driver_version.as_ref().map(|driver_version| format!("Driver: {driver_version}")),
+                    ]
+                    .into_iter()
+                    .flatten()
+                    .join("  ")
+                })
+                .collect::<Vec<String>>(),
+        );
+    }
+
+    if let Some(memory) = manager.memory_information() {
+        print_section("Memory", &[format!(
+            "RAM: {} / {}{}  SWAP: {} / {}{}",
+            formatter(memory.used_memory),
+            formatter(memory.total_memory),
+            percentage(memory.used_memory, memory.total_memory),
+            formatter(memory.used_swap),
+            formatter(memory.total_swap),
+            percentage(memory.used_swap, memory.total_swap)
+        )]);
+    }
+
+    if let Some(disks) = manager.disk_information() {
+        print_section(
+            "Disks",
+            &disks
+                .iter()
+                .map(|disk| {
+                    format!(
+                        "{} {} ({}): {} / {}{}",
+                        disk.mount_point,
+                        disk.name,
+                        super::to_string_or_unknown(disk.file_system.as_ref()),
+                        formatter(disk.used),
+                        formatter(disk.total),
+                        percentage(disk.used, disk.total)
+                    )
+                })
+                .collect::<Vec<String>>(),
+        );
+    }
+
+    if let Some(pools) = manager.storage_pool_information() {
+        print_section("Pools", &pools.iter().map(|pool| format!("{} {} {}", pool.name, pool.kind, pool.health)).collect::<Vec<String>>());
+    }
+
+    let network = manager.network_information();
+    let mut network_lines = vec![format!("Internet: {}", network.connectivity)];
+    network_lines.extend(
+        network
+            .networks
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|interface| !interface.flags.is_some_and(|flags| flags.is_loopback) && interface.ips.as_ref().is_some_and(|ips| !ips.is_empty()))
+            .map(|interface| format!("{}: {}", interface.name, interface.ips.unwrap_or_default().iter().join(", "))),
+    );
+    print_section("Network", &network_lines);
+
+    if let Some(batteries) = manager.battery_information() {
+        print_section(
+            "Battery",
+            &batteries
+                .iter()
+                .map(|battery| {
+                    super::localize(&format!(
+                        "{} {:.0}% {}  Health: {:.0}%",
+                        super::to_string_or_unknown(battery.model.as_ref()),
+                        battery.charge * 100.0,
+                        battery.state,
+                        battery.health
+                    ))
+                })
+                .collect::<Vec<String>>(),
+        );
+    }
+
+    if let Some(components) = manager.component_information()
+        && !components.is_empty()
+    {
+        print_section("Sensors", &[components
+            .iter()
+            .map(|component| format!("{}: {}", component.name, super::format_temperature(component.temperature, 0)))
+            .join("  ")]);
+    }
+
+    if let Some(fans) = manager.fan_information()
+        && !fans.is_empty()
+    {
+        print_section("Fans", &[fans
+            .iter()
+            .map(|fan| format!("{}: {:.0} rpm", fan.name, fan.speed.get::<revolution_per_minute>()))
+            .join("  ")]);
+    }
+
+    if let Some(power_rails) = manager.power_information()
+        && !power_rails.is_empty()
+    {
+        print_section("Power", &[power_rails.iter().map(|rail| format!("{}: {:.2} W", rail.name, rail.power.get::<watt>())).join("  ")]);
+    }
+}
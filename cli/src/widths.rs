@@ -0,0 +1,26 @@
+// Column widths only change when the rows do. Finding the widest cell
+// means formatting every process or interface, which on every frame costs
+// more than drawing the few rows that fit on screen, so the widths are
+// kept until the rows are read again.
+
+/// `N` widths, valid as long as the key stays the same. The key is
+/// whatever changes the rows, like when they were read and the filter
+pub struct WidthCache<K, const N: usize> {
+    key:    Option<K>,
+    widths: [usize; N],
+}
+
+impl<K: PartialEq, const N: usize> WidthCache<K, N> {
+    pub const fn new() -> Self {
+        Self { key: None, widths: [0; N] }
+    }
+
+    /// The cached widths, or the ones `compute` returns if the key changed
+    pub fn get(&mut self, key: K, compute: impl FnOnce() -> [usize; N]) -> [usize; N] {
+        if self.key.as_ref() != Some(&key) {
+            self.widths = compute();
+            self.key = Some(key);
+        }
+        self.widths
+    }
+}